@@ -0,0 +1,136 @@
+// ==============================================================================
+//  그룹 멤버에 대한 파괴적 작업 (삭제, 빈 폴더 정리)
+// ------------------------------------------------------------------------------
+//  API 서버와 GUI처럼 "검색 결과 + 삭제할 경로 목록"을 받아 삭제를 실행하는 곳에서
+//  같은 검사를 거치도록 한 곳에 모았습니다.
+//  - 요청한 경로는 모두 검색 결과의 그룹 멤버여야 합니다.
+//  - 각 그룹에는 최소 한 개의 파일이 남아야 합니다.
+//  - 검색 이후 내용이 바뀐 파일은 다른 멤버와 같다고 보장할 수 없으므로 삭제하지 않습니다.
+//
+//  중복을 대량으로 지우고 나면 파일이 모두 빠진 빈 폴더의 "뼈대"가 남습니다.
+//  `prune_empty_dirs`는 삭제한 파일이 있던 폴더부터 위로 올라가며 비게 된 폴더를 지웁니다.
+//  (--prune-empty-dirs) 검색 루트 자체와 루트 밖의 폴더는 지우지 않습니다.
+// ==============================================================================
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::{DuplicateGroup, calculate_hash, in_use, safety};
+
+/// 삭제 실행 결과.
+#[derive(Default)]
+pub struct DeleteReport {
+    pub deleted: Vec<PathBuf>,
+    /// 삭제하지 않은 경로와 그 이유.
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// `targets`에 있는 그룹 멤버들을 삭제하고, 삭제된 멤버를 `groups`에서 제거합니다.
+///
+/// 요청 자체가 잘못되었다면(그룹 멤버가 아닌 경로, 모든 사본을 지우는 그룹) 아무것도
+/// 삭제하지 않고 그 이유를 `Err`로 반환합니다.
+pub fn delete_members(groups: &mut Vec<DuplicateGroup>, targets: &HashSet<PathBuf>) -> Result<DeleteReport, String> {
+    let members: HashSet<&PathBuf> = groups.iter().flat_map(|g| &g.paths).collect();
+    if let Some(unknown) = targets.iter().find(|t| !members.contains(t)) {
+        return Err(format!("'{}'은(는) 검색 결과의 그룹 멤버가 아닙니다", unknown.display()));
+    }
+    if let Some(i) = groups.iter().position(|g| !safety::has_survivor(&g.paths, |p| targets.contains(p))) {
+        return Err(format!("그룹 {}의 모든 파일이 삭제 대상입니다. 최소 하나는 유지해야 합니다", i + 1));
+    }
+
+    let mut report = DeleteReport::default();
+    for group in groups.iter_mut() {
+        // 심볼릭 링크 그룹은 해시가 아니라 대상 경로로 묶였으므로 링크 자체를 지웁니다.
+        let content_hash = group.symlink_target.is_none().then(|| group.hash.clone());
+        let algorithm = group.algorithm;
+        group.paths.retain(|path| {
+            if !targets.contains(path) {
+                return true;
+            }
+            if let Some(hash) = &content_hash
+                && calculate_hash(path, algorithm).ok().as_ref() != Some(hash)
+            {
+                report.skipped.push((path.clone(), String::from("검색 이후 변경되었거나 읽을 수 없음")));
+                return true;
+            }
+            // 다른 프로세스가 잠갔거나 쓰고 있는 파일은 지우지 않습니다.
+            if let Err(e) = in_use::ensure_idle(path) {
+                report.skipped.push((path.clone(), e.to_string()));
+                return true;
+            }
+            match fs::remove_file(path) {
+                Ok(()) => {
+                    report.deleted.push(path.clone());
+                    false
+                }
+                Err(e) => {
+                    report.skipped.push((path.clone(), e.to_string()));
+                    true
+                }
+            }
+        });
+    }
+    // 멤버가 하나 이하로 줄어든 그룹은 더 이상 중복 그룹이 아닙니다.
+    groups.retain(|g| g.paths.len() > 1);
+    Ok(report)
+}
+
+/// 삭제 뒤에 남은 빈 폴더를 정리하는 방식 (--prune-empty-dirs).
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PruneMode {
+    /// 비게 된 폴더를 지웁니다.
+    Remove,
+    /// 지우지 않고, 지울 수 있는 폴더만 보고합니다.
+    DryRun,
+}
+
+/// 빈 폴더 정리 결과.
+#[derive(Default)]
+pub struct PruneReport {
+    /// 지운(dry-run이라면 지울 수 있는) 폴더. 깊은 폴더부터 정리한 순서입니다.
+    pub removed: Vec<PathBuf>,
+    /// 비어 있지만 지우지 못한 폴더와 그 이유.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// `deleted` 파일들이 있던 폴더부터 위로 올라가며, 비게 된 폴더를 아래에서 위로 지웁니다.
+///
+/// `roots` 아래의 폴더만 대상이며 루트 자체는 남깁니다. 하위 폴더가 모두 지워져 비게 된 폴더도
+/// 지우므로, 파일이 하나도 남지 않은 폴더 트리는 통째로 정리됩니다. 숨김 파일(`.DS_Store` 등)이
+/// 하나라도 남은 폴더는 비어 있지 않으므로 지우지 않습니다.
+pub fn prune_empty_dirs(deleted: &[PathBuf], roots: &[PathBuf], mode: PruneMode) -> PruneReport {
+    let inside_root = |dir: &Path| roots.iter().any(|root| dir.starts_with(root) && dir != root);
+    // 정리할 후보: 삭제한 파일의 상위 폴더 중 루트 아래에 있는 것들. 깊은 폴더부터 봅니다.
+    let candidates: BTreeSet<(std::cmp::Reverse<usize>, &Path)> = deleted
+        .iter()
+        .flat_map(|path| path.ancestors().skip(1).take_while(|dir| inside_root(dir)))
+        .map(|dir| (std::cmp::Reverse(dir.components().count()), dir))
+        .collect();
+
+    let mut report = PruneReport::default();
+    // 지운(지울 수 있는) 폴더. dry-run에서는 실제로 남아 있으므로, 상위 폴더가 비었는지
+    // 판단할 때 이 폴더들은 없는 것으로 봅니다.
+    let mut gone: HashSet<PathBuf> = HashSet::new();
+    for (_, dir) in candidates {
+        // 폴더를 읽을 수 없다면(이미 없어졌거나 권한이 없음) 비었는지 알 수 없으므로 건너뜁니다.
+        let Ok(mut entries) = fs::read_dir(dir) else { continue };
+        let empty = entries.all(|entry| entry.is_ok_and(|entry| gone.contains(&entry.path())));
+        if !empty {
+            continue;
+        }
+        if mode == PruneMode::Remove
+            && let Err(e) = fs::remove_dir(dir)
+        {
+            report.failed.push((dir.to_path_buf(), e.to_string()));
+            continue;
+        }
+        gone.insert(dir.to_path_buf());
+        report.removed.push(dir.to_path_buf());
+    }
+    report
+}
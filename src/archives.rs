@@ -0,0 +1,184 @@
+// ==============================================================================
+//  백업 아카이브 세대 사이의 중복 분석 (archives 하위 명령)
+// ------------------------------------------------------------------------------
+//  매일/매주 tar로 전체 백업을 만들면, 바뀌지 않은 파일이 세대마다 다시 저장됩니다.
+//  오래된 아카이브를 지워도 되는지 판단하려면 "그 아카이브에만 있는 내용이 있는가"를 알아야 합니다.
+//
+//  이 모듈은 여러 tar 아카이브(.tar, gzip으로 압축한 .tar.gz)의 일반 파일 멤버를 풀지 않고
+//  스트림으로 읽으며 내용 해시로 색인한 뒤 다음을 보고합니다.
+//  - 아카이브마다: 멤버 수, 크기, 그 아카이브에만 있는 내용
+//  - 삭제 검토: 명령줄에 적은 순서(오래된 것부터)대로, 모든 멤버의 내용이 아직 남겨 둔 다른
+//    아카이브에 있는 아카이브를 "삭제 가능"으로 표시합니다. 앞에서 삭제 가능으로 표시한 아카이브는
+//    뒤의 아카이브를 판단할 때 없는 것으로 보므로, 표시된 아카이브를 모두 지워도 내용을 잃지 않습니다.
+//  - 여러 번 저장된 내용 중 회수 가능 공간이 큰 것들
+//
+//  크기는 모두 압축 전(멤버 크기) 기준입니다. 경로가 바뀌었어도 내용이 같으면 같은 파일로 봅니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use crate::hashing::{HashAlgorithm, Hasher};
+use crate::{Progress, format_count, format_size};
+
+/// gzip 파일의 처음 두 바이트.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// 멤버 하나의 위치: (아카이브 번호, 아카이브 안의 경로).
+type Location = (usize, String);
+
+/// 아카이브 하나의 색인 결과.
+#[derive(Default)]
+struct ArchiveInfo {
+    members: u64,
+    bytes: u64,
+    /// 끝까지 읽지 못했다면 그 이유. 이런 아카이브는 삭제 가능으로 표시하지 않습니다.
+    error: Option<String>,
+}
+
+/// 분석 결과를 종료 코드로 나타내기 위한 요약.
+pub struct Summary {
+    /// 두 번 이상 저장된 내용이 있습니다.
+    pub redundant: bool,
+    /// 끝까지 읽지 못한 아카이브가 있습니다.
+    pub failed: bool,
+}
+
+/// 아카이브들을 색인하고 세대 사이의 중복을 보고합니다. `paths`는 오래된 것부터 적은 순서입니다.
+pub fn report(paths: &[PathBuf], algorithm: HashAlgorithm, top: usize) -> Summary {
+    println!("📦 아카이브 {}개의 파일을 내용 해시({})로 색인합니다...", paths.len(), algorithm.name());
+    let mut progress = Progress::new();
+    let mut indexed = 0u64;
+    // (크기, 해시) -> 그 내용이 저장된 위치들.
+    let mut contents: HashMap<(u64, String), Vec<Location>> = HashMap::new();
+    let mut infos: Vec<ArchiveInfo> = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        let mut info = ArchiveInfo::default();
+        let result = index_archive(path, algorithm, |member, size, hash| {
+            indexed += 1;
+            progress.update("아카이브 색인 중", indexed, None);
+            info.members += 1;
+            info.bytes += size;
+            contents.entry((size, hash)).or_default().push((index, member));
+        });
+        if let Err(e) = result {
+            info.error = Some(e.to_string());
+        }
+        infos.push(info);
+    }
+    progress.finish();
+
+    // 내용마다 그 내용을 가진 아카이브 번호 목록 (중복 없이).
+    let holders: HashMap<&(u64, String), Vec<usize>> = contents
+        .iter()
+        .map(|(key, locations)| {
+            let mut archives: Vec<usize> = locations.iter().map(|(archive, _)| *archive).collect();
+            archives.dedup();
+            (key, archives)
+        })
+        .collect();
+
+    // 오래된 것부터, 남겨 둔 다른 아카이브에 모든 내용이 있는 아카이브를 삭제 가능으로 표시합니다.
+    let mut deletable = vec![false; paths.len()];
+    for index in 0..paths.len() {
+        if infos[index].error.is_some() {
+            continue;
+        }
+        let covered = holders
+            .values()
+            .filter(|archives| archives.contains(&index))
+            .all(|archives| archives.iter().any(|&other| other != index && !deletable[other]));
+        deletable[index] = covered;
+    }
+
+    println!("\n📋 아카이브별 요약 (앞에 적은 아카이브부터 삭제를 검토합니다)");
+    for (index, (path, info)) in paths.iter().zip(&infos).enumerate() {
+        // 이 아카이브에만 있는 내용 (다른 어느 아카이브에도 없는 것).
+        let (only_count, only_bytes) = holders
+            .iter()
+            .filter(|(_, archives)| archives.as_slice() == [index])
+            .fold((0u64, 0u64), |(count, bytes), ((size, _), _)| (count + 1, bytes + size));
+        let label = match (&info.error, deletable[index]) {
+            (Some(_), _) => "읽기 실패",
+            (None, true) => "삭제 가능",
+            (None, false) => "유지",
+        };
+        println!(
+            "  [{}] {}: 파일 {}개, {}, 이 아카이브에만 있는 내용 {}개 ({})",
+            label,
+            path.display(),
+            format_count(info.members),
+            format_size(info.bytes),
+            format_count(only_count),
+            format_size(only_bytes)
+        );
+        if let Some(error) = &info.error {
+            println!("      {}", error);
+        }
+    }
+
+    let mut redundant: Vec<(&(u64, String), &Vec<Location>)> =
+        contents.iter().filter(|(_, locations)| locations.len() > 1).collect();
+    let total: u64 = infos.iter().map(|info| info.bytes).sum();
+    let wasted = |(size, _): &(u64, String), locations: &Vec<Location>| size * (locations.len() as u64 - 1);
+    let wasted_total: u64 = redundant.iter().map(|(key, locations)| wasted(key, locations)).sum();
+    println!(
+        "\n여러 번 저장된 내용: {}개, 회수 가능 {} (전체 {} 중)",
+        redundant.len(),
+        format_size(wasted_total),
+        format_size(total)
+    );
+
+    // 회수 가능 공간이 큰 순서, 같으면 해시 순서로 정렬하여 실행마다 같은 결과를 냅니다.
+    redundant.sort_by(|(a, la), (b, lb)| wasted(b, lb).cmp(&wasted(a, la)).then_with(|| a.1.cmp(&b.1)));
+    if top > 0 && !redundant.is_empty() {
+        println!("\n회수 가능 공간이 큰 내용 상위 {}개:", top.min(redundant.len()));
+    }
+    for (i, ((size, _), locations)) in redundant.iter().take(top).enumerate() {
+        println!("\n--- 내용 {} ({}번 저장, 파일당 {}) ---", i + 1, locations.len(), format_size(*size));
+        for (archive, member) in locations.iter() {
+            println!("  - {}: {}", paths[*archive].display(), member);
+        }
+    }
+
+    Summary { redundant: !redundant.is_empty(), failed: infos.iter().any(|info| info.error.is_some()) }
+}
+
+/// 아카이브 하나의 일반 파일 멤버를 차례로 해시하여 `found(경로, 크기, 해시)`를 호출합니다.
+/// gzip으로 압축된 아카이브는 확장자가 아니라 파일의 처음 두 바이트로 알아봅니다.
+fn index_archive(path: &Path, algorithm: HashAlgorithm, mut found: impl FnMut(String, u64, String)) -> io::Result<()> {
+    // 어떤 아카이브의 어느 멤버가 문제였는지 알 수 있도록 오류 메시지에 붙입니다.
+    let context = |e: &io::Error, member: Option<&str>| match member {
+        Some(member) => io::Error::new(e.kind(), format!("'{}'의 멤버 '{}': {}", path.display(), member, e)),
+        None => io::Error::new(e.kind(), format!("'{}': {}", path.display(), e)),
+    };
+    let mut reader = BufReader::new(File::open(path).map_err(|e| context(&e, None))?);
+    let compressed = reader.fill_buf().map_err(|e| context(&e, None))?.starts_with(&GZIP_MAGIC);
+    let reader: Box<dyn Read> = if compressed { Box::new(GzDecoder::new(reader)) } else { Box::new(reader) };
+    let mut archive = tar::Archive::new(reader);
+    let mut buffer = vec![0; 64 * 1024];
+    for entry in archive.entries().map_err(|e| context(&e, None))? {
+        let mut entry = entry.map_err(|e| context(&e, None))?;
+        // 폴더, 링크, 장치 파일 등은 내용이 없으므로 건너뜁니다.
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let member = entry.path().map_err(|e| context(&e, None))?.display().to_string();
+        let size = entry.size();
+        let mut hasher = Hasher::new(algorithm);
+        loop {
+            match entry.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => hasher.update(&buffer[..read]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(context(&e, Some(&member))),
+            }
+        }
+        found(member, size, hasher.finalize());
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// ==============================================================================
+//  전원이 나가도 망가지지 않는 파일 쓰기
+// ------------------------------------------------------------------------------
+//  체크포인트, 카탈로그, 무시 목록, 지표 파일처럼 다음 실행이 다시 읽는 파일을 제자리에서 고쳐 쓰다가
+//  전원이 나가거나 프로세스가 죽으면, 반쯤 쓴 파일이 남아 이후의 모든 실행이 그 파일을 읽지 못하거나
+//  일부만 읽게 됩니다.
+//  - 같은 폴더의 임시 파일(`.<이름>.<PID>.tmp`)에 모두 쓴 뒤 디스크에 내려 씁니다. (fsync)
+//  - 임시 파일의 이름을 원래 이름으로 바꿉니다. 이름 바꾸기는 원자적이므로, 읽는 쪽은 이전 내용이나
+//    새 내용 중 하나만 봅니다.
+//  - 유닉스에서는 폴더도 디스크에 내려 써서, 바뀐 이름이 전원 장애 뒤에도 남게 합니다.
+//  쓰는 도중 실패하면 임시 파일을 지우고 원래 파일은 그대로 둡니다.
+// ==============================================================================
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// `fill`로 내용을 채워 `path`를 원자적으로 바꿉니다.
+pub fn write(path: &Path, fill: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>) -> io::Result<()> {
+    let temp = temp_path(path);
+    if let Err(e) = write_temp(&temp, fill).and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    sync_parent(path);
+    Ok(())
+}
+
+/// 임시 파일에 내용을 모두 쓰고 디스크에 내려 씁니다.
+fn write_temp(temp: &Path, fill: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(temp)?);
+    fill(&mut writer)?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
+/// 같은 폴더의 숨김 임시 파일. 여러 실행이 같은 파일을 쓰더라도 서로의 임시 파일을 덮지 않도록 PID를 붙입니다.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/// 이름 바꾸기가 기록된 폴더를 디스크에 내려 씁니다. 실패해도 파일 내용은 이미 온전하므로 무시합니다.
+#[cfg(unix)]
+fn sync_parent(path: &Path) {
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    if let Ok(dir) = File::open(parent) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Windows 등에서는 폴더를 열어 내려 쓸 수 없으므로 이름 바꾸기에 맡깁니다.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) {}
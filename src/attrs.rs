@@ -0,0 +1,77 @@
+// ==============================================================================
+//  확장 속성과 POSIX ACL까지 같은 파일만 묶기 (--compare-attrs)
+// ------------------------------------------------------------------------------
+//  내용이 같아도 보안 레이블(SELinux `security.selinux` 등)이나 접근 권한(ACL)이 다른 파일을
+//  하나로 정리하면, 남긴 원본의 권한으로 다른 사용자가 파일을 읽게 되는 사고가 날 수 있습니다.
+//
+//  --compare-attrs는 내용으로 찾은 그룹을 멤버들의 확장 속성 이름과 값으로 다시 나눕니다.
+//  Linux는 POSIX ACL을 `system.posix_acl_access`, `system.posix_acl_default` 확장 속성으로
+//  보여주므로 ACL도 함께 비교됩니다. 단, --xattr-cache가 기록하는 해시 캐시 속성은 파일의 내용에서
+//  나온 값이라 비교에서 뺍니다.
+//
+//  권한이 없어 읽을 수 없는 속성 네임스페이스(예: 일반 사용자에게 `trusted.*`)는 운영체제가 목록에서
+//  빼므로 비교할 수 없습니다. 속성을 읽는 중 오류가 난 파일은 오류 요약에 남기고 그룹에서 뺍니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io;
+use std::path::Path;
+
+use serde_json::json;
+
+use crate::{DuplicateGroup, ScanError, ScanLog, interrupt, record_error};
+
+/// 비교에서 빼는 속성 이름의 접두사. (--xattr-cache나 shatag이 기록하는 해시 캐시)
+#[cfg(unix)]
+use crate::xattr_cache::PREFIX as CACHE_PREFIX;
+
+/// 파일의 확장 속성 (이름, 값) 목록을 이름순으로 모읍니다. 심볼릭 링크는 링크 자체의 속성을 읽습니다.
+#[cfg(unix)]
+pub fn signature(path: &Path) -> io::Result<Vec<(OsString, Vec<u8>)>> {
+    let mut attrs = Vec::new();
+    for name in xattr::list(path)? {
+        if name.to_string_lossy().starts_with(CACHE_PREFIX) {
+            continue;
+        }
+        // 목록을 읽은 뒤 속성이 지워졌다면 값이 없는 것으로 봅니다.
+        let value = xattr::get(path, &name)?.unwrap_or_default();
+        attrs.push((name, value));
+    }
+    attrs.sort();
+    Ok(attrs)
+}
+
+/// 확장 속성을 지원하지 않는 플랫폼. `main`에서 --compare-attrs를 미리 거부하므로 호출되지 않습니다.
+#[cfg(not(unix))]
+pub fn signature(_path: &Path) -> io::Result<Vec<(OsString, Vec<u8>)>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "확장 속성을 지원하지 않는 플랫폼입니다"))
+}
+
+/// 그룹마다 멤버들을 확장 속성이 같은 것끼리 다시 나누고, 두 개 이상 남은 부분만 그룹으로 돌려줍니다.
+pub fn split_groups(groups: Vec<DuplicateGroup>, errors: &mut Vec<ScanError>, log: &mut ScanLog) -> Vec<DuplicateGroup> {
+    let before = groups.len();
+    let mut split = Vec::new();
+    for group in groups {
+        // 중단 요청을 받았다면 아직 나누지 않은 그룹은 버립니다. 속성을 확인하지 않은 그룹을
+        // 보고하면 --compare-attrs의 약속을 어기게 됩니다.
+        if interrupt::requested() {
+            break;
+        }
+        let mut by_attrs: HashMap<Vec<(OsString, Vec<u8>)>, Vec<_>> = HashMap::new();
+        for path in group.paths.iter() {
+            match signature(path) {
+                Ok(attrs) => by_attrs.entry(attrs).or_default().push(path.clone()),
+                Err(e) => record_error(errors, log, Some(path.clone()), "속성 읽기", e.kind().to_string(), e.to_string()),
+            }
+        }
+        // 실행마다 같은 순서가 되도록 원래 그룹의 멤버 순서대로 나눈 그룹을 만듭니다.
+        let mut parts: Vec<Vec<_>> = by_attrs.into_values().filter(|paths| paths.len() > 1).collect();
+        parts.sort_by_key(|paths| group.paths.iter().position(|p| p == &paths[0]));
+        for paths in parts {
+            split.push(group.subgroup(paths));
+        }
+    }
+    log.event("stage_end", json!({ "stage": "attrs", "groups_before": before, "duplicate_groups": split.len() }));
+    split
+}
@@ -0,0 +1,205 @@
+// ==============================================================================
+//  음원 비교: 태그 무시 (--match audio)
+// ------------------------------------------------------------------------------
+//  음악 관리 프로그램으로 제목, 앨범 아트, 재생 횟수 같은 태그를 고치면 파일 안의 메타데이터
+//  블록만 바뀌고 소리는 그대로이지만, 내용 해시로는 같은 음원으로 보이지 않습니다.
+//
+//  --match audio는 평소처럼 내용 해시로 중복을 찾은 뒤, 음원 파일마다 메타데이터를 건너뛰고
+//  오디오 데이터만 해시합니다. 이 해시가 같은 파일들을 한 그룹으로 더 묶습니다.
+//  - mp3:  앞의 ID3v2 태그와 뒤의 ID3v1, APEv2 태그를 뺀 나머지 (MPEG 프레임)
+//  - flac: `fLaC` 뒤의 메타데이터 블록(STREAMINFO, VORBIS_COMMENT, PICTURE, PADDING 등)을 뺀 오디오 프레임
+//  - m4a:  MP4 최상위 `mdat` 박스의 내용. 태그(`moov/udta`)와 청크 위치표는 태그 길이에 따라
+//          바뀌므로 비교하지 않습니다.
+//  그룹의 `hash` 자리에는 `audio:`로 시작하는 표시와 이 해시를 넣어 내용 그룹과 구분합니다.
+// ==============================================================================
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::hashing::Hasher;
+use crate::{LooseMatcher, ScanOptions};
+
+/// 음원 그룹의 `hash` 앞에 붙는 표시. 뒤에는 오디오 데이터의 해시가 이어집니다.
+const AUDIO_MARK: &str = "audio:";
+
+/// 비교하는 음원 확장자.
+const EXTENSIONS: &[&str] = &["mp3", "flac", "m4a", "m4b"];
+
+/// 확장자로 보아 태그를 건너뛸 수 있는 음원 파일인지 확인합니다.
+pub fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(e)))
+}
+
+/// 음원 그룹이라면 그 오디오 데이터 해시를 반환합니다.
+pub fn group_hash(hash: &str) -> Option<&str> {
+    hash.strip_prefix(AUDIO_MARK)
+}
+
+/// 메타데이터를 뺀 오디오 데이터의 해시를 계산합니다.
+/// 알 수 있는 형식이 아니거나 구조가 깨졌다면 `None`이고, 파일을 읽을 수 없을 때만 `Err`입니다.
+pub fn frames_hash(path: &Path, options: &ScanOptions) -> io::Result<Option<String>> {
+    let mut file = BufReader::new(File::open(path)?);
+    let len = file.get_ref().metadata()?.len();
+    let mut magic = [0; 8];
+    let read = read_up_to(&mut file, &mut magic)?;
+    let ranges = if read >= 8 && &magic[4..8] == b"ftyp" {
+        mp4_ranges(&mut file, len)?
+    } else {
+        // ID3v2 태그는 flac 앞에도 붙을 수 있으므로 먼저 건너뜁니다.
+        let start = skip_id3v2(&mut file, len)?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut magic = [0; 4];
+        if read_up_to(&mut file, &mut magic)? == 4 && &magic == b"fLaC" {
+            flac_ranges(&mut file, start + 4, len)?
+        } else {
+            mp3_ranges(&mut file, start, len)?
+        }
+    };
+    let Some(ranges) = ranges.filter(|ranges| ranges.iter().any(|(start, end)| end > start)) else { return Ok(None) };
+
+    let mut hasher = Hasher::new(options.algorithm);
+    let mut buffer = [0; 64 * 1024];
+    for (start, end) in ranges {
+        file.seek(SeekFrom::Start(start))?;
+        let mut remaining = end - start;
+        while remaining > 0 {
+            let want = remaining.min(buffer.len() as u64) as usize;
+            let read = match file.read(&mut buffer[..want]) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "읽는 도중 파일이 짧아졌습니다")),
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            hasher.update(&buffer[..read]);
+            remaining -= read as u64;
+        }
+    }
+    Ok(Some(hasher.finalize()))
+}
+
+/// 파일 끝에 닿기 전까지 `buffer`를 채우고, 읽은 바이트 수를 반환합니다.
+fn read_up_to(file: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// 파일 앞의 ID3v2 태그들을 건너뛴 위치. 태그가 없으면 0입니다.
+/// 헤더: `ID3`, 버전 2바이트, 플래그 1바이트, 7비트씩 나눠 쓴(synchsafe) 크기 4바이트.
+fn skip_id3v2(file: &mut (impl Read + Seek), len: u64) -> io::Result<u64> {
+    let mut position = 0;
+    loop {
+        file.seek(SeekFrom::Start(position))?;
+        let mut header = [0; 10];
+        if read_up_to(file, &mut header)? < 10 || &header[..3] != b"ID3" {
+            return Ok(position);
+        }
+        let size = header[6..10].iter().fold(0u64, |size, &b| (size << 7) | u64::from(b & 0x7f));
+        // 플래그의 0x10은 태그 뒤에 10바이트 꼬리(footer)가 있다는 뜻입니다.
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        let next = position + 10 + size + footer;
+        if next > len {
+            return Ok(position);
+        }
+        position = next;
+    }
+}
+
+/// mp3: ID3v2 뒤부터, 파일 끝의 ID3v1(128바이트 `TAG`)과 APEv2(`APETAGEX` 꼬리) 태그 앞까지.
+fn mp3_ranges(file: &mut (impl Read + Seek), start: u64, len: u64) -> io::Result<Option<Vec<(u64, u64)>>> {
+    let mut end = len;
+    if end >= start + 128 {
+        file.seek(SeekFrom::Start(end - 128))?;
+        let mut tag = [0; 3];
+        if read_up_to(file, &mut tag)? == 3 && &tag == b"TAG" {
+            end -= 128;
+        }
+    }
+    if end >= start + 32 {
+        // APEv2 꼬리: `APETAGEX`, 버전 4바이트, 꼬리를 포함한(머리는 뺀) 태그 크기 4바이트, 항목 수, 플래그.
+        file.seek(SeekFrom::Start(end - 32))?;
+        let mut footer = [0; 32];
+        if read_up_to(file, &mut footer)? == 32 && &footer[..8] == b"APETAGEX" {
+            let size = u64::from(u32::from_le_bytes([footer[12], footer[13], footer[14], footer[15]]));
+            // 플래그의 최상위 비트는 태그 앞에 32바이트 머리(header)도 있다는 뜻입니다.
+            let header = if footer[23] & 0x80 != 0 { 32 } else { 0 };
+            if size + header > end - start {
+                return Ok(None);
+            }
+            end -= size + header;
+        }
+    }
+    // 처음이 MPEG 프레임 동기 신호(11비트가 모두 1)가 아니라면 mp3가 아닙니다.
+    file.seek(SeekFrom::Start(start))?;
+    let mut sync = [0; 2];
+    if read_up_to(file, &mut sync)? < 2 || sync[0] != 0xff || sync[1] & 0xe0 != 0xe0 {
+        return Ok(None);
+    }
+    Ok(Some(vec![(start, end)]))
+}
+
+/// flac: `fLaC` 뒤의 메타데이터 블록들을 건너뛴 뒤부터 파일 끝까지.
+/// 블록 머리: 1바이트(최상위 비트는 마지막 블록 표시, 나머지는 종류) + 3바이트 길이.
+fn flac_ranges(file: &mut (impl Read + Seek), mut position: u64, len: u64) -> io::Result<Option<Vec<(u64, u64)>>> {
+    loop {
+        file.seek(SeekFrom::Start(position))?;
+        let mut header = [0; 4];
+        if read_up_to(file, &mut header)? < 4 {
+            return Ok(None);
+        }
+        let size = u64::from(u32::from_be_bytes([0, header[1], header[2], header[3]]));
+        position += 4 + size;
+        if position > len {
+            return Ok(None);
+        }
+        if header[0] & 0x80 != 0 {
+            return Ok(Some(vec![(position, len)]));
+        }
+    }
+}
+
+/// m4a: 최상위 박스들 중 `mdat`의 내용. 박스 머리: 4바이트 크기(1이면 뒤에 8바이트 크기, 0이면 파일 끝까지) + 4바이트 종류.
+fn mp4_ranges(file: &mut (impl Read + Seek), len: u64) -> io::Result<Option<Vec<(u64, u64)>>> {
+    let mut ranges = Vec::new();
+    let mut position = 0;
+    while position < len {
+        file.seek(SeekFrom::Start(position))?;
+        let mut header = [0; 16];
+        if read_up_to(file, &mut header)? < 8 {
+            return Ok(None);
+        }
+        let kind = &header[4..8];
+        let (size, header_len) = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            0 => (len - position, 8),
+            1 => (u64::from_be_bytes(header[8..16].try_into().unwrap_or_default()), 16),
+            size => (u64::from(size), 8),
+        };
+        if size < header_len || position + size > len {
+            return Ok(None);
+        }
+        if kind == b"mdat" {
+            ranges.push((position + header_len, position + size));
+        }
+        position += size;
+    }
+    Ok((!ranges.is_empty()).then_some(ranges))
+}
+
+/// --match audio: 파일을 오디오 데이터 해시로 묶습니다.
+pub const MATCHER: LooseMatcher = LooseMatcher {
+    mark: AUDIO_MARK,
+    stage: "audio",
+    progress: "음원 비교 중",
+    action: "음원 읽기",
+    key: frames_hash,
+};
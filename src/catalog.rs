@@ -0,0 +1,289 @@
+// ==============================================================================
+//  오프라인 드라이브 카탈로그 (`index` 하위 명령, --against-catalogs)
+// ------------------------------------------------------------------------------
+//  선반에 꽂아 둔 외장 드라이브들은 평소에 연결되어 있지 않으므로, 새 데이터가 "이미 어느
+//  드라이브에 있는지" 확인하려면 드라이브를 하나씩 연결해야 합니다. 대신 드라이브를 연결했을 때
+//  한 번 `index build`로 모든 파일의 (크기, 해시, 경로)를 카탈로그로 기록해 두면, 드라이브를
+//  뽑은 뒤에도 --against-catalogs로 새 데이터를 모든 카탈로그와 비교할 수 있습니다.
+//
+//  카탈로그는 이름(--label)마다 파일 하나로, 사용자 데이터 폴더의 `duplicate_finder/catalogs/`에
+//  저장합니다. (`$XDG_DATA_HOME` 또는 `~/.local/share`, Windows는 `%APPDATA%`)
+//  {
+//    "label": "drive1", "root": "/mnt/drive1", "created": "2024-01-01T00:00:00Z", "algorithm": "sha256",
+//    "files": [ { "size": 1024, "hash": "...", "path": "photos/a.jpg" }, ... ]
+//  }
+//  경로는 루트에 대한 상대 경로이므로, 다음에 다른 위치에 마운트해도 그대로 알아볼 수 있습니다.
+//  비교는 --compare-manifest와 같은 방법으로 하며, 카탈로그에 없는 크기의 파일은 읽지 않습니다.
+//  `workspace` 하위 명령도 작업 공간마다 폴더 하나에 같은 형식의 카탈로그를 모아 둡니다. (workspace.rs)
+// ==============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::atomic_file;
+use crate::manifest::Manifest;
+use crate::stages::Pipeline;
+use crate::store_lock::{self, Access};
+use crate::{
+    EXIT_FILE_ERRORS, EXIT_INTERRUPTED, FdBudget, FilterMode, HashAlgorithm, HashFailure, KIND_IN_USE, MatchMode, Progress,
+    ScanError, ScanLog, ScanOptions, collect_files, default_threads, format_count, format_size, hash_unchanged, interrupt, prepare_roots,
+    print_error_summary, reader_pool, record_error,
+};
+
+/// 드라이브 하나의 카탈로그.
+#[derive(Serialize, Deserialize)]
+pub struct Catalog {
+    pub label: String,
+    /// 기록할 때 드라이브를 마운트했던 루트 폴더.
+    pub root: String,
+    /// 기록한 시각 (RFC 3339, UTC).
+    pub created: String,
+    pub algorithm: String,
+    pub files: Vec<CatalogFile>,
+}
+
+/// 카탈로그에 기록한 파일 하나.
+#[derive(Serialize, Deserialize)]
+pub struct CatalogFile {
+    pub size: u64,
+    pub hash: String,
+    /// 루트에 대한 상대 경로.
+    pub path: String,
+}
+
+/// 사용자 데이터 폴더 안의 `duplicate_finder` 폴더.
+pub fn data_dir() -> Option<PathBuf> {
+    let data = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))?;
+    Some(data.join("duplicate_finder"))
+}
+
+/// 사용자 데이터 폴더 안의 기본 카탈로그 폴더.
+pub fn default_dir() -> Option<PathBuf> {
+    Some(data_dir()?.join("catalogs"))
+}
+
+/// 이름은 파일 이름으로 쓰이므로 안전한 문자만 허용합니다.
+pub fn valid_label(label: &str) -> bool {
+    !label.is_empty() && !label.starts_with('.') && label.bytes().all(|b| b.is_ascii_alphanumeric() || b"._-".contains(&b))
+}
+
+fn catalog_path(dir: &Path, label: &str) -> io::Result<PathBuf> {
+    if !valid_label(label) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("카탈로그 이름 '{}'에는 영문자, 숫자, '.', '_', '-'만 쓸 수 있습니다", label),
+        ));
+    }
+    Ok(dir.join(format!("{}.json", label)))
+}
+
+pub fn load(path: &Path) -> io::Result<Catalog> {
+    let context = |e: &dyn std::fmt::Display| format!("카탈로그 '{}': {}", path.display(), e);
+    let text = fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), context(&e)))?;
+    serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, context(&e)))
+}
+
+/// 카탈로그 폴더의 모든 카탈로그 파일 (이름 순서).
+pub fn catalog_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "json"))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    paths.sort();
+    Ok(paths)
+}
+
+/// `index build`: 루트 폴더의 모든 파일을 해시하여 카탈로그로 저장합니다. 같은 이름의 카탈로그는 바꿉니다.
+pub fn build(dir: &Path, root: &Path, label: &str, algorithm: HashAlgorithm) -> io::Result<ExitCode> {
+    let path = catalog_path(dir, label)?;
+    let roots = prepare_roots(&[root.to_path_buf()])?;
+    let options = ScanOptions {
+        filter_mode: FilterMode::None,
+        created_after: None,
+        created_before: None,
+        include_symlinks: false,
+        strict: false,
+        normalize_names: false,
+        threads: default_threads(),
+        isolate_hardlinks: false,
+        hydrate: false,
+        device_limits: None,
+        fd_budget: reader_pool::default_fd_budget(),
+        xattr_cache: false,
+        compare_attrs: false,
+        require_same_mtime: false,
+        imported_hashes: None,
+        ignored_hashes: None,
+        match_mode: MatchMode::Content,
+        stages: Pipeline::default(),
+        algorithm,
+        extra_hash: None,
+        bloom_prepass: None,
+        spotlight: false,
+        spill: None,
+        max_memory: None,
+        checkpoint: None,
+    };
+    interrupt::install_handler();
+    println!("💽 '{}'의 파일을 해시하여 '{}' 카탈로그를 만듭니다...", roots[0].display(), label);
+
+    let mut errors: Vec<ScanError> = Vec::new();
+    let mut log = ScanLog::disabled();
+    let mut progress = Progress::new();
+    let jobs = collect_files(&roots, &options, &mut errors, &mut log, &mut progress);
+    let total = jobs.len() as u64;
+    let budget = FdBudget::new(options.fd_budget);
+    let mut files = Vec::new();
+    let mut processed = 0u64;
+    reader_pool::run(
+        jobs,
+        options.threads,
+        |(path, stamp)| {
+            let result = hash_unchanged(&path, &stamp, &budget, &options);
+            (path, stamp, result)
+        },
+        |(path, stamp, result)| {
+            processed += 1;
+            progress.update("해시 계산 중", processed, Some(total));
+            match result {
+                Ok((digests, _)) => {
+                    let relative = path.strip_prefix(&roots[0]).unwrap_or(&path);
+                    files.push(CatalogFile { size: stamp.len, hash: digests.hash, path: relative.display().to_string() });
+                }
+                Err(HashFailure::Io(e)) if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() => {
+                    return false;
+                }
+                Err(HashFailure::Changed(kind, message)) => {
+                    record_error(&mut errors, &mut log, Some(path), "변경 감지", kind, message);
+                }
+                Err(HashFailure::InUse(reason)) => {
+                    record_error(&mut errors, &mut log, Some(path), "사용 중 확인", KIND_IN_USE.to_string(), reason.to_string());
+                }
+                Err(HashFailure::Io(e)) => {
+                    record_error(&mut errors, &mut log, Some(path), "해시 계산", e.kind().to_string(), e.to_string());
+                }
+            }
+            true
+        },
+    );
+    progress.finish();
+    // 일부만 기록한 카탈로그는 "드라이브에 없음"을 잘못 알려 주므로 저장하지 않습니다.
+    if interrupt::requested() {
+        println!("⏹️  중단되었습니다. 카탈로그를 저장하지 않았습니다.");
+        return Ok(ExitCode::from(EXIT_INTERRUPTED));
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let bytes: u64 = files.iter().map(|f| f.size).sum();
+    let count = files.len();
+    let catalog = Catalog {
+        label: label.to_string(),
+        root: roots[0].canonicalize().unwrap_or_else(|_| roots[0].clone()).display().to_string(),
+        created: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        algorithm: algorithm.name().to_string(),
+        files,
+    };
+    // 해시하는 동안은 잠그지 않고, 저장하는 동안만 다른 실행이 카탈로그 폴더를 읽거나 쓰지 못하게 합니다.
+    let _lock = store_lock::acquire(&store_lock::inside(dir), Access::Exclusive, "카탈로그 폴더")?;
+    // 쓰는 도중에 죽어도 이전 카탈로그가 온전히 남도록 임시 파일에 쓴 뒤 이름을 바꿉니다.
+    atomic_file::write(&path, |writer| Ok(serde_json::to_writer(writer, &catalog)?))?;
+    println!("✅ 파일 {}개({})를 '{}'에 기록했습니다.", format_count(count), format_size(bytes), path.display());
+    print_error_summary(&errors, false);
+    Ok(if errors.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(EXIT_FILE_ERRORS) })
+}
+
+/// `index list`: 저장된 카탈로그들을 출력합니다.
+pub fn list(dir: &Path) -> io::Result<()> {
+    println!("카탈로그 폴더: {}", dir.display());
+    let _lock = store_lock::acquire(&store_lock::inside(dir), Access::Shared, "카탈로그 폴더")?;
+    let paths = catalog_files(dir)?;
+    if paths.is_empty() {
+        println!("  (없음)");
+    }
+    for path in paths {
+        let catalog = load(&path)?;
+        let bytes: u64 = catalog.files.iter().map(|f| f.size).sum();
+        println!(
+            "  {}: 파일 {}개 ({}), {} ({}, {} 기록)",
+            catalog.label,
+            format_count(catalog.files.len()),
+            format_size(bytes),
+            catalog.root,
+            catalog.algorithm,
+            catalog.created
+        );
+    }
+    Ok(())
+}
+
+/// `index remove`: 카탈로그를 지웁니다.
+pub fn remove(dir: &Path, label: &str) -> io::Result<()> {
+    let path = catalog_path(dir, label)?;
+    let _lock = store_lock::acquire(&store_lock::inside(dir), Access::Exclusive, "카탈로그 폴더")?;
+    fs::remove_file(&path).map_err(|e| io::Error::new(e.kind(), format!("카탈로그 '{}'을(를) 지울 수 없습니다: {}", label, e)))?;
+    println!("🗑️  '{}' 카탈로그를 지웠습니다.", label);
+    Ok(())
+}
+
+/// --against-catalogs: 카탈로그들을 하나의 비교 목록으로 합칩니다. `labels`가 비어 있으면 모든 카탈로그를 씁니다.
+/// 같은 내용이 여러 드라이브에 있으면 드라이브마다 첫 경로를 `이름:경로`로 모두 보여줍니다.
+pub fn manifest(dir: &Path, labels: &[String], algorithm: HashAlgorithm) -> io::Result<(Manifest, Vec<String>)> {
+    let _lock = store_lock::acquire(&store_lock::inside(dir), Access::Shared, "카탈로그 폴더")?;
+    let paths = if labels.is_empty() {
+        catalog_files(dir)?
+    } else {
+        labels.iter().map(|label| catalog_path(dir, label)).collect::<io::Result<_>>()?
+    };
+    if paths.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("'{}'에 카탈로그가 없습니다. 먼저 `index build`로 만드세요", dir.display()),
+        ));
+    }
+    let mut entries: HashMap<String, String> = HashMap::new();
+    let mut sizes = HashSet::new();
+    let mut used = Vec::new();
+    for path in paths {
+        let catalog = load(&path)?;
+        if catalog.algorithm != algorithm.name() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "'{}' 카탈로그는 {} 해시로 만들었으므로 --algorithm {}과 함께 써야 합니다",
+                    catalog.label, catalog.algorithm, catalog.algorithm
+                ),
+            ));
+        }
+        let mut seen = HashSet::new();
+        for file in &catalog.files {
+            sizes.insert(file.size);
+            if !seen.insert(&file.hash) {
+                continue;
+            }
+            let location = format!("{}:{}", catalog.label, file.path);
+            entries
+                .entry(file.hash.clone())
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(&location);
+                })
+                .or_insert(location);
+        }
+        used.push(catalog.label);
+    }
+    Ok((Manifest::with_sizes(entries, sizes), used))
+}
@@ -0,0 +1,398 @@
+// ==============================================================================
+//  긴 스캔의 주기적 체크포인트 (--checkpoint, --resume)
+// ------------------------------------------------------------------------------
+//  수백만 개의 파일을 검색하는 데는 몇 시간이 걸릴 수 있습니다. 그 사이에 프로세스가
+//  죽거나(OOM, 재부팅) 사용자가 중단하면, 체크포인트가 없을 때는 처음부터 다시 해야 합니다.
+//
+//  --checkpoint FILE을 지정하면 N개의 파일(--checkpoint-every)마다 또는 일정 시간
+//  (--checkpoint-interval)마다 지금까지의 작업을 FILE에 기록합니다.
+//  - 탐색 위치: 체크포인트를 쓰는 동안에는 폴더를 이름순으로 탐색하므로, 마지막으로 처리한
+//    경로만 기억하면 그 앞의 항목은 다시 볼 필요가 없습니다.
+//  - 크기 그룹: 지금까지 발견한 파일과 발견 시점의 크기/수정 시간.
+//  - 완료된 해시: 이미 계산한 파일의 해시.
+//  --resume으로 다시 실행하면 기록된 위치 다음부터 탐색하고, 크기와 수정 시간이 그대로인
+//  파일의 해시는 다시 계산하지 않습니다. 따라서 잃는 작업은 마지막 간격만큼입니다.
+//
+//  기록은 임시 파일에 쓰고 디스크에 내려 쓴 뒤 이름을 바꾸므로(atomic_file), 쓰는 도중에 죽거나
+//  전원이 나가도 이전 체크포인트가 온전히 남습니다.
+//  스캔을 끝까지 마치면 체크포인트 파일은 삭제됩니다.
+//
+//  경로는 JSON 문자열로 저장되므로, UTF-8이 아닌 이름의 파일은 이어서 할 때 찾을 수 없어
+//  오류로 보고됩니다.
+// ==============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::atomic_file;
+use crate::root_stats::RootTotals;
+use crate::{FileStamp, FilterMode, ScanError, ScanOptions};
+
+/// 체크포인트 파일 형식의 버전. 형식이 바뀌면 예전 파일로는 이어서 하지 않습니다.
+const VERSION: u32 = 1;
+
+/// 탐색 오류를 기록할 때의 단계 이름. (해시 단계의 실패는 이어서 할 때 다시 시도합니다)
+const WALK_STAGE: &str = "탐색";
+
+/// 체크포인트 설정 (--checkpoint, --checkpoint-every, --checkpoint-interval, --resume).
+#[derive(Clone)]
+pub struct Config {
+    pub path: PathBuf,
+    /// 이 개수의 파일을 처리할 때마다 기록합니다.
+    pub every: Option<u64>,
+    /// 마지막 기록 후 이 시간이 지나면 기록합니다.
+    pub interval: Duration,
+    /// 시작할 때 기존 체크포인트에서 이어서 할지 여부.
+    pub resume: bool,
+}
+
+/// 탐색 위치: (루트 폴더의 순서, 마지막으로 처리한 경로).
+pub type Position = (usize, PathBuf);
+
+/// 해시 단계의 (크기, 해시, 두 번째 해시) -> 경로 리스트.
+pub type HashGroups = HashMap<(u64, String, Option<String>), Vec<PathBuf>>;
+
+/// 체크포인트 파일 전체.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    version: u32,
+    /// 기록한 시각 (RFC 3339, UTC).
+    created: String,
+    /// 루트 폴더와 결과에 영향을 주는 옵션을 요약한 문자열. 다른 설정으로는 이어서 하지 않습니다.
+    settings: String,
+    walk_complete: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<(usize, String)>,
+    found_files: u64,
+    /// 발견한 파일 크기의 합. 이 항목이 생기기 전에 기록된 체크포인트에서는 0입니다.
+    #[serde(default)]
+    found_bytes: u64,
+    /// 루트별 발견 파일 수와 크기. 이 항목이 생기기 전에 기록된 체크포인트에서는 비어 있습니다.
+    #[serde(default)]
+    root_totals: Vec<RootTotals>,
+    special_files: usize,
+    /// 건너뛴 클라우드 온라인 전용 파일 수. 이 항목이 생기기 전에 기록된 체크포인트에서는 0입니다.
+    #[serde(default)]
+    placeholder_files: usize,
+    /// 이미 본 물리적 파일(장치 번호, inode). 탐색이 끝나지 않았을 때만 필요합니다.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    seen_files: Vec<(u64, u64)>,
+    files: Vec<SavedFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    symlinks: Vec<(String, Vec<String>)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<SavedError>,
+}
+
+/// 발견한 파일 하나와, 계산을 마쳤다면 그 해시.
+#[derive(Serialize, Deserialize)]
+struct SavedFile {
+    path: String,
+    size: u64,
+    /// 수정 시간 (유닉스 시각의 초, 나노초). 초는 1970년 이전이면 음수입니다.
+    modified: Option<(i64, u32)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extra_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedError {
+    path: Option<String>,
+    kind: String,
+    message: String,
+}
+
+/// 체크포인트를 쓰는 시점의 스캔 상태. `find_duplicates`의 변수들을 빌려 옵니다.
+pub struct Snapshot<'a> {
+    pub walk_complete: bool,
+    pub position: Option<&'a Position>,
+    pub found_files: u64,
+    pub found_bytes: u64,
+    pub root_totals: &'a [RootTotals],
+    pub special_files: usize,
+    pub placeholder_files: usize,
+    pub files_by_size: &'a HashMap<u64, Vec<(PathBuf, FileStamp)>>,
+    pub seen_files: &'a HashSet<(u64, u64)>,
+    /// 해시 단계라면 지금까지 계산한 (크기, 해시, 두 번째 해시) -> 경로.
+    pub files_by_hash: Option<&'a HashGroups>,
+    pub symlinks_by_target: &'a HashMap<PathBuf, Vec<PathBuf>>,
+    pub errors: &'a [ScanError],
+}
+
+/// 체크포인트에서 되살린 스캔 상태.
+pub struct Resumed {
+    pub walk_complete: bool,
+    pub position: Option<Position>,
+    pub found_files: u64,
+    pub found_bytes: u64,
+    pub root_totals: Vec<RootTotals>,
+    pub special_files: usize,
+    pub placeholder_files: usize,
+    pub files_by_size: HashMap<u64, Vec<(PathBuf, FileStamp)>>,
+    pub seen_files: HashSet<(u64, u64)>,
+    /// 계산을 마친 파일의 (해시, 두 번째 해시).
+    pub hashes: HashMap<PathBuf, (String, Option<String>)>,
+    pub symlinks_by_target: HashMap<PathBuf, Vec<PathBuf>>,
+    pub errors: Vec<ScanError>,
+}
+
+/// 언제 체크포인트를 쓸지 판단하고 파일에 기록합니다.
+pub struct Checkpointer {
+    config: Config,
+    settings: String,
+    /// 마지막 기록 이후 처리한 파일 수.
+    count: u64,
+    last_write: Instant,
+    /// 기록 실패 경고를 한 번만 출력하기 위한 표시.
+    warned: bool,
+}
+
+impl Checkpointer {
+    pub fn new(config: &Config, roots: &[PathBuf], options: &ScanOptions) -> Self {
+        Checkpointer {
+            config: config.clone(),
+            settings: settings(roots, options),
+            count: 0,
+            last_write: Instant::now(),
+            warned: false,
+        }
+    }
+
+    /// 파일 하나를 처리했음을 알리고, 체크포인트를 쓸 때가 되었는지 반환합니다.
+    pub fn tick(&mut self) -> bool {
+        self.count += 1;
+        self.config.every.is_some_and(|every| self.count >= every) || self.last_write.elapsed() >= self.config.interval
+    }
+
+    /// `--resume`이면 기존 체크포인트를 읽습니다. 파일이 없으면 처음부터 시작하도록 `None`을 반환하고,
+    /// 파일이 손상되었거나 다른 설정으로 기록된 것이면 오류를 반환합니다.
+    pub fn load(&self) -> io::Result<Option<Resumed>> {
+        if !self.config.resume {
+            return Ok(None);
+        }
+        let path = &self.config.path;
+        let context = |e: &dyn std::fmt::Display| format!("체크포인트 '{}': {}", path.display(), e);
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("ℹ️  체크포인트 '{}'이(가) 없어 처음부터 검색합니다.", path.display());
+                return Ok(None);
+            }
+            Err(e) => return Err(io::Error::new(e.kind(), context(&e))),
+        };
+        let checkpoint: Checkpoint =
+            serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, context(&e)))?;
+        if checkpoint.version != VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, context(&"지원하지 않는 형식 버전입니다")));
+        }
+        if checkpoint.settings != self.settings {
+            let message = "다른 루트 폴더나 옵션(필터, --created-after/before, --symlinks, --algorithm, --extra-hash)으로 기록되었습니다";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, context(&message)));
+        }
+        let created = checkpoint.created.clone();
+        let resumed = checkpoint.into_resumed();
+        eprintln!(
+            "ℹ️  체크포인트({})에서 이어서 검색합니다: 발견한 파일 {}개, 계산을 마친 해시 {}개",
+            created,
+            resumed.found_files,
+            resumed.hashes.len()
+        );
+        Ok(Some(resumed))
+    }
+
+    /// 현재 상태를 체크포인트 파일에 기록합니다. 실패해도 스캔은 계속하며, 경고는 한 번만 출력합니다.
+    pub fn save(&mut self, snapshot: Snapshot) {
+        self.count = 0;
+        self.last_write = Instant::now();
+        let checkpoint = Checkpoint::from_snapshot(&self.settings, snapshot);
+        if let Err(e) = write_atomically(&self.config.path, &checkpoint)
+            && !self.warned
+        {
+            self.warned = true;
+            eprintln!("⚠️  체크포인트 '{}'을(를) 기록하지 못했습니다: {}", self.config.path.display(), e);
+        }
+    }
+
+    /// 스캔을 끝까지 마쳤으므로 더 이상 필요 없는 체크포인트 파일을 지웁니다.
+    pub fn finish(&self) {
+        let _ = fs::remove_file(&self.config.path);
+    }
+}
+
+/// 체크포인트를 이어서 쓸 수 있는지 판단하는 설정 요약.
+fn settings(roots: &[PathBuf], options: &ScanOptions) -> String {
+    let filter = match &options.filter_mode {
+        FilterMode::None => String::new(),
+        FilterMode::Expr(expr) => expr.to_string(),
+    };
+    let roots: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
+    let mut settings = format!(
+        "roots={:?} filter={:?} symlinks={} algorithm={} extra={}",
+        roots,
+        filter,
+        options.include_symlinks,
+        options.algorithm.name(),
+        options.extra_hash.map_or("-", |a| a.name())
+    );
+    // 하드 링크를 따로 보는지에 따라 탐색 결과가 달라지므로, 지정했을 때만 덧붙입니다.
+    if options.isolate_hardlinks {
+        settings.push_str(" isolate=true");
+    }
+    // 온라인 전용 파일을 포함하는지도 탐색 결과를 바꿉니다.
+    if options.hydrate {
+        settings.push_str(" hydrate=true");
+    }
+    // 생성 시각 범위는 지정했을 때만 덧붙여, 범위가 없는 기존 체크포인트와도 이어서 할 수 있게 합니다.
+    if options.created_after.is_some() || options.created_before.is_some() {
+        let created = (options.created_after.map(encode_time), options.created_before.map(encode_time));
+        settings.push_str(&format!(" created={:?}", created));
+    }
+    settings
+}
+
+impl Checkpoint {
+    fn from_snapshot(settings: &str, snapshot: Snapshot) -> Self {
+        // 해시 단계라면 각 경로에 계산을 마친 해시를 붙여 저장합니다.
+        let mut hashes: HashMap<&Path, (&str, Option<&str>)> = HashMap::new();
+        for ((_, hash, extra), paths) in snapshot.files_by_hash.into_iter().flatten() {
+            for path in paths {
+                hashes.insert(path, (hash, extra.as_deref()));
+            }
+        }
+        let files = snapshot
+            .files_by_size
+            .iter()
+            .flat_map(|(&size, files)| files.iter().map(move |(path, stamp)| (size, path, stamp)))
+            .map(|(size, path, stamp)| {
+                let hash = hashes.get(path.as_path());
+                SavedFile {
+                    path: path.display().to_string(),
+                    size,
+                    modified: stamp.modified.map(encode_time),
+                    hash: hash.map(|(h, _)| h.to_string()),
+                    extra_hash: hash.and_then(|(_, e)| e.map(str::to_string)),
+                }
+            })
+            .collect();
+        Checkpoint {
+            version: VERSION,
+            created: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            settings: settings.to_string(),
+            walk_complete: snapshot.walk_complete,
+            position: snapshot.position.map(|(root, path)| (*root, path.display().to_string())),
+            found_files: snapshot.found_files,
+            found_bytes: snapshot.found_bytes,
+            root_totals: snapshot.root_totals.to_vec(),
+            special_files: snapshot.special_files,
+            placeholder_files: snapshot.placeholder_files,
+            // 탐색을 마쳤다면 새로 만날 파일이 없으므로 inode 목록은 필요 없습니다.
+            seen_files: if snapshot.walk_complete { Vec::new() } else { snapshot.seen_files.iter().copied().collect() },
+            files,
+            symlinks: snapshot
+                .symlinks_by_target
+                .iter()
+                .map(|(target, links)| {
+                    (target.display().to_string(), links.iter().map(|l| l.display().to_string()).collect())
+                })
+                .collect(),
+            errors: snapshot
+                .errors
+                .iter()
+                .filter(|e| e.stage == WALK_STAGE)
+                .map(|e| SavedError {
+                    path: e.path.as_ref().map(|p| p.display().to_string()),
+                    kind: e.kind.clone(),
+                    message: e.message.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn into_resumed(self) -> Resumed {
+        let mut files_by_size: HashMap<u64, Vec<(PathBuf, FileStamp)>> = HashMap::new();
+        let mut hashes = HashMap::new();
+        for file in self.files {
+            let path = PathBuf::from(file.path);
+            if let Some(hash) = file.hash {
+                hashes.insert(path.clone(), (hash, file.extra_hash));
+            }
+            let stamp = FileStamp { len: file.size, modified: file.modified.map(decode_time) };
+            files_by_size.entry(file.size).or_default().push((path, stamp));
+        }
+        Resumed {
+            walk_complete: self.walk_complete,
+            position: self.position.map(|(root, path)| (root, PathBuf::from(path))),
+            found_files: self.found_files,
+            found_bytes: self.found_bytes,
+            root_totals: self.root_totals,
+            special_files: self.special_files,
+            placeholder_files: self.placeholder_files,
+            files_by_size,
+            seen_files: self.seen_files.into_iter().collect(),
+            hashes,
+            symlinks_by_target: self
+                .symlinks
+                .into_iter()
+                .map(|(target, links)| (PathBuf::from(target), links.into_iter().map(PathBuf::from).collect()))
+                .collect(),
+            errors: self
+                .errors
+                .into_iter()
+                .map(|e| ScanError { path: e.path.map(PathBuf::from), stage: WALK_STAGE, kind: e.kind, message: e.message })
+                .collect(),
+        }
+    }
+}
+
+/// 이어서 하는 탐색에서, 이전 실행이 이미 처리한 항목이라 건너뛸 경로인지 판단합니다.
+///
+/// 탐색은 이름순이므로 경로를 구성 요소 단위로 비교한 순서가 곧 방문 순서입니다.
+/// 마지막 위치보다 앞선 항목은 건너뛰되, 마지막 위치를 품고 있는 상위 폴더(마지막 위치
+/// 자신이 폴더인 경우 포함)는 그 안의 나머지 항목을 보기 위해 들어가야 합니다.
+pub fn already_walked(position: Option<&Position>, root_index: usize, path: &Path, is_dir: bool) -> bool {
+    match position {
+        None => false,
+        Some((root, _)) if root_index != *root => root_index < *root,
+        Some((_, last)) if path == last.as_path() => !is_dir,
+        Some((_, last)) => path < last.as_path() && !last.starts_with(path),
+    }
+}
+
+/// 임시 파일에 쓴 뒤 이름을 바꾸어, 기록 도중에 죽어도 이전 체크포인트가 온전히 남게 합니다.
+fn write_atomically(path: &Path, checkpoint: &Checkpoint) -> io::Result<()> {
+    atomic_file::write(path, |writer| {
+        serde_json::to_writer(&mut *writer, checkpoint)?;
+        writer.write_all(b"\n")
+    })
+}
+
+/// 수정 시간을 (초, 나노초)로 바꿉니다. 1970년 이전은 초를 음수로 내림하여 나노초가 항상 0 이상이 되게 합니다.
+pub fn encode_time(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(e) => {
+            let d = e.duration();
+            if d.subsec_nanos() == 0 {
+                (-(d.as_secs() as i64), 0)
+            } else {
+                (-(d.as_secs() as i64) - 1, 1_000_000_000 - d.subsec_nanos())
+            }
+        }
+    }
+}
+
+pub fn decode_time((secs, nanos): (i64, u32)) -> SystemTime {
+    let base = if secs >= 0 {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64)
+    } else {
+        SystemTime::UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+    };
+    base + Duration::from_nanos(u64::from(nanos))
+}
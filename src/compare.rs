@@ -0,0 +1,215 @@
+// ==============================================================================
+//  두 파일 또는 두 폴더 비교 (compare 하위 명령)
+// ------------------------------------------------------------------------------
+//  "이 두 파일이 정말 같은가", "백업 폴더가 원본과 같은가"는 중복 검색보다 자주 하는 질문인데,
+//  지금까지는 `cmp`나 `diff -rq` 같은 다른 도구가 필요했습니다.
+//
+//  `compare A B`는 검색과 같은 방법으로 내용을 비교합니다.
+//  - 두 파일: 크기가 다르면 읽지 않고 다르다고 답하고, 같으면 바이트 단위로 비교하다가 처음 다른
+//    곳에서 멈춥니다. (--stages의 `bytes` 단계와 같은 비교)
+//  - 두 폴더: 상대 경로가 같은 파일끼리 위와 같이 비교하여 같은 파일, 다른 파일, 한쪽에만 있는 파일로
+//    나눕니다. 한쪽에만 있는 파일 중 크기와 해시가 같은 쌍은 옮기거나 이름을 바꾼 파일로 따로 보여줍니다.
+//  일반 파일만 비교하며 심볼릭 링크는 따라가지도, 포함하지도 않습니다.
+// ==============================================================================
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::hashing::HashAlgorithm;
+use crate::stages::same_content;
+use crate::{Progress, calculate_hash, format_count, format_size, interrupt};
+
+/// 비교 결과를 종료 코드로 나타내기 위한 요약.
+pub struct Summary {
+    /// 다른 파일이나 한쪽에만 있는 파일이 있습니다.
+    pub differs: bool,
+    /// 읽지 못한 파일이 있습니다.
+    pub failed: bool,
+}
+
+/// 두 경로를 비교합니다. 둘 다 파일이거나 둘 다 폴더여야 합니다.
+pub fn run(a: &Path, b: &Path, algorithm: HashAlgorithm) -> io::Result<Summary> {
+    let (meta_a, meta_b) = (fs::metadata(a)?, fs::metadata(b)?);
+    match (meta_a.is_dir(), meta_b.is_dir()) {
+        (false, false) => Ok(files(a, b, meta_a.len(), meta_b.len())),
+        (true, true) => dirs(a, b, algorithm),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "두 경로가 모두 파일이거나 모두 폴더여야 합니다")),
+    }
+}
+
+/// 두 파일 비교.
+fn files(a: &Path, b: &Path, size_a: u64, size_b: u64) -> Summary {
+    if size_a != size_b {
+        println!("❌ 두 파일이 다릅니다. (크기 {} / {})", format_size(size_a), format_size(size_b));
+        return Summary { differs: true, failed: false };
+    }
+    match same_content(a, b) {
+        Ok(true) => {
+            println!("✅ 두 파일의 내용이 같습니다. ({})", format_size(size_a));
+            Summary { differs: false, failed: false }
+        }
+        Ok(false) => {
+            println!("❌ 두 파일이 다릅니다. (크기는 {}로 같지만 내용이 다름)", format_size(size_a));
+            Summary { differs: true, failed: false }
+        }
+        Err(e) => {
+            eprintln!("⚠️  파일을 끝까지 비교하지 못했습니다: {}", e);
+            Summary { differs: false, failed: true }
+        }
+    }
+}
+
+/// 폴더 아래의 일반 파일을 상대 경로 순서로 모읍니다. 읽을 수 없는 항목은 `failed`에 셉니다.
+fn regular_files(folder: &Path, failed: &mut usize) -> BTreeMap<PathBuf, u64> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(folder) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("⚠️  {}", e);
+                *failed += 1;
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        match entry.metadata() {
+            Ok(metadata) => {
+                let relative = entry.path().strip_prefix(folder).unwrap_or(entry.path()).to_path_buf();
+                files.insert(relative, metadata.len());
+            }
+            Err(e) => {
+                eprintln!("⚠️  '{}': {}", entry.path().display(), e);
+                *failed += 1;
+            }
+        }
+    }
+    files
+}
+
+/// 두 폴더 비교.
+fn dirs(a: &Path, b: &Path, algorithm: HashAlgorithm) -> io::Result<Summary> {
+    println!("📁 '{}'와 '{}'를 비교합니다...", a.display(), b.display());
+    let mut failed = 0;
+    let files_a = regular_files(a, &mut failed);
+    let mut only_b = regular_files(b, &mut failed);
+
+    let mut progress = Progress::new();
+    let mut processed = 0u64;
+    let (mut same, mut same_bytes) = (0usize, 0u64);
+    let mut different: Vec<(&Path, u64, u64)> = Vec::new();
+    let mut only_a: Vec<(&Path, u64)> = Vec::new();
+    // 진행 표시와 섞이지 않도록 읽기 오류는 모아 두었다가 비교가 끝난 뒤 출력합니다.
+    let mut errors = Vec::new();
+    for (relative, &size) in &files_a {
+        if interrupt::requested() {
+            break;
+        }
+        processed += 1;
+        progress.update("비교 중", processed, Some(files_a.len() as u64));
+        let Some(other) = only_b.remove(relative) else {
+            only_a.push((relative, size));
+            continue;
+        };
+        if size != other {
+            different.push((relative, size, other));
+            continue;
+        }
+        match same_content(&a.join(relative), &b.join(relative)) {
+            Ok(true) => {
+                same += 1;
+                same_bytes += size;
+            }
+            Ok(false) => different.push((relative, size, other)),
+            Err(e) => errors.push(format!("'{}': {}", relative.display(), e)),
+        }
+    }
+    progress.finish();
+    for error in &errors {
+        eprintln!("⚠️  {}", error);
+    }
+    failed += errors.len();
+    let moved = match_moved(a, b, &mut only_a, &mut only_b, algorithm, &mut failed);
+
+    println!("\n✅ 같은 파일: {}개 ({})", format_count(same), format_size(same_bytes));
+    println!("❌ 다른 파일: {}개", format_count(different.len()));
+    for (relative, size_a, size_b) in &different {
+        if size_a == size_b {
+            println!("  ≠ {} (내용 다름)", relative.display());
+        } else {
+            println!("  ≠ {} (크기 {} / {})", relative.display(), format_size(*size_a), format_size(*size_b));
+        }
+    }
+    println!("⬅️  '{}'에만 있는 파일: {}개", a.display(), format_count(only_a.len()));
+    for (relative, size) in &only_a {
+        println!("  - {} ({})", relative.display(), format_size(*size));
+    }
+    println!("➡️  '{}'에만 있는 파일: {}개", b.display(), format_count(only_b.len()));
+    for (relative, size) in &only_b {
+        println!("  + {} ({})", relative.display(), format_size(*size));
+    }
+    if !moved.is_empty() {
+        println!("🔀 옮기거나 이름을 바꾼 파일: {}개", moved.len());
+        for (from, to) in &moved {
+            println!("  {} → {}", from.display(), to.display());
+        }
+    }
+    if interrupt::requested() {
+        println!("⏹️  중단되었습니다. 위 결과는 일부 파일만 비교한 것입니다.");
+    }
+    let differs = !different.is_empty() || !only_a.is_empty() || !only_b.is_empty() || !moved.is_empty();
+    Ok(Summary { differs, failed: failed > 0 || interrupt::requested() })
+}
+
+/// 한쪽에만 있는 파일 중 크기와 해시가 같은 쌍을 찾아 두 목록에서 빼고 (A 경로, B 경로)로 돌려줍니다.
+/// 한쪽에만 있는 파일끼리 크기가 같은 것만 해시합니다.
+fn match_moved(
+    a: &Path,
+    b: &Path,
+    only_a: &mut Vec<(&Path, u64)>,
+    only_b: &mut BTreeMap<PathBuf, u64>,
+    algorithm: HashAlgorithm,
+    failed: &mut usize,
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut hash = |path: PathBuf| match calculate_hash(&path, algorithm) {
+        Ok(hash) => Some(hash),
+        Err(e) => {
+            eprintln!("⚠️  '{}': {}", path.display(), e);
+            *failed += 1;
+            None
+        }
+    };
+    let sizes_a: HashSet<u64> = only_a.iter().map(|(_, size)| *size).collect();
+    // B에만 있는 파일 중 A에만 있는 파일과 크기가 같은 것의 (크기, 해시) -> 경로들.
+    let mut candidates: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+    for (relative, size) in only_b.iter().filter(|(_, size)| sizes_a.contains(size)) {
+        if let Some(digest) = hash(b.join(relative)) {
+            candidates.entry((*size, digest)).or_default().push(relative.clone());
+        }
+    }
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+    let sizes_b: HashSet<u64> = candidates.keys().map(|(size, _)| *size).collect();
+    let mut moved = Vec::new();
+    only_a.retain(|(relative, size)| {
+        if interrupt::requested() || !sizes_b.contains(size) {
+            return true;
+        }
+        let Some(digest) = hash(a.join(relative)) else { return true };
+        match candidates.get_mut(&(*size, digest)).and_then(|paths| paths.pop()) {
+            Some(to) => {
+                only_b.remove(&to);
+                moved.push((relative.to_path_buf(), to));
+                false
+            }
+            None => true,
+        }
+    });
+    moved
+}
@@ -0,0 +1,188 @@
+// ==============================================================================
+//  다른 폴더에 내용이 모두 들어 있는 폴더 보고서 (--contained-dirs)
+// ------------------------------------------------------------------------------
+//  `Backup_old`, `사진 정리 전` 같은 폴더를 정리할 때 실제로 궁금한 것은 "이 폴더의 파일이 모두 다른
+//  곳에도 있는가", 즉 폴더째 지워도 되는가입니다. 파일 단위의 그룹 목록으로는 폴더의 파일이 하나도
+//  빠짐없이 다른 곳에 있는지 알 수 없습니다.
+//
+//  --contained-dirs는 그룹 목록 대신, 안의 모든 파일(하위 폴더 포함)이 다른 한 폴더 아래에 같은 내용으로
+//  있는 폴더를 그 폴더와 함께, 크기가 큰 순서로 보여줍니다.
+//  - 검색 결과의 중복 그룹에 없는 파일은 다른 곳에 없는 파일로 봅니다. 그래서 --min-size, --exclude
+//    등으로 검색에서 뺀 파일이나 무시 목록으로 숨긴 그룹의 파일이 있는 폴더는 보고하지 않습니다.
+//    (폴더째 지워도 된다고 잘못 알리는 것보다 놓치는 쪽이 안전합니다)
+//  - 크기가 0인 파일은 내용이 없으므로 판단에서 뺍니다.
+//  - 상위 폴더도 함께 들어 있는 폴더라면 상위 폴더만 보고합니다. 두 폴더가 서로를 포함하면(한쪽에
+//    같은 내용의 사본이 더 있어 파일 수가 달라도) 한쪽만 보고하고 표시하며, 회수 공간에도 한쪽만 셉니다.
+//  심볼릭 링크는 따라가지 않으며, 심볼릭 링크 그룹은 제외합니다.
+// ==============================================================================
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::{DuplicateGroup, format_count, format_size, path_style};
+
+/// 폴더 하나(하위 폴더 포함)의 집계.
+#[derive(Default)]
+struct Folder {
+    files: u64,
+    bytes: u64,
+    /// 중복 그룹에 없는(다른 곳에 없는) 파일이 있습니다.
+    unique: bool,
+    /// 이 폴더의 파일이 속한 그룹들.
+    groups: BTreeSet<usize>,
+}
+
+/// 다른 폴더에 들어 있는 폴더 하나.
+struct Contained<'a> {
+    dir: &'a Path,
+    files: u64,
+    bytes: u64,
+    /// 내용을 모두 가진 폴더들. (그 하위 폴더도 내용을 모두 가졌다면 가장 깊은 폴더만)
+    within: Vec<&'a Path>,
+    /// 이 폴더를 포함하면서 이 폴더에도 들어 있는(서로 포함하는) 폴더들.
+    twins: Vec<&'a Path>,
+}
+
+/// 다른 폴더에 내용이 모두 들어 있는 폴더를 출력합니다. `top`이 있으면 큰 폴더부터 그만큼만 보여줍니다.
+pub fn report(groups: &[DuplicateGroup], roots: &[PathBuf], top: Option<usize>) {
+    let members: HashMap<&Path, usize> = groups
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| g.symlink_target.is_none())
+        .flat_map(|(i, g)| g.paths.iter().map(move |path| (path.as_path(), i)))
+        .collect();
+
+    // 루트를 다시 탐색하여 폴더마다 모든 파일을 집계합니다. 읽지 못한 폴더는 다른 곳에 없는 파일이
+    // 있을 수 있으므로 보고하지 않습니다.
+    let mut folders: BTreeMap<PathBuf, Folder> = BTreeMap::new();
+    for root in roots {
+        for entry in WalkDir::new(root) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    if let Some(path) = e.path() {
+                        for dir in path.ancestors().filter(|dir| dir.starts_with(root)) {
+                            folders.entry(dir.to_path_buf()).or_default().unique = true;
+                        }
+                    }
+                    continue;
+                }
+            };
+            if entry.file_type().is_dir() || entry.depth() == 0 {
+                continue;
+            }
+            let group = members.get(entry.path()).copied();
+            let size = group.map_or(0, |i| groups[i].size);
+            if group.is_none() && entry.file_type().is_file() && entry.metadata().is_ok_and(|m| m.len() == 0) {
+                continue;
+            }
+            for dir in entry.path().ancestors().skip(1).filter(|dir| dir.starts_with(root)) {
+                let folder = folders.entry(dir.to_path_buf()).or_default();
+                match group {
+                    Some(i) => {
+                        folder.files += 1;
+                        folder.bytes += size;
+                        folder.groups.insert(i);
+                    }
+                    // 심볼릭 링크나 특수 파일도 폴더째 지우면 함께 사라지므로 다른 곳에 없는 항목으로 봅니다.
+                    None => folder.unique = true,
+                }
+            }
+        }
+    }
+
+    let mut found: Vec<Contained> = Vec::new();
+    for (dir, folder) in &folders {
+        if folder.unique || folder.files == 0 {
+            continue;
+        }
+        // 그룹마다, 이 폴더 밖의 멤버를 가진 폴더들. 모든 그룹에 공통인 폴더가 내용을 모두 가진 폴더입니다.
+        // 이 폴더의 상위 폴더는 당연히 이 폴더를 포함하므로 뺍니다.
+        let mut common: Option<BTreeSet<&Path>> = None;
+        for &i in &folder.groups {
+            let holders: BTreeSet<&Path> = groups[i]
+                .paths
+                .iter()
+                .filter(|path| !path.starts_with(dir))
+                .flat_map(|path| path.ancestors().skip(1))
+                .filter(|holder| roots.iter().any(|root| holder.starts_with(root)) && !dir.starts_with(holder))
+                .collect();
+            let next = match common {
+                Some(common) => common.intersection(&holders).copied().collect(),
+                None => holders,
+            };
+            let empty = next.is_empty();
+            common = Some(next);
+            if empty {
+                break;
+            }
+        }
+        let common = common.unwrap_or_default();
+        // 내용을 모두 가진 폴더의 상위 폴더도 모두 가지므로, 가장 깊은 폴더만 남깁니다.
+        let within: Vec<&Path> =
+            common.iter().copied().filter(|holder| !common.iter().any(|other| other != holder && other.starts_with(holder))).collect();
+        if within.is_empty() {
+            continue;
+        }
+        found.push(Contained { dir, files: folder.files, bytes: folder.bytes, within, twins: Vec::new() });
+    }
+
+    // 서로 포함하는지는 파일 수가 아니라 포함 관계로 판단합니다. 이 폴더를 가진 폴더(⊂ 폴더와 그 상위
+    // 폴더) 중 보고 대상이면서, 그 ⊂ 폴더가 이 폴더(또는 그 하위 폴더)인 폴더가 쌍둥이입니다.
+    let index: HashMap<&Path, usize> = found.iter().enumerate().map(|(i, c)| (c.dir, i)).collect();
+    let twins: Vec<Vec<&Path>> = found
+        .iter()
+        .map(|c| {
+            let twins: BTreeSet<&Path> = c
+                .within
+                .iter()
+                .flat_map(|holder| holder.ancestors())
+                .filter_map(|holder| index.get(holder).map(|&j| &found[j]))
+                .filter(|other| other.within.iter().any(|holder| holder.starts_with(c.dir)))
+                .map(|other| other.dir)
+                .collect();
+            twins.into_iter().collect()
+        })
+        .collect();
+    for (c, twins) in found.iter_mut().zip(twins) {
+        c.twins = twins;
+    }
+
+    // 상위 폴더도 들어 있다면 상위 폴더만 보고합니다.
+    let reported: BTreeSet<&Path> = found.iter().map(|c| c.dir).collect();
+    found.retain(|c| !c.dir.parent().is_some_and(|parent| reported.contains(parent)));
+    // 서로 포함하는 폴더는 경로가 앞선 한쪽만 남깁니다. 둘 다 지우면 내용을 잃으므로 회수 공간에도 넣지 않습니다.
+    let reported: BTreeSet<&Path> = found.iter().map(|c| c.dir).collect();
+    found.retain(|c| !c.twins.iter().any(|twin| reported.contains(twin) && *twin < c.dir));
+    if found.is_empty() {
+        println!("✅ 다른 폴더에 내용이 모두 들어 있는 폴더가 없습니다. (전체 중복 그룹 {}개)", groups.len());
+        return;
+    }
+    // 안정 정렬이므로 크기가 같은 폴더끼리는 경로 순서가 유지됩니다.
+    found.sort_by_key(|c| std::cmp::Reverse(c.bytes));
+    let total: u64 = found.iter().map(|c| c.bytes).sum();
+    println!("\n📦 다른 폴더에 내용이 모두 들어 있는 폴더: {}개, 폴더째 지우면 회수 {}\n", format_count(found.len()), format_size(total));
+    if let Some(n) = top.filter(|n| found.len() > *n) {
+        found.truncate(n);
+        println!("(크기가 가장 큰 상위 {}개 폴더만 표시합니다.)\n", n);
+    }
+    for contained in found {
+        println!(
+            "--- {} (파일 {}개, {}) ---",
+            path_style::display(contained.dir),
+            format_count(contained.files),
+            format_size(contained.bytes)
+        );
+        for holder in &contained.within {
+            println!("  ⊂ {}", path_style::display(holder));
+        }
+        for twin in &contained.twins {
+            println!("  ({}에도 이 폴더의 내용이 모두 있고 그 반대도 같습니다. 둘 중 한쪽만 지우세요)", path_style::display(twin));
+        }
+        println!();
+    }
+    println!("ℹ️  표시한 폴더는 안의 모든 파일이 ⊂ 폴더에 같은 내용으로 있어 폴더째 지워도 내용을 잃지 않습니다.");
+    println!("   폴더마다 따로 판단한 것이므로, 여러 폴더를 지울 때는 각 폴더의 ⊂ 폴더가 남아 있는지 확인하세요.");
+}
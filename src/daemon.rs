@@ -0,0 +1,179 @@
+// ==============================================================================
+//  데몬 모드 (--daemon)와 Prometheus 메트릭 (--metrics-listen)
+// ------------------------------------------------------------------------------
+//  파일 서버처럼 계속 쌓이는 저장소에서는 한 번의 검색보다, 주기적으로 다시 검색하여
+//  중복이 얼마나 늘고 있는지 지켜보는 것이 중요합니다. 데몬 모드는 같은 검색을 지정한
+//  간격마다 반복하고, 매 검색의 요약을 출력합니다.
+//
+//  `--metrics-listen`을 지정하면 마지막 검색의 수치를 Prometheus 텍스트 형식으로
+//  `GET /metrics`에 제공하여, 모니터링 시스템이 중복 용량이 임계값을 넘을 때
+//  경보를 보낼 수 있게 합니다.
+//
+//  `--webhook`이나 `--smtp-server`를 지정하면 매 검색의 요약을 그곳으로도 보냅니다.
+//  (notify 모듈 참고)
+//
+//  Ctrl+C(또는 SIGTERM)를 받으면 진행 중인 검색을 정리하고 종료합니다.
+// ==============================================================================
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::notify::Notifier;
+use crate::{Args, RunReport, ScanLog, ScanOptions, interrupt, scan_and_report};
+
+/// 다음 검색을 기다리는 동안 중단 요청을 확인하는 간격.
+const WAIT_STEP: Duration = Duration::from_millis(200);
+
+/// 메트릭 엔드포인트가 제공하는 수치들. HTTP 스레드와 검색 루프가 함께 접근합니다.
+#[derive(Default)]
+struct Metrics {
+    /// 데몬 시작 이후 완료된 검색 수.
+    scans_total: u64,
+    /// 데몬 시작 이후 발생한 오류 수 (분류 구분 없음).
+    errors_total: u64,
+    /// 마지막 검색이 끝난 시각 (유닉스 초).
+    last_scan_timestamp: f64,
+    /// 마지막으로 완료된 검색의 통계.
+    last: Option<RunReport>,
+}
+
+impl Metrics {
+    fn record(&mut self, report: RunReport) {
+        self.scans_total += 1;
+        self.errors_total += report.errors_by_kind.values().sum::<usize>() as u64;
+        self.last_scan_timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64());
+        self.last = Some(report);
+    }
+
+    /// Prometheus 텍스트 노출 형식(text/plain; version=0.0.4)으로 변환합니다.
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP duplicate_finder_{name} {help}");
+            let _ = writeln!(out, "# TYPE duplicate_finder_{name} {kind}");
+            let _ = writeln!(out, "duplicate_finder_{name} {value}");
+        };
+        metric("scans_total", "counter", "Number of completed scans.", self.scans_total.to_string());
+        metric("errors_total", "counter", "Number of file errors across all scans.", self.errors_total.to_string());
+        let Some(last) = &self.last else { return out };
+
+        metric(
+            "last_scan_timestamp_seconds",
+            "gauge",
+            "Unix time at which the last scan finished.",
+            self.last_scan_timestamp.to_string(),
+        );
+        metric(
+            "scan_duration_seconds",
+            "gauge",
+            "Duration of the last scan.",
+            last.duration.as_secs_f64().to_string(),
+        );
+        metric(
+            "scan_interrupted",
+            "gauge",
+            "1 if the last scan was interrupted and its numbers are partial.",
+            u8::from(last.interrupted).to_string(),
+        );
+        metric("files_scanned", "gauge", "Files found by the last scan.", last.stats.files_scanned.to_string());
+        metric("bytes_hashed", "gauge", "Bytes read for hashing by the last scan.", last.stats.bytes_hashed.to_string());
+        metric("duplicate_groups", "gauge", "Duplicate groups found by the last scan.", last.groups.to_string());
+        metric(
+            "duplicate_bytes",
+            "gauge",
+            "Bytes reclaimable by keeping one copy per group in the last scan.",
+            last.wasted_bytes.to_string(),
+        );
+        let cache = &last.stats.cache;
+        metric("cache_hits", "gauge", "Hashes taken from the cache in the last scan.", cache.hits.to_string());
+        metric("cache_misses", "gauge", "Files hashed despite the cache in the last scan.", cache.misses.to_string());
+        metric(
+            "cache_invalidations",
+            "gauge",
+            "Cache records ignored because the file size or mtime changed in the last scan.",
+            cache.invalidated.to_string(),
+        );
+        if let Some(ratio) = cache.hit_ratio() {
+            metric("cache_hit_ratio", "gauge", "Fraction of cache lookups that hit in the last scan.", ratio.to_string());
+        }
+        if let Some(saved) = cache.saved {
+            metric(
+                "cache_saved_seconds",
+                "gauge",
+                "Estimated scan time saved by the cache in the last scan.",
+                saved.as_secs_f64().to_string(),
+            );
+        }
+
+        let _ = writeln!(out, "# HELP duplicate_finder_scan_errors File errors in the last scan by kind.");
+        let _ = writeln!(out, "# TYPE duplicate_finder_scan_errors gauge");
+        for (kind, count) in &last.errors_by_kind {
+            let _ = writeln!(out, "duplicate_finder_scan_errors{{kind=\"{}\"}} {}", escape_label(kind), count);
+        }
+        out
+    }
+}
+
+/// Prometheus 레이블 값에서 특수 문자(역슬래시, 따옴표, 줄바꿈)를 이스케이프합니다.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// 메트릭을 제공하는 HTTP 서버를 백그라운드 스레드로 시작합니다.
+fn serve_metrics(listen: &str, metrics: Arc<Mutex<Metrics>>) -> io::Result<()> {
+    let server = tiny_http::Server::http(listen).map_err(io::Error::other)?;
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if request.url() == "/metrics" {
+                let body = metrics.lock().unwrap_or_else(|e| e.into_inner()).render();
+                let content_type = tiny_http::Header::from_bytes("Content-Type", "text/plain; version=0.0.4")
+                    .expect("정적인 헤더는 항상 올바릅니다");
+                tiny_http::Response::from_string(body).with_header(content_type)
+            } else {
+                tiny_http::Response::from_string("not found\n").with_status_code(404)
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+/// 중단 요청을 받을 때까지 `interval`마다 검색을 반복합니다.
+pub fn run(args: &Args, roots: &[PathBuf], options: &ScanOptions, log: &mut ScanLog, interval: Duration) -> ExitCode {
+    let metrics = Arc::new(Mutex::new(Metrics::default()));
+    if let Some(listen) = &args.metrics_listen {
+        if let Err(e) = serve_metrics(listen, Arc::clone(&metrics)) {
+            eprintln!("오류 발생: 메트릭 서버를 '{}'에서 시작할 수 없습니다: {}", listen, e);
+            return ExitCode::from(crate::EXIT_FATAL);
+        }
+        println!("📈 메트릭을 http://{}/metrics 에서 제공합니다.", listen);
+    }
+    let notifier = Notifier::from_args(args);
+    println!("🔁 데몬 모드: {} 간격으로 검색을 반복합니다. (Ctrl+C로 종료)", humantime::format_duration(interval));
+
+    loop {
+        let started = Instant::now();
+        println!("\n🕒 {} 검색 시작", humantime::format_rfc3339_seconds(SystemTime::now()));
+        let (_, report) = scan_and_report(args, roots, options, log, None, None);
+        if let Some(report) = report {
+            if let Some(notifier) = &notifier {
+                notifier.deliver(roots, &report);
+            }
+            metrics.lock().unwrap_or_else(|e| e.into_inner()).record(report);
+        }
+        // 다음 검색 시각까지 기다리되, 중단 요청에는 곧바로 반응합니다.
+        while !interrupt::requested() && started.elapsed() < interval {
+            thread::sleep(WAIT_STEP.min(interval.saturating_sub(started.elapsed())));
+        }
+        if interrupt::requested() {
+            println!("\n데몬을 종료합니다.");
+            return ExitCode::SUCCESS;
+        }
+    }
+}
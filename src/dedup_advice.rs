@@ -0,0 +1,138 @@
+// ==============================================================================
+//  중복 제거 파일 시스템 도입 검토 보고서 (--dedup-report)
+// ------------------------------------------------------------------------------
+//  ZFS dedup, btrfs + bees, Windows 데이터 중복 제거처럼 파일 시스템이 스스로 같은 블록을
+//  한 번만 저장하게 하면, 파일을 지우거나 링크로 바꾸지 않고도 공간을 줄일 수 있습니다.
+//  하지만 도입에는 비용이 있으므로(특히 ZFS의 중복 제거 표는 메모리에 있어야 빠릅니다),
+//  먼저 "이 데이터에서 얼마나 줄어드는가"를 알아야 합니다.
+//
+//  이 모듈은 검색에서 찾은 중복 파일을 바탕으로, 파일 시스템마다 중복 제거의 단위와 제외 규칙을
+//  적용하여 절약될 공간(할당 단위 기준)과 중복 제거율(논리 크기로 전체 ÷ 중복 제거 후)을 추정합니다.
+//  - 파일 전체가 같은 사본만 셉니다. 블록 단위 중복 제거는 일부만 같은 파일에서도 공간을 줄이므로
+//    실제 절약량은 이 추정보다 클 수 있습니다. (하한)
+//  - 압축은 고려하지 않습니다.
+// ==============================================================================
+
+use crate::{DuplicateGroup, ScanStats, format_size, number_format};
+
+/// ZFS 중복 제거 표(DDT) 항목 하나가 메모리에서 차지하는 크기. 흔히 쓰는 어림값입니다.
+const DDT_ENTRY_BYTES: u64 = 320;
+
+/// 파일 시스템 하나의 중복 제거 방식.
+struct Model {
+    name: &'static str,
+    /// 이 크기 이하의 파일은 섹터 단위로, 더 큰 파일은 `record` 단위로 공간을 할당합니다.
+    record: u64,
+    /// 가장 작은 할당 단위.
+    sector: u64,
+    /// 이보다 작은 파일은 중복 제거하지 않습니다.
+    min_file: u64,
+    /// `min_file` 규칙의 이유 (보고서에 표시).
+    excluded: &'static str,
+    /// 중복 제거 표의 메모리 비용을 추정할지 여부 (ZFS).
+    ddt: bool,
+}
+
+const MODELS: &[Model] = &[
+    Model {
+        name: "ZFS dedup (recordsize 128 KB, ashift 12)",
+        record: 128 * 1024,
+        sector: 4096,
+        min_file: 0,
+        excluded: "",
+        ddt: true,
+    },
+    Model {
+        name: "btrfs + bees (4 KB 블록)",
+        record: 4096,
+        sector: 4096,
+        // btrfs는 2 KB 이하의 작은 파일을 메타데이터 안에 인라인으로 저장하며, bees는 이를 합치지 않습니다.
+        min_file: 2049,
+        excluded: "메타데이터에 인라인 저장되는 2 KB 이하 파일",
+        ddt: false,
+    },
+    Model {
+        name: "Windows 데이터 중복 제거 (NTFS/ReFS)",
+        record: 4096,
+        sector: 4096,
+        // 기본 설정에서 32 KB보다 작은 파일은 최적화하지 않습니다.
+        min_file: 32 * 1024,
+        excluded: "기본 설정에서 최적화하지 않는 32 KB 미만 파일",
+        ddt: false,
+    },
+];
+
+impl Model {
+    /// 크기가 `size`인 사본 하나를 중복 제거했을 때 돌아오는 공간. 대상이 아니면 `None`입니다.
+    fn saved(&self, size: u64) -> Option<u64> {
+        if size < self.min_file || size == 0 {
+            return None;
+        }
+        let unit = if size <= self.record { self.sector } else { self.record };
+        Some(size.div_ceil(unit) * unit)
+    }
+}
+
+/// 파일 시스템마다 중복 제거로 절약될 공간을 추정하여 출력합니다.
+pub fn report(groups: &[DuplicateGroup], stats: &ScanStats) {
+    // 심볼릭 링크 그룹은 파일 내용을 저장하지 않으므로 셀 것이 없습니다.
+    let groups: Vec<&DuplicateGroup> = groups.iter().filter(|g| g.symlink_target.is_none()).collect();
+    let logical: u64 = groups.iter().map(|g| g.wasted_bytes()).sum();
+    let copies: u64 = groups.iter().map(|g| g.paths.len() as u64 - 1).sum();
+    let total = stats.bytes_scanned;
+
+    println!(
+        "\n🗄️  중복 제거 파일 시스템의 예상 절약량 (검색한 데이터 {}, 파일 {}개, 파일 단위 중복 {})",
+        format_size(total),
+        stats.files_scanned,
+        format_size(logical)
+    );
+    for model in MODELS {
+        // 절약량은 실제 할당 단위로, 중복 제거율은 전체 크기와 같은 논리 크기로 계산합니다.
+        let (mut saved, mut saved_logical, mut eligible, mut skipped) = (0u64, 0u64, 0u64, 0u64);
+        for group in &groups {
+            let extra = group.paths.len() as u64 - 1;
+            match model.saved(group.size) {
+                Some(bytes) => {
+                    saved += bytes * extra;
+                    saved_logical += group.size * extra;
+                    eligible += extra;
+                }
+                None => skipped += extra,
+            }
+        }
+        println!("\n{}", model.name);
+        println!("  절약:         {} (중복 제거율 {})", format_size(saved), ratio(total, saved_logical));
+        if skipped > 0 {
+            println!("  대상 사본:    {}개 ({} {}개 제외)", eligible, model.excluded, skipped);
+        } else {
+            println!("  대상 사본:    {}개", eligible);
+        }
+        if model.ddt {
+            // 중복 제거 후 남는 블록마다 항목이 하나씩 생깁니다. 파일마다 마지막 블록이 하나씩
+            // 더 있다고 보고 넉넉하게 셉니다.
+            let unique_files = stats.files_scanned.saturating_sub(copies);
+            let entries = total.saturating_sub(saved_logical) / model.record + unique_files;
+            println!(
+                "  DDT 메모리:   약 {} (고유 블록 약 {}개 × {} B)",
+                format_size(entries.saturating_mul(DDT_ENTRY_BYTES)),
+                entries,
+                DDT_ENTRY_BYTES
+            );
+        }
+    }
+
+    println!("\n- 파일 전체가 같은 중복만 반영한 하한입니다. 블록 단위 중복 제거는 일부만 같은 파일(가상 머신");
+    println!("  이미지, 백업 등)에서도 공간을 줄이므로 실제 절약량은 더 클 수 있습니다. 압축은 고려하지 않았습니다.");
+    println!("- 중복 제거율이 낮다면 파일 시스템 전체에 중복 제거를 켜는 대신, 찾은 중복만 정리하는 편이");
+    println!("  비용이 적습니다. (--simulate로 정리 방법별 회수 공간을 확인할 수 있습니다)");
+}
+
+/// 중복 제거율 `전체 ÷ (전체 - 절약)`을 "1.25배" 형태로 나타냅니다.
+fn ratio(total: u64, saved: u64) -> String {
+    let remaining = total.saturating_sub(saved);
+    if total == 0 || remaining == 0 {
+        return "-".to_string();
+    }
+    format!("{}배", number_format::format().fixed(total as f64 / remaining as f64, 2))
+}
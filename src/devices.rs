@@ -0,0 +1,128 @@
+// ==============================================================================
+//  장치별 동시 읽기 수 (--device-threads)
+// ------------------------------------------------------------------------------
+//  --threads는 모든 파일에 하나의 스레드 수를 씁니다. 루트가 SSD와 하드 디스크에 걸쳐 있으면
+//  스레드 8개가 하드 디스크 한 대를 동시에 읽어 헤드가 이리저리 움직이는(thrashing) 동안
+//  SSD는 놀게 됩니다.
+//
+//  --device-threads를 주면 파일을 그 파일이 있는 장치(st_dev)별로 나누고, 장치마다 따로 정한 수의
+//  스레드로 읽습니다. (reader_pool::run_partitioned) 전체 스레드 수는 장치별 수의 합입니다.
+//    --device-threads 4                       모든 장치를 4개씩
+//    --device-threads 4 --device-threads /mnt/hdd=1   /mnt/hdd가 있는 장치만 1개
+//  `경로=N`은 그 경로가 있는 장치에 적용됩니다. 경로 없이 준 수가 없다면 나머지 장치는 --threads를 씁니다.
+//  장치를 알 수 없는 플랫폼에서는 모든 파일이 한 장치에 있는 것으로 봅니다.
+//
+//  --threads도 경로 없는 --device-threads도 주지 않았다면 장치마다 저장 매체 종류를 보고 정합니다.
+//  Linux에서는 /sys/dev/block/<주:부>/queue/rotational로 하드 디스크인지 알아내어, 하드 디스크는
+//  스레드 1개(헤드가 한 곳씩 차례로 읽도록), SSD와 NVMe는 기본 스레드 수(CPU 코어 수)로 읽습니다.
+//  종류를 알 수 없는 장치(네트워크 파일 시스템, btrfs처럼 장치 번호가 가상인 경우, 다른 플랫폼)는
+//  기본 스레드 수를 씁니다. 가상 머신의 가상 디스크는 실제 매체와 관계없이 하드 디스크로 보고하기도 하므로,
+//  그럴 때는 --threads나 --device-threads로 정합니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+/// --device-threads 값 하나: `N` 또는 `경로=N`.
+#[derive(Clone, Debug)]
+pub struct Spec {
+    path: Option<PathBuf>,
+    threads: NonZeroUsize,
+}
+
+/// --device-threads의 값을 읽습니다.
+pub fn parse_spec(text: &str) -> Result<Spec, String> {
+    let (path, count) = match text.rsplit_once('=') {
+        Some((path, count)) if !path.is_empty() => (Some(PathBuf::from(path)), count),
+        Some(_) => return Err(String::from("'=' 앞에 경로가 없습니다")),
+        None => (None, text),
+    };
+    let threads = count.trim().parse().map_err(|_| format!("'{}'은(는) 1 이상의 스레드 수가 아닙니다", count))?;
+    Ok(Spec { path, threads })
+}
+
+/// 하드 디스크에서 동시에 읽을 스레드 수.
+const ROTATIONAL_THREADS: usize = 1;
+
+/// 장치마다 동시에 읽을 스레드 수.
+#[derive(Clone, Debug)]
+pub struct DeviceLimits {
+    /// `경로=N`으로 정한 장치가 아닌 장치의 스레드 수.
+    default: usize,
+    /// 기본값을 정해 주지 않았으므로, 하드 디스크는 기본값 대신 `ROTATIONAL_THREADS`를 씁니다.
+    auto: bool,
+    by_device: HashMap<u64, usize>,
+}
+
+impl DeviceLimits {
+    /// 모든 장치를 저장 매체 종류로 정합니다. 종류를 알 수 없거나 SSD라면 `fallback`을 씁니다.
+    pub fn auto(fallback: usize) -> Self {
+        DeviceLimits { default: fallback, auto: true, by_device: HashMap::new() }
+    }
+
+    /// 값들을 장치 번호로 바꿉니다. 경로 없이 준 수가 없다면 `fallback`(--threads)을 기본으로 쓰고,
+    /// `auto`라면(--threads를 주지 않았다면) 하드 디스크는 저장 매체 종류로 정합니다.
+    pub fn resolve(specs: &[Spec], fallback: usize, auto: bool) -> io::Result<Self> {
+        let mut limits = DeviceLimits { default: fallback, auto, by_device: HashMap::new() };
+        for spec in specs {
+            match &spec.path {
+                None => {
+                    limits.default = spec.threads.get();
+                    limits.auto = false;
+                }
+                Some(path) => {
+                    let device = device_of(path).ok_or_else(|| {
+                        let message = format!("--device-threads의 경로 '{}'을(를) 읽을 수 없습니다", path.display());
+                        io::Error::new(io::ErrorKind::NotFound, message)
+                    })?;
+                    limits.by_device.insert(device, spec.threads.get());
+                }
+            }
+        }
+        Ok(limits)
+    }
+
+    /// 장치의 스레드 수. 장치를 알 수 없는 파일(`None`)은 기본값을 씁니다.
+    pub fn limit(&self, device: &Option<u64>) -> usize {
+        let Some(device) = *device else { return self.default };
+        match self.by_device.get(&device) {
+            Some(&threads) => threads,
+            None if self.auto && rotational(device) == Some(true) => ROTATIONAL_THREADS,
+            None => self.default,
+        }
+    }
+}
+
+/// 장치가 하드 디스크인지. 알 수 없으면 `None`입니다.
+#[cfg(target_os = "linux")]
+pub fn rotational(device: u64) -> Option<bool> {
+    let dir = PathBuf::from(format!("/sys/dev/block/{}:{}", libc::major(device), libc::minor(device)));
+    // 파티션에는 queue가 없으므로 그 파티션이 있는 디스크의 값을 읽습니다.
+    let text = std::fs::read_to_string(dir.join("queue/rotational"))
+        .or_else(|_| std::fs::read_to_string(dir.join("../queue/rotational")))
+        .ok()?;
+    match text.trim() {
+        "1" => Some(true),
+        "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn rotational(_device: u64) -> Option<bool> {
+    None
+}
+
+/// 경로가 있는 장치 번호. 읽을 수 없으면 `None`입니다.
+#[cfg(unix)]
+pub fn device_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::symlink_metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(not(unix))]
+pub fn device_of(path: &Path) -> Option<u64> {
+    path.symlink_metadata().ok().map(|_| 0)
+}
@@ -0,0 +1,365 @@
+// ==============================================================================
+//  여러 서버에 걸친 분산 검색 (`worker` / `coordinator` 하위 명령)
+// ------------------------------------------------------------------------------
+//  파일 서버 여러 대에 흩어진 중복 데이터를 찾으려면, 각 서버가 자신의 로컬 저장소를
+//  검색하고 그 결과를 한 곳에 모아 비교해야 합니다.
+//
+//  - worker: 로컬 루트 폴더의 모든 파일을 해시하여 (크기, 해시, 경로) 기록을 코디네이터로
+//    보냅니다. 다른 서버의 파일과 같은지는 로컬에서 알 수 없으므로, 크기가 겹치지 않는
+//    파일도 모두 해시합니다. (--xattr-cache를 쓰면 다음 실행부터는 바뀐 파일만 읽습니다.)
+//  - coordinator: 모든 worker의 기록을 모아 서버 간 중복 그룹을 계산하고 HTTP로 제공합니다.
+//    `serve`와 같은 접근 조건(`server::Access`)을 따르므로, 루프백이 아닌 주소에서 열려면 토큰이
+//    필요하고 worker도 같은 토큰을 `Authorization: Bearer`로 보내야 합니다.
+//
+//  프로토콜 (본문은 한 줄에 기록 하나인 NDJSON):
+//  POST /hosts/{host}/records?reset=1   해당 호스트의 이전 기록을 지우고 새 검색을 시작
+//  POST /hosts/{host}/records           기록 추가. 각 줄: {"size": 1, "hash": "...", "path": "..."}
+//  POST /hosts/{host}/complete          해당 호스트의 검색 완료
+//  GET  /hosts                          호스트별 기록 수와 완료 여부
+//  GET  /groups[?cross_host=1]          중복 그룹 (cross_host=1이면 여러 호스트에 걸친 그룹만).
+//                                       그룹마다 본 검색과 같은 방법으로 만든 식별자 "id"가 있습니다.
+// ==============================================================================
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tiny_http::{Method, Request, Server};
+
+use crate::stages::Pipeline;
+use crate::server::{Access, Reply, check_content_type, lock, respond};
+use crate::{
+    FdBudget, FilterMode, HashAlgorithm, MatchMode, Progress, ScanError, ScanLog, ScanOptions, collect_files, default_threads,
+    hash_unchanged, interrupt, prepare_roots, print_error_summary, reader_pool, record_error, group_id, HashFailure, KIND_IN_USE,
+};
+
+/// 한 번의 요청으로 보내는 기록 수.
+const BATCH_SIZE: usize = 1000;
+
+/// 중단 요청을 확인하기 위해 요청 대기를 잠시 멈추는 간격.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// worker가 보내는 파일 기록 하나.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    size: u64,
+    hash: String,
+    path: String,
+}
+
+// --- worker ---
+
+/// 로컬 루트를 검색하여 모든 파일의 기록을 코디네이터로 보냅니다.
+pub fn run_worker(
+    coordinator: &str,
+    host: Option<&str>,
+    roots: &[PathBuf],
+    filter: Option<String>,
+    xattr_cache: bool,
+    algorithm: HashAlgorithm,
+    token: Option<&str>,
+) -> io::Result<ExitCode> {
+    let host = match host {
+        Some(host) => host.to_string(),
+        None => local_hostname().ok_or_else(|| io::Error::other("호스트 이름을 알 수 없습니다. --host로 지정하세요"))?,
+    };
+    if !valid_host(&host) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("호스트 이름 '{}'에는 영문자, 숫자, '.', '_', '-'만 쓸 수 있습니다", host),
+        ));
+    }
+    let roots = prepare_roots(roots)?;
+    let options = ScanOptions {
+        filter_mode: FilterMode::from_arg(filter),
+        created_after: None,
+        created_before: None,
+        include_symlinks: false,
+        strict: false,
+        normalize_names: false,
+        threads: default_threads(),
+        isolate_hardlinks: false,
+        hydrate: false,
+        device_limits: None,
+        fd_budget: reader_pool::default_fd_budget(),
+        xattr_cache,
+        compare_attrs: false,
+        require_same_mtime: false,
+        imported_hashes: None,
+        ignored_hashes: None,
+        match_mode: MatchMode::Content,
+        stages: Pipeline::default(),
+        algorithm,
+        extra_hash: None,
+        bloom_prepass: None,
+        spotlight: false,
+        spill: None,
+        max_memory: None,
+        checkpoint: None,
+    };
+    let base = format!("{}/hosts/{}", coordinator.trim_end_matches('/'), host);
+    interrupt::install_handler();
+    println!("🛰️  '{}' 호스트로서 {}에 검색 결과를 보냅니다.", host, coordinator);
+
+    // 이전 실행의 기록이 남아 있지 않도록 먼저 이 호스트의 기록을 비웁니다.
+    post(&format!("{}/records?reset=1", base), String::new(), token)?;
+
+    let mut errors: Vec<ScanError> = Vec::new();
+    let mut log = ScanLog::disabled();
+    let mut progress = Progress::new();
+    let jobs = collect_files(&roots, &options, &mut errors, &mut log, &mut progress);
+
+    let total = jobs.len() as u64;
+    let budget = FdBudget::new(options.fd_budget);
+    let mut batch = String::new();
+    let (mut processed, mut sent, mut pending) = (0u64, 0usize, 0usize);
+    let mut send_error = None;
+    reader_pool::run(
+        jobs,
+        options.threads,
+        |(path, stamp)| {
+            let result = hash_unchanged(&path, &stamp, &budget, &options);
+            (path, stamp, result)
+        },
+        |(path, stamp, result)| {
+            processed += 1;
+            progress.update("해시 계산 중", processed, Some(total));
+            match result {
+                Ok((digests, _)) => {
+                    let record = Record { size: stamp.len, hash: digests.hash, path: path.display().to_string() };
+                    batch.push_str(&serde_json::to_string(&record).unwrap_or_default());
+                    batch.push('\n');
+                    sent += 1;
+                    pending += 1;
+                }
+                Err(HashFailure::Io(e)) if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() => {
+                    return false;
+                }
+                Err(HashFailure::Changed(kind, message)) => {
+                    record_error(&mut errors, &mut log, Some(path), "변경 감지", kind, message);
+                }
+                Err(HashFailure::InUse(reason)) => {
+                    record_error(&mut errors, &mut log, Some(path), "사용 중 확인", KIND_IN_USE.to_string(), reason.to_string());
+                }
+                Err(HashFailure::Io(e)) => {
+                    record_error(&mut errors, &mut log, Some(path), "해시 계산", e.kind().to_string(), e.to_string());
+                }
+            }
+            if pending >= BATCH_SIZE {
+                pending = 0;
+                if let Err(e) = post(&format!("{}/records", base), std::mem::take(&mut batch), token) {
+                    send_error = Some(e);
+                    return false;
+                }
+            }
+            true
+        },
+    );
+    progress.finish();
+    if let Some(e) = send_error {
+        return Err(e);
+    }
+    if !batch.is_empty() {
+        post(&format!("{}/records", base), batch, token)?;
+    }
+    if interrupt::requested() {
+        println!("⏹️  검색이 중단되었습니다. {}개 기록을 보냈지만 완료로 표시하지 않습니다.", sent);
+        return Ok(ExitCode::from(crate::EXIT_INTERRUPTED));
+    }
+    post(&format!("{}/complete", base), String::new(), token)?;
+    println!("✅ {}개 파일의 기록을 보냈습니다.", sent);
+    print_error_summary(&errors, false);
+    Ok(if errors.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(crate::EXIT_FILE_ERRORS) })
+}
+
+fn post(url: &str, body: String, token: Option<&str>) -> io::Result<()> {
+    let mut request = ureq::post(url).header("Content-Type", "application/x-ndjson");
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    request
+        .send(body)
+        .map(|_| ())
+        .map_err(|e| io::Error::other(format!("코디네이터 요청 실패 ({}): {}", url, e)))
+}
+
+/// 호스트 이름은 URL 경로에 그대로 들어가므로 안전한 문자만 허용합니다.
+fn valid_host(host: &str) -> bool {
+    !host.is_empty() && host.bytes().all(|b| b.is_ascii_alphanumeric() || b"._-".contains(&b))
+}
+
+#[cfg(unix)]
+fn local_hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    // SAFETY: 버퍼의 길이를 정확히 넘기며, gethostname은 그 범위 안에서만 씁니다.
+    if unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) } != 0 {
+        return None;
+    }
+    let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8(buffer[..end].to_vec()).ok()
+}
+
+#[cfg(not(unix))]
+fn local_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+// --- coordinator ---
+
+/// 호스트 하나에서 받은 기록.
+#[derive(Default)]
+struct HostRecords {
+    /// (크기, 해시) -> 그 내용을 가진 경로들.
+    files: HashMap<(u64, String), Vec<String>>,
+    records: usize,
+    complete: bool,
+    updated: Option<SystemTime>,
+}
+
+/// 모든 worker의 기록을 모아 서버 간 중복 그룹을 제공합니다.
+pub fn run_coordinator(listen: &str, access: Access) -> io::Result<ExitCode> {
+    let server = Server::http(listen).map_err(io::Error::other)?;
+    let hosts: Arc<Mutex<BTreeMap<String, HostRecords>>> = Arc::default();
+    let access = Arc::new(access);
+    interrupt::install_handler();
+    println!("🗂️  코디네이터가 http://{} 에서 worker의 기록을 기다립니다. (Ctrl+C로 종료)", listen);
+
+    while !interrupt::requested() {
+        let Some(request) = server.recv_timeout(POLL_INTERVAL)? else { continue };
+        let hosts = Arc::clone(&hosts);
+        let access = Arc::clone(&access);
+        thread::spawn(move || handle(request, &hosts, &access));
+    }
+    let hosts = lock(&hosts);
+    let groups = cross_host_groups(&hosts, true);
+    println!(
+        "\n코디네이터를 종료합니다. 호스트 {}개, 여러 호스트에 걸친 중복 그룹 {}개.",
+        hosts.len(),
+        groups.len()
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn handle(mut request: Request, hosts: &Mutex<BTreeMap<String, HostRecords>>, access: &Access) {
+    if let Err(reply) = access.check(&request) {
+        respond(request, reply);
+        return;
+    }
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let method = request.method().clone();
+
+    let reply: Reply = match (&method, segments.as_slice()) {
+        (Method::Post, ["hosts", host, "records"]) if valid_host(host) => {
+            let reset = query.split('&').any(|p| p == "reset=1");
+            match check_content_type(&request, "application/x-ndjson").map(|()| receive_records(&mut request)) {
+                Err(reply) => reply,
+                Ok(Ok(records)) => {
+                    let mut hosts = lock(hosts);
+                    let entry = hosts.entry(host.to_string()).or_default();
+                    if reset {
+                        *entry = HostRecords::default();
+                    }
+                    let received = records.len();
+                    for record in records {
+                        entry.files.entry((record.size, record.hash)).or_default().push(record.path);
+                    }
+                    entry.records += received;
+                    entry.updated = Some(SystemTime::now());
+                    (200, json!({ "received": received }))
+                }
+                Ok(Err(e)) => (400, json!({ "error": e.to_string() })),
+            }
+        }
+        (Method::Post, ["hosts", host, "complete"]) => {
+            let mut hosts = lock(hosts);
+            match hosts.get_mut(*host) {
+                Some(entry) => {
+                    entry.complete = true;
+                    entry.updated = Some(SystemTime::now());
+                    println!("✅ '{}' 호스트의 검색이 완료되었습니다. (기록 {}개)", host, entry.records);
+                    (200, json!({ "host": host }))
+                }
+                None => (404, json!({ "error": "알 수 없는 호스트입니다" })),
+            }
+        }
+        (Method::Get, ["hosts"]) => {
+            let hosts = lock(hosts);
+            let list: Vec<_> = hosts
+                .iter()
+                .map(|(name, h)| {
+                    json!({
+                        "host": name,
+                        "records": h.records,
+                        "complete": h.complete,
+                        "updated": h.updated.map(|t| humantime::format_rfc3339_seconds(t).to_string()),
+                    })
+                })
+                .collect();
+            (200, json!(list))
+        }
+        (Method::Get, ["groups"]) => {
+            let cross_host_only = query.split('&').any(|p| p == "cross_host=1");
+            (200, json!(cross_host_groups(&lock(hosts), cross_host_only)))
+        }
+        _ => (404, json!({ "error": "알 수 없는 경로입니다" })),
+    };
+    respond(request, reply);
+}
+
+/// NDJSON 본문을 기록 목록으로 읽습니다.
+fn receive_records(request: &mut Request) -> io::Result<Vec<Record>> {
+    let mut records = Vec::new();
+    for line in io::BufReader::new(request.as_reader()).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push(serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+    }
+    Ok(records)
+}
+
+/// 모든 호스트의 기록을 합쳐 멤버가 2개 이상인 그룹을 회수 가능 공간이 큰 순서로 만듭니다.
+fn cross_host_groups(hosts: &BTreeMap<String, HostRecords>, cross_host_only: bool) -> Vec<serde_json::Value> {
+    let mut merged: HashMap<&(u64, String), Vec<(&str, &str)>> = HashMap::new();
+    for (host, records) in hosts {
+        for (key, paths) in &records.files {
+            merged.entry(key).or_default().extend(paths.iter().map(|p| (host.as_str(), p.as_str())));
+        }
+    }
+    let mut groups: Vec<_> = merged
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .filter_map(|((size, hash), mut members)| {
+            let host_count = members.iter().map(|(h, _)| *h).collect::<HashSet<_>>().len();
+            if cross_host_only && host_count < 2 {
+                return None;
+            }
+            members.sort();
+            let wasted = size * (members.len() as u64 - 1);
+            Some((wasted, *size, hash.clone(), host_count, members))
+        })
+        .collect();
+    groups.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+    groups
+        .into_iter()
+        .map(|(wasted, size, hash, host_count, members)| {
+            json!({
+                "id": group_id(size, &hash),
+                "size": size,
+                "hash": hash,
+                "wasted_bytes": wasted,
+                "hosts": host_count,
+                "members": members.iter().map(|(h, p)| json!({ "host": h, "path": p })).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
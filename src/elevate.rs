@@ -0,0 +1,142 @@
+// ==============================================================================
+//  권한이 없어 지우지 못한 파일을 관리자 권한으로 다시 지우기 (--sudo-helper)
+// ------------------------------------------------------------------------------
+//  다른 사용자나 root가 만든 폴더의 사본은 TUI에서 삭제를 실행해도 "권한 거부"로 남습니다. 상태 줄의
+//  "N개 파일은 삭제하지 못했습니다"만 보고 넘어가면 정리가 반쯤 끝난 채로 남습니다.
+//
+//  TUI에서 권한이 없어 지우지 못한 파일을 모아 두었다가, TUI를 닫은 뒤 목록을 보여주고 그 파일들만
+//  관리자 권한으로 다시 지웁니다.
+//  - --sudo-helper PROGRAM(`sudo`, `doas`, `pkexec`, Windows의 `gsudo` 등)을 지정하면 묻지 않고
+//    `PROGRAM <이 프로그램> elevated-delete`를 실행합니다. 지정하지 않았다면 유닉스의 터미널에서
+//    `sudo`로 다시 시도할지 묻습니다.
+//  - 관리자 권한으로 실행되는 쪽은 표준 입력으로 받은 목록의 파일만 지웁니다. 지우기 전에 내용이 여전히
+//    그룹의 해시와 같은지, 그리고 남기기로 한 사본 중 하나가 같은 내용으로 남아 있는지 다시 확인합니다.
+//  관리자 권한으로 지운 파일이 있던 폴더는 --prune-empty-dirs로 정리하지 않습니다.
+// ==============================================================================
+
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::hashing::HashAlgorithm;
+use crate::{EXIT_FILE_ERRORS, calculate_hash, confirm, format_size, in_use};
+
+/// 권한이 없어 지우지 못한 그룹 멤버 하나.
+#[derive(Serialize, Deserialize)]
+pub struct Denied {
+    pub path: PathBuf,
+    pub size: u64,
+    /// 그룹의 내용 해시. 심볼릭 링크 그룹이라면 `None`입니다.
+    pub hash: Option<String>,
+    pub algorithm: String,
+    /// 같은 그룹에서 남기기로 한 멤버들. 하나라도 같은 내용으로 남아 있어야 지웁니다.
+    pub survivors: Vec<PathBuf>,
+}
+
+/// 지우지 못한 파일들을 보여주고, 관리자 권한으로 다시 지울지 정합니다.
+pub fn offer(denied: &[Denied], helper: Option<&str>) {
+    if denied.is_empty() {
+        return;
+    }
+    let bytes: u64 = denied.iter().map(|d| d.size).sum();
+    println!("🔒 권한이 없어 지우지 못한 파일 {}개 ({}):", denied.len(), format_size(bytes));
+    for entry in denied {
+        println!("  - {}", entry.path.display());
+    }
+    let helper = match helper {
+        Some(helper) => helper,
+        // 지정하지 않았다면 사용자에게 물을 수 있을 때만 sudo를 제안합니다.
+        None if cfg!(unix) && io::stdin().is_terminal() => {
+            if !confirm("sudo로 이 파일들만 다시 지울까요?") {
+                return;
+            }
+            "sudo"
+        }
+        None => {
+            println!("   --sudo-helper로 권한을 올릴 프로그램을 지정하거나, 관리자 권한으로 다시 실행하여 정리하세요.");
+            return;
+        }
+    };
+    if let Err(e) = run(denied, helper) {
+        eprintln!("⚠️  관리자 권한으로 다시 지우지 못했습니다: {}", e);
+    }
+}
+
+/// `helper <이 프로그램> elevated-delete`를 실행하고 목록을 표준 입력으로 넘깁니다.
+fn run(denied: &[Denied], helper: &str) -> io::Result<()> {
+    let mut words = helper.split_whitespace();
+    let program = words.next().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "--sudo-helper가 비어 있습니다"))?;
+    let exe = std::env::current_exe()?;
+    // 권한을 올린 프로세스의 작업 폴더가 다를 수 있으므로 절대 경로로 넘깁니다.
+    let denied: Vec<Denied> = denied
+        .iter()
+        .map(|d| Denied {
+            path: std::path::absolute(&d.path).unwrap_or_else(|_| d.path.clone()),
+            size: d.size,
+            hash: d.hash.clone(),
+            algorithm: d.algorithm.clone(),
+            survivors: d.survivors.iter().map(|p| std::path::absolute(p).unwrap_or_else(|_| p.clone())).collect(),
+        })
+        .collect();
+    let mut child = Command::new(program)
+        .args(words)
+        .arg(exe)
+        .arg("elevated-delete")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("'{}'을(를) 실행할 수 없습니다: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(serde_json::to_string(&denied)?.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("'{}'이(가) 실패했습니다: {}", program, status)));
+    }
+    Ok(())
+}
+
+/// `elevated-delete`: 관리자 권한으로 실행되어, 표준 입력의 목록에서 다시 확인한 파일만 지웁니다.
+pub fn delete_from_stdin() -> io::Result<ExitCode> {
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text)?;
+    let denied: Vec<Denied> = serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut deleted = 0;
+    let mut skipped = 0;
+    for entry in &denied {
+        match delete_checked(entry) {
+            Ok(()) => deleted += 1,
+            Err(e) => {
+                skipped += 1;
+                eprintln!("⚠️  '{}'을(를) 지우지 않았습니다: {}", entry.path.display(), e);
+            }
+        }
+    }
+    println!("🗑️  관리자 권한으로 {}개 파일을 지웠습니다.", deleted);
+    Ok(if skipped == 0 { ExitCode::SUCCESS } else { ExitCode::from(EXIT_FILE_ERRORS) })
+}
+
+/// 파일이 여전히 그룹의 내용과 같고, 같은 내용의 사본이 남아 있을 때만 지웁니다.
+fn delete_checked(entry: &Denied) -> io::Result<()> {
+    let same = |path: &Path| -> io::Result<bool> {
+        match &entry.hash {
+            Some(hash) => {
+                let algorithm = HashAlgorithm::from_name(&entry.algorithm)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "알 수 없는 해시 알고리즘"))?;
+                Ok(calculate_hash(path, algorithm)? == *hash)
+            }
+            // 심볼릭 링크 그룹은 링크가 그대로 있는지만 확인합니다.
+            None => Ok(fs::symlink_metadata(path)?.file_type().is_symlink()),
+        }
+    };
+    if !same(&entry.path)? {
+        return Err(io::Error::other("검색 이후 내용이 바뀌었습니다"));
+    }
+    if !entry.survivors.iter().any(|survivor| same(survivor).unwrap_or(false)) {
+        return Err(io::Error::other("같은 내용으로 남은 사본이 없습니다"));
+    }
+    in_use::ensure_idle(&entry.path)?;
+    fs::remove_file(&entry.path)
+}
@@ -0,0 +1,204 @@
+// ==============================================================================
+//  백업 도구용 제외 목록 생성 (--emit-excludes)
+// ------------------------------------------------------------------------------
+//  중복을 지우기 전이라도, 백업이 같은 데이터를 여러 벌 저장하지 않도록 원본을 뺀 나머지
+//  사본을 백업 도구의 제외 파일 형식으로 내보냅니다. 어느 사본을 원본으로 볼지는 --keep
+//  규칙을 따릅니다.
+//
+//  - rsync (`--exclude-from`): 루트 폴더 기준의 고정 경로(`/하위/경로`). rsync의 원본으로
+//    해당 루트 폴더를 지정한다고 가정합니다. 와일드카드 문자는 역슬래시로 이스케이프합니다.
+//    패턴에 루트가 드러나지 않으므로 루트는 하나만 받습니다. 남기는 사본과 패턴이 같은 사본은
+//    그 사본까지 백업에서 빠지므로 쓰지 않습니다.
+//  - borg (`--exclude-from`): 절대 경로를 그대로 비교하는 `pf:` 패턴.
+//  - restic (`--exclude-file`): 절대 경로 패턴. 와일드카드 문자는 역슬래시로, restic이
+//    환경 변수로 해석하는 `$`는 `[$]`로 이스케이프합니다.
+// ==============================================================================
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::DuplicateGroup;
+use crate::keep::KeepRule;
+
+/// 제외 목록을 쓸 백업 도구의 형식.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExcludeFormat {
+    Rsync,
+    Borg,
+    Restic,
+}
+
+/// 제외 목록을 쓴 결과.
+pub struct EmitReport {
+    /// 목록에 쓴 경로 수.
+    pub written: usize,
+    /// 제외하여 백업에서 줄어드는 바이트 수.
+    pub bytes: u64,
+    /// 형식상 표현할 수 없어 건너뛴 경로 (줄바꿈이 들어 있거나 UTF-8이 아닌 이름 등).
+    pub skipped: Vec<PathBuf>,
+    /// 남기는 사본과 패턴이 같아 쓰지 않은 경로. 쓰면 남기는 사본도 백업에서 빠집니다.
+    pub shadowed: Vec<PathBuf>,
+}
+
+/// 각 그룹에서 원본을 뺀 나머지 사본을 `format` 형식으로 `output`에 씁니다.
+/// 심볼릭 링크 그룹은 백업 용량을 줄이지 않으므로 포함하지 않습니다.
+pub fn emit(
+    output: &Path,
+    format: ExcludeFormat,
+    groups: &[DuplicateGroup],
+    roots: &[PathBuf],
+    keep: &KeepRule,
+) -> io::Result<EmitReport> {
+    let mut writer = BufWriter::new(File::create(output)?);
+    let mut report = EmitReport { written: 0, bytes: 0, skipped: Vec::new(), shadowed: Vec::new() };
+    let groups: Vec<&DuplicateGroup> = groups.iter().filter(|g| g.symlink_target.is_none()).collect();
+    let kept: HashSet<String> =
+        groups.iter().filter_map(|g| pattern(format, &g.paths[keep.keeper(&g.paths)], roots)).collect();
+    for group in groups {
+        for path in keep.redundant(&group.paths) {
+            match pattern(format, path, roots) {
+                Some(line) if kept.contains(&line) => report.shadowed.push(path.clone()),
+                Some(line) => {
+                    writeln!(writer, "{}", line)?;
+                    report.written += 1;
+                    report.bytes += group.size;
+                }
+                None => report.skipped.push(path.clone()),
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(report)
+}
+
+/// 경로 하나를 해당 형식의 제외 패턴 한 줄로 바꿉니다. 표현할 수 없다면 `None`입니다.
+fn pattern(format: ExcludeFormat, path: &Path, roots: &[PathBuf]) -> Option<String> {
+    let text = path.to_str()?;
+    if text.contains(['\n', '\r']) {
+        return None;
+    }
+    match format {
+        ExcludeFormat::Rsync => {
+            // 경로를 포함하는 가장 구체적인(긴) 루트를 기준으로 삼습니다.
+            let root = roots.iter().filter(|r| path.starts_with(r)).max_by_key(|r| r.as_os_str().len())?;
+            let relative = path.strip_prefix(root).ok()?.to_str()?;
+            Some(format!("/{}", escape_glob(relative, false)))
+        }
+        ExcludeFormat::Borg => Some(format!("pf:{}", text)),
+        ExcludeFormat::Restic => Some(escape_glob(text, true)),
+    }
+}
+
+/// 글롭 특수 문자(`*`, `?`, `[`, `\`)를 역슬래시로 이스케이프합니다.
+fn escape_glob(text: &str, escape_dollar: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '*' | '?' | '[' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '$' if escape_dollar => escaped.push_str("[$]"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use super::*;
+    use crate::hashing::HashAlgorithm;
+    use crate::keep::{KeepPolicy, ScoreWeights};
+
+    fn roots(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    fn group(paths: &[&str], symlink: bool) -> DuplicateGroup {
+        DuplicateGroup {
+            size: 10,
+            hash: String::from("h"),
+            algorithm: HashAlgorithm::Sha256,
+            extra_hash: None,
+            paths: roots(paths),
+            symlink_target: symlink.then(|| PathBuf::from("/target")),
+            stamps: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn rsync_patterns_are_relative_to_the_closest_root() {
+        let roots = roots(&["/srv", "/srv/share"]);
+        assert_eq!(pattern(ExcludeFormat::Rsync, Path::new("/srv/a/b.txt"), &roots).as_deref(), Some("/a/b.txt"));
+        assert_eq!(pattern(ExcludeFormat::Rsync, Path::new("/srv/share/c.txt"), &roots).as_deref(), Some("/c.txt"));
+        // 루트 밖의 경로와, 이름이 루트로 시작할 뿐인 경로는 표현할 수 없습니다.
+        assert_eq!(pattern(ExcludeFormat::Rsync, Path::new("/other/x"), &roots), None);
+        assert_eq!(pattern(ExcludeFormat::Rsync, Path::new("/srvx/y"), &roots), None);
+    }
+
+    #[test]
+    fn escapes_wildcards_per_format() {
+        let roots = roots(&["/r"]);
+        let path = Path::new(r"/r/a*b?[c]\d$HOME");
+        assert_eq!(pattern(ExcludeFormat::Rsync, path, &roots).as_deref(), Some(r"/a\*b\?\[c]\\d$HOME"));
+        assert_eq!(pattern(ExcludeFormat::Borg, path, &roots).as_deref(), Some(r"pf:/r/a*b?[c]\d$HOME"));
+        assert_eq!(pattern(ExcludeFormat::Restic, path, &roots).as_deref(), Some(r"/r/a\*b\?\[c]\\d[$]HOME"));
+    }
+
+    #[test]
+    fn skips_paths_with_line_breaks() {
+        let roots = roots(&["/r"]);
+        for format in [ExcludeFormat::Rsync, ExcludeFormat::Borg, ExcludeFormat::Restic] {
+            assert_eq!(pattern(format, Path::new("/r/a\nb"), &roots), None);
+            assert_eq!(pattern(format, Path::new("/r/a\rb"), &roots), None);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_non_utf8_paths() {
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/r/\xff"));
+        assert_eq!(pattern(ExcludeFormat::Borg, path, &roots(&["/r"])), None);
+    }
+
+    #[test]
+    fn emit_writes_redundant_copies_and_reports_the_rest() {
+        let output = std::env::temp_dir().join(format!("duplicate_finder-excludes-test-{}", std::process::id()));
+        let groups = [
+            group(&["/r/keep.txt", "/r/copy.txt", "/r/bad\nname"], false),
+            group(&["/r/link1", "/r/link2"], true),
+        ];
+        let keep = KeepRule::new(KeepPolicy::First, &[], ScoreWeights::default());
+        let report = emit(&output, ExcludeFormat::Rsync, &groups, &roots(&["/r"]), &keep);
+        let written = fs::read_to_string(&output);
+        let _ = fs::remove_file(&output);
+        let report = report.unwrap();
+        assert_eq!(written.unwrap(), "/copy.txt\n");
+        assert_eq!((report.written, report.bytes), (1, 10));
+        assert_eq!(report.skipped, roots(&["/r/bad\nname"]));
+        assert!(report.shadowed.is_empty());
+    }
+
+    #[test]
+    fn emit_skips_copies_that_share_a_kept_pattern() {
+        // 두 루트의 같은 상대 경로는 rsync 패턴이 같으므로, 남기는 사본까지 빠지지 않도록 쓰지 않습니다.
+        let output = std::env::temp_dir().join(format!("duplicate_finder-excludes-shadow-test-{}", std::process::id()));
+        let groups = [group(&["/a/x.txt", "/b/x.txt", "/b/y.txt"], false)];
+        let keep = KeepRule::new(KeepPolicy::First, &[], ScoreWeights::default());
+        let report = emit(&output, ExcludeFormat::Rsync, &groups, &roots(&["/a", "/b"]), &keep);
+        let written = fs::read_to_string(&output);
+        let _ = fs::remove_file(&output);
+        let report = report.unwrap();
+        assert_eq!(written.unwrap(), "/y.txt\n");
+        assert_eq!(report.shadowed, roots(&["/b/x.txt"]));
+    }
+}
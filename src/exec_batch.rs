@@ -0,0 +1,62 @@
+// ==============================================================================
+//  결과 전체를 외부 명령 하나에 넘기기 (--exec-batch)
+// ------------------------------------------------------------------------------
+//  자산 관리 시스템에 등록하거나 티켓을 만드는 연동은 그룹마다 프로세스를 띄우면 수천 번 실행되고,
+//  그때마다 인증과 연결을 새로 맺습니다. 이런 도구는 결과 전체를 한 번에 받는 편이 훨씬 빠릅니다.
+//
+//  --exec-batch COMMAND [ARG...] ';'는 검색이 끝난 뒤 명령을 한 번만 실행하고, --save-results와 같은
+//  형식의 JSON(`--schema`로 확인)을 표준 입력으로 넘깁니다.
+//  - 셸을 거치지 않고 바로 실행하므로 인자 안의 공백이나 따옴표를 다시 해석하지 않습니다.
+//  - 인자 안의 `{groups}`, `{files}`, `{wasted}`(회수 가능 바이트), `{roots}`(루트 수),
+//    `{interrupted}`(`true`/`false`)는 실행하기 전에 값으로 바꿉니다. (예: `--title "중복 {groups}개"`)
+//  - 명령의 표준 출력과 표준 에러는 그대로 터미널에 나옵니다. 명령이 실패(0이 아닌 종료 코드)하면
+//    검색도 실패로 끝납니다.
+// ==============================================================================
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::results::SavedResults;
+
+/// 인자 템플릿에 채울 값들.
+pub struct Values {
+    pub groups: usize,
+    pub files: usize,
+    pub wasted: u64,
+    pub roots: usize,
+    pub interrupted: bool,
+}
+
+/// `command`(프로그램과 인자)를 한 번 실행하고 `results`를 JSON으로 표준 입력에 씁니다.
+pub fn run(command: &[String], results: &SavedResults, values: &Values) -> io::Result<()> {
+    let (program, args) = command.split_first().ok_or_else(|| io::Error::other("실행할 명령이 비어 있습니다"))?;
+    let mut text = serde_json::to_string(results)?;
+    text.push('\n');
+    let mut child = Command::new(program)
+        .args(args.iter().map(|arg| expand(arg, values)))
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("'{}'을(를) 실행할 수 없습니다: {}", program, e)))?;
+    // 입력을 다 읽지 않고 끝나는 명령도 있으므로, 파이프가 닫힌 것은 실패로 보지 않고 종료 코드로 판단합니다.
+    if let Some(mut stdin) = child.stdin.take()
+        && let Err(e) = stdin.write_all(text.as_bytes())
+        && e.kind() != io::ErrorKind::BrokenPipe
+    {
+        let _ = child.wait();
+        return Err(e);
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("'{}'이(가) 실패했습니다: {}", program, status)));
+    }
+    Ok(())
+}
+
+/// 인자 안의 `{이름}`을 값으로 바꿉니다. 모르는 이름은 그대로 둡니다.
+fn expand(arg: &str, values: &Values) -> String {
+    arg.replace("{groups}", &values.groups.to_string())
+        .replace("{files}", &values.files.to_string())
+        .replace("{wasted}", &values.wasted.to_string())
+        .replace("{roots}", &values.roots.to_string())
+        .replace("{interrupted}", &values.interrupted.to_string())
+}
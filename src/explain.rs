@@ -0,0 +1,177 @@
+// ==============================================================================
+//  결과마다 중복으로 판단한 근거 보여주기 (--explain)
+// ------------------------------------------------------------------------------
+//  같은 "중복 그룹"이라도 믿을 수 있는 정도는 어느 단계에서 같다고 판단했는지에 따라 다릅니다.
+//  바이트 비교로 확인한 그룹은 확실하지만, 앞뒤 샘플만 같은 그룹이나 이름과 크기만 같은 그룹은
+//  추정입니다. --explain은 그룹마다 거쳐 온 단계(크기 → 샘플 → 전체 해시 → 바이트 비교)와
+//  마지막으로 같음을 확정한 단계를 함께 출력합니다.
+//
+//  내용이 달라도 묶는 모드(--match exif/office/pdf/audio)의 그룹은 멤버마다 첫 번째 파일과의
+//  유사도를 함께 보여줍니다. 유사도는 파일을 내용에 따라 정해지는 경계(content-defined chunking)로
+//  평균 4 KB 조각들로 나눈 뒤, 두 파일에 함께 있는 조각의 바이트 수를 큰 파일의 크기로 나눈 값입니다.
+//  앞쪽에 데이터가 끼어들어 위치가 밀려도 뒤의 조각은 그대로 같게 나오므로, 증분 저장한 PDF처럼
+//  일부만 바뀐 파일은 높게, 다시 인코딩한 사진처럼 바이트가 통째로 바뀐 파일은 0%에 가깝게 나옵니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::stages::{self, Pipeline};
+use crate::{DuplicateGroup, NAME_SIZE_MARK, ScanOptions, audio, office, pdf, photos};
+
+/// 조각 경계를 정할 때 보는 해시의 상위 비트 수. 평균 조각 크기는 2^12 = 4 KB입니다.
+const BOUNDARY_BITS: u32 = 12;
+
+/// 조각 크기의 하한과 상한. 같은 바이트가 길게 이어져 경계가 생기지 않는 파일도 조각을 나눕니다.
+const MIN_CHUNK: usize = 1024;
+const MAX_CHUNK: usize = 64 * 1024;
+
+/// 조각 내용을 요약하는 FNV-1a 해시의 시작값과 곱하는 소수.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// 바이트마다 더하는 무작위 값 표(Gear 해시). 실행마다 같은 경계가 나오도록 고정된 씨앗으로 만듭니다.
+const GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut i = 0;
+    while i < 256 {
+        // splitmix64
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+/// 그룹이 어느 단계를 거쳐 같다고 판단되었는지 한 줄로 설명합니다.
+pub fn basis(group: &DuplicateGroup, options: &ScanOptions) -> String {
+    let algorithm = group.algorithm.name();
+    let mut text = if let Some(target) = &group.symlink_target {
+        format!("링크 대상 경로가 같음 ('{}')", target.display())
+    } else if group.hash.starts_with(NAME_SIZE_MARK) {
+        String::from("이름 + 크기 (파일 내용은 읽지 않은 추정)")
+    } else if photos::group_key(&group.hash).is_some() {
+        String::from("EXIF 촬영 정보 (촬영 시각, 카메라 모델, 픽셀 수가 같음. 내용은 다를 수 있는 추정)")
+    } else if office::group_hash(&group.hash).is_some() {
+        format!("문서 파트 해시 (문서 속성을 뺀 ZIP 파트의 {} 해시가 같음)", algorithm)
+    } else if pdf::group_hash(&group.hash).is_some() {
+        format!("페이지 내용 해시 (페이지 내용 스트림과 페이지가 쓰는 이미지, 폼의 {} 해시가 같음)", algorithm)
+    } else if audio::group_hash(&group.hash).is_some() {
+        format!("오디오 데이터 해시 (ID3, Vorbis 주석, MP4 태그를 뺀 오디오 데이터의 {} 해시가 같음)", algorithm)
+    } else {
+        content_basis(&options.stages, algorithm, stages::group_sample(&group.hash).is_some())
+    };
+    if let Some((extra, _)) = &group.extra_hash {
+        text.push_str(&format!(" + 추가 해시({}) 일치", extra.name()));
+    }
+    if options.compare_attrs {
+        text.push_str(" + 확장 속성과 ACL 일치");
+    }
+    if options.require_same_mtime {
+        text.push_str(" + 수정 시각(초 단위) 일치");
+    }
+    // 캐시나 다른 도구에서 가져온 해시는 이번 실행에서 파일을 읽어 계산한 값이 아닐 수 있습니다.
+    if options.stages.hash && (options.xattr_cache || options.imported_hashes.is_some()) {
+        text.push_str(" (해시 일부는 --xattr-cache/--import-hashes에 기록된 값)");
+    }
+    text
+}
+
+/// 내용 그룹의 단계 목록. 마지막 단계가 같음을 확정한 단계입니다.
+fn content_basis(pipeline: &Pipeline, algorithm: &str, sampled: bool) -> String {
+    let mut steps = vec![String::from("크기")];
+    if pipeline.sample {
+        steps.push(String::from("앞뒤 샘플"));
+    }
+    if pipeline.hash {
+        steps.push(format!("전체 해시({})", algorithm));
+    }
+    if pipeline.bytes {
+        steps.push(String::from("바이트 비교"));
+    }
+    let decisive = steps.last().cloned().unwrap_or_default();
+    let note = if sampled { " (가운데 내용은 비교하지 않은 추정)" } else { "" };
+    format!("{}{} [{}]", decisive, note, steps.join(" → "))
+}
+
+/// 내용이 달라도 묶는 모드(--match exif/office/pdf/audio)의 그룹인지 여부. 이런 그룹에만 유사도를 보여줍니다.
+pub fn is_near_duplicate(group: &DuplicateGroup) -> bool {
+    photos::group_key(&group.hash).is_some()
+        || office::group_hash(&group.hash).is_some()
+        || pdf::group_hash(&group.hash).is_some()
+        || audio::group_hash(&group.hash).is_some()
+}
+
+/// 멤버마다 첫 번째 멤버와의 유사도(0.0 ~ 1.0)를 계산합니다. 읽을 수 없는 멤버는 오류 메시지입니다.
+pub fn similarities(group: &DuplicateGroup) -> Vec<Result<f64, String>> {
+    let Some((first, rest)) = group.paths.split_first() else { return Vec::new() };
+    let reference = match chunks(first) {
+        Ok(reference) => reference,
+        Err(e) => return group.paths.iter().map(|_| Err(e.to_string())).collect(),
+    };
+    let mut scores = vec![Ok(1.0)];
+    scores.extend(rest.iter().map(|path| chunks(path).map(|other| reference.similarity(&other)).map_err(|e| e.to_string())));
+    scores
+}
+
+/// 파일을 나눈 조각들: (조각 해시, 길이) -> 나온 횟수.
+struct Chunks {
+    counts: HashMap<(u64, usize), u32>,
+    total: u64,
+}
+
+impl Chunks {
+    /// 두 파일에 함께 있는 조각의 바이트 수를 큰 파일의 크기로 나눈 값.
+    fn similarity(&self, other: &Chunks) -> f64 {
+        let larger = self.total.max(other.total);
+        if larger == 0 {
+            return 1.0;
+        }
+        let shared: u64 = self
+            .counts
+            .iter()
+            .filter_map(|(key, &count)| other.counts.get(key).map(|&theirs| count.min(theirs) as u64 * key.1 as u64))
+            .sum();
+        shared as f64 / larger as f64
+    }
+}
+
+/// 파일을 읽으며 내용에 따라 정해지는 경계로 조각을 나눕니다.
+fn chunks(path: &Path) -> io::Result<Chunks> {
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut counts = HashMap::new();
+    let mut total = 0u64;
+    // 경계를 정하는 Gear 해시와, 조각 내용을 요약하는 FNV-1a 해시.
+    let (mut gear, mut fnv, mut len) = (0u64, FNV_OFFSET, 0usize);
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        total += read as u64;
+        for &byte in &buffer[..read] {
+            gear = (gear << 1).wrapping_add(GEAR[byte as usize]);
+            fnv = (fnv ^ byte as u64).wrapping_mul(FNV_PRIME);
+            len += 1;
+            if (len >= MIN_CHUNK && gear >> (64 - BOUNDARY_BITS) == 0) || len >= MAX_CHUNK {
+                *counts.entry((fnv, len)).or_insert(0) += 1;
+                (gear, fnv, len) = (0, FNV_OFFSET, 0);
+            }
+        }
+    }
+    if len > 0 {
+        *counts.entry((fnv, len)).or_insert(0) += 1;
+    }
+    Ok(Chunks { counts, total })
+}
@@ -0,0 +1,102 @@
+// ==============================================================================
+//  이미 데이터를 공유하는 사본 알아보기 (FIEMAP)
+// ------------------------------------------------------------------------------
+//  btrfs, XFS 같은 파일 시스템에서는 `cp --reflink`나 중복 제거 도구(duperemove, bees)로
+//  이미 합친 사본이 디스크의 같은 블록(익스텐트)을 가리킵니다. 경로는 둘이지만 데이터는 한 벌이므로,
+//  이런 사본을 지우거나 링크로 바꾸어도 공간이 돌아오지 않습니다.
+//
+//  Linux의 FIEMAP ioctl은 파일의 각 부분이 디스크의 어느 위치(물리 오프셋)에 있는지와, 그 익스텐트를
+//  다른 파일과 공유하는지(FIEMAP_EXTENT_SHARED)를 알려줍니다. 모든 익스텐트가 공유 중인 파일은
+//  (논리 오프셋, 물리 오프셋, 길이) 목록을 "배치 서명"으로 삼고, 서명이 같은 두 파일은 모든 데이터를
+//  공유한다고 봅니다. 서명을 얻을 수 없는 경우(지원하지 않는 파일 시스템, 아직 디스크에 쓰지 않은
+//  데이터, 인라인/압축 익스텐트)에는 `None`이며 공유하지 않는 것으로 다룹니다.
+// ==============================================================================
+
+use std::path::Path;
+
+/// 파일 데이터의 디스크 배치. (논리 오프셋, 물리 오프셋, 길이) 목록이며 이어지는 익스텐트는 합쳐 둡니다.
+pub type Layout = Vec<(u64, u64, u64)>;
+
+/// 모든 익스텐트가 다른 파일과 공유 중이라면 그 배치를 반환합니다.
+#[cfg(target_os = "linux")]
+pub fn shared_layout(path: &Path) -> Option<Layout> {
+    use std::fs::File;
+    use std::os::unix::io::AsRawFd;
+
+    // linux/fiemap.h
+    const FS_IOC_FIEMAP: libc::c_ulong = 0xC020_660B;
+    const FIEMAP_FLAG_SYNC: u32 = 0x1;
+    const EXTENT_LAST: u32 = 0x1;
+    // 위치를 알 수 없거나(UNKNOWN, DELALLOC), 다른 파일과 같은 블록이라도 비교할 수 없는 익스텐트.
+    const EXTENT_UNRELIABLE: u32 = 0x2 | 0x4 | 0x8 | 0x200 | 0x400;
+    const EXTENT_SHARED: u32 = 0x2000;
+    const BATCH: usize = 64;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct Extent {
+        logical: u64,
+        physical: u64,
+        length: u64,
+        reserved64: [u64; 2],
+        flags: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    struct Request {
+        start: u64,
+        length: u64,
+        flags: u32,
+        mapped_extents: u32,
+        extent_count: u32,
+        reserved: u32,
+        extents: [Extent; BATCH],
+    }
+
+    let file = File::open(path).ok()?;
+    let mut layout: Layout = Vec::new();
+    let mut start = 0;
+    loop {
+        let mut request = Request {
+            start,
+            length: u64::MAX - start,
+            flags: FIEMAP_FLAG_SYNC,
+            mapped_extents: 0,
+            extent_count: BATCH as u32,
+            reserved: 0,
+            extents: [Extent::default(); BATCH],
+        };
+        // SAFETY: 열린 파일 디스크립터와, 커널이 채울 익스텐트 배열을 포함한 fiemap 구조체를 넘깁니다.
+        if unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FIEMAP, &mut request) } != 0 {
+            return None;
+        }
+        let mapped = &request.extents[..(request.mapped_extents as usize).min(BATCH)];
+        let Some(last) = mapped.last() else { break };
+        for extent in mapped {
+            if extent.flags & EXTENT_SHARED == 0 || extent.flags & EXTENT_UNRELIABLE != 0 {
+                return None;
+            }
+            match layout.last_mut() {
+                Some((logical, physical, length))
+                    if *logical + *length == extent.logical && *physical + *length == extent.physical =>
+                {
+                    *length += extent.length;
+                }
+                _ => layout.push((extent.logical, extent.physical, extent.length)),
+            }
+        }
+        if last.flags & EXTENT_LAST != 0 {
+            break;
+        }
+        start = last.logical + last.length;
+    }
+    // 익스텐트가 없는 파일(빈 파일, 구멍만 있는 파일)은 공유할 데이터가 없습니다.
+    (!layout.is_empty()).then_some(layout)
+}
+
+/// FIEMAP은 Linux에만 있습니다.
+#[cfg(not(target_os = "linux"))]
+pub fn shared_layout(_path: &Path) -> Option<Layout> {
+    None
+}
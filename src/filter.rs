@@ -0,0 +1,403 @@
+// ==============================================================================
+//  조건식 필터 (--filter)
+// ------------------------------------------------------------------------------
+//  `--file-filter`는 이름 하나나 확장자 하나만 받으므로 "jpg나 png 중 썸네일 폴더에 없는 것"
+//  같은 조건을 표현할 수 없습니다. --filter는 여러 조건을 and/or/not과 괄호로 묶습니다.
+//
+//    --filter '(ext:jpg or ext:png) and not path:**/thumbnails/**'
+//
+//  조건 (값에는 와일드카드를 쓸 수 있습니다):
+//  - ext:<확장자>   확장자(점 제외)       예: ext:jpg, ext:jp*g
+//  - name:<이름>    파일 이름             예: name:report.txt, name:IMG_*.heic
+//  - path:<경로>    검색 루트를 포함한 전체 경로   예: path:**/thumbnails/**
+//  와일드카드: `*`는 `/`를 넘지 않는 임의의 문자열, `**`는 `/`를 포함한 임의의 문자열
+//  (`**/`는 폴더가 없는 경우도 포함), `?`는 `/`가 아닌 한 글자입니다.
+//  공백이나 괄호가 든 값은 따옴표로 감쌉니다: name:"my file (1).txt"
+//
+//  우선순위는 not > and > or 입니다. 키워드는 대소문자를 가리지 않습니다.
+//  `--file-filter`의 값도 조건 하나짜리 식(`*.log` → ext:log, 그 외 → name:...)으로 바꾸어 같은 방법으로 검사합니다.
+// ==============================================================================
+
+use std::fmt;
+use std::path::Path;
+
+use crate::names;
+
+/// 괄호와 `not`을 겹쳐 쓸 수 있는 최대 깊이. 파서와 검사가 재귀하므로 스택이 넘치기 전에 거부합니다.
+const MAX_DEPTH: usize = 64;
+
+/// 식 하나의 최대 낱말 수. and/or를 길게 이어도 식의 트리가 이보다 깊어지지 않습니다.
+const MAX_TOKENS: usize = 1024;
+
+/// 필터 조건식.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// 확장자 패턴.
+    Ext(String),
+    /// 파일 이름 패턴.
+    Name(String),
+    /// 전체 경로 패턴.
+    Path(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// 경로가 조건을 만족하는지 검사합니다. `normalize`가 참이면 이름을 NFC로 정규화하여 비교합니다.
+    pub fn matches(&self, path: &Path, normalize: bool) -> bool {
+        match self {
+            Expr::Ext(pattern) => path.extension().is_some_and(|e| matches_name(e, pattern, normalize)),
+            Expr::Name(pattern) => path.file_name().is_some_and(|n| matches_name(n, pattern, normalize)),
+            Expr::Path(pattern) => {
+                // Windows의 `\` 구분자도 `/`로 맞춰 비교합니다.
+                let text = path.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/");
+                glob(&names::comparable(pattern, normalize), &names::comparable(&text, normalize))
+            }
+            Expr::Not(inner) => !inner.matches(path, normalize),
+            Expr::And(left, right) => left.matches(path, normalize) && right.matches(path, normalize),
+            Expr::Or(left, right) => left.matches(path, normalize) || right.matches(path, normalize),
+        }
+    }
+}
+
+/// 이름이나 확장자를 패턴과 비교합니다. 와일드카드가 없으면 기존처럼 그대로 비교합니다.
+fn matches_name(actual: &std::ffi::OsStr, pattern: &str, normalize: bool) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return names::matches(actual, pattern, normalize);
+    }
+    glob(&names::comparable(pattern, normalize), &names::comparable(&actual.to_string_lossy(), normalize))
+}
+
+/// 와일드카드 패턴 비교. (`*`, `**`, `?`)
+fn glob(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_chars(&pattern, &text)
+}
+
+/// `matched[i][j]`: 패턴의 `i`번째 글자부터가 텍스트의 `j`번째 글자부터에 맞는지를 패턴의 뒤에서부터 채웁니다.
+/// 되돌아가며 시도하지 않으므로 `*a*a*a…b` 같은 패턴도 (패턴 길이 × 텍스트 길이)에 끝납니다.
+fn glob_chars(pattern: &[char], text: &[char]) -> bool {
+    let n = text.len();
+    let mut matched = vec![vec![false; n + 1]; pattern.len() + 1];
+    matched[pattern.len()][n] = true;
+    for i in (0..pattern.len()).rev() {
+        let mut row = vec![false; n + 1];
+        match pattern[i..] {
+            ['*', '*', ..] => {
+                let rest = i + 2;
+                // `**/`는 폴더가 하나도 없는 경우(`a/**/b`가 `a/b`에 맞는 경우)도 포함합니다.
+                let skip_slash = pattern.get(rest) == Some(&'/');
+                let mut any_later = false;
+                for j in (0..=n).rev() {
+                    any_later |= matched[rest][j];
+                    row[j] = any_later || (skip_slash && matched[rest + 1][j]);
+                }
+            }
+            // `*`는 `/`를 넘지 않습니다.
+            ['*', ..] => {
+                for j in (0..=n).rev() {
+                    row[j] = matched[i + 1][j] || (j < n && text[j] != '/' && row[j + 1]);
+                }
+            }
+            ['?', ..] => {
+                for j in 0..n {
+                    row[j] = text[j] != '/' && matched[i + 1][j + 1];
+                }
+            }
+            [c, ..] => {
+                for j in 0..n {
+                    row[j] = text[j] == c && matched[i + 1][j + 1];
+                }
+            }
+            [] => {}
+        }
+        matched[i] = row;
+    }
+    matched[0][0]
+}
+
+impl fmt::Display for Expr {
+    /// 다시 읽을 수 있는 형태로 씁니다. 이항 연산은 항상 괄호로 감쌉니다.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |f: &mut fmt::Formatter<'_>, key: &str, value: &str| {
+            if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+                write!(f, "{}:\"{}\"", key, value)
+            } else {
+                write!(f, "{}:{}", key, value)
+            }
+        };
+        match self {
+            Expr::Ext(pattern) => value(f, "ext", pattern),
+            Expr::Name(pattern) => value(f, "name", pattern),
+            Expr::Path(pattern) => value(f, "path", pattern),
+            Expr::Not(inner) => write!(f, "not {}", inner),
+            Expr::And(left, right) => write!(f, "({} and {})", left, right),
+            Expr::Or(left, right) => write!(f, "({} or {})", left, right),
+        }
+    }
+}
+
+/// 식을 이루는 낱말.
+#[derive(PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+}
+
+/// 식을 낱말로 나눕니다. 따옴표 안의 공백과 괄호는 값의 일부입니다.
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' || c == '\'' {
+                        loop {
+                            match chars.next() {
+                                Some(q) if q == c => break,
+                                Some(inner) => word.push(inner),
+                                None => return Err(format!("닫히지 않은 따옴표({})가 있습니다", c)),
+                            }
+                        }
+                    } else {
+                        word.push(c);
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// 낱말 목록을 앞에서부터 읽는 재귀 하강 파서.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// 지금 읽고 있는 괄호와 `not`의 깊이.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    /// or := and ("or" and)*
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.peek_keyword("or") {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    /// and := unary ("and" unary)*
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.peek_keyword("and") {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    /// unary := "not" unary | "(" or ")" | 조건
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("괄호와 not을 {}겹보다 깊게 쓸 수 없습니다", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let expr = self.nested();
+        self.depth -= 1;
+        expr
+    }
+
+    /// 깊이를 센 뒤의 `unary`.
+    fn nested(&mut self) -> Result<Expr, String> {
+        if self.peek_keyword("not") {
+            self.position += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let token = self.tokens.get(self.position).ok_or("식이 중간에 끝났습니다")?;
+        self.position += 1;
+        match token {
+            Token::Open => {
+                let expr = self.or()?;
+                if self.tokens.get(self.position) != Some(&Token::Close) {
+                    return Err(String::from("닫는 괄호가 없습니다"));
+                }
+                self.position += 1;
+                Ok(expr)
+            }
+            Token::Close => Err(String::from("여는 괄호 없이 닫는 괄호가 있습니다")),
+            Token::Word(word) => atom(word),
+        }
+    }
+}
+
+/// `종류:값` 조건 하나를 읽습니다.
+fn atom(word: &str) -> Result<Expr, String> {
+    let Some((key, value)) = word.split_once(':') else {
+        return Err(format!("'{}'은(는) 조건이 아닙니다. ext:, name:, path: 중 하나로 시작해야 합니다", word));
+    };
+    if value.is_empty() {
+        return Err(format!("'{}'에 값이 없습니다", word));
+    }
+    match key.to_ascii_lowercase().as_str() {
+        "ext" => Ok(Expr::Ext(value.trim_start_matches('.').to_string())),
+        "name" => Ok(Expr::Name(value.to_string())),
+        "path" => Ok(Expr::Path(value.to_string())),
+        _ => Err(format!("알 수 없는 조건 '{}:'입니다. ext:, name:, path: 중 하나를 쓰세요", key)),
+    }
+}
+
+/// `--filter`의 값을 읽습니다.
+pub fn parse(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(text)?, position: 0, depth: 0 };
+    if parser.tokens.is_empty() {
+        return Err(String::from("식이 비어 있습니다"));
+    }
+    if parser.tokens.len() > MAX_TOKENS {
+        return Err(format!("식이 너무 깁니다. (낱말 {}개까지)", MAX_TOKENS));
+    }
+    let expr = parser.or()?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(expr),
+        Some(Token::Close) => Err(String::from("여는 괄호 없이 닫는 괄호가 있습니다")),
+        Some(Token::Open) | Some(Token::Word(_)) => {
+            Err(String::from("조건 사이에 and나 or가 필요합니다"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(filter: &str, path: &str) -> bool {
+        parse(filter).unwrap().matches(Path::new(path), false)
+    }
+
+    #[test]
+    fn respects_precedence_and_parentheses() {
+        assert_eq!(
+            parse("ext:jpg or ext:png and not name:a.png").unwrap().to_string(),
+            "(ext:jpg or (ext:png and not name:a.png))"
+        );
+        assert_eq!(
+            parse("(ext:jpg OR ext:png) AND NOT path:**/thumbs/**").unwrap().to_string(),
+            "((ext:jpg or ext:png) and not path:**/thumbs/**)"
+        );
+        assert!(matches("(ext:jpg or ext:png) and not path:**/thumbs/**", "/p/a.png"));
+        assert!(!matches("(ext:jpg or ext:png) and not path:**/thumbs/**", "/p/thumbs/a.png"));
+        assert!(matches("not not ext:txt", "a.txt"));
+    }
+
+    #[test]
+    fn display_round_trips_quoted_values() {
+        let expr = parse("name:\"my file (1).txt\" or ext:.LOG").unwrap();
+        assert_eq!(expr.to_string(), "(name:\"my file (1).txt\" or ext:LOG)");
+        assert_eq!(parse(&expr.to_string()).unwrap(), expr);
+        assert_eq!(parse("name:'a b'").unwrap(), Expr::Name("a b".to_string()));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for text in [
+            "",
+            "   ",
+            "(ext:jpg",
+            "ext:jpg)",
+            ")",
+            "()",
+            "ext:jpg ext:png",
+            "ext:jpg and",
+            "not",
+            "or ext:jpg",
+            "jpg",
+            "ext:",
+            "size:10",
+            "name:\"open",
+        ] {
+            assert!(parse(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn limits_nesting_depth_and_length() {
+        let nested = |depth: usize| format!("{}ext:a{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH - 1)).is_ok());
+        assert!(parse(&nested(MAX_DEPTH)).is_err());
+        assert!(parse(&format!("{}ext:a", "not ".repeat(MAX_DEPTH - 1))).is_ok());
+        assert!(parse(&format!("{}ext:a", "not ".repeat(100_000))).is_err());
+        assert!(parse(&vec!["ext:a"; MAX_TOKENS / 2].join(" or ")).is_ok());
+        assert!(parse(&vec!["ext:a"; MAX_TOKENS].join(" or ")).is_err());
+    }
+
+    #[test]
+    fn globs_single_star_and_question_mark() {
+        assert!(glob("IMG_*.heic", "IMG_0001.heic"));
+        assert!(glob("*", ""));
+        assert!(glob("a*", "a"));
+        assert!(!glob("*", "a/b"));
+        assert!(glob("?.txt", "a.txt"));
+        assert!(!glob("?.txt", ".txt"));
+        assert!(!glob("?", "/"));
+        assert!(!glob("abc", "ab"));
+        assert!(!glob("", "a"));
+        assert!(glob("", ""));
+        assert!(glob("한*", "한글"));
+    }
+
+    #[test]
+    fn globs_double_star_across_folders() {
+        assert!(glob("**/thumbs/**", "/p/thumbs/a.png"));
+        assert!(glob("a/**/b", "a/b"));
+        assert!(glob("a/**/b", "a/x/y/b"));
+        assert!(!glob("a/**/b", "a/xb"));
+        assert!(glob("a/**", "a/"));
+        assert!(glob("**", "any/thing"));
+        assert!(glob("/x/**.log", "/x/y/z.log"));
+        assert!(!glob("/x/*.log", "/x/y/z.log"));
+    }
+
+    #[test]
+    fn glob_does_not_backtrack_exponentially() {
+        let pattern = format!("{}b", "*a".repeat(40));
+        let text = "a".repeat(200);
+        assert!(!glob(&pattern, &text));
+        assert!(glob(&format!("{}**", "**a".repeat(30)), &"a/".repeat(100)));
+    }
+
+    #[test]
+    fn matches_extension_and_name_without_wildcards_exactly() {
+        assert!(matches("ext:jpg", "/a/b.jpg"));
+        assert!(!matches("ext:jpg", "/a/b.jpeg"));
+        assert!(matches("ext:jp*g", "/a/b.jpeg"));
+        assert!(!matches("ext:jpg", "/a/jpg"));
+        assert!(matches("name:b.jpg", "/a/b.jpg"));
+        assert!(!matches("name:b", "/a/b.jpg"));
+    }
+}
@@ -0,0 +1,124 @@
+// ==============================================================================
+//  폴더 사이의 중복 관계 그래프 (--export-dot)
+// ------------------------------------------------------------------------------
+//  그룹 목록은 파일 단위라, "어느 폴더와 어느 폴더가 많이 겹치는가"를 보려면 경로를 눈으로 모아야
+//  합니다. 정리 계획은 보통 폴더 단위(이 백업 폴더를 저 폴더로 합친다)로 세우므로 폴더 사이의 관계가
+//  한눈에 보여야 합니다.
+//
+//  --export-dot FILE은 중복 그룹의 멤버가 있는 폴더를 노드로, 같은 그룹의 멤버를 가진 두 폴더를
+//  간선으로 잇는 Graphviz DOT 그래프를 씁니다.
+//  - 간선의 무게는 두 폴더가 함께 가진 내용의 크기(그룹마다 파일 크기 한 번)와 그 그룹 수입니다.
+//    무게가 클수록 선을 굵게 그립니다.
+//  - 노드에는 그 폴더에 있는 중복 파일 수와 크기를, 한 폴더 안의 사본이 있다면 그 회수 가능 공간도 씁니다.
+//  `dot -Tsvg graph.dot -o graph.svg`나 `sfdp`(노드가 많을 때)로 그림을 만듭니다.
+//  심볼릭 링크 그룹은 공간을 차지하지 않으므로 제외합니다.
+// ==============================================================================
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{DuplicateGroup, format_size, path_style};
+
+/// 폴더 하나(노드)의 집계.
+#[derive(Default)]
+struct Folder {
+    /// 이 폴더에 있는 중복 그룹 멤버의 수와 크기.
+    files: u64,
+    bytes: u64,
+    /// 멤버가 이 폴더 안에 여럿인 그룹에서, 하나만 남기면 회수되는 공간.
+    internal_wasted: u64,
+}
+
+/// 두 폴더 사이(간선)의 집계.
+#[derive(Default)]
+struct Link {
+    groups: u64,
+    shared_bytes: u64,
+}
+
+/// 그래프를 쓴 결과.
+pub struct GraphReport {
+    pub folders: usize,
+    pub links: usize,
+}
+
+/// 중복 그룹으로 폴더 관계 그래프를 만들어 `output`에 DOT 형식으로 씁니다.
+pub fn export(output: &Path, groups: &[DuplicateGroup]) -> io::Result<GraphReport> {
+    let mut folders: BTreeMap<&Path, Folder> = BTreeMap::new();
+    // 간선은 (작은 쪽, 큰 쪽) 순서의 폴더 쌍으로 셉니다.
+    let mut links: BTreeMap<(&Path, &Path), Link> = BTreeMap::new();
+    for group in groups.iter().filter(|g| g.symlink_target.is_none()) {
+        let mut dirs: Vec<&Path> = group.paths.iter().map(|path| path.parent().unwrap_or(Path::new(""))).collect();
+        dirs.sort();
+        // 정렬했으므로 같은 폴더의 멤버는 이웃해 있습니다.
+        for run in dirs.chunk_by(|a, b| a == b) {
+            let folder = folders.entry(run[0]).or_default();
+            folder.files += run.len() as u64;
+            folder.bytes += run.len() as u64 * group.size;
+            folder.internal_wasted += (run.len() as u64 - 1) * group.size;
+        }
+        dirs.dedup();
+        for (i, a) in dirs.iter().enumerate() {
+            for b in &dirs[i + 1..] {
+                let link = links.entry((a, b)).or_default();
+                link.groups += 1;
+                link.shared_bytes += group.size;
+            }
+        }
+    }
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    writeln!(writer, "graph duplicates {{")?;
+    writeln!(writer, "  graph [overlap=false, splines=true];")?;
+    writeln!(writer, "  node [shape=box, style=rounded, fontname=\"sans-serif\"];")?;
+    writeln!(writer, "  edge [fontname=\"sans-serif\", fontsize=10];")?;
+    for (dir, folder) in &folders {
+        let mut label = format!("{}\n중복 파일 {}개 ({})", shown(dir), folder.files, format_size(folder.bytes));
+        if folder.internal_wasted > 0 {
+            label.push_str(&format!("\n폴더 안 사본 {}", format_size(folder.internal_wasted)));
+        }
+        writeln!(writer, "  {} [label={}];", quoted(&shown(dir)), quoted(&label))?;
+    }
+    let heaviest = links.values().map(|link| link.shared_bytes).max().unwrap_or(0).max(1);
+    for ((a, b), link) in &links {
+        // 가장 무거운 간선을 8, 가장 가벼운 간선을 1에 가깝게 그립니다.
+        let width = 1.0 + 7.0 * link.shared_bytes as f64 / heaviest as f64;
+        writeln!(
+            writer,
+            "  {} -- {} [label={}, weight={}, penwidth={:.2}];",
+            quoted(&shown(a)),
+            quoted(&shown(b)),
+            quoted(&format!("{} (그룹 {}개)", format_size(link.shared_bytes), link.groups)),
+            link.groups,
+            width
+        )?;
+    }
+    writeln!(writer, "}}")?;
+    writer.flush()?;
+    Ok(GraphReport { folders: folders.len(), links: links.len() })
+}
+
+/// 노드 이름으로 쓸 폴더 경로. 현재 폴더의 파일이라 부모가 비어 있으면 `.`으로 씁니다.
+fn shown(dir: &Path) -> String {
+    if dir.as_os_str().is_empty() { String::from(".") } else { path_style::display(dir) }
+}
+
+/// DOT의 큰따옴표 문자열. 큰따옴표와 역슬래시는 이스케이프하고, 줄바꿈은 `\n`으로 씁니다.
+fn quoted(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,393 @@
+// ==============================================================================
+//  네이티브 GUI (`gui` 하위 명령, `gui` 기능으로 빌드할 때만 포함)
+// ------------------------------------------------------------------------------
+//  중복 파일 정리가 가장 필요한 사람들 중 상당수는 터미널을 사용하지 않습니다.
+//  egui 기반의 창에서 다음을 모두 할 수 있도록 합니다.
+//  - 검색 설정: 루트 폴더(한 줄에 하나), 이름/확장자 필터, 심볼릭 링크 포함 여부
+//  - 검색 중 실시간 진행 상황
+//  - 그룹 탐색과 멤버별 삭제 표시, 선택한 이미지 파일의 미리보기
+//  - 삭제 실행 (TUI와 같은 안전장치: 위험한 루트 거부, 그룹마다 최소 한 개 유지,
+//    검색 이후 바뀐 파일은 삭제하지 않음)
+//
+//  빌드: cargo build --release --features gui
+// ==============================================================================
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::actions::PruneMode;
+use crate::stages::Pipeline;
+use crate::progress::{Progress, ProgressSnapshot};
+use crate::{
+    DuplicateGroup, FilterMode, HashAlgorithm, MatchMode, ScanLog, ScanOptions, ScanOutcome, ScanSummary, actions, default_threads,
+    find_duplicates, format_size, prepare_roots, reader_pool, safety, sort_groups,
+};
+
+/// 검색 중 화면을 다시 그리는 간격.
+const REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 미리보기를 지원하는 이미지 확장자.
+const PREVIEW_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+/// 한글을 표시할 수 있는 시스템 글꼴 후보. egui의 기본 글꼴에는 한글이 없습니다.
+const CJK_FONT_CANDIDATES: [&str; 6] = [
+    "/usr/share/fonts/truetype/nanum/NanumGothic.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+    "/Library/Fonts/AppleGothic.ttf",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+];
+
+/// 백그라운드에서 실행 중인 검색.
+struct RunningScan {
+    progress: Arc<Mutex<ProgressSnapshot>>,
+    result: Receiver<io::Result<ScanOutcome>>,
+}
+
+/// GUI의 전체 상태.
+struct GuiApp {
+    roots_text: String,
+    filter: String,
+    symlinks: bool,
+    force_dangerous_root: bool,
+    /// 삭제 뒤에 비게 된 폴더를 정리할지 여부.
+    prune_empty_dirs: bool,
+    scan: Option<RunningScan>,
+    /// 마지막 검색의 루트들. 빈 폴더 정리는 이 루트 안에서만, 루트 자체는 남기고 이루어집니다.
+    scan_roots: Vec<PathBuf>,
+    groups: Vec<DuplicateGroup>,
+    /// 삭제 표시된 멤버 경로들.
+    marked: HashSet<PathBuf>,
+    selected_group: Option<usize>,
+    preview: Option<PathBuf>,
+    /// 마지막 검색의 루트가 위험하다면 삭제를 거부하는 이유.
+    delete_blocked: Option<String>,
+    confirming: bool,
+    status: String,
+}
+
+/// GUI 창을 열고, 사용자가 창을 닫을 때까지 실행합니다.
+pub fn run(roots: Vec<PathBuf>) -> io::Result<()> {
+    let app = GuiApp {
+        roots_text: roots.iter().map(|r| r.display().to_string()).collect::<Vec<_>>().join("\n"),
+        filter: String::new(),
+        symlinks: false,
+        force_dangerous_root: false,
+        prune_empty_dirs: false,
+        scan: None,
+        scan_roots: Vec::new(),
+        groups: Vec::new(),
+        marked: HashSet::new(),
+        selected_group: None,
+        preview: None,
+        delete_blocked: None,
+        confirming: false,
+        status: String::from("검색할 폴더를 입력하고 '검색 시작'을 누르세요."),
+    };
+    let options = eframe::NativeOptions::default();
+    eframe::run_native(
+        "duplicate_finder",
+        options,
+        Box::new(|cc| {
+            install_cjk_font(&cc.egui_ctx);
+            egui_extras::install_image_loaders(&cc.egui_ctx);
+            Ok(Box::new(app))
+        }),
+    )
+    .map_err(|e| io::Error::other(e.to_string()))
+}
+
+/// 시스템에서 찾은 첫 번째 한글 글꼴을 기본 글꼴 뒤에 대체 글꼴로 추가합니다.
+fn install_cjk_font(ctx: &egui::Context) {
+    let Some(bytes) = CJK_FONT_CANDIDATES.iter().find_map(|path| fs::read(path).ok()) else { return };
+    let mut fonts = egui::FontDefinitions::default();
+    fonts.font_data.insert("cjk".to_owned(), Arc::new(egui::FontData::from_owned(bytes)));
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts.families.entry(family).or_default().push("cjk".to_owned());
+    }
+    ctx.set_fonts(fonts);
+}
+
+impl eframe::App for GuiApp {
+    fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
+        self.poll_scan();
+        if self.scan.is_some() {
+            ui.ctx().request_repaint_after(REPAINT_INTERVAL);
+        }
+
+        egui::Panel::top("config").show(ui, |ui| self.draw_config(ui));
+        egui::Panel::bottom("status").show(ui, |ui| {
+            ui.label(&self.status);
+        });
+        egui::Panel::left("groups").resizable(true).default_size(320.0).show(ui, |ui| self.draw_groups(ui));
+        egui::CentralPanel::default().show(ui, |ui| self.draw_members(ui));
+
+        if self.confirming {
+            self.draw_confirm(ui.ctx());
+        }
+    }
+}
+
+impl GuiApp {
+    // --- 검색 ---
+
+    fn draw_config(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("루트 폴더 (한 줄에 하나):");
+            ui.add(egui::TextEdit::multiline(&mut self.roots_text).desired_rows(2).desired_width(400.0));
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("필터 (예: *.jpg):");
+                    ui.text_edit_singleline(&mut self.filter);
+                });
+                ui.checkbox(&mut self.symlinks, "심볼릭 링크 포함");
+                ui.checkbox(&mut self.force_dangerous_root, "위험한 루트에서도 삭제 허용");
+                ui.checkbox(&mut self.prune_empty_dirs, "삭제 후 빈 폴더 정리");
+            });
+            let running = self.scan.is_some();
+            if ui.add_enabled(!running, egui::Button::new("검색 시작")).clicked() {
+                self.start_scan();
+            }
+        });
+        if let Some(scan) = &self.scan {
+            let progress = scan.progress.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            match progress.total {
+                Some(total) if total > 0 => {
+                    ui.add(
+                        egui::ProgressBar::new(progress.done as f32 / total as f32)
+                            .text(format!("{}: {} / {}", progress.stage, progress.done, total)),
+                    );
+                }
+                _ => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!("{}: {}", progress.stage, progress.done));
+                    });
+                }
+            }
+            // 지금 보고 있는 경로: 멈춘 것처럼 보일 때 어느 파일에서 막혔는지 알 수 있습니다.
+            if let Some(current) = &progress.current {
+                ui.label(egui::RichText::new(current).small().weak());
+            }
+        }
+    }
+
+    fn start_scan(&mut self) {
+        let requested: Vec<PathBuf> =
+            self.roots_text.lines().map(str::trim).filter(|l| !l.is_empty()).map(PathBuf::from).collect();
+        if requested.is_empty() {
+            self.status = String::from("검색할 폴더를 하나 이상 입력하세요.");
+            return;
+        }
+        let roots = match prepare_roots(&requested) {
+            Ok(roots) => roots,
+            Err(e) => {
+                self.status = format!("루트 폴더를 읽을 수 없습니다: {}", e);
+                return;
+            }
+        };
+        let filter = self.filter.trim();
+        let options = ScanOptions {
+            filter_mode: FilterMode::from_arg((!filter.is_empty()).then(|| filter.to_string())),
+            created_after: None,
+            created_before: None,
+            include_symlinks: self.symlinks,
+            strict: false,
+            normalize_names: false,
+            threads: default_threads(),
+            isolate_hardlinks: false,
+            hydrate: false,
+            device_limits: None,
+            fd_budget: reader_pool::default_fd_budget(),
+            xattr_cache: false,
+            compare_attrs: false,
+            require_same_mtime: false,
+            imported_hashes: None,
+            ignored_hashes: None,
+            match_mode: MatchMode::Content,
+            stages: Pipeline::default(),
+            algorithm: HashAlgorithm::Sha256,
+            extra_hash: None,
+            bloom_prepass: None,
+            spotlight: false,
+            spill: None,
+            max_memory: None,
+            checkpoint: None,
+        };
+        self.delete_blocked = safety::check_roots(&roots, self.force_dangerous_root)
+            .err()
+            .map(|reasons| format!("위험한 루트를 검색했으므로 삭제가 비활성화되어 있습니다: {}", reasons.join(", ")));
+        self.scan_roots = roots.clone();
+
+        let progress = Arc::new(Mutex::new(ProgressSnapshot::default()));
+        let (sender, receiver) = mpsc::channel();
+        let shared = Arc::clone(&progress);
+        thread::spawn(move || {
+            let mut progress = Progress::shared(shared);
+            let _ = sender.send(find_duplicates(&roots, &options, &mut ScanLog::disabled(), &mut progress, None));
+        });
+        self.scan = Some(RunningScan { progress, result: receiver });
+        self.groups.clear();
+        self.marked.clear();
+        self.selected_group = None;
+        self.preview = None;
+        self.status = String::from("검색 중...");
+    }
+
+    /// 백그라운드 검색이 끝났다면 결과를 가져옵니다.
+    fn poll_scan(&mut self) {
+        let Some(scan) = &self.scan else { return };
+        let Ok(result) = scan.result.try_recv() else { return };
+        self.scan = None;
+        match result {
+            Ok(mut outcome) => {
+                sort_groups(&mut outcome.duplicates);
+                let summary = ScanSummary::of(&outcome.duplicates);
+                self.status = format!(
+                    "중복 그룹 {}개, 중복 파일 {}개, 회수 가능 {}. 처리하지 못한 파일 {}개.",
+                    summary.groups,
+                    summary.files,
+                    format_size(summary.wasted_bytes),
+                    outcome.errors.len()
+                );
+                self.groups = outcome.duplicates;
+                self.selected_group = (!self.groups.is_empty()).then_some(0);
+            }
+            Err(e) => self.status = format!("검색 오류: {}", e),
+        }
+    }
+
+    // --- 그룹 탐색 ---
+
+    fn draw_groups(&mut self, ui: &mut egui::Ui) {
+        ui.heading("중복 그룹");
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, group) in self.groups.iter().enumerate() {
+                let marked = group.paths.iter().filter(|p| self.marked.contains(*p)).count();
+                let label = format!(
+                    "그룹 {} · {}개 · {} · 삭제 {}",
+                    i + 1,
+                    group.paths.len(),
+                    format_size(group.size),
+                    marked
+                );
+                if ui.selectable_label(self.selected_group == Some(i), label).clicked() {
+                    self.selected_group = Some(i);
+                    self.preview = None;
+                }
+            }
+        });
+    }
+
+    fn draw_members(&mut self, ui: &mut egui::Ui) {
+        let Some(group) = self.selected_group.and_then(|i| self.groups.get(i)) else {
+            ui.label("왼쪽에서 그룹을 선택하세요.");
+            return;
+        };
+        ui.heading(format!("파일당 {}, 회수 가능 {}", format_size(group.size), format_size(group.wasted_bytes())));
+        if let Some(target) = &group.symlink_target {
+            ui.label(format!("심볼릭 링크 → '{}'", target.display()));
+        }
+        for path in &group.paths {
+            ui.horizontal(|ui| {
+                let mut delete = self.marked.contains(path);
+                if ui.checkbox(&mut delete, "삭제").changed() {
+                    if delete {
+                        self.marked.insert(path.clone());
+                    } else {
+                        self.marked.remove(path);
+                    }
+                }
+                if ui.selectable_label(self.preview.as_ref() == Some(path), path.display().to_string()).clicked() {
+                    self.preview = Some(path.clone());
+                }
+            });
+        }
+        ui.separator();
+        if ui.add_enabled(!self.marked.is_empty(), egui::Button::new("삭제 실행")).clicked() {
+            self.request_execute();
+        }
+        if let Some(path) = &self.preview {
+            ui.separator();
+            if is_previewable(path) {
+                let uri = format!("file://{}", path.display());
+                ui.add(egui::Image::new(uri).max_height(ui.available_height()).fit_to_original_size(1.0).shrink_to_fit());
+            } else {
+                ui.label("이 파일 형식은 미리보기를 지원하지 않습니다.");
+            }
+        }
+    }
+
+    // --- 삭제 실행 ---
+
+    fn request_execute(&mut self) {
+        if let Some(reason) = &self.delete_blocked {
+            self.status = format!("{}. '위험한 루트에서도 삭제 허용'을 선택하고 다시 검색하세요.", reason);
+            return;
+        }
+        if let Some(i) = self.groups.iter().position(|g| !safety::has_survivor(&g.paths, |p| self.marked.contains(p))) {
+            self.status = format!("그룹 {}의 모든 파일이 삭제 표시되어 있습니다. 최소 하나는 유지해야 합니다.", i + 1);
+            self.selected_group = Some(i);
+            return;
+        }
+        self.confirming = true;
+    }
+
+    fn draw_confirm(&mut self, ctx: &egui::Context) {
+        let response = egui::Modal::new(egui::Id::new("confirm_delete")).show(ctx, |ui| {
+            ui.heading("삭제 확인");
+            ui.label(format!("삭제 표시된 {}개 파일을 삭제합니다. 되돌릴 수 없습니다.", self.marked.len()));
+            ui.horizontal(|ui| {
+                if ui.button("삭제").clicked() {
+                    self.execute();
+                    self.confirming = false;
+                }
+                if ui.button("취소").clicked() {
+                    self.confirming = false;
+                    self.status = String::from("삭제를 취소했습니다.");
+                }
+            });
+        });
+        if response.should_close() {
+            self.confirming = false;
+        }
+    }
+
+    fn execute(&mut self) {
+        let targets = std::mem::take(&mut self.marked);
+        match actions::delete_members(&mut self.groups, &targets) {
+            Ok(report) => {
+                let mut status = format!("{}개 파일을 삭제했습니다.", report.deleted.len());
+                if !report.skipped.is_empty() {
+                    status.push_str(&format!(" {}개 파일은 건너뛰었습니다.", report.skipped.len()));
+                }
+                if self.prune_empty_dirs {
+                    let pruned = actions::prune_empty_dirs(&report.deleted, &self.scan_roots, PruneMode::Remove);
+                    status.push_str(&format!(" 빈 폴더 {}개를 지웠습니다.", pruned.removed.len()));
+                    if !pruned.failed.is_empty() {
+                        status.push_str(&format!(" {}개 폴더는 지우지 못했습니다.", pruned.failed.len()));
+                    }
+                }
+                self.status = status;
+            }
+            Err(message) => self.status = message,
+        }
+        self.selected_group = self.selected_group.filter(|&i| i < self.groups.len());
+        self.preview = None;
+    }
+}
+
+fn is_previewable(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| PREVIEW_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
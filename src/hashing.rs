@@ -0,0 +1,245 @@
+// ==============================================================================
+//  해시 알고리즘 (--algorithm, --extra-hash)
+// ------------------------------------------------------------------------------
+//  중복 판정에는 SHA-256을 기본으로 쓰지만, 기존 카탈로그와 비교하기 위한 MD5/SHA-1,
+//  빠른 로컬 비교를 위한 BLAKE3도 고를 수 있습니다. `Hasher`는 알고리즘마다 다른 해시
+//  계산기를 하나의 타입으로 감싸, 파일을 한 번 읽으면서 여러 해시를 함께 계산할 수 있게 합니다.
+//
+//  하드웨어 가속 (--hash-backend)
+//  - SHA-256, SHA-1은 x86/x86_64에서 실행할 때 CPU가 SHA-NI 명령어를 지원하는지 확인하여 씁니다.
+//    BLAKE3는 AVX-512, AVX2, SSE4.1 중 가장 빠른 것을, aarch64에서는 NEON을 씁니다.
+//    명령어 선택은 라이브러리가 실행할 때 하므로 따로 고를 것이 없습니다.
+//  - BLAKE3는 파일 하나를 여러 스레드로 나누어 계산할 수도 있습니다. 큰 파일 몇 개가 시간을 대부분
+//    차지할 때 빨라지지만, 작은 파일이 많을 때는 파일마다 스레드(--threads)를 쓰는 편이 낫습니다.
+//    auto(기본)는 BACKEND_PARALLEL_MIN 이상의 파일만 나누고, single-thread와 multi-thread로 바꿀 수 있습니다.
+//  --profile은 실제로 쓴 구현을 함께 보여줍니다.
+// ==============================================================================
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// 파일 내용의 해시를 계산하는 알고리즘.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// 알고리즘 이름 (확장 속성 이름, 결과 파일에 사용).
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// 결과 파일 등에 기록된 이름으로 알고리즘을 찾습니다.
+    pub fn from_name(name: &str) -> Option<Self> {
+        HashAlgorithm::from_str(name, true).ok()
+    }
+
+    /// 16진수 해시 문자열의 길이.
+    pub fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Md5 => 32,
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Sha256 | HashAlgorithm::Blake3 => 64,
+        }
+    }
+
+    /// 메모리에 있는 바이트의 해시.
+    pub fn digest(self, bytes: &[u8]) -> String {
+        let mut hasher = Hasher::new(self);
+        hasher.update(bytes);
+        hasher.finalize()
+    }
+}
+
+/// 해시 계산 방식 (--hash-backend).
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashBackend {
+    /// CPU가 지원하는 가장 빠른 명령어를 쓰고, BLAKE3는 큰 파일만 여러 스레드로 나누어 계산합니다.
+    #[default]
+    Auto,
+    /// BLAKE3도 파일 하나를 스레드 하나로 계산합니다.
+    SingleThread,
+    /// BLAKE3는 크기와 관계없이 파일 하나를 여러 스레드로 나누어 계산합니다.
+    MultiThread,
+}
+
+/// auto에서 BLAKE3를 여러 스레드로 나누어 계산하는 최소 파일 크기.
+const BACKEND_PARALLEL_MIN: u64 = 64 * 1024 * 1024;
+
+/// 여러 스레드로 계산할 때 한 번에 읽는 크기. BLAKE3는 조각이 커야 스레드로 나눌 수 있습니다.
+pub const PARALLEL_CHUNK: usize = 4 * 1024 * 1024;
+
+/// 실행 중 쓰는 해시 계산 방식. 시작할 때 한 번 정합니다.
+static BACKEND: OnceLock<HashBackend> = OnceLock::new();
+
+/// 해시 계산 방식을 정합니다. 두 번째부터는 무시합니다.
+pub fn set_backend(backend: HashBackend) {
+    let _ = BACKEND.set(backend);
+}
+
+/// 이 크기의 파일을 이 알고리즘들로 해시할 때 BLAKE3를 여러 스레드로 나눌지 여부.
+/// (`native` 기능 없이 빌드했다면 스레드 풀이 없으므로 항상 거짓입니다)
+pub fn parallel_for(size: u64, algorithms: &[HashAlgorithm]) -> bool {
+    if !cfg!(feature = "native") || !algorithms.contains(&HashAlgorithm::Blake3) {
+        return false;
+    }
+    match BACKEND.get().copied().unwrap_or_default() {
+        HashBackend::Auto => size >= BACKEND_PARALLEL_MIN,
+        HashBackend::SingleThread => false,
+        HashBackend::MultiThread => true,
+    }
+}
+
+/// 알고리즘에 실제로 쓰는 구현을 설명합니다. (--profile)
+pub fn describe(algorithm: HashAlgorithm) -> String {
+    match algorithm {
+        HashAlgorithm::Md5 => String::from("소프트웨어 구현 (MD5에는 하드웨어 명령어가 없습니다)"),
+        HashAlgorithm::Sha1 | HashAlgorithm::Sha256 => {
+            if sha_instructions() {
+                String::from("SHA-NI 하드웨어 가속")
+            } else {
+                String::from("소프트웨어 구현 (CPU가 SHA 명령어를 지원하지 않거나 이 빌드에서 쓸 수 없습니다)")
+            }
+        }
+        HashAlgorithm::Blake3 => {
+            let threads = match BACKEND.get().copied().unwrap_or_default() {
+                _ if !cfg!(feature = "native") => String::from("파일마다 스레드 하나"),
+                HashBackend::Auto => format!("{} 이상의 파일은 여러 스레드", crate::format_size(BACKEND_PARALLEL_MIN)),
+                HashBackend::SingleThread => String::from("파일마다 스레드 하나"),
+                HashBackend::MultiThread => String::from("모든 파일을 여러 스레드"),
+            };
+            format!("{}, {}", simd_instructions(), threads)
+        }
+    }
+}
+
+/// sha2/sha1 라이브러리가 SHA-NI를 쓰는지. (라이브러리와 같은 조건으로 확인합니다)
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn sha_instructions() -> bool {
+    std::arch::is_x86_feature_detected!("sha")
+        && std::arch::is_x86_feature_detected!("sse2")
+        && std::arch::is_x86_feature_detected!("ssse3")
+        && std::arch::is_x86_feature_detected!("sse4.1")
+}
+
+/// 다른 아키텍처에서는 sha2의 `asm` 기능 없이는 하드웨어 명령어를 쓰지 않습니다.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn sha_instructions() -> bool {
+    false
+}
+
+/// BLAKE3 라이브러리가 고르는 SIMD 명령어.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn simd_instructions() -> &'static str {
+    if std::arch::is_x86_feature_detected!("avx512f") && std::arch::is_x86_feature_detected!("avx512vl") {
+        "AVX-512"
+    } else if std::arch::is_x86_feature_detected!("avx2") {
+        "AVX2"
+    } else if std::arch::is_x86_feature_detected!("sse4.1") {
+        "SSE4.1"
+    } else {
+        "SSE2"
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn simd_instructions() -> &'static str {
+    "NEON"
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn simd_instructions() -> &'static str {
+    "이식 가능한 구현"
+}
+
+/// 한 줄에 해시 하나씩 적힌 목록 파일을 읽습니다 (--ignore-hashes). 줄의 첫 단어만 보므로
+/// `sha256sum` 같은 도구의 출력도 받습니다. 빈 줄과 `#`로 시작하는 줄은 건너뜁니다.
+pub fn load_hash_list(path: &Path, algorithm: HashAlgorithm) -> io::Result<HashSet<String>> {
+    let context = |message: String| format!("해시 목록 '{}': {}", path.display(), message);
+    let text = fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), context(e.to_string())))?;
+    let mut hashes = HashSet::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // sha256sum은 이름에 특수 문자가 있는 파일의 줄 앞에 `\`를 붙입니다.
+        let hash = line.split_whitespace().next().unwrap_or_default().trim_start_matches('\\').to_ascii_lowercase();
+        if hash.len() != algorithm.hex_len() || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            let message = format!("{}번째 줄 '{}'은(는) {} 해시가 아닙니다", number + 1, line, algorithm.name());
+            return Err(io::Error::new(io::ErrorKind::InvalidData, context(message)));
+        }
+        hashes.insert(hash);
+    }
+    Ok(hashes)
+}
+
+/// 알고리즘별 해시 계산기를 하나로 감싼 타입.
+pub enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    // BLAKE3 계산기는 다른 것들보다 훨씬 크므로(약 2KB) 힙에 둡니다.
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+            HashAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Md5(h) => h.update(bytes),
+            Hasher::Sha1(h) => h.update(bytes),
+            Hasher::Sha256(h) => h.update(bytes),
+            Hasher::Blake3(h) => {
+                h.update(bytes);
+            }
+        }
+    }
+
+    /// `update`와 같지만, BLAKE3는 조각을 여러 스레드로 나누어 계산합니다. (`parallel_for`가 참일 때 씁니다)
+    pub fn update_parallel(&mut self, bytes: &[u8]) {
+        match self {
+            #[cfg(feature = "native")]
+            Hasher::Blake3(h) => {
+                h.update_rayon(bytes);
+            }
+            _ => self.update(bytes),
+        }
+    }
+
+    /// 최종 해시를 16진수 문자열로 반환합니다.
+    pub fn finalize(self) -> String {
+        match self {
+            Hasher::Md5(h) => format!("{:x}", h.finalize()),
+            Hasher::Sha1(h) => format!("{:x}", h.finalize()),
+            Hasher::Sha256(h) => format!("{:x}", h.finalize()),
+            Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+        }
+    }
+}
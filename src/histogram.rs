@@ -0,0 +1,74 @@
+// ==============================================================================
+//  중복 데이터의 크기 분포 (--histogram)
+// ------------------------------------------------------------------------------
+//  같은 "낭비된 공간 100GB"라도 작은 파일 수백만 개인지, 거대한 ISO 몇 개인지에 따라
+//  정리 방법이 달라집니다. 중복 그룹을 파일 크기 구간별로 나누어 그룹 수, 파일 수,
+//  회수 가능한 공간을 보여줍니다.
+// ==============================================================================
+
+use crate::{DuplicateGroup, format_count, format_size, number_format};
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * MB;
+
+/// 크기 구간: (이 크기 미만, 표시 이름). 마지막 구간은 상한이 없습니다.
+const BUCKETS: [(u64, &str); 5] = [
+    (MB, "< 1 MB"),
+    (10 * MB, "1 – 10 MB"),
+    (100 * MB, "10 – 100 MB"),
+    (GB, "100 MB – 1 GB"),
+    (u64::MAX, "≥ 1 GB"),
+];
+
+/// 막대 그래프의 최대 길이 (글자 수).
+const BAR_WIDTH: usize = 30;
+
+/// 구간 하나의 집계.
+#[derive(Clone, Copy, Default)]
+struct Bucket {
+    groups: usize,
+    files: usize,
+    wasted_bytes: u64,
+}
+
+/// 크기 구간별 중복 집계.
+pub struct SizeHistogram {
+    buckets: [Bucket; BUCKETS.len()],
+}
+
+impl SizeHistogram {
+    /// 그룹들을 파일 크기 구간으로 나누어 집계합니다. 심볼릭 링크 그룹은 공간을 차지하지 않으므로 제외합니다.
+    pub fn of(groups: &[DuplicateGroup]) -> Self {
+        let mut buckets = [Bucket::default(); BUCKETS.len()];
+        for group in groups.iter().filter(|g| g.symlink_target.is_none()) {
+            let index = BUCKETS.iter().position(|(limit, _)| group.size < *limit).unwrap_or(BUCKETS.len() - 1);
+            let bucket = &mut buckets[index];
+            bucket.groups += 1;
+            bucket.files += group.paths.len();
+            bucket.wasted_bytes += group.wasted_bytes();
+        }
+        SizeHistogram { buckets }
+    }
+
+    /// 구간별 수치와, 회수 가능한 공간의 비율을 막대로 출력합니다.
+    pub fn print(&self) {
+        let total: u64 = self.buckets.iter().map(|b| b.wasted_bytes).sum();
+        println!("\n📊 파일 크기별 중복 분포");
+        // 한글은 터미널에서 두 칸을 차지하므로 머리글은 아래 열 너비(14, 8, 10, 12)에 맞춰 직접 정렬합니다.
+        println!("  크기               그룹       파일    회수 가능  회수 가능 비율");
+        for ((_, label), bucket) in BUCKETS.iter().zip(&self.buckets) {
+            let ratio = if total == 0 { 0.0 } else { bucket.wasted_bytes as f64 / total as f64 };
+            let bar = "█".repeat((ratio * BAR_WIDTH as f64).round() as usize);
+            println!(
+                "  {:<14} {:>8} {:>10} {:>12}  {:<width$} {:>5}%",
+                label,
+                format_count(bucket.groups),
+                format_count(bucket.files),
+                format_size(bucket.wasted_bytes),
+                bar,
+                number_format::format().decimal(ratio * 100.0),
+                width = BAR_WIDTH
+            );
+        }
+    }
+}
@@ -0,0 +1,221 @@
+// ==============================================================================
+//  일부러 남겨 둔 중복의 무시 목록 (`ignore` 하위 명령, TUI의 `i` 키)
+// ------------------------------------------------------------------------------
+//  프로젝트마다 같은 라이브러리를 복사해 둔 폴더처럼, 중복인 줄 알면서 일부러 남겨 두는
+//  파일들이 있습니다. 이런 그룹이 검색할 때마다 다시 보고되면 정말 정리해야 할 중복이
+//  묻히므로, "의도한 중복"으로 기록해 두고 다음 검색부터 숨깁니다.
+//
+//  기록은 그룹의 (해시 알고리즘, 크기, 해시)와 그때의 멤버 경로들입니다. 다음 검색의 그룹이
+//  같은 내용이고 멤버가 모두 기록된 경로 안에 있을 때만 숨기므로, 같은 파일의 사본이 새
+//  위치에 또 생기면 그룹이 다시 보고됩니다.
+//
+//  무시 목록 파일 (JSON, 기본 위치는 사용자 캐시 폴더의 `duplicate_finder/ignored.json`):
+//  {
+//    "groups": [ { "id": "...", "algorithm": "sha256", "size": 1024, "hash": "...",
+//                  "paths": ["/abs/a", "/abs/b"], "added": "2024-01-01T00:00:00Z" }, ... ]
+//  }
+//  경로는 현재 폴더와 관계없이 맞출 수 있도록 절대 경로로 기록합니다.
+// ==============================================================================
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::atomic_file;
+use crate::results::SavedResults;
+use crate::store_lock::{self, Access};
+use crate::{DuplicateGroup, format_size, group_id};
+
+/// 무시 목록 파일 전체.
+#[derive(Serialize, Deserialize, Default)]
+pub struct IgnoreList {
+    pub groups: Vec<IgnoredGroup>,
+}
+
+/// 무시하기로 한 그룹 하나.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IgnoredGroup {
+    /// (크기, 해시)로 만든 그룹 식별자. 출력의 `[id]`와 같은 값입니다.
+    pub id: String,
+    pub algorithm: String,
+    pub size: u64,
+    pub hash: String,
+    /// 기록할 때의 멤버 경로들 (절대 경로).
+    pub paths: Vec<String>,
+    /// 기록한 시각 (RFC 3339, UTC).
+    pub added: String,
+}
+
+impl IgnoredGroup {
+    /// 검색에서 찾은 그룹으로 기록을 만듭니다.
+    pub fn of(group: &DuplicateGroup) -> Self {
+        IgnoredGroup {
+            id: group_id(group.size, &group.hash),
+            algorithm: group.algorithm.name().to_string(),
+            size: group.size,
+            hash: group.hash.clone(),
+            paths: group.paths.iter().map(|p| absolute(p)).collect(),
+            added: now(),
+        }
+    }
+}
+
+/// 사용자 캐시 폴더 안의 기본 무시 목록 파일 위치.
+/// (`$XDG_CACHE_HOME`, 없으면 `~/.cache`, Windows에서는 `%LOCALAPPDATA%`)
+pub fn default_path() -> Option<PathBuf> {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("duplicate_finder").join("ignored.json"))
+}
+
+impl IgnoreList {
+    /// 무시 목록을 읽습니다. 파일이 아직 없다면 빈 목록입니다.
+    /// 다른 실행이 고쳐 쓰는 중이라면 반쯤 쓴 파일을 읽지 않도록 공유 잠금을 잡고 읽습니다.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let _lock = store_lock::acquire(&store_lock::beside(path), Access::Shared, "무시 목록")?;
+        Self::read(path)
+    }
+
+    /// 다른 실행이 그 사이에 기록한 그룹을 잃지 않도록, 배타 잠금을 쥔 채로 무시 목록을 읽고 `change`로
+    /// 고친 뒤 저장합니다. `change`가 실패하면 저장하지 않습니다.
+    pub fn update(path: &Path, change: impl FnOnce(&mut Self) -> io::Result<()>) -> io::Result<()> {
+        let _lock = store_lock::acquire(&store_lock::beside(path), Access::Exclusive, "무시 목록")?;
+        let mut list = Self::read(path)?;
+        change(&mut list)?;
+        list.save(path)
+    }
+
+    fn read(path: &Path) -> io::Result<Self> {
+        let context = |e: &dyn std::fmt::Display| format!("무시 목록 '{}': {}", path.display(), e);
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, context(&e))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(IgnoreList::default()),
+            Err(e) => Err(io::Error::new(e.kind(), context(&e))),
+        }
+    }
+
+    /// 무시 목록을 저장합니다. 캐시 폴더가 없다면 만듭니다.
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut text = serde_json::to_string_pretty(self)?;
+        text.push('\n');
+        atomic_file::write(path, |writer| writer.write_all(text.as_bytes()))
+    }
+
+    /// 그룹을 추가합니다. 같은 내용의 기록이 이미 있다면 경로를 합칩니다.
+    pub fn add(&mut self, entry: IgnoredGroup) {
+        let same = |g: &&mut IgnoredGroup| g.algorithm == entry.algorithm && g.size == entry.size && g.hash == entry.hash;
+        match self.groups.iter_mut().find(same) {
+            Some(existing) => {
+                for path in entry.paths {
+                    if !existing.paths.contains(&path) {
+                        existing.paths.push(path);
+                    }
+                }
+            }
+            None => self.groups.push(entry),
+        }
+    }
+
+    /// 식별자가 `id`인 기록을 지우고, 지웠는지 여부를 반환합니다.
+    /// 충돌 때문에 `-`로 경로 해시가 붙은 식별자도 받습니다.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let id = id.split('-').next().unwrap_or(id);
+        let before = self.groups.len();
+        self.groups.retain(|g| g.id != id);
+        self.groups.len() != before
+    }
+
+    /// 그룹이 무시 목록에 있는지 여부. 같은 내용이고, 멤버가 모두 기록된 경로여야 합니다.
+    fn covers(&self, group: &DuplicateGroup) -> bool {
+        self.groups.iter().any(|ignored| {
+            ignored.algorithm == group.algorithm.name()
+                && ignored.size == group.size
+                && ignored.hash == group.hash
+                && group.paths.iter().all(|p| ignored.paths.contains(&absolute(p)))
+        })
+    }
+
+    /// 무시 목록에 있는 그룹을 빼고, 뺀 그룹 수를 반환합니다.
+    pub fn hide(&self, duplicates: &mut Vec<DuplicateGroup>) -> usize {
+        let before = duplicates.len();
+        duplicates.retain(|group| !self.covers(group));
+        before - duplicates.len()
+    }
+}
+
+/// `ignore add`: 저장된 결과 파일에서 식별자로 고른 그룹들을 무시 목록에 추가합니다.
+pub fn add_from_results(list_path: &Path, results: &SavedResults, ids: &[String]) -> io::Result<()> {
+    IgnoreList::update(list_path, |list| {
+        for id in ids {
+            let group = results
+                .groups
+                .iter()
+                .find(|g| g.id.clone().unwrap_or_else(|| group_id(g.size, &g.hash)) == *id)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("결과 파일에 그룹 '{}'이(가) 없습니다", id)))?;
+            list.add(IgnoredGroup {
+                id: group_id(group.size, &group.hash),
+                algorithm: results.algorithm.clone(),
+                size: group.size,
+                hash: group.hash.clone(),
+                paths: group.paths.iter().map(|p| absolute(&results.resolve(p))).collect(),
+                added: now(),
+            });
+            println!("🙈 그룹 [{}] ({}개 파일)을(를) 무시 목록에 추가했습니다.", id, group.paths.len());
+        }
+        Ok(())
+    })
+}
+
+/// `ignore remove`: 식별자로 고른 기록을 무시 목록에서 지웁니다.
+pub fn remove(list_path: &Path, ids: &[String]) -> io::Result<()> {
+    IgnoreList::update(list_path, |list| {
+        for id in ids {
+            if !list.remove(id) {
+                return Err(io::Error::new(io::ErrorKind::NotFound, format!("무시 목록에 그룹 '{}'이(가) 없습니다", id)));
+            }
+            println!("👀 그룹 [{}]을(를) 무시 목록에서 지웠습니다. 다음 검색부터 다시 보고됩니다.", id);
+        }
+        Ok(())
+    })
+}
+
+/// `ignore list`: 무시 목록의 기록을 출력합니다.
+pub fn print(list_path: &Path) -> io::Result<()> {
+    let list = IgnoreList::load(list_path)?;
+    println!("무시 목록: {}", list_path.display());
+    if list.groups.is_empty() {
+        println!("  (비어 있음)");
+    }
+    for group in &list.groups {
+        println!(
+            "\n[{}] 파일당 {}, {}개 경로, {} 추가 ({})",
+            group.id,
+            format_size(group.size),
+            group.paths.len(),
+            group.added,
+            group.algorithm
+        );
+        for path in &group.paths {
+            println!("  - {}", path);
+        }
+    }
+    Ok(())
+}
+
+/// 경로를 현재 폴더 기준의 절대 경로 문자열로 바꿉니다. 파일 시스템은 확인하지 않습니다.
+fn absolute(path: &Path) -> String {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()).display().to_string()
+}
+
+fn now() -> String {
+    humantime::format_rfc3339_seconds(SystemTime::now()).to_string()
+}
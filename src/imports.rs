@@ -0,0 +1,311 @@
+// ==============================================================================
+//  다른 도구의 해시 가져오기 (--import-hashes)
+// ------------------------------------------------------------------------------
+//  czkawka나 fclones로 이미 해시를 계산해 둔 사용자가 이 프로그램으로 옮겨 오거나 두 도구를
+//  함께 쓸 때, 수 테라바이트를 처음부터 다시 읽지 않도록 그 도구들이 남긴 해시를 가져옵니다.
+//
+//  - czkawka: 중복 검색 캐시의 JSON 사본(`cache_duplicates_Blake3_*.json`).
+//    czkawka 설정에서 "캐시를 JSON으로도 저장"을 켜면 만들어집니다. 파일마다 크기, 수정 시간(초),
+//    전체 내용의 BLAKE3 해시가 기록되어 있습니다. (`_prehash` 캐시는 파일 앞부분의 해시라 쓸 수 없습니다)
+//  - fclones: `fclones group --format json`의 결과 보고서. 그룹마다 파일 크기와 해시가 있고,
+//    머리말에 보고서를 만든 시각과 해시 함수(`--hash-fn`)가 기록되어 있습니다.
+//  - csv: 기존 카탈로그 도구나 스크립트가 내보낸 `경로,크기,수정 시각,해시` 목록.
+//    첫 줄이 머리글(`path,size,mtime,sha256` 등)이면 열 이름으로 순서를 정하고, 아니면 이 순서로 읽습니다.
+//    수정 시각은 유닉스 초(`1714550400`, `1714550400.25`)나 RFC 3339 시각이며, 해시 알고리즘은
+//    목록에 적혀 있지 않으므로 --algorithm을 따릅니다. 경로에 쉼표가 있다면 큰따옴표로 감쌉니다.
+//
+//  가져온 해시는 파일이 그 뒤로 바뀌지 않았다고 확인될 때만 씁니다.
+//  czkawka와 csv는 크기와 수정 시간이 기록과 같아야 하고(기록에 초 미만이 없다면 초 단위로 비교), fclones는 크기가 같고 보고서를 만든 시각 이후로
+//  수정되지 않았어야 합니다. 해시 함수가 --algorithm과 다르면 새로 계산한 해시와 비교할 수 없으므로
+//  가져오지 않습니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::{FileStamp, HashAlgorithm};
+
+/// 해시를 가져올 수 있는 도구.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// czkawka의 중복 검색 캐시 (JSON 사본)
+    Czkawka,
+    /// fclones의 `group --format json` 보고서
+    Fclones,
+    /// `경로,크기,수정 시각,해시` 열의 CSV 목록
+    Csv,
+}
+
+impl ImportFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            ImportFormat::Czkawka => "czkawka",
+            ImportFormat::Fclones => "fclones",
+            ImportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// 가져온 해시가 아직 유효한지 확인하는 기준.
+enum Recorded {
+    /// 기록된 수정 시간(유닉스 초). 파일의 수정 시간이 같은 초여야 합니다. (czkawka, csv)
+    Modified(u64),
+    /// 초 미만까지 기록된 수정 시간. 파일의 수정 시간이 정확히 같아야 합니다. (csv)
+    At(SystemTime),
+    /// 보고서를 만든 시각. 파일이 그 이후로 수정되지 않았어야 합니다. (fclones)
+    NotAfter(SystemTime),
+}
+
+struct Entry {
+    len: u64,
+    recorded: Recorded,
+    hash: String,
+}
+
+/// 다른 도구에서 가져온 (경로 -> 해시) 목록.
+pub struct ImportedHashes {
+    algorithm: HashAlgorithm,
+    entries: HashMap<PathBuf, Entry>,
+}
+
+impl ImportedHashes {
+    /// 가져온 해시의 알고리즘.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    /// 가져온 해시의 수.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 파일의 해시가 있고, 기록 이후로 파일이 바뀌지 않았다면 그 해시를 반환합니다.
+    pub fn lookup(&self, path: &Path, stamp: &FileStamp) -> Option<String> {
+        let entry = self.entries.get(&std::path::absolute(path).ok()?)?;
+        let modified = stamp.modified?;
+        let unchanged = match entry.recorded {
+            Recorded::Modified(secs) => modified.duration_since(UNIX_EPOCH).ok()?.as_secs() == secs,
+            Recorded::At(time) => modified == time,
+            Recorded::NotAfter(time) => modified <= time,
+        };
+        (entry.len == stamp.len && unchanged).then(|| entry.hash.clone())
+    }
+
+    /// 형식이 올바른 해시만 추가합니다. 상대 경로는 `base` 기준으로 풀어냅니다.
+    fn insert(&mut self, base: &Path, path: &Path, len: u64, recorded: Recorded, hash: &str) {
+        let valid = hash.len() == self.algorithm.hex_len() && hash.bytes().all(|b| b.is_ascii_hexdigit());
+        if let (true, Ok(path)) = (valid, std::path::absolute(base.join(path))) {
+            self.entries.insert(path, Entry { len, recorded, hash: hash.to_ascii_lowercase() });
+        }
+    }
+}
+
+/// czkawka 캐시의 항목. (필요한 필드만 읽습니다)
+#[derive(Deserialize)]
+struct CzkawkaEntry {
+    path: PathBuf,
+    size: u64,
+    modified_date: u64,
+    hash: String,
+}
+
+/// fclones 보고서. (필요한 필드만 읽습니다)
+#[derive(Deserialize)]
+struct FclonesReport {
+    header: FclonesHeader,
+    groups: Vec<FclonesGroup>,
+}
+
+#[derive(Deserialize)]
+struct FclonesHeader {
+    timestamp: String,
+    #[serde(default)]
+    command: Vec<String>,
+    #[serde(default)]
+    base_dir: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct FclonesGroup {
+    file_len: u64,
+    file_hash: String,
+    files: Vec<PathBuf>,
+}
+
+/// `format` 형식의 파일에서 해시를 읽어 옵니다. 알고리즘을 기록하지 않는 형식(csv)은 `algorithm`으로 봅니다.
+pub fn load(format: ImportFormat, path: &Path, algorithm: HashAlgorithm) -> io::Result<ImportedHashes> {
+    // 어떤 파일이 문제였는지 알 수 있도록 오류 메시지에 경로를 붙입니다.
+    let context = |e: &dyn std::fmt::Display| format!("{} 파일 '{}': {}", format.name(), path.display(), e);
+    let invalid = |e: &dyn std::fmt::Display| io::Error::new(io::ErrorKind::InvalidData, context(e));
+    let text = fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), context(&e)))?;
+    match format {
+        ImportFormat::Czkawka => {
+            // czkawka는 해시 종류를 캐시 파일 이름에만 기록합니다. (예: cache_duplicates_Blake3_70.json)
+            let name = path.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+            if name.contains("prehash") {
+                return Err(invalid(&"파일 앞부분의 해시(prehash) 캐시는 전체 내용의 해시가 아니므로 쓸 수 없습니다"));
+            }
+            if !name.contains("blake3") {
+                return Err(invalid(&"BLAKE3 캐시(cache_duplicates_Blake3_*.json)만 가져올 수 있습니다"));
+            }
+            let cache: HashMap<String, CzkawkaEntry> = serde_json::from_str(&text).map_err(|e| {
+                invalid(&format!("{} (czkawka 설정에서 캐시를 JSON으로도 저장하도록 켠 뒤 만든 .json 파일이 필요합니다)", e))
+            })?;
+            let mut imported = ImportedHashes { algorithm: HashAlgorithm::Blake3, entries: HashMap::new() };
+            for entry in cache.into_values() {
+                let recorded = Recorded::Modified(entry.modified_date);
+                imported.insert(Path::new(""), &entry.path, entry.size, recorded, &entry.hash);
+            }
+            Ok(imported)
+        }
+        ImportFormat::Fclones => {
+            let report: FclonesReport = serde_json::from_str(&text).map_err(|e| invalid(&e))?;
+            let algorithm = fclones_algorithm(&report.header.command).map_err(|e| invalid(&e))?;
+            let Some(created) = parse_timestamp(&report.header.timestamp) else {
+                return Err(invalid(&format!("보고서 시각 '{}'을(를) 읽을 수 없습니다", report.header.timestamp)));
+            };
+            let mut imported = ImportedHashes { algorithm, entries: HashMap::new() };
+            for group in report.groups {
+                for file in group.files {
+                    let recorded = Recorded::NotAfter(created);
+                    imported.insert(&report.header.base_dir, &file, group.file_len, recorded, &group.file_hash);
+                }
+            }
+            Ok(imported)
+        }
+        ImportFormat::Csv => {
+            let mut imported = ImportedHashes { algorithm, entries: HashMap::new() };
+            load_csv(&mut imported, &text).map_err(|e| invalid(&e))?;
+            Ok(imported)
+        }
+    }
+}
+
+/// CSV 목록을 읽습니다. 형식이 틀린 줄이 있으면 그 줄 번호와 함께 실패합니다.
+fn load_csv(imported: &mut ImportedHashes, text: &str) -> Result<(), String> {
+    // 기본 열 순서: 경로, 크기, 수정 시각, 해시
+    let mut columns = [0, 1, 2, 3];
+    let rows = text.trim_start_matches('\u{feff}').lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    for (index, (number, line)) in rows.enumerate() {
+        let at = |message: &str| format!("{}번째 줄: {}", number + 1, message);
+        let fields = csv_fields(line).ok_or_else(|| at("닫히지 않은 큰따옴표가 있습니다"))?;
+        // 첫 줄의 크기 열이 숫자가 아니면 머리글로 보고 열 이름으로 순서를 정합니다.
+        if index == 0 && fields.get(1).is_none_or(|size| size.trim().parse::<u64>().is_err()) {
+            columns = header_columns(&fields).map_err(|e| at(&e))?;
+            continue;
+        }
+        let field = |column: usize| fields.get(columns[column]).map(|f| f.trim());
+        let (Some(path), Some(size), Some(mtime), Some(hash)) = (field(0), field(1), field(2), field(3)) else {
+            return Err(at("경로, 크기, 수정 시각, 해시 열이 모두 있어야 합니다"));
+        };
+        let size = size.parse().map_err(|_| at(&format!("크기 '{}'이(가) 숫자가 아닙니다", size)))?;
+        let recorded = parse_mtime(mtime).ok_or_else(|| at(&format!("수정 시각 '{}'을(를) 읽을 수 없습니다", mtime)))?;
+        imported.insert(Path::new(""), Path::new(path), size, recorded, hash);
+    }
+    Ok(())
+}
+
+/// 머리글에서 (경로, 크기, 수정 시각, 해시) 열의 위치를 찾습니다. 이름은 대소문자를 가리지 않습니다.
+fn header_columns(names: &[String]) -> Result<[usize; 4], String> {
+    const NAMES: [(&str, &[&str]); 4] = [
+        ("경로", &["path", "file", "filename", "name"]),
+        ("크기", &["size", "bytes", "length"]),
+        ("수정 시각", &["mtime", "modified", "modified_time", "last_modified"]),
+        ("해시", &["hash", "checksum", "digest", "md5", "sha1", "sha256", "blake3"]),
+    ];
+    let mut columns = [0; 4];
+    for (column, (label, aliases)) in columns.iter_mut().zip(NAMES) {
+        *column = names
+            .iter()
+            .position(|name| aliases.contains(&name.trim().to_ascii_lowercase().as_str()))
+            .ok_or_else(|| format!("머리글에 {} 열({})이 없습니다", label, aliases.join(", ")))?;
+    }
+    Ok(columns)
+}
+
+/// CSV 한 줄을 필드로 나눕니다. 큰따옴표로 감싼 필드 안의 쉼표와 `""`(따옴표 하나)를 처리합니다.
+/// 따옴표가 닫히지 않았다면 `None`입니다.
+fn csv_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    (!quoted).then_some(fields)
+}
+
+/// CSV의 수정 시각을 읽습니다. 유닉스 초(소수점 이하 허용)나 RFC 3339 시각입니다.
+fn parse_mtime(text: &str) -> Option<Recorded> {
+    let time = match text.split_once('.') {
+        // 시간대가 없는 시각은 UTC로 봅니다.
+        _ if text.contains(['-', ':']) => parse_timestamp(text).or_else(|| humantime::parse_rfc3339_weak(text).ok())?,
+        Some((secs, fraction)) if !fraction.is_empty() && fraction.len() <= 9 => {
+            let nanos: u32 = format!("{:0<9}", fraction).parse().ok()?;
+            UNIX_EPOCH + Duration::new(secs.parse().ok()?, nanos)
+        }
+        Some(_) => return None,
+        None => UNIX_EPOCH + Duration::from_secs(text.parse().ok()?),
+    };
+    let since = time.duration_since(UNIX_EPOCH).ok()?;
+    Some(if since.subsec_nanos() == 0 { Recorded::Modified(since.as_secs()) } else { Recorded::At(time) })
+}
+
+/// fclones를 실행한 명령줄에서 `--hash-fn`을 찾아 알고리즘을 정합니다. (기본값은 metro)
+fn fclones_algorithm(command: &[String]) -> Result<HashAlgorithm, String> {
+    let mut hash_fn = "metro";
+    for (i, arg) in command.iter().enumerate() {
+        if let Some(value) = arg.strip_prefix("--hash-fn=") {
+            hash_fn = value;
+        } else if arg == "--hash-fn"
+            && let Some(value) = command.get(i + 1)
+        {
+            hash_fn = value;
+        }
+    }
+    match hash_fn {
+        "blake3" => Ok(HashAlgorithm::Blake3),
+        "sha256" => Ok(HashAlgorithm::Sha256),
+        other => Err(format!(
+            "해시 함수 '{}'은(는) 이 프로그램이 계산할 수 없습니다. fclones를 --hash-fn blake3 또는 sha256으로 실행한 보고서가 필요합니다",
+            other
+        )),
+    }
+}
+
+/// RFC 3339 시각(`2024-05-01T12:00:00.123+09:00`, `...Z`)을 읽습니다.
+/// humantime은 UTC(`Z`)만 읽으므로 시간대 오프셋은 직접 처리합니다.
+fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    if let Some(utc) = text.strip_suffix('Z') {
+        return humantime::parse_rfc3339_weak(utc).ok();
+    }
+    let split = text.len().checked_sub(6)?;
+    let (local, offset) = (text.get(..split)?, text.get(split..)?);
+    let (sign, offset) = offset.split_at_checked(1)?;
+    let (hours, minutes) = offset.split_once(':')?;
+    let offset = Duration::from_secs(hours.parse::<u64>().ok()? * 3600 + minutes.parse::<u64>().ok()? * 60);
+    let local = humantime::parse_rfc3339_weak(local).ok()?;
+    // 현지 시각에서 오프셋을 되돌려 UTC로 바꿉니다.
+    match sign {
+        "+" => local.checked_sub(offset),
+        "-" => local.checked_add(offset),
+        _ => None,
+    }
+}
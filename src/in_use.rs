@@ -0,0 +1,91 @@
+// ==============================================================================
+//  다른 프로세스가 잠갔거나 쓰고 있는 파일 감지
+// ------------------------------------------------------------------------------
+//  데이터베이스, 가상 머신 이미지, 내려받는 중인 파일처럼 다른 프로세스가 쥐고 있는 파일은
+//  해시를 계산하는 동안 내용이 바뀌어 엉뚱한 그룹에 들어가거나, 삭제 도중에 실패하기 쉽습니다.
+//  해시 계산과 삭제 직전에 아래 방법으로 확인하여, 사용 중인 파일은 건너뛰고 오류 요약에 남깁니다.
+//
+//  - 유닉스: 다른 프로세스가 파일 전체나 일부에 건 쓰기 잠금(fcntl)과 배타 잠금(flock)을 확인합니다.
+//    이런 잠금은 권고(advisory) 잠금이라, 잠금을 쓰지 않고 파일을 쓰는 프로그램은 알아낼 수 없습니다.
+//    (그런 변경은 해시 계산 전후의 크기/수정 시간 비교로 걸러집니다)
+//  - Windows: 쓰기를 허용하지 않는 공유 모드로 잠깐 열어 봅니다. 다른 프로세스가 쓰기용으로
+//    열었거나 배타적으로 열었다면 공유 위반으로 실패합니다. 바이트 범위 잠금도 함께 확인합니다.
+// ==============================================================================
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io;
+use std::path::Path;
+
+/// 다른 프로세스가 파일을 잠갔거나 쓰고 있다면 그 이유를 반환합니다.
+/// 확인을 위해 파일을 열 수 없다면 `Err`입니다.
+pub fn check(path: &Path) -> io::Result<Option<&'static str>> {
+    let file = match open_probe(path) {
+        Ok(file) => file,
+        #[cfg(windows)]
+        Err(e) if matches!(e.raw_os_error(), Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)) => {
+            return Ok(Some("다른 프로세스가 쓰기용으로 열었거나 배타적으로 열었습니다"));
+        }
+        Err(e) => return Err(e),
+    };
+    #[cfg(unix)]
+    if record_locked(&file)? {
+        return Ok(Some("다른 프로세스가 쓰기 잠금(fcntl)을 걸었습니다"));
+    }
+    // 공유 잠금을 잠깐 걸어 봅니다. 유닉스에서는 flock, Windows에서는 바이트 범위 잠금입니다.
+    match file.try_lock_shared() {
+        Ok(()) => {
+            file.unlock()?;
+            Ok(None)
+        }
+        Err(TryLockError::WouldBlock) => Ok(Some("다른 프로세스가 배타 잠금을 걸었습니다")),
+        Err(TryLockError::Error(e)) if e.kind() == io::ErrorKind::Unsupported => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// 사용 중인 파일이라면 `ResourceBusy` 오류를 반환합니다. 삭제 직전에 씁니다.
+/// 읽기 권한이 없어 확인할 수 없는 파일은 삭제 자체의 결과에 맡깁니다.
+pub fn ensure_idle(path: &Path) -> io::Result<()> {
+    match check(path) {
+        Ok(Some(reason)) => Err(io::Error::new(io::ErrorKind::ResourceBusy, reason)),
+        Ok(None) | Err(_) => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+const ERROR_SHARING_VIOLATION: i32 = 32;
+#[cfg(windows)]
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+/// 확인용으로 파일을 읽기 전용으로 엽니다. Windows에서는 다른 프로세스의 쓰기를 허용하지 않는
+/// 공유 모드로 열어, 이미 쓰기용으로 열린 파일이면 실패하게 합니다.
+fn open_probe(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true);
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_SHARE_READ | FILE_SHARE_DELETE: 쓰기 공유만 거부합니다.
+        options.share_mode(0x1 | 0x4);
+    }
+    options.open(path)
+}
+
+/// 파일의 어느 부분에라도 다른 프로세스의 fcntl 쓰기 잠금이 걸려 있는지 확인합니다.
+#[cfg(unix)]
+fn record_locked(file: &File) -> io::Result<bool> {
+    use std::os::fd::AsRawFd;
+    // 파일 전체(l_start 0, l_len 0)에 읽기 잠금을 건다고 가정하고, 충돌하는 잠금이 있는지 묻습니다.
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_RDLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) } != 0 {
+        let e = io::Error::last_os_error();
+        // 잠금을 지원하지 않는 파일 시스템이라면 잠금도 없는 것으로 봅니다.
+        if matches!(e.raw_os_error(), Some(libc::ENOLCK | libc::EINVAL | libc::EOPNOTSUPP)) {
+            return Ok(false);
+        }
+        return Err(e);
+    }
+    Ok(lock.l_type != libc::F_UNLCK as libc::c_short)
+}
@@ -0,0 +1,80 @@
+// ==============================================================================
+//  Ctrl+C 처리
+// ------------------------------------------------------------------------------
+//  스캔 도중 Ctrl+C를 누르면 프로세스를 즉시 죽이는 대신 "중단 요청" 플래그만
+//  세웁니다. 스캔 루프는 이 플래그를 주기적으로 확인하여 하던 파일까지만 처리하고
+//  빠져나오며, 그때까지 확인된 중복 그룹과 요약을 출력합니다.
+//  부분 결과 출력조차 기다리기 싫다면 Ctrl+C를 한 번 더 누르면 즉시 종료합니다.
+//  데몬 모드를 서비스 관리자(systemd 등)가 멈출 수 있도록 SIGTERM도 같은 방식으로 처리합니다.
+//  --max-runtime의 시간 제한도 같은 플래그를 세워, 제한 시간이 지나면 Ctrl+C를 누른 것처럼
+//  하던 파일까지만 처리하고 멈춥니다. 시간 제한은 별도 스레드 없이 플래그를 확인할 때 마감 시각과
+//  비교하므로, 스레드를 만들 수 없는 WASI 환경에서도 동작합니다.
+//  WASI에는 시그널이 없으므로 `native` 기능 없이 빌드하면 Ctrl+C 핸들러는 설치하지 않습니다.
+// ==============================================================================
+
+#[cfg(feature = "native")]
+use std::process;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// 중단 요청 여부. 시그널 핸들러 스레드와 스캔 스레드가 함께 접근하므로 원자적 타입을 사용합니다.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// 중단 요청이 사용자가 아니라 --max-runtime의 시간 제한에서 왔는지 여부.
+static TIME_LIMIT: AtomicBool = AtomicBool::new(false);
+
+/// --max-runtime의 마감 시각.
+static DEADLINE: OnceLock<Instant> = OnceLock::new();
+
+/// 두 번째 Ctrl+C로 즉시 종료할 때 사용하는 종료 코드 (128 + SIGINT).
+pub const FORCED_EXIT_CODE: i32 = 130;
+
+/// Ctrl+C(SIGINT)와 SIGTERM 핸들러를 설치합니다. 설치에 실패해도 스캔은 계속할 수 있으므로 경고만 출력합니다.
+#[cfg(feature = "native")]
+pub fn install_handler() {
+    let result = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            // 이미 중단 요청을 받은 상태에서 또 눌렀다면 더 기다리지 않습니다.
+            eprintln!("\n강제 종료합니다.");
+            process::exit(FORCED_EXIT_CODE);
+        }
+        eprintln!("\n⏹️  중단 요청을 받았습니다. 지금까지의 결과를 정리합니다... (즉시 종료하려면 한 번 더 누르세요)");
+    });
+    if let Err(e) = result {
+        eprintln!("경고: Ctrl+C 핸들러를 설치할 수 없습니다: {}", e);
+    }
+}
+
+/// 시그널을 받을 수 없는 빌드(WASI)에서는 핸들러 없이 --max-runtime으로만 중단합니다.
+#[cfg(not(feature = "native"))]
+pub fn install_handler() {}
+
+/// 사용자가 중단을 요청했거나 --max-runtime의 마감 시각이 지났는지 반환합니다.
+pub fn requested() -> bool {
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        return true;
+    }
+    let Some(deadline) = DEADLINE.get() else { return false };
+    if Instant::now() < *deadline {
+        return false;
+    }
+    TIME_LIMIT.store(true, Ordering::SeqCst);
+    if !INTERRUPTED.swap(true, Ordering::SeqCst) {
+        eprintln!("\n⏱️  --max-runtime 시간이 다 되었습니다. 지금까지의 결과를 정리합니다...");
+    }
+    true
+}
+
+/// 지금부터 `limit`이 지나면 중단 요청으로 보도록 마감 시각을 정합니다. (--max-runtime)
+pub fn start_time_limit(limit: Duration) {
+    // 너무 먼 마감 시각은 표현할 수 없으므로 제한이 없는 것으로 봅니다.
+    if let Some(deadline) = Instant::now().checked_add(limit) {
+        let _ = DEADLINE.set(deadline);
+    }
+}
+
+/// 중단 요청이 --max-runtime의 시간 제한 때문이었는지 반환합니다.
+pub fn time_limit_reached() -> bool {
+    TIME_LIMIT.load(Ordering::SeqCst)
+}
@@ -0,0 +1,251 @@
+// ==============================================================================
+//  그룹마다 남길 원본을 고르는 규칙 (--keep)
+// ------------------------------------------------------------------------------
+//  중복 그룹을 정리하는 기능(제외 목록 생성 등)은 "어느 사본을 남기고 나머지를 중복으로
+//  볼 것인가"를 정해야 합니다. 이 모듈은 그 규칙을 한 곳에 모아, 각 기능이 같은 기준으로
+//  원본(keeper)을 고르도록 합니다.
+//
+//  단순한 규칙(최신, 가장 오래된, 경로가 짧은)으로는 "Downloads의 사본보다 정리된 사진
+//  보관함의 사본을 남긴다" 같은 판단을 할 수 없으므로, `--keep score`는 여러 기준의 점수를
+//  더해 가장 높은 멤버를 남깁니다.
+//
+//  점수 = 폴더 우선순위(--prefer-dir)
+//       - depth × 폴더 깊이
+//       + name  × 파일 이름 품질 (복사본 표시가 없으면 1, 있으면 표시마다 1씩 감소)
+//       + mtime × 수정 시간 순위 (그룹에서 가장 오래된 파일 1 ~ 가장 최근 파일 0)
+//       + exif  × EXIF 정보 유무 (있으면 1)
+//  각 가중치는 --score-weights로 바꿀 수 있습니다.
+//
+//  `file (1).jpg`, `file - Copy.txt`, `file.bak`, `file_final_v2.doc`처럼 이름에 사본 표시가 붙은 파일은
+//  거의 항상 실수로 생긴 사본입니다. score가 아닌 규칙도 사본 표시가 가장 적은 멤버들 중에서만 원본을
+//  고르므로, 예를 들어 `--keep newest`라도 `file.jpg`와 `file (1).jpg` 중에서는 `file.jpg`를 남깁니다.
+//  알아보는 표시 (대소문자 구분 없음):
+//  - 끝의 ` (N)`, `(N)` (브라우저 다운로드, 탐색기), ` (copy)`, ` (another copy)`, ` (3rd copy)` (Nautilus)
+//  - ` - Copy`, ` - 복사본`, ` - 사본` (Windows 탐색기), ` copy`, ` copy N` (Finder), 앞의 `Copy of `
+//  - `_final`, `-final`, ` final`과 `_v2`, `-v3`, ` v10` 같은 버전 표시
+//  - 끝의 `~`와 `.bak`, `.old`, `.orig`, `.backup`, `.tmp` 확장자
+// ==============================================================================
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use clap::ValueEnum;
+
+/// 그룹마다 원본으로 남길 파일을 고르는 규칙.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeepPolicy {
+    /// 경로 순서상 첫 번째 파일. (--no-sort를 쓰지 않았다면 사전순으로 가장 앞선 경로)
+    /// score가 아닌 규칙은 모두 이름에 사본 표시가 가장 적은 멤버들 중에서 고릅니다.
+    #[default]
+    First,
+    /// 수정 시간이 가장 최근인 파일.
+    Newest,
+    /// 수정 시간이 가장 오래된 파일.
+    Oldest,
+    /// 경로가 가장 짧은(폴더 깊이가 얕은) 파일.
+    ShortestPath,
+    /// 폴더 우선순위, 폴더 깊이, 파일 이름, 수정 시간, EXIF 유무의 점수가 가장 높은 파일.
+    Score,
+}
+
+/// `--keep score`의 기준별 가중치.
+#[derive(Clone, Copy, Debug)]
+pub struct ScoreWeights {
+    pub depth: f64,
+    pub name: f64,
+    pub mtime: f64,
+    pub exif: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights { depth: 1.0, name: 5.0, mtime: 2.0, exif: 3.0 }
+    }
+}
+
+/// `--score-weights depth=1,name=5,mtime=2,exif=3` 값을 읽습니다. 빠진 항목은 기본값을 씁니다.
+pub fn parse_score_weights(value: &str) -> Result<ScoreWeights, String> {
+    let mut weights = ScoreWeights::default();
+    for item in value.split(',').filter(|s| !s.trim().is_empty()) {
+        let (key, number) = item.split_once('=').ok_or_else(|| format!("'{}'은(는) 이름=값 형식이 아닙니다", item))?;
+        let number: f64 = number.trim().parse().map_err(|_| format!("'{}'은(는) 숫자가 아닙니다", number))?;
+        match key.trim() {
+            "depth" => weights.depth = number,
+            "name" => weights.name = number,
+            "mtime" => weights.mtime = number,
+            "exif" => weights.exif = number,
+            other => return Err(format!("알 수 없는 항목 '{}' (depth, name, mtime, exif)", other)),
+        }
+    }
+    Ok(weights)
+}
+
+/// `--prefer-dir 경로=가중치` 값을 읽습니다. (예: `~/Pictures/Library=50`, `~/Downloads=-20`)
+pub fn parse_dir_weight(value: &str) -> Result<(PathBuf, f64), String> {
+    let (path, number) = value.rsplit_once('=').ok_or("경로=가중치 형식이어야 합니다")?;
+    let weight = number.trim().parse().map_err(|_| format!("'{}'은(는) 숫자가 아닙니다", number))?;
+    Ok((PathBuf::from(path), weight))
+}
+
+/// 원본을 고르는 규칙과 점수 설정의 묶음.
+pub struct KeepRule {
+    pub policy: KeepPolicy,
+    /// 폴더별 우선순위 가중치. 경로가 여러 폴더에 속하면 가장 구체적인(긴) 폴더의 값을 씁니다.
+    dirs: Vec<(PathBuf, f64)>,
+    weights: ScoreWeights,
+}
+
+impl KeepRule {
+    pub fn new(policy: KeepPolicy, dirs: &[(PathBuf, f64)], weights: ScoreWeights) -> Self {
+        // 멤버 경로와 비교할 수 있도록 폴더를 정규화합니다. (존재하지 않는 폴더는 그대로 둡니다)
+        let dirs = dirs.iter().map(|(dir, weight)| (dir.canonicalize().unwrap_or_else(|_| dir.clone()), *weight)).collect();
+        KeepRule { policy, dirs, weights }
+    }
+
+    /// `paths` 중 원본으로 남길 파일의 위치를 반환합니다. `paths`는 비어 있지 않아야 합니다.
+    /// 같은 조건의 후보가 여럿이면 앞에 있는 파일을 고릅니다.
+    pub fn keeper(&self, paths: &[PathBuf]) -> usize {
+        if self.policy == KeepPolicy::Score {
+            // 점수에는 이름 품질이 이미 들어 있으므로 후보를 줄이지 않습니다.
+            let scores = self.scores(paths);
+            return (0..paths.len()).fold(0, |best, i| if scores[i] > scores[best] { i } else { best });
+        }
+        // 사본 표시가 가장 적은 멤버들만 후보로 둡니다.
+        let markers: Vec<usize> = paths.iter().map(|p| copy_markers(p)).collect();
+        let fewest = markers.iter().min().copied().unwrap_or(0);
+        let candidates = paths.iter().enumerate().filter(|(i, _)| markers[*i] == fewest);
+        let best = match self.policy {
+            KeepPolicy::First | KeepPolicy::Score => candidates.min_by_key(|(i, _)| *i),
+            KeepPolicy::Newest => candidates.max_by(|(ia, a), (ib, b)| modified(a).cmp(&modified(b)).then(ib.cmp(ia))),
+            // 수정 시간을 읽을 수 없는 파일(None)은 가장 오래된 것으로 보지 않도록 뒤로 보냅니다.
+            KeepPolicy::Oldest => {
+                candidates.min_by_key(|(_, p)| modified(p).map_or((1, SystemTime::UNIX_EPOCH), |t| (0, t)))
+            }
+            KeepPolicy::ShortestPath => candidates.min_by_key(|(_, p)| (p.components().count(), p.as_os_str().len())),
+        };
+        best.map_or(0, |(index, _)| index)
+    }
+
+    /// 원본을 제외한 나머지(중복으로 볼) 파일들.
+    pub fn redundant<'a>(&self, paths: &'a [PathBuf]) -> impl Iterator<Item = &'a PathBuf> + use<'a> {
+        let keeper = self.keeper(paths);
+        paths.iter().enumerate().filter(move |(i, _)| *i != keeper).map(|(_, p)| p)
+    }
+
+    /// 그룹 멤버 각각의 점수. (`--keep score`의 판단 근거로 출력에도 씁니다)
+    pub fn scores(&self, paths: &[PathBuf]) -> Vec<f64> {
+        let times: Vec<Option<SystemTime>> = paths.iter().map(|p| modified(p)).collect();
+        let oldest = times.iter().flatten().min().copied();
+        let newest = times.iter().flatten().max().copied();
+        paths
+            .iter()
+            .zip(&times)
+            .map(|(path, time)| {
+                let resolved = path.canonicalize().unwrap_or_else(|_| path.clone());
+                let dir = self
+                    .dirs
+                    .iter()
+                    .filter(|(dir, _)| resolved.starts_with(dir))
+                    .max_by_key(|(dir, _)| dir.components().count())
+                    .map_or(0.0, |(_, weight)| *weight);
+                let depth = resolved.components().count().saturating_sub(1) as f64;
+                // 그룹에서 가장 오래된 파일이 1, 가장 최근 파일이 0입니다.
+                let age = match (time, oldest, newest) {
+                    (Some(t), Some(old), Some(new)) if new > old => {
+                        let span = new.duration_since(old).unwrap_or_default().as_secs_f64();
+                        new.duration_since(*t).unwrap_or_default().as_secs_f64() / span
+                    }
+                    _ => 0.0,
+                };
+                dir - self.weights.depth * depth
+                    + self.weights.name * name_quality(path)
+                    + self.weights.mtime * age
+                    + self.weights.exif * f64::from(u8::from(has_exif(path)))
+            })
+            .collect()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::symlink_metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// 백업 사본에 붙는 확장자.
+const BACKUP_EXTENSIONS: [&str; 5] = ["bak", "old", "orig", "backup", "tmp"];
+
+/// 이름 끝에 붙는 사본 표시. (탐색기, Finder, 그리고 "최종" 표시)
+const COPY_SUFFIXES: [&str; 9] = [" - copy", " - 복사본", " - 사본", " copy", " 복사본", " 사본", "_final", "-final", " final"];
+
+/// 파일 이름 품질: 복사본 표시가 없으면 1, 표시가 하나 있을 때마다 1씩 줄어듭니다.
+fn name_quality(path: &Path) -> f64 {
+    1.0 - copy_markers(path) as f64
+}
+
+/// 파일 이름에 붙은 사본 표시의 수. 표시를 끝에서부터 하나씩 떼어 내며 셉니다.
+/// (`report_final_v2 (1).doc.bak`은 `.bak`, ` (1)`, `_v2`, `_final`로 4개)
+fn copy_markers(path: &Path) -> usize {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_lowercase()) else { return 0 };
+    let mut count = 0;
+    let mut rest = name.as_str();
+    // 백업 표시(`~`, `.bak` 등)는 원래 확장자 뒤에 붙습니다.
+    loop {
+        if let Some(stripped) = rest.strip_suffix('~') {
+            rest = stripped;
+        } else if let Some((stem, extension)) = rest.rsplit_once('.')
+            && !stem.is_empty()
+            && BACKUP_EXTENSIONS.contains(&extension)
+        {
+            rest = stem;
+        } else {
+            break;
+        }
+        count += 1;
+    }
+    // 나머지 표시는 원래 확장자 앞의 이름 부분에 붙습니다.
+    let mut stem = match rest.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => rest,
+    };
+    if let Some(stripped) = stem.strip_prefix("copy of ") {
+        stem = stripped;
+        count += 1;
+    }
+    while let Some(stripped) = strip_copy_suffix(stem) {
+        stem = stripped.trim_end();
+        count += 1;
+    }
+    count
+}
+
+/// 이름 끝의 사본 표시 하나를 떼어 냅니다. 표시가 없으면 `None`.
+fn strip_copy_suffix(stem: &str) -> Option<&str> {
+    let is_number = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    // ` (1)`, ` (another copy)`, ` (3rd copy)`
+    if let Some((before, inner)) = stem.strip_suffix(')').and_then(|s| s.rsplit_once('('))
+        && (is_number(inner) || inner.ends_with("copy"))
+    {
+        return Some(before);
+    }
+    if let Some(stripped) = COPY_SUFFIXES.iter().find_map(|suffix| stem.strip_suffix(suffix)) {
+        return Some(stripped);
+    }
+    // Finder의 ` copy 2`와 버전 표시 `_v2`
+    let (before, last) = stem.rsplit_once([' ', '_', '-'])?;
+    if is_number(last) && before.ends_with(" copy") {
+        return Some(&before[..before.len() - " copy".len()]);
+    }
+    last.strip_prefix('v').filter(|version| is_number(version)).map(|_| before)
+}
+
+/// 파일 앞부분에 EXIF 블록의 시작 표시(`Exif\0\0`, JPEG의 APP1과 HEIC에서 사용)가 있는지 확인합니다.
+fn has_exif(path: &Path) -> bool {
+    const HEAD: u64 = 64 * 1024;
+    let read = || -> io::Result<Vec<u8>> {
+        let mut head = Vec::new();
+        File::open(path)?.take(HEAD).read_to_end(&mut head)?;
+        Ok(head)
+    };
+    read().is_ok_and(|head| head.windows(6).any(|w| w == b"Exif\0\0"))
+}
@@ -0,0 +1,719 @@
+// ==============================================================================
+//  메일 첨부 파일의 중복 분석 (mail 하위 명령)
+// ------------------------------------------------------------------------------
+//  문서가 메일로 오가면 같은 첨부 파일이 답장, 전달, 참조마다 다시 저장되고, 그중 일부는 내려받아
+//  디스크에도 있습니다. 메일함 파일은 메일 전체가 한 파일이므로 파일 단위의 검색으로는 찾을 수 없습니다.
+//
+//  이 모듈은 메일함을 읽어 첨부 파일을 풀지 않고 메모리에서 디코딩하여 내용 해시로 색인한 뒤 다음을
+//  보고합니다.
+//  - 메일 사이에 여러 번 저장된 첨부 파일
+//  - 폴더(-r)를 함께 지정하면, 디스크에도 같은 내용의 파일이 있는 첨부 파일
+//
+//  읽는 메일함은 다음과 같습니다.
+//  - 파일: 첫 줄이 `From `으로 시작하면 mbox(메일 여러 개), 아니면 메일 하나(.eml)로 읽습니다.
+//  - 폴더: Maildir의 `cur`, `new` 폴더에 있는 메일과, 폴더 아래의 `.eml`, `.mbox`, `mbox` 파일을 읽습니다.
+//    (Thunderbird처럼 확장자가 없는 mbox 파일은 파일로 직접 지정합니다)
+//  MIME 구조를 따라 multipart와 전달된 메일(message/rfc822) 안까지 찾으며, 파일 이름이 있거나
+//  `Content-Disposition: attachment`인 부분을 첨부 파일로 봅니다. 본문과 크기가 0인 첨부 파일은 뺍니다.
+//  base64와 quoted-printable로 인코딩된 내용은 디코딩하여, 디스크의 원래 파일과 같은 해시가 되게 합니다.
+// ==============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::hashing::HashAlgorithm;
+use crate::{Progress, calculate_hash, format_count, format_size, interrupt};
+
+/// 이보다 깊이 중첩된 MIME 부분은 따라가지 않습니다. (잘못 만든 메일로 스택이 넘치지 않도록)
+const MAX_DEPTH: usize = 32;
+
+/// 첨부 파일 하나가 저장된 곳.
+struct Attachment {
+    /// 메일 위치: 메일 파일의 경로, mbox라면 `경로 #번호`.
+    message: String,
+    subject: String,
+    filename: String,
+}
+
+/// 분석 결과를 종료 코드로 나타내기 위한 요약.
+pub struct Summary {
+    /// 여러 번 저장되었거나 디스크에도 있는 첨부 파일이 있습니다.
+    pub redundant: bool,
+    /// 읽지 못한 메일함이나 파일이 있거나 중단되었습니다.
+    pub failed: bool,
+}
+
+/// 메일함들의 첨부 파일을 색인하고, 메일 사이와 `roots` 폴더의 파일 사이의 중복을 보고합니다.
+pub fn report(mailboxes: &[PathBuf], roots: &[PathBuf], algorithm: HashAlgorithm, top: usize) -> Summary {
+    println!("✉️  메일함 {}개의 첨부 파일을 내용 해시({})로 색인합니다...", mailboxes.len(), algorithm.name());
+    let mut progress = Progress::new();
+    // 진행 표시와 섞이지 않도록 읽기 오류는 모아 두었다가 색인이 끝난 뒤 출력합니다.
+    let mut errors: Vec<String> = Vec::new();
+    let mut messages = 0u64;
+    // (크기, 해시) -> 그 내용이 첨부된 곳들.
+    let mut contents: HashMap<(u64, String), Vec<Attachment>> = HashMap::new();
+    let mut index = |message: String, raw: &[u8]| {
+        messages += 1;
+        progress.update("메일 읽는 중", messages, None);
+        let mut found = Vec::new();
+        let subject = parse_message(raw, &mut |filename, data| found.push((filename, data)));
+        for (filename, data) in found {
+            let key = (data.len() as u64, algorithm.digest(&data));
+            contents.entry(key).or_default().push(Attachment { message: message.clone(), subject: subject.clone(), filename });
+        }
+    };
+    for mailbox in mailboxes {
+        if interrupt::requested() {
+            break;
+        }
+        let result = match fs::metadata(mailbox) {
+            Ok(metadata) if metadata.is_dir() => read_folder(mailbox, &mut errors, &mut index),
+            Ok(_) => read_file(mailbox, &mut index),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            errors.push(format!("'{}': {}", mailbox.display(), e));
+        }
+    }
+    progress.finish();
+
+    // 디스크에서는 첨부 파일과 크기가 같은 파일만 해시합니다.
+    let mut on_disk: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+    if !roots.is_empty() && !interrupt::requested() {
+        let sizes: HashSet<u64> = contents.keys().map(|(size, _)| *size).collect();
+        let mut checked = 0u64;
+        for root in roots {
+            for entry in WalkDir::new(root) {
+                if interrupt::requested() {
+                    break;
+                }
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        errors.push(e.to_string());
+                        continue;
+                    }
+                };
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let size = match entry.metadata() {
+                    Ok(metadata) => metadata.len(),
+                    Err(e) => {
+                        errors.push(format!("'{}': {}", entry.path().display(), e));
+                        continue;
+                    }
+                };
+                if !sizes.contains(&size) {
+                    continue;
+                }
+                checked += 1;
+                progress.update("디스크 파일 비교 중", checked, None);
+                match calculate_hash(entry.path(), algorithm) {
+                    Ok(hash) if contents.contains_key(&(size, hash.clone())) => {
+                        on_disk.entry((size, hash)).or_default().push(entry.into_path());
+                    }
+                    Ok(_) => {}
+                    Err(e) => errors.push(format!("'{}': {}", entry.path().display(), e)),
+                }
+            }
+        }
+        progress.finish();
+    }
+    for error in &errors {
+        eprintln!("⚠️  {}", error);
+    }
+
+    let attachments: u64 = contents.values().map(|places| places.len() as u64).sum();
+    let bytes: u64 = contents.iter().map(|((size, _), places)| size * places.len() as u64).sum();
+    println!(
+        "\n📎 메일 {}개에서 첨부 파일 {}개 ({})를 찾았습니다. (서로 다른 내용 {}개)",
+        format_count(messages),
+        format_count(attachments),
+        format_size(bytes),
+        format_count(contents.len())
+    );
+    let repeated: Vec<(&(u64, String), &Vec<Attachment>)> = contents.iter().filter(|(_, places)| places.len() > 1).collect();
+    println!(
+        "메일 사이에 여러 번 저장된 첨부 파일: 내용 {}개, 회수 가능 {}",
+        format_count(repeated.len()),
+        format_size(repeated.iter().map(|((size, _), places)| size * (places.len() as u64 - 1)).sum())
+    );
+    if !roots.is_empty() {
+        println!(
+            "디스크에도 있는 첨부 파일: 내용 {}개 ({})",
+            format_count(on_disk.len()),
+            format_size(on_disk.keys().map(|(size, _)| size).sum())
+        );
+    }
+
+    // 메일과 디스크를 합쳐 두 번 이상 저장된 내용을, 회수 가능 공간이 큰 순서(같으면 해시 순서)로 보여줍니다.
+    let copies = |key: &(u64, String), places: &Vec<Attachment>| places.len() + on_disk.get(key).map_or(0, Vec::len);
+    let mut redundant: Vec<(&(u64, String), &Vec<Attachment>)> =
+        contents.iter().filter(|(key, places)| copies(key, places) > 1).collect();
+    let wasted = |key: &(u64, String), places: &Vec<Attachment>| key.0 * (copies(key, places) as u64 - 1);
+    redundant.sort_by(|(a, pa), (b, pb)| wasted(b, pb).cmp(&wasted(a, pa)).then_with(|| a.1.cmp(&b.1)));
+    if top > 0 && !redundant.is_empty() {
+        println!("\n회수 가능 공간이 큰 내용 상위 {}개:", top.min(redundant.len()));
+    }
+    for (i, (key, places)) in redundant.iter().take(top).enumerate() {
+        let disk = on_disk.get(*key).map(Vec::as_slice).unwrap_or_default();
+        println!("\n--- 내용 {} (메일 {}번, 디스크 {}개, 파일당 {}) ---", i + 1, places.len(), disk.len(), format_size(key.0));
+        for place in places.iter() {
+            println!("  ✉️  {} — {} ('{}')", place.message, place.filename, place.subject);
+        }
+        for path in disk {
+            println!("  💾 {}", path.display());
+        }
+    }
+    if interrupt::requested() {
+        println!("\n⏹️  중단되었습니다. 위 결과는 일부 메일과 파일만 읽은 것입니다.");
+    }
+
+    Summary { redundant: !redundant.is_empty(), failed: !errors.is_empty() || interrupt::requested() }
+}
+
+/// 폴더 아래의 Maildir 메일과 .eml, .mbox, mbox 파일을 읽습니다. 읽지 못한 파일은 `errors`에 남깁니다.
+fn read_folder(folder: &Path, errors: &mut Vec<String>, index: &mut impl FnMut(String, &[u8])) -> io::Result<()> {
+    for entry in WalkDir::new(folder).sort_by_file_name() {
+        if interrupt::requested() {
+            break;
+        }
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(e.to_string());
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let in_maildir = path.parent().and_then(Path::file_name).is_some_and(|name| name == "cur" || name == "new");
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        let result = match extension.as_deref() {
+            // Apple Mail은 `이름.mbox` 폴더 안에 확장자 없는 `mbox` 파일로 저장합니다.
+            Some("mbox") => read_file(path, index),
+            None if path.file_name().is_some_and(|name| name == "mbox") => read_file(path, index),
+            Some("eml") => fs::read(path).map(|raw| index(path.display().to_string(), &raw)),
+            _ if in_maildir => fs::read(path).map(|raw| index(path.display().to_string(), &raw)),
+            _ => continue,
+        };
+        if let Err(e) = result {
+            errors.push(format!("'{}': {}", path.display(), e));
+        }
+    }
+    Ok(())
+}
+
+/// 파일 하나를 mbox 또는 메일 하나로 읽습니다. mbox는 메일을 하나씩 나누어 읽으므로 큰 파일도 메모리에
+/// 한꺼번에 올리지 않습니다.
+fn read_file(path: &Path, index: &mut impl FnMut(String, &[u8])) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if !reader.fill_buf()?.starts_with(b"From ") {
+        let mut raw = Vec::new();
+        reader.read_to_end(&mut raw)?;
+        index(path.display().to_string(), &raw);
+        return Ok(());
+    }
+    // 빈 줄 다음의 `From `으로 시작하는 줄이 메일의 경계입니다. (본문의 같은 줄은 `>From `으로 바뀌어 있습니다)
+    let mut number = 0u64;
+    let mut message = Vec::new();
+    let mut line = Vec::new();
+    let mut after_blank = true;
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 || (after_blank && line.starts_with(b"From ")) {
+            if number > 0 {
+                index(format!("{} #{}", path.display(), number), &message);
+            }
+            if read == 0 || interrupt::requested() {
+                return Ok(());
+            }
+            number += 1;
+            message.clear();
+        } else {
+            message.extend_from_slice(&line);
+        }
+        after_blank = trim_line_end(&line).is_empty();
+    }
+}
+
+/// 메일 하나에서 첨부 파일을 찾아 `found(파일 이름, 디코딩한 내용)`을 호출하고, 메일의 제목을 돌려줍니다.
+fn parse_message(raw: &[u8], found: &mut impl FnMut(String, Vec<u8>)) -> String {
+    let (headers, body) = split_headers(raw);
+    walk_part(&headers, body, 0, found);
+    header(&headers, "subject").map(decode_words).unwrap_or_default()
+}
+
+/// MIME 부분 하나. multipart와 전달된 메일은 안으로 들어가고, 첨부 파일이면 `found`를 호출합니다.
+fn walk_part(headers: &[(String, String)], body: &[u8], depth: usize, found: &mut impl FnMut(String, Vec<u8>)) {
+    let (mime, type_params) = parse_value(header(headers, "content-type").unwrap_or("text/plain"));
+    let encoding = header(headers, "content-transfer-encoding").unwrap_or("").trim().to_ascii_lowercase();
+    if depth < MAX_DEPTH {
+        if mime.starts_with("multipart/")
+            && let Some(boundary) = param(&type_params, "boundary")
+        {
+            for part in split_multipart(body, &boundary) {
+                let (headers, body) = split_headers(part);
+                walk_part(&headers, body, depth + 1, found);
+            }
+            return;
+        }
+        // 전달된 메일은 그 메일의 첨부 파일을 찾습니다.
+        if mime == "message/rfc822" {
+            let message = decode_body(&encoding, body);
+            let (headers, body) = split_headers(&message);
+            walk_part(&headers, body, depth + 1, found);
+            return;
+        }
+    }
+    let (disposition, disposition_params) = parse_value(header(headers, "content-disposition").unwrap_or(""));
+    let filename = param(&disposition_params, "filename").or_else(|| param(&type_params, "name"));
+    if filename.is_none() && disposition != "attachment" {
+        return;
+    }
+    let data = decode_body(&encoding, body);
+    if !data.is_empty() {
+        found(filename.map_or_else(|| "(이름 없음)".to_string(), |name| decode_words(&name)), data);
+    }
+}
+
+/// 헤더와 본문을 나눕니다. 헤더는 (소문자 이름, 이어진 줄을 합친 값)의 목록입니다.
+fn split_headers(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut offset = 0;
+    for line in raw.split_inclusive(|&b| b == b'\n') {
+        offset += line.len();
+        let line = trim_line_end(line);
+        if line.is_empty() {
+            break;
+        }
+        let text = String::from_utf8_lossy(line);
+        // 공백으로 시작하는 줄은 앞 헤더가 이어진 것입니다.
+        if text.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(text.trim());
+            }
+        } else if let Some((name, value)) = text.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, &raw[offset.min(raw.len())..])
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+/// `text/plain; charset="utf-8"` 같은 헤더 값을 (소문자 값, (소문자 이름, 값) 매개변수들)로 나눕니다.
+fn parse_value(value: &str) -> (String, Vec<(String, String)>) {
+    // 따옴표 안의 `;`는 구분 기호가 아닙니다.
+    let mut pieces = Vec::new();
+    let (mut current, mut quoted) = (String::new(), false);
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => pieces.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    pieces.push(current);
+    let mut pieces = pieces.into_iter();
+    let main = pieces.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let params = pieces
+        .filter_map(|piece| piece.split_once('=').map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string())))
+        .collect();
+    (main, params)
+}
+
+/// 매개변수 값. RFC 2231 형식(`filename*=UTF-8''%ED%95%9C.pdf`, 긴 값을 나눈 `filename*0*=`...)도 읽습니다.
+fn param(params: &[(String, String)], name: &str) -> Option<String> {
+    if let Some((_, value)) = params.iter().find(|(key, _)| key == name) {
+        return Some(value.clone());
+    }
+    // (퍼센트 인코딩 여부, 값) 조각들.
+    let mut pieces: Vec<(bool, &str)> = Vec::new();
+    if let Some((_, value)) = params.iter().find(|(key, _)| *key == format!("{}*", name)) {
+        pieces.push((true, value));
+    } else {
+        for i in 0.. {
+            let (plain, encoded) = (format!("{}*{}", name, i), format!("{}*{}*", name, i));
+            match params.iter().find(|(key, _)| *key == plain || *key == encoded) {
+                Some((key, value)) => pieces.push((key.ends_with('*'), value)),
+                None => break,
+            }
+        }
+    }
+    if pieces.is_empty() {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    for (i, (encoded, value)) in pieces.into_iter().enumerate() {
+        if !encoded {
+            bytes.extend_from_slice(value.as_bytes());
+            continue;
+        }
+        // 인코딩된 첫 조각은 `문자 집합'언어'`로 시작합니다. 문자 집합은 UTF-8로 가정합니다.
+        let value = if i == 0 { value.splitn(3, '\'').nth(2).unwrap_or(value) } else { value };
+        bytes.extend(percent_decode(value.as_bytes()));
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 구분선(`--boundary`)으로 multipart 본문을 부분들로 나눕니다. 첫 구분선 앞과 끝 구분선(`--boundary--`)
+/// 뒤는 버립니다. 끝 구분선이 없는(잘린) 메일은 마지막 부분을 끝까지로 봅니다.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut offset = 0;
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let line_start = offset;
+        offset += line.len();
+        let Some(rest) = trim_line_end(line).strip_prefix(delimiter.as_bytes()) else { continue };
+        let closing = rest.trim_ascii() == b"--";
+        if !rest.trim_ascii().is_empty() && !closing {
+            continue;
+        }
+        if let Some(start) = start {
+            // 구분선 앞의 줄 바꿈은 구분선에 속합니다.
+            parts.push(trim_line_end(&body[start..line_start]));
+        }
+        if closing {
+            return parts;
+        }
+        start = Some(offset);
+    }
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+/// Content-Transfer-Encoding에 따라 본문을 디코딩합니다. 7bit, 8bit, binary는 그대로 둡니다.
+fn decode_body(encoding: &str, body: &[u8]) -> Vec<u8> {
+    match encoding {
+        "base64" => base64_decode(body),
+        "quoted-printable" => quoted_printable_decode(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+/// base64. 메일은 줄마다 끊겨 있으므로 알파벳이 아닌 문자는 건너뛰고, `=`에서 멈춥니다.
+fn base64_decode(input: &[u8]) -> Vec<u8> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let (mut bits, mut count) = (0u32, 0u32);
+    for &c in input.iter().take_while(|&&c| c != b'=') {
+        let Some(v) = value(c) else { continue };
+        bits = (bits << 6) | u32::from(v);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            output.push((bits >> count) as u8);
+        }
+    }
+    output
+}
+
+/// quoted-printable. `underscore_space`이면 헤더의 인코딩된 단어(Q)처럼 `_`를 공백으로 읽습니다.
+fn quoted_printable_decode(input: &[u8], underscore_space: bool) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' => {
+                let rest = &input[i + 1..];
+                if let Some(byte) = rest.get(..2).and_then(hex_byte) {
+                    output.push(byte);
+                    i += 3;
+                } else if rest.starts_with(b"\r\n") {
+                    i += 3;
+                } else if rest.starts_with(b"\n") {
+                    i += 2;
+                } else {
+                    output.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if underscore_space => {
+                output.push(b' ');
+                i += 1;
+            }
+            c => {
+                output.push(c);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input.get(i + 1..i + 3).and_then(hex_byte) {
+            Some(byte) if input[i] == b'%' => {
+                output.push(byte);
+                i += 3;
+            }
+            _ => {
+                output.push(input[i]);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+/// 16진수 두 자리. `from_str_radix`는 `+`로 시작하는 값도 받으므로 두 글자를 직접 확인합니다.
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    let [high, low] = digits else { return None };
+    let digit = |c: u8| char::from(c).to_digit(16);
+    Some((digit(*high)? * 16 + digit(*low)?) as u8)
+}
+
+/// 헤더의 인코딩된 단어(`=?UTF-8?B?7ZWc6riA?=`, RFC 2047)를 풉니다. UTF-8과 Latin-1 이외의 문자 집합은
+/// UTF-8로 가정합니다.
+fn decode_words(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+    // 인코딩된 단어 사이의 공백은 버립니다.
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let word = &rest[start + 2..];
+        let mut fields = word.splitn(3, '?');
+        let (Some(charset), Some(kind), Some(tail)) = (fields.next(), fields.next(), fields.next()) else { break };
+        let Some(end) = tail.find("?=") else { break };
+        let encoded = &tail[..end];
+        let bytes = match kind {
+            "B" | "b" => base64_decode(encoded.as_bytes()),
+            "Q" | "q" => quoted_printable_decode(encoded.as_bytes(), true),
+            _ => {
+                output.push_str(&rest[..start + 2]);
+                rest = word;
+                after_word = false;
+                continue;
+            }
+        };
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            output.push_str(between);
+        }
+        if charset.eq_ignore_ascii_case("iso-8859-1") || charset.eq_ignore_ascii_case("latin1") {
+            output.extend(bytes.iter().map(|&b| char::from(b)));
+        } else {
+            output.push_str(&String::from_utf8_lossy(&bytes));
+        }
+        rest = &tail[end + 2..];
+        after_word = true;
+    }
+    output.push_str(rest);
+    output
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 메일 하나에서 찾은 (첨부 파일 이름, 내용)들과 제목.
+    fn attachments(raw: &str) -> (Vec<(String, Vec<u8>)>, String) {
+        let mut found = Vec::new();
+        let subject = parse_message(raw.as_bytes(), &mut |name, data| found.push((name, data)));
+        (found, subject)
+    }
+
+    #[test]
+    fn finds_attachments_in_nested_multipart() {
+        let raw = "Subject: =?UTF-8?B?7ZWc6riA?= report\r\n\
+            Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+            \r\n\
+            preamble\r\n\
+            --outer\r\n\
+            Content-Type: text/plain\r\n\
+            \r\n\
+            body text\r\n\
+            --outer\r\n\
+            Content-Type: multipart/alternative; boundary=inner\r\n\
+            \r\n\
+            --inner\r\n\
+            Content-Type: application/pdf; name=\"a.pdf\"\r\n\
+            Content-Transfer-Encoding: base64\r\n\
+            \r\n\
+            aGVs\r\n\
+            bG8=\r\n\
+            --inner--\r\n\
+            --outer\r\n\
+            Content-Disposition: attachment; filename*=UTF-8''%ED%95%9C.txt\r\n\
+            Content-Transfer-Encoding: quoted-printable\r\n\
+            \r\n\
+            a=3Db=\r\n\
+            c\r\n\
+            --outer--\r\n\
+            epilogue\r\n";
+        let (found, subject) = attachments(raw);
+        assert_eq!(subject, "한글 report");
+        assert_eq!(found, vec![("a.pdf".to_string(), b"hello".to_vec()), ("한.txt".to_string(), b"a=bc".to_vec())]);
+    }
+
+    #[test]
+    fn skips_body_and_empty_attachments() {
+        let raw = "Content-Type: multipart/mixed; boundary=b\n\n--b\nContent-Type: text/plain\n\nhi\n--b\n\
+            Content-Disposition: attachment; filename=empty.bin\n\n\n--b--\n";
+        assert!(attachments(raw).0.is_empty());
+    }
+
+    #[test]
+    fn reads_forwarded_message_and_unnamed_attachment() {
+        let raw = "Content-Type: message/rfc822\n\nSubject: inner\nContent-Disposition: attachment\n\ndata\n";
+        assert_eq!(attachments(raw).0, vec![("(이름 없음)".to_string(), b"data\n".to_vec())]);
+    }
+
+    #[test]
+    fn keeps_last_part_of_truncated_multipart() {
+        let raw = "Content-Type: multipart/mixed; boundary=b\n\n--b\nContent-Type: text/plain; name=cut.txt\n\npartial";
+        assert_eq!(attachments(raw).0, vec![("cut.txt".to_string(), b"partial".to_vec())]);
+    }
+
+    #[test]
+    fn multipart_without_boundary_is_not_followed() {
+        let raw = "Content-Type: multipart/mixed\n\n--b\nContent-Disposition: attachment; filename=x\n\ny\n--b--\n";
+        assert!(attachments(raw).0.is_empty());
+    }
+
+    #[test]
+    fn stops_at_maximum_depth() {
+        let nested = |depth: usize| {
+            let mut raw = "Content-Type: message/rfc822\n\n".repeat(depth);
+            raw.push_str("Content-Disposition: attachment; filename=deep\n\nx\n");
+            raw
+        };
+        assert_eq!(attachments(&nested(3)).0, vec![("deep".to_string(), b"x\n".to_vec())]);
+        // 깊이 제한을 넘는 중첩은 스택을 넘치지 않고 따라가기를 멈춥니다.
+        assert!(attachments(&nested(MAX_DEPTH * 4)).0.is_empty());
+    }
+
+    #[test]
+    fn splits_headers_with_continuation_lines() {
+        let (headers, body) = split_headers(b"Subject: one\r\n\ttwo\r\nX-Empty:\r\nno colon line\r\n\r\nbody");
+        assert_eq!(headers, vec![("subject".to_string(), "one two".to_string()), ("x-empty".to_string(), String::new())]);
+        assert_eq!(body, b"body");
+        // 빈 줄이 없으면 본문은 비어 있습니다.
+        assert_eq!(split_headers(b"Subject: only").1, b"");
+    }
+
+    #[test]
+    fn parses_quoted_parameters() {
+        let (value, params) = parse_value("Attachment; filename=\"a;b.txt\"; Size=3; broken");
+        assert_eq!(value, "attachment");
+        assert_eq!(params, vec![("filename".to_string(), "a;b.txt".to_string()), ("size".to_string(), "3".to_string())]);
+    }
+
+    #[test]
+    fn joins_rfc2231_continuations() {
+        let params = vec![
+            ("filename*0*".to_string(), "UTF-8''%ED%95%9C".to_string()),
+            ("filename*1".to_string(), "-plain".to_string()),
+            ("filename*2*".to_string(), "%2Etxt".to_string()),
+        ];
+        assert_eq!(param(&params, "filename").as_deref(), Some("한-plain.txt"));
+        assert_eq!(param(&params, "name"), None);
+    }
+
+    #[test]
+    fn decodes_base64_with_line_breaks_and_garbage() {
+        assert_eq!(base64_decode(b"aGVs\r\nbG8=\r\n"), b"hello");
+        assert_eq!(base64_decode(b"aGV*sbG8"), b"hello");
+        // `=` 뒤는 읽지 않습니다.
+        assert_eq!(base64_decode(b"aGk=aGk="), b"hi");
+        // 잘린 입력은 완성된 바이트까지만 돌려줍니다.
+        assert_eq!(base64_decode(b"aGVsb"), b"hel");
+        assert_eq!(base64_decode(b""), b"");
+    }
+
+    #[test]
+    fn decodes_quoted_printable_edge_cases() {
+        assert_eq!(quoted_printable_decode(b"caf=C3=A9", false), "café".as_bytes());
+        assert_eq!(quoted_printable_decode(b"soft=\r\nbreak=\nhere", false), b"softbreakhere");
+        // 16진수가 아닌 `=` 뒤와 끝의 `=`는 그대로 둡니다.
+        assert_eq!(quoted_printable_decode(b"a=ZZb=4", false), b"a=ZZb=4");
+        assert_eq!(quoted_printable_decode(b"end=", false), b"end=");
+        assert_eq!(quoted_printable_decode(b"a_b", false), b"a_b");
+        assert_eq!(quoted_printable_decode(b"a_b", true), b"a b");
+    }
+
+    #[test]
+    fn hex_byte_accepts_only_two_hex_digits() {
+        assert_eq!(hex_byte(b"4f"), Some(0x4f));
+        assert_eq!(hex_byte(b"A0"), Some(0xa0));
+        assert_eq!(hex_byte(b"+f"), None);
+        assert_eq!(hex_byte(b"-1"), None);
+        assert_eq!(hex_byte(b" 1"), None);
+        assert_eq!(hex_byte(b"f"), None);
+        assert_eq!(hex_byte(b"fff"), None);
+        assert_eq!(quoted_printable_decode(b"=+f", false), b"=+f");
+        assert_eq!(percent_decode(b"%+f%41%4"), b"%+fA%4");
+    }
+
+    #[test]
+    fn decodes_encoded_words() {
+        assert_eq!(decode_words("=?UTF-8?B?7ZWc?= =?utf-8?b?6riA?="), "한글");
+        assert_eq!(decode_words("=?ISO-8859-1?Q?caf=E9_au_lait?="), "café au lait");
+        assert_eq!(decode_words("plain =?UTF-8?Q?x?= tail"), "plain x tail");
+        // 알 수 없는 인코딩과 닫히지 않은 단어는 그대로 둡니다.
+        assert_eq!(decode_words("=?UTF-8?X?abc?= end"), "=?UTF-8?X?abc?= end");
+        assert_eq!(decode_words("=?UTF-8?B?7ZWc"), "=?UTF-8?B?7ZWc");
+        assert_eq!(decode_words("=?broken"), "=?broken");
+    }
+
+    #[test]
+    fn splits_mbox_at_from_lines_after_blank_lines() {
+        let path = std::env::temp_dir().join(format!("duplicate_finder-mail-test-{}.mbox", std::process::id()));
+        fs::write(
+            &path,
+            "From a@example.org Mon Jan  1 00:00:00 2024\nSubject: one\n\nbody line\nFrom inside a paragraph\n\n>From quoted\n\n\
+             From b@example.org Mon Jan  1 00:00:00 2024\nSubject: two\n\nbody\n",
+        )
+        .unwrap();
+        let mut messages = Vec::new();
+        let result = read_file(&path, &mut |name, raw| messages.push((name, String::from_utf8_lossy(raw).into_owned())));
+        let _ = fs::remove_file(&path);
+        result.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].0.ends_with(" #1") && messages[1].0.ends_with(" #2"));
+        assert!(messages[0].1.contains("From inside a paragraph") && messages[0].1.contains(">From quoted"));
+        assert!(messages[1].1.starts_with("Subject: two"));
+    }
+
+    #[test]
+    fn reads_file_without_from_line_as_one_message() {
+        let path = std::env::temp_dir().join(format!("duplicate_finder-mail-test-{}.eml", std::process::id()));
+        fs::write(&path, "Subject: single\n\nFrom here\n").unwrap();
+        let mut messages = Vec::new();
+        let result = read_file(&path, &mut |name, raw| messages.push((name, raw.to_vec())));
+        let _ = fs::remove_file(&path);
+        result.unwrap();
+        assert_eq!(messages, vec![(path.display().to_string(), b"Subject: single\n\nFrom here\n".to_vec())]);
+    }
+}
@@ -16,175 +16,2855 @@
 //     고유한 서명(해시)을 계산하여 진짜 중복 파일을 찾아냅니다.
 // ==============================================================================
 
+// `native` 기능 없이(WASI용으로) 빌드하면 TUI, API 서버, 데몬 모드에서만 쓰는 삭제/통계 코드가
+// 사용되지 않으므로, 그 빌드에서만 사용하지 않는 코드 경고를 끕니다.
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
 // --- 외부 라이브러리 및 표준 라이브러리 모듈 가져오기 (use statements) ---
 
 // std::collections::HashMap: 키-값 쌍을 저장하는 해시 맵 자료구조.
 // 파일 크기 -> 파일 경로 리스트, 파일 해시 -> 파일 경로 리스트를 만드는 데 사용됩니다.
-use std::collections::HashMap;
+// std::collections::HashSet: 이미 본 물리적 파일(장치 번호, inode)을 기억하는 데 사용됩니다.
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 // std::env: 현재 환경에 대한 정보를 다루는 모듈.
 // 여기서는 기본 폴더를 설정하기 위해 현재 작업 디렉터리를 가져오는 데 사용했었지만,
 // 이제는 필수 인자로 변경되어 직접적인 사용은 없습니다. (미래 확장을 위해 남겨둘 수 있음)
 // use std::env;
 
-// std::ffi::OsStr: 운영체제 네이티브 문자열(OS String)을 다루기 위한 타입.
-// 파일 이름이나 경로를 비교할 때, 유니코드(String)로 변환할 수 없는 문자가
-// 포함될 수 있는 경우에도 안전하게 처리하기 위해 사용합니다.
-use std::ffi::OsStr;
-
 // std::fs::File: 파일 시스템의 파일을 다루기 위한 구조체.
-use std::fs::File;
+// 해시할 파일은 이제 read_hints::open으로 열므로 직접 쓰지 않습니다.
+// use std::fs::File;
+
+// std::io::{...}: 입출력(I/O) 작업을 위한 모듈.
+// - io: Result<T, io::Error> 와 같은 공통 I/O 타입을 사용하기 위함.
+// - BufReader: 파일을 효율적으로 읽기 위한 버퍼 리더.
+// - Read: 데이터를 읽어오는 기능을 제공하는 트레이트(trait).
+// - IsTerminal: 표준 입력이 터미널인지(사용자에게 질문할 수 있는지) 확인하는 트레이트.
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+
+// std::path::{Path, PathBuf}: 파일 시스템 경로를 다루기 위한 타입.
+// - Path: 경로에 대한 빌려온(borrowed) 슬라이스. 변경 불가능.
+// - PathBuf: 경로를 소유(owned)하며 변경 가능한 문자열 버퍼.
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::path::{Path, PathBuf};
+
+// std::process::ExitCode: `main`에서 반환하여 프로세스 종료 코드를 지정합니다.
+use std::process::ExitCode;
+
+// std::time::Instant: 단계별 소요 시간을 측정하기 위한 단조 증가 시계.
+// std::time::SystemTime: 파일의 수정 시간을 비교하기 위한 시각 타입.
+use std::time::{Duration, Instant, SystemTime};
+
+// 외부 라이브러리 `clap`: 커맨드라인 인자 파싱을 위한 강력한 도구.
+// derive 기능을 통해 구조체 정의만으로 손쉽게 CLI를 만들 수 있습니다.
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
+
+// 해시 알고리즘(SHA-256, MD5, SHA-1, BLAKE3)과 여러 해시를 함께 계산하는 계산기.
+use hashing::{HashAlgorithm, HashBackend, Hasher};
+
+// 외부 라이브러리 `serde_json`: 로그 레코드와 같은 구조화된 출력을 JSON으로 만듭니다.
+use serde_json::json;
+
+// 외부 라이브러리 `walkdir`: 디렉터리를 재귀적으로 탐색하는 편리한 도구.
+use walkdir::WalkDir;
+
+// 그룹 멤버 삭제와 그 안전 검사 (API 서버, GUI 공용).
+mod actions;
+// 여러 tar 백업 아카이브 사이의 중복 분석(archives 하위 명령).
+mod archives;
+// 메일함(Maildir, mbox)의 첨부 파일 사이와 디스크 파일과의 중복 분석(mail 하위 명령).
+mod mail;
+// 임시 파일에 쓴 뒤 이름을 바꾸는, 전원이 나가도 망가지지 않는 파일 쓰기.
+mod atomic_file;
+// 태그를 뺀 오디오 데이터로 음원을 비교하는 모드(--match audio).
+mod audio;
+// 오프라인 드라이브 카탈로그(index 하위 명령, --against-catalogs).
+mod catalog;
+// 긴 검색을 중단한 곳부터 이어서 하는 주기적 체크포인트(--checkpoint, --resume).
+mod checkpoint;
+// 주기적 검색을 반복하는 데몬 모드(--daemon)와 메트릭 엔드포인트.
+#[cfg(feature = "native")]
+mod daemon;
+// 두 파일 또는 두 폴더의 내용 비교(compare 하위 명령).
+mod compare;
+// 다른 폴더에 내용이 모두 들어 있는 폴더 보고서(--contained-dirs).
+mod contained_dirs;
+// 중복 제거 파일 시스템의 예상 절약량 보고서(--dedup-report).
+mod dedup_advice;
+// 여러 서버의 검색 결과를 모아 비교하는 분산 검색(worker, coordinator).
+#[cfg(feature = "native")]
+mod distributed;
+// 확장 속성과 POSIX ACL까지 같은 파일만 묶는 비교(--compare-attrs).
+mod attrs;
+// 장치마다 따로 정하는 동시 읽기 수(--device-threads).
+mod devices;
+// 권한이 없어 지우지 못한 파일을 관리자 권한으로 다시 지우기(--sudo-helper).
+#[cfg(feature = "native")]
+mod elevate;
+// 백업 도구용 제외 목록 생성(--emit-excludes).
+mod excludes;
+// 결과 전체를 JSON으로 외부 명령 하나에 넘기기(--exec-batch).
+mod exec_batch;
+// 그룹마다 중복으로 판단한 근거와 유사도 보여주기(--explain).
+mod explain;
+// 이미 reflink로 데이터를 공유하는 사본 알아보기(FIEMAP).
+mod extents;
+// and/or/not으로 조건을 묶는 필터 식(--filter).
+mod filter;
+// 폴더 사이의 중복 관계를 Graphviz 그래프로 내보내기(--export-dot).
+mod folder_graph;
+// 해시 알고리즘 선택(--algorithm, --extra-hash).
+mod hashing;
+// 네이티브 GUI(gui 하위 명령). `gui` 기능으로 빌드할 때만 포함됩니다.
+#[cfg(feature = "gui")]
+mod gui;
+// 중복 데이터의 파일 크기 분포(--histogram).
+mod histogram;
+// 일부러 남겨 둔 중복의 무시 목록(ignore 하위 명령, --show-ignored).
+mod ignore;
+// czkawka, fclones가 계산해 둔 해시 가져오기(--import-hashes).
+mod imports;
+// Ctrl+C를 눌렀을 때 부분 결과를 정리하고 종료하기 위한 중단 요청 처리.
+mod interrupt;
+// 다른 프로세스가 잠갔거나 쓰고 있는 파일 감지.
+mod in_use;
+// 그룹마다 남길 원본을 고르는 규칙(--keep).
+mod keep;
+// 감시 모드가 파일을 옮기거나 링크로 바꿀 때의 메타데이터 보존.
+mod metadata;
+// 파일 이름 비교와 유니코드 정규화(--normalize-names).
+mod names;
+// 사용자 로캘에 맞춘 숫자와 크기 표기(--locale).
+mod number_format;
+// 데몬 모드의 검색 결과를 웹훅과 메일로 보내는 기능(--webhook, --smtp-*).
+#[cfg(feature = "native")]
+mod notify;
+// 원격 해시 목록과 로컬 파일의 비교(--compare-manifest).
+mod manifest;
+// 메타데이터를 뺀 파트로 오피스 문서를 비교하는 모드(--match office).
+mod office;
+// 파일 소유자별 할당량 영향(--by-owner).
+mod owners;
+// 출력할 경로의 형식(--absolute, --relative-to).
+mod path_style;
+// 페이지 내용 스트림으로 PDF를 비교하는 모드(--match pdf).
+mod pdf;
+// 사진의 EXIF 촬영 정보로 묶는 사진 라이브러리 모드(--match exif).
+mod photos;
+// 내용을 읽으면 내려받기가 시작되는 클라우드 온라인 전용 파일 감지(--hydrate).
+mod placeholders;
+// 크기만 먼저 세어 크기가 유일한 파일의 경로는 기억하지 않는 사전 조사(--bloom-prepass).
+mod prepass;
+// TUI에서 이미지 멤버를 터미널 그래픽 프로토콜로 그리는 미리보기(--preview).
+#[cfg(feature = "native")]
+mod preview;
+// 단계별 소요 시간과 가장 느린 파일 측정(--profile).
+mod profile;
+// 진행 상황 한 줄 표시기.
+mod progress;
+// 접근 시각을 바꾸지 않고 페이지 캐시를 밀어내지 않는 검색용 파일 읽기.
+mod read_hints;
+// 미러링해야 하는 루트들에서 복제가 부족한 파일 찾기(--min-copies).
+mod redundancy;
+// 미러여야 하는 두 루트 사이의 조용한 손상(bit rot) 찾기(--check-mirror).
+mod mirror_check;
+// 결과 파일 저장(--save-results)과 재검증(verify).
+mod results;
+// 저장된 두 결과의 비교(report diff).
+mod report;
+// 병렬 해시 계산용 스레드 풀과 파일 디스크립터 예산.
+mod reader_pool;
+// 여러 루트를 검색했을 때의 루트별 통계.
+mod root_stats;
+// 위험한 루트에서의 파괴적 작업을 막는 안전장치.
+mod safety;
+// 멤버가 모두 한 폴더 안에 있는 그룹만 보는 보고서(--same-dir).
+mod same_dir;
+// 수정 시각까지 같은 파일만 묶는 비교(--require-same-mtime).
+mod same_mtime;
+// 답을 아는 검사 폴더를 만들어 검색 결과를 확인하는 자체 검사(selftest 하위 명령).
+mod selftest;
+// 다른 서비스가 HTTP로 검색을 실행하는 REST API 서버(serve).
+#[cfg(feature = "native")]
+mod server;
+// 정리 방법별 회수 공간 시뮬레이션(--simulate).
+mod simulate;
+// 스냅샷 폴더 사이의 같은 파일을 하드 링크로 합치기와 되돌리기(snapshots 하위 명령).
+mod snapshots;
+// 메모리를 넘는 크기 그룹 목록을 임시 파일에 나누어 쓰고 병합하는 저장(--spill-threshold).
+mod spill;
+// macOS Spotlight 색인으로 후보가 없는 폴더를 건너뛰는 탐색(--spotlight).
+mod spotlight;
+// 크기, 샘플, 전체 해시, 바이트 비교 중 고르는 비교 단계(--stages).
+mod stages;
+// 카탈로그, 무시 목록, 체크포인트를 동시에 고쳐 쓰지 않도록 하는 잠금 파일(--lock-wait).
+mod store_lock;
+// 구조화된 스캔 로그 파일(--log-file) 기록기.
+mod scan_log;
+// 모니터링 도구가 읽을 실행 지표 파일(--metrics-out).
+mod telemetry;
+// 대화형 TUI 모드(--tui) 구현을 담은 하위 모듈.
+#[cfg(feature = "native")]
+mod tui;
+// 드롭 폴더에 들어온 새 파일을 기존 내용과 비교하는 감시 모드(--watch).
+mod watch;
+// 서로 다른 때에 검색한 여러 루트를 이름 하나로 모으는 작업 공간(workspace 하위 명령).
+mod workspace;
+// 파일의 확장 속성에 해시를 저장하는 캐시(--xattr-cache).
+mod xattr_cache;
+
+use progress::Progress;
+use reader_pool::FdBudget;
+use actions::{PruneMode, PruneReport};
+use excludes::ExcludeFormat;
+use imports::ImportFormat;
+use histogram::SizeHistogram;
+use path_style::PathStyle;
+use profile::{Profile, ProfileFormat};
+use keep::{KeepPolicy, KeepRule, ScoreWeights};
+use manifest::Manifest;
+use watch::WatchAction;
+use ignore::IgnoreList;
+use results::SavedResults;
+use stages::{Pipeline, Stage};
+use owners::OwnerStats;
+use root_stats::{RootStats, RootTotals};
+use scan_log::ScanLog;
+
+/// 파일 시스템에서 중복된 파일을 찾아 그룹화하여 출력하는 프로그램
+// `#[derive(Parser, Debug)]`: clap의 derive 매크로를 사용하여 이 구조체를 CLI 파서로 만듭니다.
+// Debug 트레이트는 `{:#?}` 등을 통해 구조체를 보기 좋게 출력하는 데 필요합니다.
+#[derive(Parser, Debug)]
+// `#[command(...)]`: 프로그램의 버전, 설명 등 메타데이터를 설정합니다. `--help` 시 출력됩니다.
+// - subcommand_negates_reqs: 하위 명령(예: completions)을 사용할 때는 --root-folder를 요구하지 않습니다.
+// - args_conflicts_with_subcommands: 검색 옵션과 하위 명령을 함께 쓰면 오류로 처리합니다.
+#[command(version, about, long_about = None, subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+// --spill-dir은 임시 파일을 쓰는 두 옵션 중 하나와 함께 써야 합니다.
+#[command(group(ArgGroup::new("spilling").args(["spill_threshold", "max_memory"]).multiple(true)))]
+struct Args {
+    /// 검색 대신 실행할 하위 명령 (셸 자동 완성, man 페이지 생성 등).
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// [필수] 검색을 시작할 루트 폴더 경로. 여러 번 지정하면 모든 폴더를 함께 검색합니다.
+    /// (예: `-r /mnt/a -r /mnt/b`)
+    // `#[arg(...)]`: 각 필드에 대한 CLI 옵션 설정을 정의합니다.
+    // - short: 짧은 옵션 이름 (e.g., -r)
+    // - long: 긴 옵션 이름 (e.g., --root-folder)
+    // - value_name: 도움말에 표시될 값의 이름 (e.g., <FOLDER_PATH>)
+    // - required_unless_present: 하위 명령이 없을 때는 (--schema가 아니라면) 반드시 지정해야 합니다.
+    //   하위 명령이 있을 때는 비어 있을 수 있습니다.
+    // Vec<PathBuf> 타입이므로 clap은 옵션이 반복될 때마다 값을 추가합니다.
+    #[arg(short, long, value_name = "FOLDER_PATH", required_unless_present = "schema")]
+    root_folder: Vec<PathBuf>,
+
+    /// 검색할 파일 이름을 지정합니다 (예: "report.txt", "*.log").
+    // 이 필드는 Option<String> 이므로, clap은 자동으로 선택적(optional) 인자로 처리합니다.
+    #[arg(short, long, value_name = "FILENAME_PATTERN")]
+    file_filter: Option<String>,
+
+    /// 여러 조건을 and/or/not과 괄호로 묶은 필터 식. 조건은 `ext:`(확장자), `name:`(파일 이름),
+    /// `path:`(전체 경로)이고 값에는 `*`, `**`, `?`를 쓸 수 있습니다.
+    /// 예: `'(ext:jpg or ext:png) and not path:**/thumbnails/**'`
+    #[arg(long, value_name = "EXPR", value_parser = filter::parse, conflicts_with = "file_filter")]
+    filter: Option<filter::Expr>,
+
+    /// 생성 시각(birth time)이 이 시각 이후인 파일만 검색합니다. `2024-05-01`, `2024-05-01T09:30:00`(UTC)
+    /// 형식이나, `30days`처럼 지금으로부터 거슬러 올라간 기간을 받습니다. 생성 시각을 알 수 없는 파일은 건너뜁니다.
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    created_after: Option<SystemTime>,
+
+    /// 생성 시각(birth time)이 이 시각 이전인 파일만 검색합니다. (형식은 --created-after와 같습니다)
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    created_before: Option<SystemTime>,
+
+    /// 결과를 대화형 TUI로 열어 그룹을 탐색하고, 파일별로 유지/삭제를 표시해 실행합니다.
+    #[arg(long)]
+    tui: bool,
+
+    /// TUI에서 선택한 그룹의 이미지 멤버를 그릴 터미널 그래픽 프로토콜. `auto`는 터미널을 알아보고,
+    /// 지원하지 않는 터미널이면 미리보기 없이 엽니다.
+    #[arg(long, value_enum, value_name = "PROTOCOL", default_value_t = PreviewProtocol::Auto, requires = "tui")]
+    preview: PreviewProtocol,
+
+    /// 파일별 이벤트, 경고, 단계별 소요 시간을 JSON Lines 형식으로 기록할 로그 파일 경로.
+    #[arg(long, value_name = "LOG_FILE")]
+    log_file: Option<PathBuf>,
+
+    /// 해시 계산 전에 필터에 맞는 파일 수와 전체 크기를 빠르게 세어 보여주고,
+    /// 계속 진행할지 묻습니다. 센 파일 수는 진행 상황 표시의 전체 수로도 사용됩니다.
+    #[arg(long)]
+    estimate: bool,
+
+    /// 처리하지 못한 파일/폴더의 전체 목록을 마지막 요약에 함께 출력합니다.
+    /// (지정하지 않으면 오류 종류별 개수만 출력합니다.)
+    #[arg(long)]
+    show_errors: bool,
+
+    /// 읽을 수 없는 파일/폴더(또는 스캔 중 변경된 파일)를 하나라도 만나면 그 즉시 검색을
+    /// 중단하고, 문제가 된 경로를 보고한 뒤 종료 코드 4로 종료합니다. (규정 준수 검사용)
+    #[arg(long)]
+    strict: bool,
+
+    /// 회수 가능한 공간이 가장 큰 N개의 그룹만 보고합니다. (디스크 정리의 빠른 선별용)
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+
+    /// 개별 경로 없이 집계 수치(그룹 수, 파일 수, 낭비된 공간, 가장 큰 그룹)만 출력합니다.
+    /// 중복 추세를 추적하는 모니터링 스크립트용입니다.
+    #[arg(long, conflicts_with = "tui")]
+    summary_only: bool,
+
+    /// 결과 뒤에 중복 그룹을 파일 크기 구간(< 1 MB, 1–10 MB, …, ≥ 1 GB)별로 나눈 그룹 수, 파일 수,
+    /// 회수 가능한 공간을 출력합니다. 낭비가 작은 파일 다수에서 오는지 큰 파일 몇 개에서 오는지 보여줍니다.
+    #[arg(long, conflicts_with = "tui")]
+    histogram: bool,
+
+    /// 결과 뒤에 중복 파일을 소유자별로 모아, 사용자마다 가진 중복 파일과 그룹마다 원본(--keep) 하나만
+    /// 남겼을 때 줄어드는 사용량(할당량)을 출력합니다. 여러 사용자가 쓰는 서버의 정리 근거용입니다. (유닉스 전용)
+    #[arg(long, conflicts_with = "tui")]
+    by_owner: bool,
+
+    /// 그룹마다 어느 단계(크기, 샘플, 전체 해시, 바이트 비교)에서 같다고 판단했는지 함께 출력합니다.
+    /// --match exif/office/pdf/audio로 묶은 그룹은 멤버마다 첫 번째 파일과의 바이트 유사도도 보여줍니다.
+    #[arg(long, conflicts_with_all = ["tui", "summary_only", "simulate", "dedup_report"])]
+    explain: bool,
+
+    /// 그룹 목록 대신, 정리 방법(삭제, 하드 링크, reflink)별로 실제로 회수되는 공간을 볼륨마다
+    /// 계산해 보여줍니다. 기존 하드 링크, 다른 볼륨의 사본, 블록 할당 단위를 반영하며
+    /// 파일은 바꾸지 않습니다. 원본은 --keep 규칙으로 고릅니다.
+    #[arg(long, conflicts_with_all = ["tui", "summary_only"])]
+    simulate: bool,
+
+    /// 그룹 목록 대신, ZFS dedup, btrfs + bees, Windows 데이터 중복 제거를 이 데이터에 적용했을 때
+    /// 절약될 공간과 중복 제거율을 추정하여 보여줍니다. ZFS는 중복 제거 표의 메모리 비용도 함께 추정합니다.
+    /// 파일 시스템 도입을 검토할 때 씁니다. (파일 전체가 같은 중복만 반영한 하한)
+    #[arg(long, conflicts_with_all = ["tui", "summary_only", "simulate"])]
+    dedup_report: bool,
+
+    /// 그룹 목록 대신, 멤버가 모두 같은 폴더에 있는 그룹(한 폴더 안에서 복사해 붙여넣은 사본)만
+    /// 회수 가능한 공간이 큰 순서로 보여줍니다. --top으로 개수를 제한할 수 있습니다.
+    #[arg(long, conflicts_with_all = ["tui", "summary_only", "simulate", "dedup_report"])]
+    same_dir: bool,
+
+    /// 그룹 목록 대신, 안의 모든 파일이 다른 한 폴더 아래에 같은 내용으로 있어 폴더째 지워도 되는 폴더를
+    /// 그 폴더와 함께 크기가 큰 순서로 보여줍니다. --top으로 개수를 제한할 수 있습니다.
+    #[arg(long, conflicts_with_all = ["tui", "summary_only", "simulate", "dedup_report", "same_dir"])]
+    contained_dirs: bool,
+
+    /// 검색이 끝난 뒤 단계별 소요 시간(폴더 탐색, 메타데이터 조회, 해시 계산, 그룹 비교)과
+    /// 해시 계산이 가장 오래 걸린 파일들을 출력합니다. `--profile json`은 같은 내용을 JSON으로
+    /// 표준 오류에 출력하므로, 결과 출력과 섞이지 않고 `2>`로 따로 받을 수 있습니다.
+    #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "text", conflicts_with = "tui")]
+    profile: Option<ProfileFormat>,
+
+    /// 검색이 끝날 때마다 실행 지표(상태, 단계별 소요 시간, 파일 수와 바이트 수, 캐시 적중률,
+    /// 오류 분류별 개수)를 JSON으로 기록할 파일. 모니터링 도구가 주기적인 검색을 지켜볼 때 씁니다.
+    #[arg(long, value_name = "FILE")]
+    metrics_out: Option<PathBuf>,
+
+    /// 같은 아이노드를 가리키는 하드 링크를 각각 별개의 파일로 보아 서로의 중복으로 보고합니다.
+    /// (하드 링크 묶음을 다른 곳에 실제 사본으로 풀어낼 때) 지정하지 않으면 하드 링크는 파일 하나로 셉니다.
+    #[arg(long)]
+    isolate: bool,
+
+    /// OneDrive, iCloud Drive, Dropbox의 온라인 전용(자리 표시) 파일도 해시합니다. 파일을 읽는 동안
+    /// 운영체제가 파일 전체를 내려받습니다. 지정하지 않으면 건너뛰고 그 수를 요약에 표시합니다.
+    #[arg(long)]
+    hydrate: bool,
+
+    /// 심볼릭 링크도 검색 대상에 포함합니다. 링크의 "내용"은 링크가 가리키는 대상 경로
+    /// 문자열로 간주하여, 같은 곳을 가리키는 중복 링크들을 찾아냅니다.
+    #[arg(long)]
+    symlinks: bool,
+
+    /// 결과 정렬을 생략합니다. 기본적으로 그룹은 파일 크기(큰 순) → 해시 순으로,
+    /// 그룹 안의 파일은 경로 순으로 정렬되어 실행할 때마다 같은 순서로 출력됩니다.
+    #[arg(long)]
+    no_sort: bool,
+
+    /// 파일 시스템 루트(`/`), 홈 폴더 전체, 마운트 지점의 루트를 검색할 때도
+    /// TUI에서의 삭제를 허용합니다. 기본적으로 이런 루트에서는 삭제가 거부됩니다.
+    #[arg(long)]
+    force_dangerous_root: bool,
+
+    /// TUI에서 삭제한 뒤, 파일이 모두 빠져 비게 된 폴더를 아래에서 위로 지웁니다. 검색 루트 자체는
+    /// 남깁니다. `--prune-empty-dirs dry-run`은 지우지 않고, 종료할 때 지울 수 있는 폴더 목록만 보여줍니다.
+    #[arg(long, value_enum, value_name = "MODE", num_args = 0..=1, default_missing_value = "remove", requires = "tui")]
+    prune_empty_dirs: Option<PruneMode>,
+
+    /// TUI에서 권한이 없어 지우지 못한 파일을, TUI를 닫은 뒤 이 프로그램(`sudo`, `doas`, `pkexec` 등)으로
+    /// 권한을 올려 다시 지웁니다. 지정하지 않으면 유닉스의 터미널에서 `sudo`로 다시 시도할지 묻습니다.
+    #[arg(long, value_name = "PROGRAM", requires = "tui")]
+    sudo_helper: Option<String>,
+
+    /// 파일 이름을 비교하기 전에 유니코드 NFC로 정규화합니다. macOS(NFD)와 Linux(NFC) 사이에서
+    /// 복사된 한글/악센트 문자 이름도 같은 이름으로 취급합니다.
+    #[arg(long)]
+    normalize_names: bool,
+
+    /// 파일 해시를 동시에 계산할 스레드 수. 기본값은 CPU 코어 수이며, 지정하지 않으면 Linux에서는
+    /// 하드 디스크에 있는 파일을 스레드 1개로 읽습니다. (장치마다 따로)
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+
+    /// 파일을 장치(디스크)별로 나누어, 장치마다 따로 정한 수의 스레드로 읽습니다. `N`은 모든 장치,
+    /// `경로=N`은 그 경로가 있는 장치의 수입니다. 여러 번 지정할 수 있습니다.
+    /// (예: `--device-threads 4 --device-threads /mnt/hdd=1`) 경로 없이 준 수가 없다면 나머지 장치는 --threads를 쓰고,
+    /// --threads도 없다면 저장 매체 종류로 정합니다.
+    #[arg(long, value_name = "[PATH=]N", value_parser = devices::parse_spec)]
+    device_threads: Vec<devices::Spec>,
+
+    /// 해시 계산 중 동시에 열어 둘 수 있는 파일 수의 상한. 기본값은 운영체제의 열린 파일 수
+    /// 제한(RLIMIT_NOFILE)에서 로그 파일 등을 위한 여유분을 뺀 값입니다.
+    #[arg(long)]
+    fd_budget: Option<NonZeroUsize>,
+
+    /// 계산한 해시를 각 파일의 사용자 확장 속성에 shatag 형식(`user.shatag.<알고리즘>`, `user.shatag.ts`)으로
+    /// 수정 시간과 함께 기록하고, 다음 실행에서 파일이 바뀌지 않았다면 다시 읽지 않고 사용합니다.
+    /// shatag/cshatag이 남긴 해시도 씁니다.
+    #[arg(long)]
+    xattr_cache: bool,
+
+    /// 내용이 같아도 확장 속성(SELinux 레이블 등 보안 레이블 포함)과 POSIX ACL이 모두 같은 파일만
+    /// 중복으로 묶습니다. --xattr-cache가 기록한 해시 캐시 속성은 비교하지 않습니다. (유닉스 전용)
+    #[arg(long)]
+    compare_attrs: bool,
+
+    /// 내용이 같아도 수정 시각(초 단위)까지 같은 파일만 중복으로 묶습니다. 날짜가 다른 같은 내용을
+    /// 서로 다른 판으로 보존해야 할 때 씁니다. 수정 시각을 읽을 수 없는 파일은 그룹에서 뺍니다.
+    #[arg(long)]
+    require_same_mtime: bool,
+
+    /// 다른 도구가 계산해 둔 해시를 가져와, 그 뒤로 바뀌지 않은 파일은 다시 읽지 않습니다.
+    /// FORMAT은 `czkawka`(중복 검색 캐시의 JSON 사본, `cache_duplicates_Blake3_*.json`),
+    /// `fclones`(`fclones group --format json` 보고서), `csv`(`경로,크기,수정 시각,해시` 목록)입니다.
+    /// 가져온 해시의 알고리즘이 --algorithm과 같아야 하며, csv는 --algorithm의 해시로 봅니다.
+    /// 확장자가 .csv라면 FORMAT을 생략할 수 있습니다. (예: `--import-hashes hashes.csv`,
+    /// `--algorithm blake3 --import-hashes czkawka ~/.cache/czkawka/cache_duplicates_Blake3_70.json`)
+    #[arg(long, num_args = 1..=2, value_names = ["FORMAT", "FILE"], conflicts_with = "extra_hash")]
+    import_hashes: Option<Vec<String>>,
+
+    /// 해시가 이 파일에 있는 내용은 중복으로 묶지 않습니다. 라이선스 파일, 템플릿 헤더처럼 여러 곳에
+    /// 있는 것이 당연한 내용을 뺄 때 씁니다. 한 줄에 --algorithm의 16진수 해시 하나이며, `sha256sum`
+    /// 같은 도구의 출력(해시 뒤에 파일 이름)도 그대로 받습니다. `#`로 시작하는 줄은 주석입니다.
+    #[arg(long, value_name = "FILE")]
+    ignore_hashes: Option<PathBuf>,
+
+    /// 중복 검색 대신, 로컬 파일을 모두 해시하여 중앙 보관 서버의 해시 목록과 비교합니다. 이미 보관된 파일과
+    /// 아직 보관되지 않은 파일을 보여줍니다. 목록은 HTTP(S) 주소나 파일 경로이며, `sha256sum` 같은 체크섬
+    /// 도구의 출력 형식(--algorithm과 같은 해시)입니다. (예: `--compare-manifest https://archive/manifest.sha256`)
+    #[arg(long, value_name = "URL_OR_FILE", conflicts_with_all = ["tui", "daemon", "watch", "estimate", "match_mode", "stages"])]
+    compare_manifest: Option<String>,
+
+    /// 중복 검색 대신, 로컬 파일을 `index build`로 만들어 둔 오프라인 드라이브 카탈로그들과 비교하여 이미 어느
+    /// 드라이브에 있는지 보여줍니다. 이름을 적지 않으면 모든 카탈로그와 비교합니다. (예: `--against-catalogs drive1 drive2`)
+    #[arg(
+        long,
+        value_name = "LABEL",
+        num_args = 0..,
+        conflicts_with_all = ["tui", "daemon", "watch", "estimate", "match_mode", "stages", "compare_manifest"]
+    )]
+    against_catalogs: Option<Vec<String>>,
+
+    /// 카탈로그 폴더. 생략하면 사용자 데이터 폴더의 `duplicate_finder/catalogs`를 씁니다.
+    #[arg(long, value_name = "DIR")]
+    catalog_dir: Option<PathBuf>,
+
+    /// 멤버가 서로 다른 루트에 걸친 그룹만 보고합니다. 한 루트 안에서만 겹치는 중복은 뺍니다.
+    /// ("드라이브 B에 있는 것 중 이미 드라이브 A에 있는 것") 루트를 2개 이상 지정해야 합니다.
+    #[arg(long, conflicts_with = "min_copies")]
+    inter_root_only: bool,
+
+    /// 중복 검색 대신, 서로를 미러링해야 하는 루트들에서 내용이 N개 미만의 루트에만 있는 파일
+    /// (복제가 부족한 데이터)을 루트별로 보여줍니다. 루트를 N개 이상 지정해야 합니다.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(2..),
+        conflicts_with_all = ["tui", "daemon", "watch", "estimate", "match_mode", "stages", "compare_manifest", "against_catalogs"]
+    )]
+    min_copies: Option<u64>,
+
+    /// 중복 검색 대신, 서로 미러여야 하는 두 루트에서 상대 경로와 크기가 같은데 내용이 다른 파일
+    /// (조용한 손상 의심)을 보여줍니다. 루트를 정확히 2개 지정해야 하며, 파일은 항상 다시 읽습니다.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "tui", "daemon", "watch", "estimate", "match_mode", "stages", "compare_manifest", "against_catalogs", "min_copies",
+            "xattr_cache", "import_hashes"
+        ]
+    )]
+    check_mirror: bool,
+
+    /// 파일이 수억 개인 트리에서 메모리를 아끼기 위해, 먼저 파일 크기만 훑어 블룸 필터에 기록하고
+    /// 두 번째 탐색에서는 크기가 반복되는 파일만 기억합니다. 값은 필터에 쓸 메모리(MB, 기본 64)입니다.
+    /// 폴더를 두 번 읽으므로 탐색 시간은 늘어납니다.
+    #[arg(long, value_name = "MB", num_args = 0..=1, default_missing_value = "64", conflicts_with = "checkpoint")]
+    bloom_prepass: Option<NonZeroUsize>,
+
+    /// (macOS) 탐색 전에 Spotlight 색인에 필터와 크기 조건에 맞는 파일을 물어, 후보 파일이 없는 폴더는
+    /// 열지 않습니다. 색인에 없는 폴더(숨김 폴더, 개인 정보 보호 목록)에만 있는 파일은 찾지 못합니다.
+    #[arg(long, conflicts_with = "bloom_prepass")]
+    spotlight: bool,
+
+    /// 메모리에 모인 파일이 이 개수가 될 때마다 크기 그룹을 정렬하여 임시 파일로 내보내고, 탐색이 끝나면
+    /// 외부 병합으로 크기가 같은 파일을 모읍니다. 메타데이터만으로도 메모리를 넘는 볼륨을 검색할 때 씁니다.
+    #[arg(long, value_name = "N", conflicts_with = "checkpoint")]
+    spill_threshold: Option<NonZeroUsize>,
+
+    /// --spill-threshold와 --max-memory의 임시 파일을 만들 폴더. 기본값은 시스템 임시 폴더입니다.
+    #[arg(long, value_name = "DIR", requires = "spilling")]
+    spill_dir: Option<PathBuf>,
+
+    /// 크기 그룹, 해시 작업 목록, 블룸 필터 등이 쓸 메모리의 상한(MB, 추정치). 상한에 가까워지면
+    /// 크기 그룹을 임시 파일로 내보내고, 해시 단계는 후보를 나누어 차례로 처리합니다.
+    #[arg(long, value_name = "MB", conflicts_with = "checkpoint")]
+    max_memory: Option<NonZeroUsize>,
+
+    /// 긴 검색의 진행 상태(탐색 위치, 발견한 파일, 계산을 마친 해시)를 이 파일에 주기적으로 기록합니다.
+    /// 검색이 중간에 죽거나 중단되어도 `--resume`으로 마지막 기록 지점부터 이어서 할 수 있습니다.
+    /// 검색을 끝까지 마치면 파일은 삭제됩니다.
+    #[arg(long, value_name = "FILE", conflicts_with = "daemon")]
+    checkpoint: Option<PathBuf>,
+
+    /// 이 개수의 파일을 처리할 때마다 체크포인트를 기록합니다.
+    #[arg(long, value_name = "N", requires = "checkpoint")]
+    checkpoint_every: Option<NonZeroU64>,
+
+    /// 마지막 기록 후 이 시간(예: `5m`, `30s`)이 지나면 체크포인트를 기록합니다.
+    #[arg(long, value_name = "INTERVAL", default_value = "5m", requires = "checkpoint")]
+    checkpoint_interval: humantime::Duration,
+
+    /// `--checkpoint` 파일이 있으면 그 지점부터 이어서 검색합니다. 파일이 없으면 처음부터 검색합니다.
+    #[arg(long, requires = "checkpoint")]
+    resume: bool,
+
+    /// 검색에 쓸 수 있는 최대 시간(예: `2h`, `90m`). 시간이 다 되면 Ctrl+C를 누른 것처럼 하던 파일까지만
+    /// 처리하고 멈추며, 그때까지 확인된 그룹을 출력합니다. `--checkpoint`와 함께 쓰면 다음 실행에서
+    /// `--resume`으로 이어서 할 수 있습니다.
+    #[arg(long, value_name = "DURATION", conflicts_with = "daemon")]
+    max_runtime: Option<humantime::Duration>,
+
+    /// 찾은 중복 그룹 전체를 JSON 파일로 저장합니다. 나중에 `verify` 하위 명령으로
+    /// 저장된 결과가 여전히 유효한지 확인할 수 있습니다.
+    #[arg(long, value_name = "FILE")]
+    save_results: Option<PathBuf>,
+
+    /// 결과 목록, 오류 요약, 결과 파일(--save-results), 로그 파일(--log-file)의 경로를
+    /// 현재 폴더 기준의 절대 경로로 씁니다. 지정하지 않으면 루트 폴더를 입력한 그대로 이어 붙인 경로입니다.
+    #[arg(long, conflicts_with = "relative_to")]
+    absolute: bool,
+
+    /// --absolute와 같은 곳의 경로를 BASE 폴더 기준의 상대 경로로 씁니다.
+    /// 결과 파일에는 BASE가 함께 기록되어 `verify`와 `ignore add`가 다른 폴더에서도 파일을 찾습니다.
+    #[arg(long, value_name = "BASE")]
+    relative_to: Option<PathBuf>,
+
+    /// 각 그룹에서 원본으로 남길 파일을 고르는 규칙. 나머지 파일을 중복 사본으로 봅니다.
+    #[arg(long, value_enum, default_value_t = KeepPolicy::First)]
+    keep: KeepPolicy,
+
+    /// `--keep score`에서 폴더에 줄 우선순위 가중치. 여러 번 지정할 수 있으며, 음수는 그 폴더의
+    /// 사본을 덜 남기게 합니다. (예: `--prefer-dir ~/Pictures/Library=50 --prefer-dir ~/Downloads=-20`)
+    #[arg(long, value_name = "DIR=WEIGHT", value_parser = keep::parse_dir_weight)]
+    prefer_dir: Vec<(PathBuf, f64)>,
+
+    /// `--keep score`의 기준별 가중치. 빠진 항목은 기본값을 씁니다.
+    /// (기본: `depth=1,name=5,mtime=2,exif=3`)
+    #[arg(long, value_name = "WEIGHTS", value_parser = keep::parse_score_weights)]
+    score_weights: Option<ScoreWeights>,
+
+    /// 원본(--keep)을 뺀 나머지 사본을 백업 도구의 제외 파일 형식으로 씁니다.
+    /// 형식: `rsync`(--exclude-from), `borg`(--exclude-from), `restic`(--exclude-file).
+    /// (예: `--emit-excludes restic excludes.txt`)
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "FILE"])]
+    emit_excludes: Option<Vec<String>>,
+
+    /// 중복 파일이 있는 폴더를 노드로, 같은 내용을 가진 두 폴더를 함께 가진 크기만큼 굵은 선으로 이은
+    /// Graphviz DOT 그래프를 씁니다. 어느 폴더끼리 많이 겹치는지 보고 합칠 폴더를 고를 때 씁니다.
+    /// (예: `--export-dot graph.dot` 뒤 `dot -Tsvg graph.dot -o graph.svg`)
+    #[arg(long, value_name = "FILE")]
+    export_dot: Option<PathBuf>,
+
+    /// 검색이 끝난 뒤 명령을 한 번 실행하고, 결과 전체를 --save-results와 같은 형식의 JSON으로 표준
+    /// 입력에 넘깁니다. 명령과 인자 뒤에 `';'`를 붙여 끝을 표시합니다. 인자 안의 `{groups}`, `{files}`,
+    /// `{wasted}`, `{roots}`, `{interrupted}`는 값으로 바뀝니다. 셸을 거치지 않고 실행합니다.
+    /// (예: `--exec-batch dam-import --note "중복 {groups}개" ';'`)
+    #[arg(long, num_args = 1.., value_terminator = ";", allow_hyphen_values = true, value_name = "COMMAND")]
+    exec_batch: Option<Vec<String>>,
+
+    /// 파일을 같다고 판단하는 기준. `content`(기본)는 내용을 해시하여 비교하고, `name-size`는 파일을
+    /// 전혀 읽지 않고 (파일 이름, 크기)가 같은 파일을 묶습니다. `exif`는 내용 비교에 더해 EXIF 촬영
+    /// 정보가 같은 사진을, `office`는 메타데이터만 다른 오피스 문서를, `pdf`는 페이지 내용이 같은 PDF를,
+    /// `audio`는 태그만 다른 음원을 함께 묶습니다. 기본이 아닌 기준은 바이트가 다른 파일을 묶으므로, 결과를 근거로 파일을 지우거나 빼는 기능(--tui, --emit-excludes,
+    /// --contained-dirs)이나 결과 저장, 결과 전달(--exec-batch)과는 함께 쓸 수 없습니다.
+    #[arg(
+        long = "match",
+        value_enum,
+        default_value_t = MatchMode::Content,
+        conflicts_with_all = ["tui", "save_results", "exec_batch", "emit_excludes", "contained_dirs"]
+    )]
+    match_mode: MatchMode,
+
+    /// 내용 비교에 거칠 단계를 쉼표로 나열합니다. (기본: `size,hash`) `size`로 시작하여 `size`, `sample`
+    /// (파일 앞뒤 16 KB씩의 해시), `hash`(전체 해시), `bytes`(바이트 단위 비교) 순서로 고릅니다.
+    /// 예: 큰 파일이 많으면 `size,sample,hash`, 해시 충돌까지 배제하려면 `size,hash,bytes`.
+    /// `size,sample`은 전체를 읽지 않는 추정 결과이므로 --tui와 함께 쓸 수 없습니다.
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "STAGES")]
+    stages: Option<Vec<Stage>>,
+
+    /// 파일 내용의 해시 알고리즘. 기존 MD5/SHA-1 카탈로그나 md5deep 출력과 결과를 비교할 때
+    /// `md5`나 `sha1`을, 빠른 로컬 비교에는 `blake3`를 지정합니다.
+    /// (중복 판정에는 충돌에 강한 `sha256`이나 `blake3`를 권장합니다)
+    #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+    algorithm: HashAlgorithm,
+
+    /// 파일을 한 번 읽으면서 두 번째 해시도 계산하여 결과(--save-results)와 로그에 함께 기록합니다.
+    /// (예: `--algorithm blake3 --extra-hash sha256`) 두 해시가 모두 같은 파일만 중복으로 묶습니다.
+    #[arg(long, value_enum, value_name = "ALGORITHM")]
+    extra_hash: Option<HashAlgorithm>,
+
+    /// 해시 계산 방식입니다. SHA-NI, AVX2, NEON 같은 CPU 명령어는 항상 실행할 때 확인하여 쓰며,
+    /// 이 옵션은 BLAKE3가 파일 하나를 여러 스레드로 나누어 계산할지 정합니다.
+    /// auto(기본)는 64 MB 이상의 파일만 나눕니다. 실제로 쓴 구현은 --profile에 나옵니다.
+    #[arg(long, value_enum, value_name = "BACKEND", default_value_t = HashBackend::Auto)]
+    hash_backend: HashBackend,
+
+    /// 데몬 모드: 지정한 간격(예: `30m`, `6h`)마다 검색을 반복하고 매번 요약을 출력합니다.
+    /// Ctrl+C 또는 SIGTERM을 받으면 종료합니다.
+    #[arg(long, value_name = "INTERVAL", conflicts_with_all = ["tui", "estimate"])]
+    daemon: Option<humantime::Duration>,
+
+    /// 데몬 모드에서 Prometheus 메트릭을 제공할 주소 (예: `127.0.0.1:9184`).
+    /// `GET /metrics`로 검색 시간, 파일 수, 해시한 바이트, 중복 바이트, 캐시 적중률, 오류 수를 제공합니다.
+    #[arg(long, value_name = "ADDR", requires = "daemon")]
+    metrics_listen: Option<String>,
+
+    /// 데몬 모드에서 검색이 끝날 때마다 JSON 요약을 POST로 보낼 URL.
+    #[arg(long, value_name = "URL", requires = "daemon")]
+    webhook: Option<String>,
+
+    /// 데몬 모드에서 검색이 끝날 때마다 요약 메일을 보낼 SMTP 서버 (예: `localhost:25`).
+    /// 인증과 TLS 없이 보내므로 로컬이나 내부망의 메일 중계 서버를 지정하세요.
+    #[arg(long, value_name = "HOST:PORT", requires_all = ["daemon", "smtp_from", "smtp_to"])]
+    smtp_server: Option<String>,
+
+    /// 요약 메일의 보내는 주소.
+    #[arg(long, value_name = "ADDRESS", requires = "smtp_server")]
+    smtp_from: Option<String>,
+
+    /// 요약 메일을 받을 주소. 여러 번 지정할 수 있습니다.
+    #[arg(long, value_name = "ADDRESS", requires = "smtp_server")]
+    smtp_to: Vec<String>,
+
+    /// 드롭 폴더를 감시합니다. 시작할 때 루트 폴더들과 드롭 폴더의 파일을 색인한 뒤, 드롭 폴더에 새로 생긴
+    /// 파일이 색인된 내용과 같으면 --watch-action을 적용합니다. Ctrl+C 또는 SIGTERM을 받으면 종료합니다.
+    #[arg(long, value_name = "DROP_FOLDER", conflicts_with_all = ["tui", "daemon", "estimate"])]
+    watch: Option<PathBuf>,
+
+    /// 감시 모드에서 새 파일이 기존 내용과 같을 때 할 일.
+    /// report(보고만), hardlink(기존 파일의 하드 링크로 바꿈), quarantine(--quarantine-dir로 옮김)
+    #[arg(long, value_enum, value_name = "ACTION", default_value_t = WatchAction::Report, requires = "watch")]
+    watch_action: WatchAction,
+
+    /// --watch-action quarantine에서 중복 파일을 옮길 폴더. 드롭 폴더 밖에 있어야 합니다.
+    #[arg(long, value_name = "DIR", requires = "watch")]
+    quarantine_dir: Option<PathBuf>,
+
+    /// 감시 모드에서 드롭 폴더를 다시 살펴보는 간격. 새 파일은 두 번 연속 그대로일 때 처리합니다.
+    #[arg(long, value_name = "INTERVAL", default_value = "2s", requires = "watch")]
+    watch_interval: humantime::Duration,
+
+    /// 무시 목록 파일. 생략하면 사용자 캐시 폴더의 `duplicate_finder/ignored.json`을 씁니다.
+    /// (`ignore` 하위 명령과 TUI의 `i` 키로 기록합니다)
+    #[arg(long, value_name = "FILE")]
+    ignore_file: Option<PathBuf>,
+
+    /// 무시 목록에 있는 그룹도 숨기지 않고 보여줍니다.
+    #[arg(long)]
+    show_ignored: bool,
+
+    /// 카탈로그 폴더, 무시 목록, `--checkpoint` 파일을 다른 실행이 쓰고 있을 때 이 시간(예: `10m`)까지
+    /// 차례를 기다립니다. 생략하면 기다리지 않고 바로 실패합니다. 하위 명령에도 쓸 수 있습니다.
+    #[arg(long, value_name = "DURATION", global = true)]
+    lock_wait: Option<humantime::Duration>,
+
+    /// 개수와 크기를 쓸 로캘 (예: `de_DE`, `fr_FR`, `en_US`). 천 단위 구분 기호, 소수점 기호, 크기 단위
+    /// 기호가 바뀝니다. 값 없이 `--locale`만 쓰면 LC_ALL, LC_NUMERIC, LANG을 따릅니다. 생략하면 지금까지처럼
+    /// 구분 기호 없이 씁니다. JSON, CSV, 결과 파일의 숫자와 `(… bytes)` 값은 바꾸지 않습니다. 하위 명령에도 쓸 수 있습니다.
+    #[arg(long, value_name = "LOCALE", num_args = 0..=1, default_missing_value = "", global = true)]
+    locale: Option<String>,
+
+    /// 해시를 계산할 때 페이지 캐시를 거치지 않고 디스크에서 바로 읽습니다. (Linux의 O_DIRECT, macOS의
+    /// F_NOCACHE) 수 TB를 검색해도 같은 서버의 다른 서비스가 쓰는 캐시를 밀어내지 않습니다. 하위 명령에도
+    /// 쓸 수 있습니다.
+    #[arg(long, global = true)]
+    direct_io: bool,
+
+    /// 확인 질문에 모두 "예"로 답하고 바로 진행합니다.
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// 결과 파일(--save-results) 형식의 JSON Schema 문서를 출력하고 종료합니다.
+    #[arg(long, exclusive = true)]
+    schema: bool,
+}
+
+// --- 종료 코드 ---
+// 이 프로그램을 감싸는 스크립트가 출력 문구를 해석하지 않고도 결과를 알 수 있도록
+// 종료 코드를 다음과 같이 정의합니다.
+
+/// 검색을 마쳤고 중복 파일이 없습니다.
+const EXIT_NO_DUPLICATES: u8 = 0;
+/// 검색을 마쳤고 중복 파일을 찾았습니다.
+const EXIT_DUPLICATES_FOUND: u8 = 1;
+/// 검색은 마쳤지만 일부 파일/폴더를 읽지 못했습니다. (결과가 불완전할 수 있음)
+const EXIT_FILE_ERRORS: u8 = 2;
+/// 치명적인 오류로 작업을 완료하지 못했습니다. (잘못된 인자, 로그 파일 생성 실패 등)
+const EXIT_FATAL: u8 = 3;
+/// --strict 모드에서 읽을 수 없는 파일/폴더를 만나 검색을 중단했습니다.
+const EXIT_STRICT_FAILURE: u8 = 4;
+/// --max-runtime의 시간 제한에 걸려 검색을 멈췄습니다. 출력된 결과는 부분 결과입니다.
+const EXIT_TIME_LIMIT: u8 = 5;
+/// `verify`: 저장된 결과 중 바뀌었거나 사라진(또는 읽을 수 없는) 파일이 있습니다.
+const EXIT_VERIFY_STALE: u8 = 1;
+/// 사용자가 Ctrl+C로 검색을 중단했습니다. 출력된 결과는 부분 결과입니다. (128 + SIGINT)
+const EXIT_INTERRUPTED: u8 = 130;
+
+/// 사전 추정 단계(--estimate)에서 센 검색 대상의 규모.
+struct ScanEstimate {
+    files: u64,
+    bytes: u64,
+}
+
+/// 스캔 결과: 찾은 중복 그룹과, 처리하지 못한 파일/폴더의 수.
+struct ScanOutcome {
+    duplicates: Vec<DuplicateGroup>,
+    /// 처리하지 못한 파일/폴더 목록. 스캔 도중에는 출력하지 않고 모아 두었다가
+    /// 결과 출력이 끝난 뒤 한꺼번에 요약합니다.
+    errors: Vec<ScanError>,
+    /// 사용자의 중단 요청으로 스캔을 끝까지 마치지 못했다면 `true`.
+    /// 이 경우 `duplicates`에는 중단 시점까지 확인된 그룹만 들어 있습니다.
+    interrupted: bool,
+    /// --strict 모드에서 오류를 만나 스캔을 중단했다면 `true`.
+    strict_abort: bool,
+    /// 탐색 중 건너뛴 특수 파일(FIFO, 소켓, 장치 파일)의 수.
+    special_files: usize,
+    /// 탐색 중 건너뛴 클라우드 온라인 전용 파일의 수. (--hydrate가 없을 때)
+    placeholder_files: usize,
+    /// 처리량과 캐시 사용에 대한 수치.
+    stats: ScanStats,
+    /// 루트마다 탐색에서 발견한 파일 수와 크기. (`roots`와 같은 순서)
+    root_totals: Vec<RootTotals>,
+    /// 단계별 소요 시간과 가장 느린 파일 (--profile).
+    profile: Profile,
+}
+
+/// 스캔 한 번 동안 센 처리량과 캐시 사용 수치.
+#[derive(Clone, Copy, Default)]
+struct ScanStats {
+    /// 탐색 단계에서 발견한 (필터를 통과한) 파일 수.
+    files_scanned: u64,
+    /// 탐색 단계에서 발견한 파일 크기의 합. (--dedup-report의 전체 데이터 크기)
+    bytes_scanned: u64,
+    /// 해시 단계에서 해시를 얻은 파일 수. (캐시에서 가져온 해시 포함, 이어서 하기 전에 마친 파일은 제외)
+    files_hashed: u64,
+    /// 해시 계산을 위해 실제로 읽은 바이트 수. (캐시에서 가져온 해시는 제외)
+    bytes_hashed: u64,
+    /// 캐시(--xattr-cache)의 적중, 실패, 무효화 수와 추정 절약 시간.
+    cache: xattr_cache::CacheStats,
+}
+
+impl ScanStats {
+    /// 탐색 단계와 해시 단계의 처리 속도: (파일 수/초, 바이트/초). 걸린 시간이 0이면 `None`입니다.
+    fn throughput(&self, profile: &Profile) -> [(&'static str, Option<(f64, f64)>); 2] {
+        let rate = |files: u64, bytes: u64, duration: Duration| {
+            let seconds = duration.as_secs_f64();
+            (seconds > 0.0).then(|| (files as f64 / seconds, bytes as f64 / seconds))
+        };
+        [
+            ("walk", rate(self.files_scanned, self.bytes_scanned, profile.walk + profile.stat)),
+            ("hash", rate(self.files_hashed, self.bytes_hashed, profile.hash)),
+        ]
+    }
+
+    /// 검색이 끝난 뒤 단계별 처리 속도를 출력합니다. 플래그나 장비를 바꾼 실행끼리 비교할 수 있도록
+    /// 걸린 시간과 처리량을 함께 보여줍니다.
+    fn print_throughput(&self, profile: &Profile) {
+        println!("\n🚀 처리 속도");
+        let [(_, walk), (_, hash)] = self.throughput(profile);
+        let shown = |rate: Option<(f64, f64)>| match rate {
+            Some((files, bytes)) => format!("{} 파일/s, {}/s", number_format::format().decimal(files), format_size(bytes as u64)),
+            None => String::from("-"),
+        };
+        // 한글은 터미널에서 두 칸을 차지하므로 이름 열은 공백으로 직접 맞춥니다. (--profile과 같은 방법)
+        println!(
+            "  폴더 탐색   파일 {}개 ({}), {}초 → {}",
+            format_count(self.files_scanned),
+            format_size(self.bytes_scanned),
+            number_format::format().fixed((profile.walk + profile.stat).as_secs_f64(), 3),
+            shown(walk)
+        );
+        let cached = if self.cache.hits > 0 { format!(", 캐시 {}개 포함", format_count(self.cache.hits)) } else { String::new() };
+        println!(
+            "  해시 계산   파일 {}개{} ({} 새로 읽음), {}초 → {}",
+            format_count(self.files_hashed),
+            cached,
+            format_size(self.bytes_hashed),
+            number_format::format().fixed(profile.hash.as_secs_f64(), 3),
+            shown(hash)
+        );
+    }
+}
+
+/// 스캔 한 번의 실행 결과를 요약한 통계. 데몬 모드의 메트릭으로 내보냅니다.
+struct RunReport {
+    duration: Duration,
+    stats: ScanStats,
+    groups: usize,
+    wasted_bytes: u64,
+    /// 오류 분류별 개수.
+    errors_by_kind: BTreeMap<String, usize>,
+    interrupted: bool,
+    /// 단계별 소요 시간. (--metrics-out)
+    profile: Profile,
+}
+
+impl RunReport {
+    fn of(duration: Duration, outcome: &ScanOutcome) -> Self {
+        let mut errors_by_kind = BTreeMap::new();
+        for error in &outcome.errors {
+            *errors_by_kind.entry(error.kind.clone()).or_default() += 1;
+        }
+        RunReport {
+            duration,
+            stats: outcome.stats,
+            groups: outcome.duplicates.len(),
+            wasted_bytes: outcome.duplicates.iter().map(DuplicateGroup::wasted_bytes).sum(),
+            errors_by_kind,
+            interrupted: outcome.interrupted,
+            profile: outcome.profile.clone(),
+        }
+    }
+}
+
+/// 내용이 완전히 같은 파일들의 묶음(중복 그룹).
+#[derive(Clone)]
+struct DuplicateGroup {
+    /// 그룹에 속한 파일 하나의 크기 (모든 멤버의 크기가 같습니다).
+    /// EXIF 그룹(--match exif), 오피스 문서 그룹(--match office), PDF 그룹(--match pdf), 음원 그룹(--match audio)은
+    /// 멤버마다 크기가 다를 수 있어 가장 작은 멤버의 크기입니다.
+    size: u64,
+    /// 그룹 멤버들이 공유하는 해시 (16진수 문자열).
+    hash: String,
+    /// `hash`를 계산한 알고리즘.
+    algorithm: HashAlgorithm,
+    /// --extra-hash로 함께 계산한 두 번째 해시 (알고리즘, 16진수 문자열).
+    extra_hash: Option<(HashAlgorithm, String)>,
+    /// 그룹에 속한 파일 경로들. 항상 2개 이상입니다.
+    paths: Vec<PathBuf>,
+    /// 심볼릭 링크 그룹(--symlinks)이라면 멤버들이 공통으로 가리키는 대상 경로.
+    /// 이 경우 `size`와 `hash`는 대상 경로 문자열의 길이와 해시입니다.
+    symlink_target: Option<PathBuf>,
+    /// 멤버마다 해시하기 전에 확인한 크기와 수정 시간. 정리하기 직전에 다시 비교하여 검색 이후 바뀐 파일을 거릅니다.
+    /// 심볼릭 링크 그룹처럼 파일을 읽지 않은 그룹은 비어 있습니다.
+    stamps: HashMap<PathBuf, FileStamp>,
+}
+
+impl DuplicateGroup {
+    /// 멤버만 `paths`로 바꾼 그룹. 그룹을 속성이나 수정 시각으로 다시 나눌 때 씁니다.
+    fn subgroup(&self, paths: Vec<PathBuf>) -> DuplicateGroup {
+        DuplicateGroup {
+            size: self.size,
+            hash: self.hash.clone(),
+            algorithm: self.algorithm,
+            extra_hash: self.extra_hash.clone(),
+            stamps: paths.iter().filter_map(|p| self.stamps.get(p).map(|s| (p.clone(), *s))).collect(),
+            paths,
+            symlink_target: self.symlink_target.clone(),
+        }
+    }
+
+    /// 사본을 하나만 남기고 나머지를 정리했을 때 회수할 수 있는 바이트 수.
+    /// 심볼릭 링크는 파일 내용을 저장하지 않으므로 회수할 공간이 없습니다.
+    fn wasted_bytes(&self) -> u64 {
+        if self.symlink_target.is_some() {
+            return 0;
+        }
+        self.size * (self.paths.len() as u64 - 1)
+    }
+
+    /// 실행이 바뀌어도 같은 그룹을 가리키는 식별자. 그룹 번호는 결과가 바뀌면 밀리므로,
+    /// 어제와 오늘의 결과를 비교하는 스크립트는 이 값으로 그룹을 맞춰 봅니다.
+    fn id(&self) -> String {
+        group_id(self.size, &self.hash)
+    }
+}
+
+/// (크기, 해시)로 만든 그룹 식별자: `크기:해시`의 SHA-256 앞 16자리.
+/// 해시 자리에 표시가 붙는 그룹(exif:, office:, name-size: 등)도 같은 방법으로 만듭니다.
+fn group_id(size: u64, hash: &str) -> String {
+    let mut id = HashAlgorithm::Sha256.digest(format!("{}:{}", size, hash).as_bytes());
+    id.truncate(16);
+    id
+}
+
+/// 결과 전체의 그룹 식별자. --compare-attrs로 나뉜 그룹이나 바이트 비교에서 갈라진 해시 충돌 그룹은
+/// (크기, 해시)가 같으므로, 같은 식별자가 둘 이상이면 그룹에서 가장 앞선 경로의 해시를 덧붙여 구분합니다.
+fn group_ids(groups: &[DuplicateGroup]) -> Vec<String> {
+    let ids: Vec<String> = groups.iter().map(DuplicateGroup::id).collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for id in &ids {
+        *counts.entry(id.as_str()).or_default() += 1;
+    }
+    ids.iter()
+        .zip(groups)
+        .map(|(id, group)| match group.paths.iter().min() {
+            Some(first) if counts[id.as_str()] > 1 => {
+                let path = HashAlgorithm::Sha256.digest(first.as_os_str().as_encoded_bytes());
+                format!("{}-{}", id, &path[..6])
+            }
+            _ => id.clone(),
+        })
+        .collect()
+}
+
+/// 중복 그룹 전체에 대한 집계 수치.
+struct ScanSummary {
+    groups: usize,
+    files: usize,
+    wasted_bytes: u64,
+    /// 회수 가능한 공간이 가장 큰 그룹의 (파일 수, 파일 크기, 회수 가능 바이트, 그룹 식별자).
+    largest: Option<(usize, u64, u64, String)>,
+}
+
+impl ScanSummary {
+    fn of(groups: &[DuplicateGroup]) -> Self {
+        ScanSummary {
+            groups: groups.len(),
+            files: groups.iter().map(|g| g.paths.len()).sum(),
+            wasted_bytes: groups.iter().map(DuplicateGroup::wasted_bytes).sum(),
+            largest: groups
+                .iter()
+                .zip(group_ids(groups))
+                .max_by_key(|(g, _)| g.wasted_bytes())
+                .map(|(g, id)| (g.paths.len(), g.size, g.wasted_bytes(), id)),
+        }
+    }
+
+    /// 집계 수치를 출력합니다. 스크립트가 읽기 쉽도록 바이트 수는 원래 값도 함께 표시합니다.
+    fn print(&self, title: &str) {
+        println!("{}", title);
+        println!("  중복 그룹: {}개", format_count(self.groups));
+        println!("  중복 파일: {}개", format_count(self.files));
+        // 괄호 안의 바이트 수는 스크립트가 읽는 값이므로 로캘과 관계없이 구분 기호 없이 씁니다.
+        println!("  낭비된 공간: {} ({} bytes)", format_size(self.wasted_bytes), self.wasted_bytes);
+        if let Some((files, size, wasted, id)) = &self.largest {
+            println!(
+                "  가장 큰 그룹: {}개 파일, 파일당 {}, 회수 가능 {} ({} bytes), 식별자 {}",
+                format_count(*files),
+                format_size(*size),
+                format_size(*wasted),
+                wasted,
+                id
+            );
+        }
+    }
+}
+
+/// 스캔 중 처리하지 못한 파일/폴더 하나에 대한 기록.
+struct ScanError {
+    /// 문제가 된 경로. (walkdir가 경로를 알려주지 않는 경우 `None`)
+    path: Option<PathBuf>,
+    /// 오류가 발생한 단계 (예: "탐색", "해시 계산").
+    stage: &'static str,
+    /// 요약에서 오류를 종류별로 묶는 데 사용하는 분류 이름.
+    /// I/O 오류라면 `io::ErrorKind`의 설명(예: "permission denied"),
+    /// 그 외에는 `KIND_*` 상수 중 하나입니다.
+    kind: String,
+    /// 사용자에게 보여줄 오류 메시지 원문.
+    message: String,
+}
+
+/// 탐색 이후 해시 계산 전이나 해시 계산 도중에 내용이 바뀐 파일의 오류 분류.
+const KIND_MODIFIED: &str = "스캔 중 변경됨";
+
+/// 다른 프로세스가 잠갔거나 쓰고 있어 해시를 계산하지 않은 파일의 오류 분류.
+const KIND_IN_USE: &str = "다른 프로세스가 사용 중";
+
+/// 파일을 발견했을 때의 크기와 수정 시간.
+/// 해시 계산 직전과 직후에 다시 비교하여, 스캔 도중에 바뀐 파일의 (서로 다른 시점의
+/// 내용이 섞인) 해시를 결과에 쓰지 않도록 합니다.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        FileStamp { len: metadata.len(), modified: metadata.modified().ok() }
+    }
+
+    /// 파일이 `self`를 기록한 시점 이후로 바뀌지 않았는지 확인합니다.
+    /// 바뀌었거나 다시 읽을 수 없다면 (오류 분류, 메시지)를 반환합니다.
+    fn verify(&self, path: &Path, when: &str) -> Result<(), (String, String)> {
+        match std::fs::metadata(path) {
+            Ok(metadata) if FileStamp::of(&metadata) == *self => Ok(()),
+            Ok(_) => Err((KIND_MODIFIED.to_string(), format!("{} 파일의 크기 또는 수정 시간이 바뀌었습니다", when))),
+            Err(e) => Err((e.kind().to_string(), e.to_string())),
+        }
+    }
+}
+
+/// 중복 검색 외에 제공하는 하위 명령들.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 지정한 셸의 자동 완성 스크립트를 표준 출력으로 내보냅니다.
+    /// (예: `duplicate_finder completions bash > /etc/bash_completion.d/duplicate_finder`)
+    Completions {
+        /// 자동 완성 스크립트를 생성할 셸 (bash, zsh, fish, elvish, powershell).
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// man 페이지(roff 형식)를 표준 출력으로 내보냅니다.
+    /// (예: `duplicate_finder man > duplicate_finder.1`)
+    Man,
+    /// --save-results로 저장한 결과 파일의 각 멤버를 다시 해시하여, 그룹이 여전히 유효한지,
+    /// 어떤 파일이 바뀌었거나 사라졌는지 보고합니다.
+    Verify {
+        /// 검증할 결과 파일.
+        results_file: PathBuf,
+    },
+    /// --save-results로 저장한 결과들을 비교하는 보고서를 출력합니다.
+    Report {
+        #[command(subcommand)]
+        report: ReportCommand,
+    },
+    /// 일부러 남겨 둔 중복 그룹을 무시 목록에 기록하여, 다음 검색부터 보고하지 않게 합니다.
+    Ignore {
+        /// 무시 목록 파일. 생략하면 사용자 캐시 폴더의 `duplicate_finder/ignored.json`을 씁니다.
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+        #[command(subcommand)]
+        action: IgnoreCommand,
+    },
+    /// 외장 드라이브의 파일 카탈로그를 만들어 두어, 드라이브를 뽑은 뒤에도 --against-catalogs로 비교할 수 있게 합니다.
+    Index {
+        /// 카탈로그 폴더. 생략하면 사용자 데이터 폴더의 `duplicate_finder/catalogs`를 씁니다.
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+        #[command(subcommand)]
+        action: IndexCommand,
+    },
+    /// 서로 다른 때에 검색한 여러 루트(한꺼번에 연결할 수 없는 드라이브들)를 이름 하나의 작업 공간에 모아,
+    /// 그 전체에 걸친 중복을 보고합니다. (예: `duplicate_finder workspace archive add /mnt/drive3`)
+    Workspace {
+        /// 작업 공간 이름.
+        name: String,
+        /// 작업 공간들을 둘 폴더. 생략하면 사용자 데이터 폴더의 `duplicate_finder/workspaces`를 씁니다.
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+        #[command(subcommand)]
+        action: WorkspaceCommand,
+    },
+    /// 검색 시작, 진행 상황 스트리밍, 그룹 조회, 삭제 실행을 제공하는 REST API 서버를 실행합니다.
+    #[cfg(feature = "native")]
+    Serve {
+        /// 요청을 받을 주소.
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// 위험한 루트(`/`, 홈 폴더 전체, 마운트 루트)를 검색한 결과에서도 삭제를 허용합니다.
+        #[arg(long)]
+        force_dangerous_root: bool,
+        /// 모든 요청에 요구할 Bearer 토큰. 생략하면 DUPLICATE_FINDER_TOKEN 환경 변수를 읽고,
+        /// 둘 다 없으면 루프백 주소에서만 요청을 받습니다.
+        #[arg(long)]
+        token: Option<String>,
+        /// 브라우저 요청을 허용할 출처(예: `http://localhost:3000`). 여러 번 지정할 수 있습니다.
+        #[arg(long, value_name = "ORIGIN")]
+        allow_origin: Vec<String>,
+    },
+    /// 로컬 루트 폴더의 모든 파일을 해시하여 (크기, 해시, 경로) 기록을 코디네이터로 보냅니다.
+    #[cfg(feature = "native")]
+    Worker {
+        /// 기록을 받을 코디네이터 주소. (예: `http://10.0.0.1:8090`)
+        #[arg(long, value_name = "URL")]
+        coordinator: String,
+        /// 코디네이터에 보고할 이 서버의 이름. 생략하면 시스템 호스트 이름을 씁니다.
+        #[arg(long)]
+        host: Option<String>,
+        /// 검색할 로컬 루트 폴더들.
+        #[arg(short, long = "root-folder", value_name = "FOLDER_PATH", required = true)]
+        roots: Vec<PathBuf>,
+        /// 검색할 파일 이름을 지정합니다. (본 검색의 --file-filter와 같은 형식)
+        #[arg(short = 'f', long = "file-filter", value_name = "FILENAME_PATTERN")]
+        filter: Option<String>,
+        /// 확장 속성의 해시 캐시를 사용합니다. (--xattr-cache 참고)
+        #[arg(long)]
+        xattr_cache: bool,
+        /// 해시 알고리즘. 같은 코디네이터에 보고하는 worker는 모두 같은 알고리즘을 써야 합니다.
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+        /// 코디네이터에 보낼 Bearer 토큰. 생략하면 DUPLICATE_FINDER_TOKEN 환경 변수를 읽습니다.
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// 여러 tar 백업 아카이브(.tar, .tar.gz)의 파일을 내용 해시로 색인하여, 세대 사이에 중복 저장된
+    /// 파일과 지워도 되는(모든 내용이 남겨 둔 다른 아카이브에 있는) 오래된 아카이브를 보고합니다.
+    Archives {
+        /// 분석할 아카이브들. 오래된 것부터 적으면 오래된 아카이브부터 삭제를 검토합니다.
+        #[arg(required = true, num_args = 2..)]
+        archives: Vec<PathBuf>,
+        /// 해시 알고리즘.
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+        /// 여러 번 저장된 내용 중 회수 가능 공간이 큰 순서로 보여줄 개수.
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        top: usize,
+    },
+    /// 메일함(Maildir 폴더, mbox 파일, .eml 파일)의 첨부 파일을 내용 해시로 색인하여, 메일 사이에 여러 번
+    /// 저장된 첨부 파일과 -r 폴더에도 있는 첨부 파일을 보고합니다.
+    Mail {
+        /// 읽을 메일함들. 폴더는 Maildir의 메일과 그 아래의 .eml, .mbox 파일을 읽습니다.
+        #[arg(required = true)]
+        mailboxes: Vec<PathBuf>,
+        /// 첨부 파일과 같은 내용의 파일을 찾을 폴더. 여러 번 지정할 수 있습니다.
+        #[arg(short, long = "root", value_name = "DIR")]
+        roots: Vec<PathBuf>,
+        /// 해시 알고리즘.
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+        /// 두 번 이상 저장된 내용 중 회수 가능 공간이 큰 순서로 보여줄 개수.
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        top: usize,
+    },
+    /// 두 파일이 바이트 단위로 같은지, 또는 두 폴더 사이의 같은 파일, 다른 파일, 한쪽에만 있는 파일을
+    /// 보고합니다. (예: `duplicate_finder compare ~/Photos /mnt/backup/Photos`)
+    Compare {
+        a: PathBuf,
+        b: PathBuf,
+        /// 옮기거나 이름을 바꾼 파일을 찾을 때 쓰는 해시 알고리즘.
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+    },
+    /// 같은 사본, 하드 링크, 심볼릭 링크, 희소 파일, UTF-8이 아닌 이름 등 답을 아는 검사 폴더를 만들고,
+    /// 검색이 예상한 그룹을 정확히 찾는지 확인합니다. 모두 통과하면 0, 아니면 1로 끝납니다.
+    Selftest {
+        /// 검사 폴더를 만들 위치. 없는 폴더여야 합니다. (기본값: 임시 폴더 아래의 새 폴더)
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+        /// 검사가 끝난 뒤 검사 폴더를 지우지 않고 남깁니다.
+        #[arg(long)]
+        keep: bool,
+    },
+    /// 백업 스냅샷 폴더(`daily.0`, `daily.1` 등) 사이의 같은 파일을 하드 링크로 합쳐 백업 공간을 줄이거나,
+    /// 합친 것을 되돌립니다.
+    Snapshots {
+        #[command(subcommand)]
+        action: SnapshotsCommand,
+    },
+    /// worker들의 기록을 모아 여러 서버에 걸친 중복 그룹을 계산하고 HTTP로 제공합니다.
+    #[cfg(feature = "native")]
+    Coordinator {
+        /// 요청을 받을 주소.
+        #[arg(long, default_value = "127.0.0.1:8090")]
+        listen: String,
+        /// worker와 조회 요청에 요구할 Bearer 토큰. 생략하면 DUPLICATE_FINDER_TOKEN 환경 변수를 읽고,
+        /// 둘 다 없으면 루프백 주소에서만 요청을 받습니다. (serve --token 참고)
+        #[arg(long)]
+        token: Option<String>,
+        /// 브라우저 요청을 허용할 출처. 여러 번 지정할 수 있습니다.
+        #[arg(long, value_name = "ORIGIN")]
+        allow_origin: Vec<String>,
+    },
+    /// --sudo-helper가 권한을 올려 실행하는 내부 명령. 표준 입력으로 받은 목록의 파일을 다시 확인한 뒤 지웁니다.
+    #[cfg(feature = "native")]
+    #[command(hide = true)]
+    ElevatedDelete,
+    /// 검색 설정, 진행 상황, 그룹 탐색과 미리보기, 삭제 실행을 제공하는 GUI 창을 엽니다.
+    #[cfg(feature = "gui")]
+    Gui {
+        /// 처음에 입력해 둘 루트 폴더들.
+        roots: Vec<PathBuf>,
+    },
+}
+
+/// `ignore` 하위 명령의 작업.
+#[derive(Subcommand, Debug)]
+enum IgnoreCommand {
+    /// --save-results로 저장한 결과 파일에서 그룹 식별자로 고른 그룹들을 무시 목록에 추가합니다.
+    /// (예: `duplicate_finder ignore add scan.json 3f9a0c1e5b7d2468`)
+    Add {
+        /// 그룹을 고를 결과 파일.
+        results_file: PathBuf,
+        /// 추가할 그룹의 식별자들 (출력의 `[id]`).
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// 식별자로 고른 그룹들을 무시 목록에서 지웁니다. 다음 검색부터 다시 보고됩니다.
+    Remove {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// 무시 목록의 그룹들을 출력합니다.
+    List,
+}
+
+/// `snapshots` 하위 명령의 작업.
+#[derive(Subcommand, Debug)]
+enum SnapshotsCommand {
+    /// NEWER의 파일 중 OLDER에 같은 파일(크기, 수정 시각, 권한, 소유자, 확장 속성, 내용)이 있는 것을
+    /// OLDER 파일의 하드 링크로 바꿉니다. 바꾼 파일은 --journal에 기록합니다.
+    /// (예: `duplicate_finder snapshots link /backup/daily.1 /backup/daily.0 --journal daily.0.undo`)
+    Link {
+        /// 이전 스냅샷 폴더. 이 폴더의 파일은 바꾸지 않습니다.
+        older: PathBuf,
+        /// 새 스냅샷 폴더. 같은 파일을 OLDER 파일의 하드 링크로 바꿉니다.
+        newer: PathBuf,
+        /// 되돌리기 기록 파일. 새 파일이어야 하며, 스냅샷 폴더 밖에 두어야 합니다.
+        #[arg(long, value_name = "FILE", required_unless_present = "dry_run")]
+        journal: Option<PathBuf>,
+        /// 파일을 바꾸지 않고, 바꿀 파일 수와 회수될 공간만 보여줍니다.
+        #[arg(long, conflicts_with = "journal")]
+        dry_run: bool,
+        /// 해시 알고리즘. (바꾸기 직전에 바이트 비교로 한 번 더 확인합니다)
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+        /// 이보다 작은(바이트) 파일은 바꾸지 않습니다. 빈 파일은 언제나 건너뜁니다.
+        #[arg(long, value_name = "BYTES", default_value_t = 1)]
+        min_size: u64,
+    },
+    /// `snapshots link`의 되돌리기 기록을 읽어, 아직 이전 스냅샷과 연결된 파일을 독립된 복사본으로 되돌립니다.
+    Undo {
+        /// `snapshots link --journal`로 남긴 기록 파일.
+        journal: PathBuf,
+    },
+}
+
+/// `index` 하위 명령의 작업.
+#[derive(Subcommand, Debug)]
+enum IndexCommand {
+    /// 루트 폴더의 모든 파일을 해시하여 카탈로그로 저장합니다. 같은 이름의 카탈로그는 새로 만듭니다.
+    /// (예: `duplicate_finder index build /mnt/drive1 --label drive1`)
+    Build {
+        /// 카탈로그로 만들 폴더 (보통 드라이브의 마운트 위치).
+        root: PathBuf,
+        /// 카탈로그 이름. 비교 결과에 `이름:경로`로 나옵니다.
+        #[arg(long)]
+        label: String,
+        /// 해시 알고리즘. 비교할 때 --algorithm과 같아야 합니다.
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+    },
+    /// 저장된 카탈로그들을 출력합니다.
+    List,
+    /// 카탈로그를 지웁니다.
+    Remove { label: String },
+}
+
+/// `workspace` 하위 명령의 작업.
+#[derive(Subcommand, Debug)]
+enum WorkspaceCommand {
+    /// 루트 폴더의 모든 파일을 해시하여 작업 공간에 검색 하나로 더합니다. 같은 이름의 검색은 새로 바꿉니다.
+    Add {
+        /// 검색할 폴더 (보통 드라이브의 마운트 위치).
+        root: PathBuf,
+        /// 검색 이름. 보고서에 `이름:경로`로 나옵니다. 생략하면 루트 폴더의 이름을 씁니다.
+        #[arg(long)]
+        label: Option<String>,
+        /// 해시 알고리즘. 작업 공간의 모든 검색이 같아야 합니다.
+        #[arg(long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+    },
+    /// 작업 공간의 검색들을 출력합니다.
+    List,
+    /// 작업 공간에서 검색 하나를 뺍니다.
+    Remove { label: String },
+    /// 모든 검색을 합쳐 여러 번 저장된 내용과, 검색마다 그 검색에만 있는 내용을 보고합니다.
+    Report {
+        /// 여러 번 저장된 내용 중 회수 가능 공간이 큰 순서로 보여줄 개수.
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        top: usize,
+    },
+}
+
+/// `report` 하위 명령의 보고서 종류.
+#[derive(Subcommand, Debug)]
+enum ReportCommand {
+    /// 두 결과 사이에 새로 생긴 그룹, 사라진 그룹, 멤버가 늘거나 준 그룹을 그룹 식별자로 맞추어 보여줍니다.
+    /// (예: `duplicate_finder report diff last-week.json this-week.json`)
+    Diff {
+        /// 이전 결과 파일.
+        old: PathBuf,
+        /// 새 결과 파일.
+        new: PathBuf,
+        /// 목록마다 경로를 보여줄 그룹 수. 회수 가능 공간의 변화가 큰 그룹부터 보여줍니다.
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        top: usize,
+    },
+}
+
+/// 스캔 동작을 결정하는 설정 모음.
+struct ScanOptions {
+    /// 파일 이름/확장자 필터.
+    filter_mode: FilterMode,
+    /// 생성 시각이 이 시각 이후인 파일만 검색합니다 (--created-after).
+    created_after: Option<SystemTime>,
+    /// 생성 시각이 이 시각 이전인 파일만 검색합니다 (--created-before).
+    created_before: Option<SystemTime>,
+    /// 심볼릭 링크를 "대상 경로 문자열"을 내용으로 하는 항목으로 포함할지 여부.
+    include_symlinks: bool,
+    /// 같은 아이노드를 가리키는 하드 링크를 각각 별개의 파일로 볼지 여부 (--isolate).
+    isolate_hardlinks: bool,
+    /// 클라우드 온라인 전용 파일도 내려받아 해시할지 여부 (--hydrate).
+    hydrate: bool,
+    /// 오류를 하나라도 만나면 스캔을 즉시 중단할지 여부 (--strict).
+    strict: bool,
+    /// 이름을 비교하기 전에 유니코드 NFC로 정규화할지 여부 (--normalize-names).
+    normalize_names: bool,
+    /// 해시를 계산하는 스레드 수 (--threads).
+    threads: usize,
+    /// 장치별 동시 읽기 수. `None`이면 모든 파일을 `threads`개의 스레드로 읽습니다. (--device-threads)
+    device_limits: Option<devices::DeviceLimits>,
+    /// 동시에 열 수 있는 파일 수의 상한 (--fd-budget).
+    fd_budget: usize,
+    /// 확장 속성에 저장된 해시를 사용하고, 새로 계산한 해시를 기록할지 여부 (--xattr-cache).
+    xattr_cache: bool,
+    /// 확장 속성과 POSIX ACL이 같은 파일끼리만 그룹으로 남길지 여부 (--compare-attrs).
+    compare_attrs: bool,
+    /// 수정 시각이 같은 파일끼리만 그룹으로 남길지 여부 (--require-same-mtime).
+    require_same_mtime: bool,
+    /// 다른 도구에서 가져온 해시 (--import-hashes).
+    imported_hashes: Option<imports::ImportedHashes>,
+    /// 중복으로 보고하지 않을 내용의 해시 (--ignore-hashes).
+    ignored_hashes: Option<HashSet<String>>,
+    /// 파일을 같다고 판단하는 기준 (--match).
+    match_mode: MatchMode,
+    /// 크기 비교 뒤에 거칠 비교 단계 (--stages).
+    stages: Pipeline,
+    /// 해시 알고리즘 (--algorithm).
+    algorithm: HashAlgorithm,
+    /// 같은 읽기에서 함께 계산하여 기록할 두 번째 해시의 알고리즘 (--extra-hash).
+    extra_hash: Option<HashAlgorithm>,
+    /// 크기 사전 조사에 쓸 블룸 필터의 메모리(바이트). `None`이면 사전 조사를 하지 않습니다. (--bloom-prepass)
+    bloom_prepass: Option<usize>,
+    /// 탐색 전에 Spotlight 색인으로 후보가 없는 폴더를 고를지 여부 (--spotlight).
+    spotlight: bool,
+    /// 메모리의 크기 그룹을 임시 파일로 내보낼 기준 (--spill-threshold, --max-memory).
+    spill: Option<spill::Config>,
+    /// 스캔이 쓸 메모리(바이트)의 추정 상한 (--max-memory).
+    max_memory: Option<usize>,
+    /// 주기적으로 진행 상태를 기록하고 이어서 할 체크포인트 설정 (--checkpoint).
+    checkpoint: Option<checkpoint::Config>,
+}
+
+/// TUI 이미지 미리보기에 쓰는 터미널 그래픽 프로토콜 (--preview).
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum PreviewProtocol {
+    /// 환경 변수로 터미널을 알아봅니다. (tmux 안에서는 끕니다)
+    #[default]
+    Auto,
+    /// kitty 그래픽 프로토콜 (kitty, Ghostty, WezTerm 등).
+    Kitty,
+    /// iTerm2 인라인 이미지 (iTerm2, WezTerm 등).
+    Iterm2,
+    /// DEC sixel (foot, mlterm 등).
+    Sixel,
+    /// 미리보기를 그리지 않습니다.
+    Off,
+}
+
+/// 파일을 "같다"고 판단하는 기준.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum MatchMode {
+    /// 크기가 같은 파일의 내용을 해시하여 비교합니다. (정확)
+    #[default]
+    Content,
+    /// 파일을 읽지 않고 (파일 이름, 크기)가 같으면 같은 파일로 추정합니다. (추정)
+    NameSize,
+    /// 내용 해시로 비교하고, 사진은 EXIF의 (촬영 시각, 카메라 모델, 크기)가 같으면 같은 사진으로도
+    /// 묶습니다. 내보내기로 바이트가 달라진 사본을 찾을 때 씁니다. (사진 그룹은 추정)
+    Exif,
+    /// 내용 해시로 비교하고, docx/xlsx/pptx 문서는 문서 속성(core.xml 등)을 뺀 파트가 같으면
+    /// 같은 문서로도 묶습니다. 열었다가 다시 저장하기만 한 사본을 찾을 때 씁니다.
+    Office,
+    /// 내용 해시로 비교하고, PDF는 페이지 내용 스트림(과 페이지가 쓰는 이미지, 폼)이 같으면 같은
+    /// 문서로도 묶습니다. 문서 ID, 생성 시각, 증분 저장의 흔적만 다른 재생성본을 찾을 때 씁니다.
+    Pdf,
+    /// 내용 해시로 비교하고, mp3/flac/m4a는 태그(ID3, Vorbis 주석, MP4 메타데이터)를 뺀 오디오
+    /// 데이터가 같으면 같은 음원으로도 묶습니다. 태그만 고친 같은 음원을 찾을 때 씁니다.
+    Audio,
+}
+
+/// --match name-size 그룹의 `hash` 자리에 넣는 표시. 내용 해시가 아님을 나타냅니다.
+const NAME_SIZE_MARK: &str = "name-size";
+
+/// 파일 이름 필터링의 다양한 모드를 정의하는 열거형(enum).
+/// 문자열을 직접 사용하는 것보다 타입-세이프(type-safe)하고,
+/// `match` 구문을 통해 코드를 명확하게 만들 수 있어 좋은 설계 패턴입니다.
+enum FilterMode {
+    /// 필터를 적용하지 않음 (모든 파일 대상).
+    None,
+    /// 조건식으로 필터링. `--file-filter`의 이름/확장자도 조건 하나짜리 식으로 바꿉니다.
+    Expr(filter::Expr),
+}
+
+impl FilterMode {
+    /// `--file-filter` 값(없을 수도 있음)을 분석하여 필터 모드를 결정합니다.
+    fn from_arg(filter: Option<String>) -> Self {
+        match filter {
+            // 필터가 제공되지 않았다면 FilterMode::None
+            None => FilterMode::None,
+            // 필터 문자열이 제공되었다면
+            Some(filter_str) => {
+                // `strip_prefix("*.")`를 사용하여 문자열이 "*."로 시작하는지 확인합니다.
+                // 맞다면, 확장자 조건으로 설정하고 "*. " 부분을 제외한 나머지(확장자)를 저장합니다.
+                if let Some(ext) = filter_str.strip_prefix("*.") {
+                    FilterMode::Expr(filter::Expr::Ext(ext.to_string()))
+                } else {
+                    // "*." 패턴이 아니라면, 파일 이름 조건으로 설정합니다.
+                    FilterMode::Expr(filter::Expr::Name(filter_str))
+                }
+            }
+        }
+    }
+}
+
+/// `--threads`를 지정하지 않았을 때의 해시 스레드 수 (CPU 코어 수).
+fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// 프로그램의 메인 진입점.
+/// 종료 코드는 파일 상단의 `EXIT_*` 상수를 참고하세요.
+fn main() -> ExitCode {
+    // 1. 커맨드라인 인자 파싱
+    // `Args::try_parse()`는 사용자가 입력한 인자를 분석하여 `Args` 구조체를 채웁니다.
+    // 만약 사용자가 `--help`를 입력했거나 필수 인자(--root-folder)를 누락했다면,
+    // clap이 만든 도움말/오류 메시지를 출력하고 종료합니다. clap의 기본 종료 코드(2)는
+    // 이 프로그램의 "일부 파일 오류" 코드와 겹치므로, 인자 오류는 치명적 오류로 보고합니다.
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() { ExitCode::from(EXIT_FATAL) } else { ExitCode::SUCCESS };
+        }
+    };
+
+    if let Some(wait) = args.lock_wait {
+        store_lock::set_wait(wait.into());
+    }
+    // --locale: 하위 명령을 포함한 모든 출력의 숫자 표기를 정합니다. 값이 없으면 환경 변수의 로캘을 쓰고,
+    // 생략하면 스크립트가 읽는 출력이 사용자 환경에 따라 바뀌지 않도록 구분 기호 없이 씁니다.
+    if let Some(locale) = &args.locale {
+        number_format::set_format(if locale.is_empty() {
+            number_format::NumberFormat::from_env()
+        } else {
+            number_format::NumberFormat::for_locale(locale)
+        });
+    }
+    // --direct-io: 하위 명령을 포함한 모든 해시 계산에 적용합니다.
+    if args.direct_io {
+        if read_hints::direct_io_supported() {
+            read_hints::set_direct_io(true);
+        } else {
+            eprintln!("⚠️  이 플랫폼에서는 --direct-io를 쓸 수 없어 보통대로 읽습니다.");
+        }
+    }
+
+    // 하위 명령이 주어졌다면 중복 검색 대신 해당 명령만 실행하고 종료합니다.
+    if let Some(command) = &args.command {
+        return match run_command(command) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("오류 발생: {}", e);
+                ExitCode::from(EXIT_FATAL)
+            }
+        };
+    }
+
+    // --schema: 다른 옵션과 함께 쓸 수 없으므로(exclusive) 문서만 출력하고 끝냅니다.
+    if args.schema {
+        print!("{}", results::SCHEMA);
+        return ExitCode::SUCCESS;
+    }
+
+    // --emit-excludes의 형식 이름은 두 값 중 첫 번째이므로 clap이 검사할 수 없습니다.
+    // 긴 검색을 마친 뒤에 실패하지 않도록 시작 전에 확인합니다.
+    if let Some(values) = &args.emit_excludes
+        && ExcludeFormat::from_str(&values[0], true).is_err()
+    {
+        eprintln!("오류 발생: --emit-excludes 형식 '{}'을(를) 알 수 없습니다. (rsync, borg, restic)", values[0]);
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // 사진과 문서 목록은 체크포인트에 기록되지 않으므로, 이어서 한 검색은 그 그룹을 빠뜨립니다.
+    if matches!(args.match_mode, MatchMode::Exif | MatchMode::Office | MatchMode::Pdf | MatchMode::Audio) && args.checkpoint.is_some() {
+        eprintln!("오류 발생: --match exif, office, pdf, audio는 --checkpoint와 함께 쓸 수 없습니다.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // 내용이 아닌 기준으로 묶은 그룹은 바이트가 다르므로 파일 시스템이 합칠 수 없습니다.
+    if args.dedup_report && args.match_mode != MatchMode::Content {
+        eprintln!("오류 발생: --dedup-report는 --match content(기본)에서만 쓸 수 있습니다.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // --stages: 단계의 순서와 다른 옵션과의 조합을 검색 전에 확인합니다.
+    let stages = match args.stages.as_deref().map(Pipeline::new).transpose() {
+        Ok(stages) => stages.unwrap_or_default(),
+        Err(message) => {
+            eprintln!("오류 발생: {}", message);
+            return ExitCode::from(EXIT_FATAL);
+        }
+    };
+    if args.stages.is_some() && args.match_mode == MatchMode::NameSize {
+        eprintln!("오류 발생: --match name-size는 파일을 읽지 않으므로 --stages와 함께 쓸 수 없습니다.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+    // 해시 캐시, 가져온 해시, 두 번째 해시, 체크포인트는 모두 전체 해시 단계의 결과를 다룹니다.
+    if !stages.hash
+        && let Some(option) = [
+            (args.xattr_cache, "--xattr-cache"),
+            (args.import_hashes.is_some(), "--import-hashes"),
+            (args.ignore_hashes.is_some(), "--ignore-hashes"),
+            (args.extra_hash.is_some(), "--extra-hash"),
+            (args.checkpoint.is_some(), "--checkpoint"),
+        ]
+        .into_iter()
+        .find_map(|(used, name)| used.then_some(name))
+    {
+        eprintln!("오류 발생: {}는 --stages에 hash 단계가 있어야 쓸 수 있습니다.", option);
+        return ExitCode::from(EXIT_FATAL);
+    }
+    // 앞뒤 일부만 같은 파일을 지우지 않도록, 추정 결과로는 삭제 화면을 열거나 결과를 남기지 않습니다. (--match와 같은 규칙)
+    if stages.estimated()
+        && let Some(option) = [
+            (args.tui, "--tui"),
+            (args.save_results.is_some(), "--save-results"),
+            (args.exec_batch.is_some(), "--exec-batch"),
+            (args.emit_excludes.is_some(), "--emit-excludes"),
+            (args.contained_dirs, "--contained-dirs"),
+        ]
+        .into_iter()
+        .find_map(|(used, name)| used.then_some(name))
+    {
+        eprintln!("오류 발생: --stages size,sample은 추정 결과이므로 {}와 함께 쓸 수 없습니다.", option);
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // `native` 기능 없이 빌드했다면(WASI 등) 터미널 화면과 HTTP가 필요한 모드는 쓸 수 없습니다.
+    if let Some(option) = [(args.tui, "--tui"), (args.daemon.is_some(), "--daemon")]
+        .into_iter()
+        .find_map(|(used, name)| used.then_some(name))
+        .filter(|_| !cfg!(feature = "native"))
+    {
+        eprintln!("오류 발생: 이 빌드에는 {}가 포함되지 않았습니다. (`native` 기능으로 빌드하세요)", option);
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // 확장 속성을 읽을 수 없는 플랫폼에서는 속성이 다른 파일을 가려낼 수 없으므로 조용히 넘어가지 않습니다.
+    if args.compare_attrs && !cfg!(unix) {
+        eprintln!("오류 발생: --compare-attrs는 확장 속성을 지원하는 유닉스 계열 운영체제에서만 쓸 수 있습니다.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+    if args.by_owner && !cfg!(unix) {
+        eprintln!("오류 발생: --by-owner는 파일 소유자를 알 수 있는 유닉스 계열 운영체제에서만 쓸 수 있습니다.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+    if args.spotlight && !cfg!(target_os = "macos") {
+        eprintln!("오류 발생: --spotlight는 Spotlight 색인이 있는 macOS에서만 쓸 수 있습니다.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    if args.extra_hash == Some(args.algorithm) {
+        eprintln!("오류 발생: --extra-hash에는 --algorithm({})과 다른 알고리즘을 지정하세요.", args.algorithm.name());
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // --ignore-hashes: 해시 목록도 검색을 시작하기 전에 읽고 형식을 확인합니다.
+    let ignored_hashes = match &args.ignore_hashes {
+        Some(path) => match hashing::load_hash_list(path, args.algorithm) {
+            Ok(hashes) => Some(hashes),
+            Err(e) => {
+                eprintln!("오류 발생: {}", e);
+                return ExitCode::from(EXIT_FATAL);
+            }
+        },
+        None => None,
+    };
+
+    // --import-hashes: 가져올 파일을 읽을 수 없거나 알고리즘이 다르면 긴 검색을 시작하기 전에 알립니다.
+    let imported_hashes = match &args.import_hashes {
+        Some(values) => {
+            // 파일만 주었다면 확장자로 형식을 정합니다. (`--import-hashes hashes.csv`)
+            let (format, file) = match values.as_slice() {
+                [format, file] => match ImportFormat::from_str(format, true) {
+                    Ok(format) => (format, file),
+                    Err(_) => {
+                        eprintln!("오류 발생: --import-hashes 형식 '{}'을(를) 알 수 없습니다. (czkawka, fclones, csv)", format);
+                        return ExitCode::from(EXIT_FATAL);
+                    }
+                },
+                [file] if Path::new(file).extension().is_some_and(|e| e.eq_ignore_ascii_case("csv")) => (ImportFormat::Csv, file),
+                _ => {
+                    eprintln!("오류 발생: --import-hashes '{}'의 형식을 알 수 없습니다. 형식을 앞에 지정하세요. (czkawka, fclones, csv)", values[0]);
+                    return ExitCode::from(EXIT_FATAL);
+                }
+            };
+            let imported = match imports::load(format, Path::new(file), args.algorithm) {
+                Ok(imported) => imported,
+                Err(e) => {
+                    eprintln!("오류 발생: {}", e);
+                    return ExitCode::from(EXIT_FATAL);
+                }
+            };
+            if imported.algorithm() != args.algorithm {
+                eprintln!(
+                    "오류 발생: 가져온 해시는 {}이므로 --algorithm {}과 함께 써야 합니다.",
+                    imported.algorithm().name(),
+                    imported.algorithm().name()
+                );
+                return ExitCode::from(EXIT_FATAL);
+            }
+            println!("📥 {} 파일에서 해시 {}개를 가져왔습니다.", format.name(), imported.len());
+            Some(imported)
+        }
+        None => None,
+    };
+
+    // 2. 검색할 루트 폴더 설정
+    // 하위 명령과 --schema가 없을 때 `root_folder`는 clap이 필수 인자로 검사하므로 항상 하나 이상 있습니다.
+    // 서로 겹치는 루트(같은 폴더, 하위 폴더, 심볼릭 링크로 가리킨 같은 폴더)는 여기서 정리합니다.
+    let roots = match prepare_roots(&args.root_folder) {
+        Ok(roots) => roots,
+        // 루트 폴더 자체를 읽을 수 없다면 "일부 파일 오류"가 아니라 검색 자체가 불가능한 상황입니다.
+        Err(e) => {
+            eprintln!("오류 발생: {}", e);
+            return ExitCode::from(EXIT_FATAL);
+        }
+    };
+
+    // --watch: 드롭 폴더와 동작 설정을 감시를 시작하기 전에 확인합니다.
+    let watch_config = match &args.watch {
+        Some(folder) => match watch_config(&args, folder, &roots) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("오류 발생: {}", e);
+                return ExitCode::from(EXIT_FATAL);
+            }
+        },
+        None => None,
+    };
+
+    if args.inter_root_only && roots.len() < 2 {
+        eprintln!("오류 발생: --inter-root-only에는 겹치지 않는 루트 폴더가 2개 이상 필요합니다. (지정한 루트: {}개)", roots.len());
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // rsync 패턴은 루트 기준의 상대 경로이므로, 루트가 여럿이면 다른 루트에 남기는 사본까지 빠질 수 있습니다.
+    if roots.len() > 1 && emit_excludes_target(&args).is_some_and(|(format, _)| format == ExcludeFormat::Rsync) {
+        eprintln!("오류 발생: --emit-excludes rsync에는 루트 폴더를 하나만 지정하세요. (지정한 루트: {}개)", roots.len());
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    if let Some(min_copies) = args.min_copies
+        && roots.len() < min_copies as usize
+    {
+        eprintln!("오류 발생: --min-copies {}에는 루트 폴더가 {}개 이상 필요합니다. (지정한 루트: {}개)", min_copies, min_copies, roots.len());
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    if args.check_mirror && roots.len() != 2 {
+        eprintln!("오류 발생: --check-mirror에는 원본과 미러 루트 폴더가 정확히 2개 필요합니다. (지정한 루트: {}개)", roots.len());
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    // --compare-manifest: 목록을 받을 수 없거나 형식이 다르면 파일을 해시하기 전에 알립니다.
+    // --against-catalogs: 카탈로그들도 같은 비교 목록으로 합칩니다.
+    let manifest = match (&args.compare_manifest, &args.against_catalogs) {
+        (Some(source), _) => Manifest::load(source, args.algorithm).map(|manifest| {
+            println!("📜 '{}'에서 {} 해시 {}개를 읽었습니다.", source, args.algorithm.name(), manifest.len());
+            Some(manifest)
+        }),
+        (None, Some(labels)) => catalog_dir(args.catalog_dir.as_deref())
+            .and_then(|dir| catalog::manifest(&dir, labels, args.algorithm))
+            .map(|(manifest, used)| {
+                println!("💽 카탈로그 {}개({})에서 내용 {}개를 읽었습니다.", used.len(), used.join(", "), manifest.len());
+                Some(manifest)
+            }),
+        (None, None) => Ok(None),
+    };
+    let manifest = match manifest {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("오류 발생: {}", e);
+            return ExitCode::from(EXIT_FATAL);
+        }
+    };
+
+    // --created-after/--created-before: 생성 시각을 기록하지 않는 플랫폼이나 파일 시스템이라면
+    // 모든 파일이 조용히 빠지므로, 루트 폴더에서 미리 확인하여 알립니다.
+    if args.created_after.is_some() || args.created_before.is_some() {
+        for root in &roots {
+            if let Err(e) = std::fs::metadata(root).and_then(|m| m.created()) {
+                eprintln!("오류 발생: '{}'에서 파일의 생성 시각을 읽을 수 없습니다: {}", root.display(), e);
+                return ExitCode::from(EXIT_FATAL);
+            }
+        }
+    }
+
+    // 3. 파일 이름 필터 모드 결정
+    // `--filter` 식이나, 사용자가 입력한 `--file-filter` 값을 분석하여 `FilterMode`를 결정합니다.
+    let filter_mode = match &args.filter {
+        Some(expr) => FilterMode::Expr(expr.clone()),
+        None => FilterMode::from_arg(args.file_filter.clone()),
+    };
+    let max_memory = args.max_memory.map(|mb| mb.get().saturating_mul(1024 * 1024));
+    let threads = args.threads.map_or_else(default_threads, NonZeroUsize::get);
+    // --threads를 주지 않았다면 장치마다 저장 매체 종류로 정합니다. (하드 디스크는 스레드 1개)
+    let device_limits = if args.device_threads.is_empty() {
+        (args.threads.is_none() && cfg!(target_os = "linux")).then(|| devices::DeviceLimits::auto(threads))
+    } else {
+        match devices::DeviceLimits::resolve(&args.device_threads, threads, args.threads.is_none()) {
+            Ok(limits) => Some(limits),
+            Err(e) => {
+                eprintln!("오류 발생: {}", e);
+                return ExitCode::from(EXIT_FATAL);
+            }
+        }
+    };
+
+    // --absolute, --relative-to: 이후의 모든 출력에 쓸 경로 형식을 정합니다.
+    match PathStyle::new(args.absolute, args.relative_to.as_deref()) {
+        Ok(style) => path_style::set_style(style),
+        Err(e) => {
+            eprintln!("오류 발생: {}", e);
+            return ExitCode::from(EXIT_FATAL);
+        }
+    }
+
+    let options = ScanOptions {
+        filter_mode,
+        created_after: args.created_after,
+        created_before: args.created_before,
+        include_symlinks: args.symlinks,
+        isolate_hardlinks: args.isolate,
+        hydrate: args.hydrate,
+        strict: args.strict,
+        normalize_names: args.normalize_names,
+        threads,
+        device_limits,
+        fd_budget: args.fd_budget.map_or_else(reader_pool::default_fd_budget, NonZeroUsize::get),
+        xattr_cache: args.xattr_cache,
+        compare_attrs: args.compare_attrs,
+        require_same_mtime: args.require_same_mtime,
+        imported_hashes,
+        ignored_hashes,
+        match_mode: args.match_mode,
+        stages,
+        algorithm: args.algorithm,
+        extra_hash: args.extra_hash,
+        // --max-memory를 함께 쓰면 블룸 필터는 예산의 4분의 1을 넘지 않습니다.
+        bloom_prepass: args
+            .bloom_prepass
+            .map(|mb| mb.get().saturating_mul(1024 * 1024))
+            .map(|memory| max_memory.map_or(memory, |max| memory.min(max / 4))),
+        spotlight: args.spotlight,
+        // --max-memory의 절반은 탐색 중에 쌓이는 크기 그룹과 같은 파일 확인용 목록의 몫입니다.
+        spill: (args.spill_threshold.is_some() || max_memory.is_some()).then(|| spill::Config {
+            dir: args.spill_dir.clone().unwrap_or_else(std::env::temp_dir),
+            threshold: args.spill_threshold.map(NonZeroUsize::get),
+            memory: max_memory.map(|memory| memory / 2),
+        }),
+        max_memory,
+        checkpoint: args.checkpoint.clone().map(|path| checkpoint::Config {
+            path,
+            every: args.checkpoint_every.map(NonZeroU64::get),
+            interval: args.checkpoint_interval.into(),
+            resume: args.resume,
+        }),
+    };
+
+    // 4. 사용자에게 현재 검색 설정을 알려줌 (사용자 경험 개선)
+    // `roots`는 `main` 함수가 소유하고 있으므로, 다른 함수에는 빌려주어야 합니다(&).
+    print_search_info(&roots, &options.filter_mode);
+
+    // 삭제가 가능한 TUI 모드에서는 위험한 루트인지 미리 확인하여, 긴 스캔을 마친 뒤가 아니라
+    // 시작할 때 사용자에게 알려줍니다. 이 경우 TUI는 탐색만 가능하고 삭제는 거부합니다.
+    let delete_blocked = if args.tui {
+        safety::check_roots(&roots, args.force_dangerous_root).err().map(|reasons| {
+            for reason in &reasons {
+                eprintln!("⚠️ {}.", reason);
+            }
+            eprintln!("   이 루트에서는 TUI 삭제가 비활성화됩니다. (--force-dangerous-root로 허용)");
+            format!("위험한 루트를 검색했으므로 삭제가 비활성화되어 있습니다: {}.", reasons.join(", "))
+        })
+    } else {
+        None
+    };
+
+    // 지금부터 Ctrl+C는 프로세스를 즉시 죽이지 않고, 스캔을 정리하며 멈추게 합니다.
+    interrupt::install_handler();
+    hashing::set_backend(args.hash_backend);
+
+    // 사전 추정이 요청되었다면, 해시 계산이라는 비싼 작업을 시작하기 전에
+    // 검색 대상의 규모를 보여주고 계속할지 확인합니다.
+    let estimate = if args.estimate {
+        let estimate = estimate_scan(&roots, &options);
+        if interrupt::requested() {
+            println!("검색을 취소했습니다.");
+            return ExitCode::from(EXIT_INTERRUPTED);
+        }
+        println!(
+            "📊 사전 추정: 검색 대상 파일 {}개, 전체 크기 {}",
+            estimate.files,
+            format_size(estimate.bytes)
+        );
+        if !args.yes && !confirm("계속 진행하시겠습니까?") {
+            println!("검색을 취소했습니다.");
+            return ExitCode::SUCCESS;
+        }
+        Some(estimate)
+    } else {
+        None
+    };
+
+    // 로그 파일이 지정되었다면 생성합니다. 생성에 실패하면 스캔을 시작하지 않습니다.
+    let mut log = match &args.log_file {
+        Some(path) => match ScanLog::create(path) {
+            Ok(log) => log,
+            Err(e) => {
+                eprintln!("오류 발생: 로그 파일 '{}'을(를) 만들 수 없습니다: {}", path.display(), e);
+                return ExitCode::from(EXIT_FATAL);
+            }
+        },
+        None => ScanLog::disabled(),
+    };
+    log.event(
+        "scan_start",
+        json!({
+            "roots": roots.iter().map(|r| path_style::display(r)).collect::<Vec<_>>(),
+            "filter": match &options.filter_mode {
+                FilterMode::None => None,
+                FilterMode::Expr(expr) => Some(expr.to_string()),
+            },
+        }),
+    );
+
+    // --max-runtime: 사전 추정과 확인 질문이 끝난 지금부터 시간을 잽니다.
+    if let Some(limit) = args.max_runtime {
+        interrupt::start_time_limit(*limit);
+    }
+
+    // --watch: 검색 대신 드롭 폴더를 감시합니다.
+    if let Some(config) = &watch_config {
+        return watch::run(config, &options, &mut log);
+    }
+
+    // --compare-manifest, --against-catalogs: 검색 대신 해시 목록과 비교합니다.
+    if let Some(manifest) = &manifest {
+        return manifest::compare(&roots, &options, manifest, args.summary_only, &mut log);
+    }
+
+    // --min-copies: 검색 대신 루트 사이의 복제 수준을 점검합니다.
+    if let Some(min_copies) = args.min_copies {
+        return redundancy::report(&roots, &options, min_copies as usize, args.summary_only, &mut log);
+    }
+
+    // --check-mirror: 검색 대신 두 미러 사이에서 내용이 달라진 파일을 찾습니다.
+    if args.check_mirror {
+        return mirror_check::report(&roots, &options, args.summary_only, &mut log);
+    }
+
+    // --daemon: 같은 스캔을 주기적으로 반복하며, 요청이 있다면 메트릭을 HTTP로 제공합니다.
+    #[cfg(feature = "native")]
+    if let Some(interval) = args.daemon {
+        return daemon::run(&args, &roots, &options, &mut log, *interval);
+    }
+
+    // 5. 중복 파일 찾기 핵심 로직 실행
+    let expected_files = estimate.map(|e| e.files);
+    scan_and_report(&args, &roots, &options, &mut log, expected_files, delete_blocked).0
+}
+
+/// --watch의 설정을 확인하고 감시 설정을 만듭니다. 파일을 바꾸는 동작은 위험한 드롭 폴더에서 거부하고,
+/// 격리 폴더가 없다면 만듭니다.
+fn watch_config(args: &Args, folder: &Path, roots: &[PathBuf]) -> Result<watch::Config, String> {
+    if args.match_mode != MatchMode::Content {
+        return Err(String::from("--watch는 --match content(기본)에서만 쓸 수 있습니다"));
+    }
+    let folder = folder
+        .canonicalize()
+        .map_err(|e| format!("감시할 폴더 '{}'을(를) 열 수 없습니다: {}", folder.display(), e))?;
+    if !folder.is_dir() {
+        return Err(format!("감시할 경로 '{}'은(는) 폴더가 아닙니다", folder.display()));
+    }
+    if args.watch_action != WatchAction::Report
+        && let Err(reasons) = safety::check_roots(std::slice::from_ref(&folder), args.force_dangerous_root)
+    {
+        return Err(format!("{}. (--force-dangerous-root로 허용)", reasons.join(", ")));
+    }
+    let quarantine_dir = match (args.watch_action, &args.quarantine_dir) {
+        (WatchAction::Quarantine, None) => {
+            return Err(String::from("--watch-action quarantine에는 --quarantine-dir가 필요합니다"));
+        }
+        (_, Some(dir)) => {
+            std::fs::create_dir_all(dir)
+                .and_then(|()| dir.canonicalize())
+                .map_err(|e| format!("격리 폴더 '{}'을(를) 만들 수 없습니다: {}", dir.display(), e))?
+        }
+        (_, None) => PathBuf::new(),
+    };
+    // 격리 폴더가 드롭 폴더 안에 있으면 옮긴 파일이 다시 새 파일로 보입니다.
+    if !quarantine_dir.as_os_str().is_empty() && quarantine_dir.starts_with(&folder) {
+        return Err(String::from("--quarantine-dir는 감시할 폴더 밖에 있어야 합니다"));
+    }
+    let mut index_roots = roots.to_vec();
+    index_roots.push(folder.clone());
+    Ok(watch::Config {
+        index_roots: prepare_roots(&index_roots).map_err(|e| e.to_string())?,
+        folder,
+        action: args.watch_action,
+        quarantine_dir: (!quarantine_dir.as_os_str().is_empty()).then_some(quarantine_dir),
+        interval: args.watch_interval.into(),
+    })
+}
+
+/// 중복 검색을 한 번 실행하고 결과를 출력한 뒤, 종료 코드와 (스캔을 마쳤다면) 실행 통계를 반환합니다.
+fn scan_and_report(
+    args: &Args,
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    log: &mut ScanLog,
+    expected_files: Option<u64>,
+    delete_blocked: Option<String>,
+) -> (ExitCode, Option<RunReport>) {
+    // 무시 목록은 검색마다 다시 읽어, 데몬 모드에서도 그 사이에 기록한 그룹을 숨깁니다.
+    // 캐시 폴더를 알 수 없고 --ignore-file도 없다면 무시 목록 없이 검색합니다.
+    let ignore_file = ignore_path(args.ignore_file.as_deref()).ok();
+    let ignored = match &ignore_file {
+        Some(path) if !args.show_ignored => match IgnoreList::load(path) {
+            Ok(list) => Some(list),
+            Err(e) => {
+                eprintln!("오류 발생: {}", e);
+                return (ExitCode::from(EXIT_FATAL), None);
+            }
+        },
+        _ => None,
+    };
+    // --checkpoint: 같은 체크포인트 파일을 두 실행이 번갈아 고쳐 쓰지 않도록 검색하는 내내 잠급니다.
+    let _checkpoint_lock = match &options.checkpoint {
+        Some(config) => {
+            match store_lock::acquire(&store_lock::beside(&config.path), store_lock::Access::Exclusive, "체크포인트 파일") {
+                Ok(lock) => Some(lock),
+                Err(e) => {
+                    eprintln!("오류 발생: {}", e);
+                    return (ExitCode::from(EXIT_FATAL), None);
+                }
+            }
+        }
+        None => None,
+    };
+    // `find_duplicates` 함수는 파일 I/O 작업을 수행하므로 실패할 수 있습니다. (io::Result)
+    // 따라서 `match` 구문을 사용하여 성공(Ok)과 실패(Err) 케이스를 모두 처리합니다.
+    let mut progress = Progress::new();
+    let started = Instant::now();
+    let result = find_duplicates(roots, options, log, &mut progress, expected_files);
+    let duration = started.elapsed();
+    progress.finish();
+    match &result {
+        Ok(outcome) => log.event(
+            "scan_end",
+            json!({
+                "groups": outcome.duplicates.len(),
+                "duplicate_files": outcome.duplicates.iter().map(|g| g.paths.len()).sum::<usize>(),
+                "wasted_bytes": outcome.duplicates.iter().map(DuplicateGroup::wasted_bytes).sum::<u64>(),
+                "file_errors": outcome.errors.len(),
+                "interrupted": outcome.interrupted,
+                "strict_abort": outcome.strict_abort,
+                "special_files": outcome.special_files,
+                "placeholder_files": outcome.placeholder_files,
+            }),
+        ),
+        Err(e) => log.event("scan_end", json!({ "error": e.to_string() })),
+    }
+    log.flush();
+    let report = result.as_ref().ok().map(|outcome| RunReport::of(duration, outcome));
+    // --metrics-out: 검색이 실패해도 모니터링 쪽에서 알 수 있도록 매번 기록합니다.
+    if let Some(path) = &args.metrics_out {
+        let metrics = telemetry::metrics(roots, report.as_ref(), result.as_ref().err().map(ToString::to_string));
+        if let Err(e) = telemetry::write(path, &metrics) {
+            eprintln!("⚠️  지표 파일 '{}'을(를) 쓸 수 없습니다: {}", path.display(), e);
+        }
+    }
+
+    let code = match result {
+        // --strict 모드에서 오류를 만나 중단했다면, 불완전한 결과는 출력하지 않고
+        // 문제가 된 경로를 정확히 보고합니다.
+        Ok(outcome) if outcome.strict_abort => {
+            eprintln!("⛔ --strict 모드: 처리할 수 없는 항목을 만나 검색을 중단했습니다.");
+            print_error_summary(&outcome.errors, true);
+            ExitCode::from(EXIT_STRICT_FAILURE)
+        }
+        // 성공 시, 찾은 중복 파일 그룹(duplicates)을 처리합니다.
+        Ok(ScanOutcome {
+            mut duplicates, errors, interrupted, special_files, placeholder_files, mut profile, stats, root_totals, ..
+        }) => {
+            // 무시 목록에 있는 그룹은 저장, 출력, 종료 코드 어디에도 넣지 않습니다.
+            let hidden = ignored.as_ref().map_or(0, |list| list.hide(&mut duplicates));
+            // --inter-root-only: 멤버가 모두 한 루트 안에 있는 그룹도 같은 방법으로 뺍니다.
+            let within_root = if args.inter_root_only {
+                let before = duplicates.len();
+                duplicates.retain(|group| root_stats::spans_roots(roots, group));
+                before - duplicates.len()
+            } else {
+                0
+            };
+            let found = !duplicates.is_empty();
+            // 스캔 결과는 해시 맵을 거치므로 순서가 실행마다 달라집니다. 저장한 결과끼리
+            // diff로 비교할 수 있도록 출력 전에 항상 같은 순서로 정렬합니다.
+            if !args.no_sort {
+                let sort_started = Instant::now();
+                sort_groups(&mut duplicates);
+                profile.compare += sort_started.elapsed();
+            }
+            // 그룹 목록은 아래에서 출력 함수로 넘어가므로, 집계 수치는 미리 계산해 둡니다.
+            let summary = ScanSummary::of(&duplicates);
+            let histogram = args.histogram.then(|| SizeHistogram::of(&duplicates));
+            let keep = KeepRule::new(args.keep, &args.prefer_dir, args.score_weights.unwrap_or_default());
+            // 루트가 여럿이면 루트마다 다른 루트와 겹치는 정도를 보여줍니다. (데몬 모드에서는 요약만 남깁니다)
+            let root_stats = (roots.len() > 1 && args.daemon.is_none())
+                .then(|| RootStats::of(roots, &root_totals, &duplicates, &keep));
+            let owner_stats = (args.by_owner && found && args.daemon.is_none()).then(|| OwnerStats::of(&duplicates, &keep));
+            // --save-results: --top으로 잘라내기 전의 전체 결과를 저장합니다.
+            if let Some(path) = &args.save_results {
+                if let Err(e) = SavedResults::new(roots, &duplicates, interrupted, options.algorithm).save(path) {
+                    eprintln!("결과 파일 '{}'을(를) 저장할 수 없습니다: {}", path.display(), e);
+                    return (ExitCode::from(EXIT_FATAL), report);
+                }
+                println!("💾 결과를 '{}'에 저장했습니다.", path.display());
+            }
+            // --emit-excludes: 저장과 마찬가지로 --top과 관계없이 전체 결과로 만듭니다.
+            if let Some((format, path)) = emit_excludes_target(args) {
+                match excludes::emit(&path, format, &duplicates, roots, &keep) {
+                    Ok(emitted) => {
+                        println!(
+                            "🧾 {} 제외 목록 '{}'에 사본 {}개({})를 썼습니다.",
+                            format!("{:?}", format).to_lowercase(),
+                            path.display(),
+                            emitted.written,
+                            format_size(emitted.bytes)
+                        );
+                        for skipped in &emitted.skipped {
+                            eprintln!("⚠️  제외 패턴으로 표현할 수 없는 경로를 건너뛰었습니다: {}", skipped.display());
+                        }
+                        for shadowed in &emitted.shadowed {
+                            eprintln!("⚠️  남기는 사본과 제외 패턴이 같아 건너뛰었습니다: {}", shadowed.display());
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("제외 목록 '{}'을(를) 쓸 수 없습니다: {}", path.display(), e);
+                        return (ExitCode::from(EXIT_FATAL), report);
+                    }
+                }
+            }
+            // --export-dot: 폴더 관계도 --top과 관계없이 전체 결과로 그립니다.
+            if let Some(path) = &args.export_dot {
+                match folder_graph::export(path, &duplicates) {
+                    Ok(graph) => println!(
+                        "🕸️  폴더 관계 그래프 '{}'에 폴더 {}개와 연결 {}개를 썼습니다.",
+                        path.display(),
+                        graph.folders,
+                        graph.links
+                    ),
+                    Err(e) => {
+                        eprintln!("그래프 파일 '{}'을(를) 쓸 수 없습니다: {}", path.display(), e);
+                        return (ExitCode::from(EXIT_FATAL), report);
+                    }
+                }
+            }
+            // --exec-batch: 저장과 마찬가지로 --top과 관계없이 전체 결과를 넘깁니다.
+            if let Some(command) = &args.exec_batch {
+                let values = exec_batch::Values {
+                    groups: summary.groups,
+                    files: summary.files,
+                    wasted: summary.wasted_bytes,
+                    roots: roots.len(),
+                    interrupted,
+                };
+                let results = SavedResults::new(roots, &duplicates, interrupted, options.algorithm);
+                if let Err(e) = exec_batch::run(command, &results, &values) {
+                    eprintln!("--exec-batch 명령이 실패했습니다: {}", e);
+                    return (ExitCode::from(EXIT_FATAL), report);
+                }
+            }
+            if interrupted && interrupt::time_limit_reached() {
+                println!("\n⏱️  --max-runtime 시간이 다 되어 검색을 멈췄습니다. 아래는 그때까지 확인된 부분 결과입니다.");
+                match &args.checkpoint {
+                    Some(path) => println!(
+                        "   진행 상태를 '{}'에 기록했습니다. 다음 실행에서 --resume으로 이어서 검색할 수 있습니다.",
+                        path.display()
+                    ),
+                    None => println!("   --checkpoint를 함께 지정하면 다음 실행에서 이어서 검색할 수 있습니다."),
+                }
+            } else if interrupted {
+                println!("\n⏹️  검색이 중단되었습니다. 아래는 중단 시점까지 확인된 부분 결과입니다.");
+            }
+            if options.stages.estimated() && duplicates.iter().any(|g| stages::group_sample(&g.hash).is_some()) {
+                println!("\n⚠️  --stages size,sample: 파일 앞뒤 일부만 비교한 추정 결과입니다.");
+                println!("   실제로 같은 파일인지 확인하려면 hash나 bytes 단계를 더해 다시 검색하세요.");
+            }
+            if options.match_mode == MatchMode::NameSize && found {
+                println!("\n⚠️  --match name-size: 파일 내용을 읽지 않고 (이름, 크기)만 비교한 추정 결과입니다.");
+                println!("   실제로 같은 파일인지 확인하려면 기본 모드(--match content)로 다시 검색하세요.");
+            }
+            if duplicates.iter().any(|g| photos::group_key(&g.hash).is_some()) {
+                println!("\n⚠️  --match exif: '사진' 그룹은 내용이 아니라 EXIF 촬영 정보(촬영 시각, 카메라, 크기)가 같은 사진들입니다.");
+                println!("   내보내면서 화질이나 보정이 달라졌을 수 있으니, 정리하기 전에 직접 확인하세요.");
+            }
+            if hidden > 0 {
+                println!("\n🙈 무시 목록에 있는 그룹 {}개를 숨겼습니다. (--show-ignored로 표시)", hidden);
+            }
+            if within_root > 0 {
+                println!("\n📁 한 루트 안에서만 겹치는 그룹 {}개를 뺐습니다. (--inter-root-only)", within_root);
+            }
+            if !found && interrupted {
+                println!("중단 시점까지 확인된 중복 그룹이 없습니다.");
+            } else if !found && args.summary_only {
+                // 요약을 읽는 스크립트가 결과와 관계없이 같은 모양을 받도록, 0인 요약을 그대로 출력합니다.
+                summary.print("📋 요약");
+            } else if !found {
+                println!("✅ 중복된 파일을 찾지 못했습니다.");
+            } else if args.simulate {
+                // --top과 관계없이 전체 결과로 계산합니다.
+                simulate::report(&duplicates, &keep);
+            } else if args.dedup_report {
+                dedup_advice::report(&duplicates, &stats);
+            } else if args.same_dir {
+                same_dir::report(&duplicates, &keep, args.top);
+            } else if args.contained_dirs {
+                contained_dirs::report(&duplicates, roots, args.top);
+            } else if args.summary_only || args.daemon.is_some() {
+                // 집계 수치만 출력합니다. (중단된 경우에는 아래의 부분 요약이 같은 역할을 합니다.)
+                // 데몬 모드에서는 매 스캔마다 전체 목록을 출력하지 않고 요약만 남깁니다.
+                if !interrupted {
+                    summary.print("📋 요약");
+                }
+            } else {
+                println!("\n✨ {}개의 중복 파일 그룹을 찾았습니다:\n", format_count(duplicates.len()));
+                // --top N: 회수 가능한 공간이 큰 순서로 정렬한 뒤 상위 N개만 남깁니다.
+                if let Some(n) = args.top {
+                    // 안정 정렬이므로 회수 가능 공간이 같은 그룹끼리는 위에서 정한 순서가 유지됩니다.
+                    duplicates.sort_by_key(|g| std::cmp::Reverse(g.wasted_bytes()));
+                    if duplicates.len() > n {
+                        duplicates.truncate(n);
+                        println!("(회수 가능한 공간이 가장 큰 상위 {}개 그룹만 표시합니다.)\n", n);
+                    }
+                }
+                if args.tui {
+                    // TUI 모드에서는 텍스트로 출력하는 대신 대화형 화면을 엽니다.
+                    // (`native` 기능 없이 빌드했다면 main에서 --tui를 거부하므로 여기에 오지 않습니다)
+                    #[cfg(feature = "native")]
+                    {
+                        let prune = args.prune_empty_dirs.map(|mode| (roots.to_vec(), mode));
+                        match tui::run(duplicates, delete_blocked, prune, ignore_file, preview::resolve(args.preview)) {
+                            Ok(outcome) => {
+                                print_pruned(&outcome.pruned, args.prune_empty_dirs);
+                                elevate::offer(&outcome.denied, args.sudo_helper.as_deref());
+                            }
+                            Err(e) => {
+                                eprintln!("TUI 오류: {}", e);
+                                return (ExitCode::from(EXIT_FATAL), report);
+                            }
+                        }
+                    }
+                    #[cfg(not(feature = "native"))]
+                    drop(delete_blocked);
+                } else {
+                    // 결과 출력 함수를 호출합니다.
+                    // --keep score라면 각 멤버의 점수와 남길 원본을 함께 보여줍니다.
+                    print_duplicates(duplicates, (args.keep == KeepPolicy::Score).then_some(&keep), args.explain.then_some(options));
+                }
+            }
+            if let Some(histogram) = histogram.filter(|_| found) {
+                histogram.print();
+            }
+            if let Some(root_stats) = root_stats {
+                root_stats.print();
+            }
+            if let Some(owner_stats) = owner_stats {
+                owner_stats.print();
+            }
+            if let Some(format) = args.profile {
+                profile.print(format);
+            }
+            if options.xattr_cache && args.daemon.is_none() {
+                stats.cache.print();
+            }
+            // --profile json처럼 다른 도구가 읽을 형식을 골랐다면 사람이 읽는 처리 속도 표는 섞지 않습니다.
+            if args.daemon.is_none() && args.profile != Some(ProfileFormat::Json) {
+                stats.print_throughput(&profile);
+            }
+            // 스캔 도중 모아 둔 오류는 결과 출력이 끝난 뒤에 한꺼번에 요약합니다.
+            print_error_summary(&errors, args.show_errors);
+            if special_files > 0 {
+                println!("\nℹ️  특수 파일(FIFO, 소켓, 장치 파일) {}개를 건너뛰었습니다.", special_files);
+            }
+            if placeholder_files > 0 {
+                println!(
+                    "\n☁️  내려받지 않은 클라우드 온라인 전용 파일 {}개를 건너뛰었습니다. (--hydrate로 내려받아 포함)",
+                    placeholder_files
+                );
+            }
+
+            // 중단되었거나 일부 파일을 처리하지 못했다면 중복 여부와 관계없이 결과가
+            // 불완전하다는 것을 가장 먼저 알려야 하므로 해당 종료 코드가 우선합니다.
+            if interrupted {
+                summary.print("📋 부분 요약");
+                ExitCode::from(if interrupt::time_limit_reached() { EXIT_TIME_LIMIT } else { EXIT_INTERRUPTED })
+            } else if !errors.is_empty() {
+                ExitCode::from(EXIT_FILE_ERRORS)
+            } else if found {
+                ExitCode::from(EXIT_DUPLICATES_FOUND)
+            } else {
+                ExitCode::from(EXIT_NO_DUPLICATES)
+            }
+        }
+        // 실패 시, 표준 에러(stderr)에 오류 메시지를 출력합니다.
+        Err(e) => {
+            eprintln!("오류 발생: {}", e);
+            ExitCode::from(EXIT_FATAL)
+        }
+    };
+    (code, report)
+}
+
+/// 무시 목록 파일의 위치. 지정하지 않았다면 사용자 캐시 폴더의 기본 위치입니다.
+fn ignore_path(file: Option<&Path>) -> io::Result<PathBuf> {
+    file.map(Path::to_path_buf).or_else(ignore::default_path).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "사용자 캐시 폴더를 알 수 없습니다. --ignore-file로 무시 목록 파일을 지정하세요")
+    })
+}
+
+/// 카탈로그 폴더의 위치. 지정하지 않았다면 사용자 데이터 폴더의 기본 위치입니다.
+fn catalog_dir(dir: Option<&Path>) -> io::Result<PathBuf> {
+    dir.map(Path::to_path_buf).or_else(catalog::default_dir).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "사용자 데이터 폴더를 알 수 없습니다. --catalog-dir로 카탈로그 폴더를 지정하세요")
+    })
+}
+
+/// 하위 명령을 실행하고 종료 코드를 반환합니다.
+fn run_command(command: &Command) -> io::Result<ExitCode> {
+    // 자동 완성 스크립트와 man 페이지 모두 `Args`에 정의된 CLI 구조로부터 생성되므로,
+    // 옵션이 추가되어도 별도의 수정 없이 항상 최신 상태를 반영합니다.
+    let mut cli = Args::command();
+    match command {
+        Command::Completions { shell } => {
+            let name = cli.get_name().to_string();
+            clap_complete::generate(*shell, &mut cli, name, &mut io::stdout());
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Man => clap_mangen::Man::new(cli).render(&mut io::stdout()).map(|()| ExitCode::SUCCESS),
+        Command::Verify { results_file } => {
+            let saved = SavedResults::load(results_file)?;
+            Ok(if results::verify(&saved) { ExitCode::SUCCESS } else { ExitCode::from(EXIT_VERIFY_STALE) })
+        }
+        Command::Ignore { file, action } => {
+            let path = ignore_path(file.as_deref())?;
+            match action {
+                IgnoreCommand::Add { results_file, ids } => {
+                    ignore::add_from_results(&path, &SavedResults::load(results_file)?, ids)
+                }
+                IgnoreCommand::Remove { ids } => ignore::remove(&path, ids),
+                IgnoreCommand::List => ignore::print(&path),
+            }
+            .map(|()| ExitCode::SUCCESS)
+        }
+        Command::Index { dir, action } => {
+            let dir = catalog_dir(dir.as_deref())?;
+            match action {
+                IndexCommand::Build { root, label, algorithm } => catalog::build(&dir, root, label, *algorithm),
+                IndexCommand::List => catalog::list(&dir).map(|()| ExitCode::SUCCESS),
+                IndexCommand::Remove { label } => catalog::remove(&dir, label).map(|()| ExitCode::SUCCESS),
+            }
+        }
+        Command::Workspace { name, dir, action } => {
+            let parent = dir.clone().or_else(workspace::default_parent).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "사용자 데이터 폴더를 알 수 없습니다. --dir로 작업 공간 폴더를 지정하세요")
+            })?;
+            let dir = workspace::dir(&parent, name)?;
+            match action {
+                WorkspaceCommand::Add { root, label, algorithm } => workspace::add(&dir, root, label.as_deref(), *algorithm),
+                WorkspaceCommand::List => catalog::list(&dir).map(|()| ExitCode::SUCCESS),
+                WorkspaceCommand::Remove { label } => catalog::remove(&dir, label).map(|()| ExitCode::SUCCESS),
+                WorkspaceCommand::Report { top } => workspace::report(&dir, name, *top),
+            }
+        }
+        Command::Report { report: ReportCommand::Diff { old, new, top } } => {
+            report::diff(&SavedResults::load(old)?, &SavedResults::load(new)?, *top).map(|()| ExitCode::SUCCESS)
+        }
+        #[cfg(feature = "native")]
+        Command::ElevatedDelete => elevate::delete_from_stdin(),
+        #[cfg(feature = "native")]
+        Command::Serve { listen, force_dangerous_root, token, allow_origin } => {
+            let access = server::Access::new(listen, server::resolve_token(token.as_deref()), allow_origin.clone())?;
+            server::run(listen, *force_dangerous_root, access)
+        }
+        #[cfg(feature = "native")]
+        Command::Worker { coordinator, host, roots, filter, xattr_cache, algorithm, token } => {
+            let token = server::resolve_token(token.as_deref());
+            distributed::run_worker(coordinator, host.as_deref(), roots, filter.clone(), *xattr_cache, *algorithm, token.as_deref())
+        }
+        #[cfg(feature = "native")]
+        Command::Coordinator { listen, token, allow_origin } => {
+            let access = server::Access::new(listen, server::resolve_token(token.as_deref()), allow_origin.clone())?;
+            distributed::run_coordinator(listen, access)
+        }
+        Command::Archives { archives, algorithm, top } => {
+            let summary = archives::report(archives, *algorithm, *top);
+            Ok(ExitCode::from(if summary.failed {
+                EXIT_FILE_ERRORS
+            } else if summary.redundant {
+                EXIT_DUPLICATES_FOUND
+            } else {
+                EXIT_NO_DUPLICATES
+            }))
+        }
+        Command::Mail { mailboxes, roots, algorithm, top } => {
+            interrupt::install_handler();
+            let summary = mail::report(mailboxes, roots, *algorithm, *top);
+            Ok(ExitCode::from(if summary.failed {
+                EXIT_FILE_ERRORS
+            } else if summary.redundant {
+                EXIT_DUPLICATES_FOUND
+            } else {
+                EXIT_NO_DUPLICATES
+            }))
+        }
+        Command::Compare { a, b, algorithm } => {
+            interrupt::install_handler();
+            let summary = compare::run(a, b, *algorithm)?;
+            Ok(ExitCode::from(if summary.failed {
+                EXIT_FILE_ERRORS
+            } else if summary.differs {
+                EXIT_DUPLICATES_FOUND
+            } else {
+                EXIT_NO_DUPLICATES
+            }))
+        }
+        Command::Selftest { dir, keep } => selftest::run(dir.as_deref(), *keep),
+        Command::Snapshots { action } => {
+            let summary = match action {
+                SnapshotsCommand::Link { older, newer, journal, dry_run: _, algorithm, min_size } => {
+                    snapshots::link(&snapshots::LinkOptions {
+                        older,
+                        newer,
+                        journal: journal.as_deref(),
+                        algorithm: *algorithm,
+                        min_size: *min_size,
+                    })?
+                }
+                SnapshotsCommand::Undo { journal } => snapshots::undo(journal)?,
+            };
+            Ok(ExitCode::from(if summary.failed { EXIT_FILE_ERRORS } else { EXIT_NO_DUPLICATES }))
+        }
+        #[cfg(feature = "gui")]
+        Command::Gui { roots } => gui::run(roots.clone()).map(|()| ExitCode::SUCCESS),
+    }
+}
+
+/// 해시 계산 없이 디렉터리만 빠르게 탐색하여, 필터에 맞는 파일 수와 전체 크기를 셉니다.
+/// 본 스캔과 같은 조건(일반 파일 + 이름/확장자 필터)을 사용하므로 그 결과가 본 스캔의
+/// 진행 상황 표시에서 전체 수로 쓰입니다. 추정 단계이므로 읽을 수 없는 항목은 조용히 건너뜁니다.
+fn estimate_scan(roots: &[PathBuf], options: &ScanOptions) -> ScanEstimate {
+    let mut estimate = ScanEstimate { files: 0, bytes: 0 };
+    let mut progress = Progress::new();
+    let mut seen = HashSet::new();
+    for entry in roots
+        .iter()
+        .flat_map(WalkDir::new)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| passes_filter(e.path(), options))
+    {
+        if interrupt::requested() {
+            break;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !passes_created(&metadata, options) {
+            continue;
+        }
+        // 본 스캔과 마찬가지로 같은 물리적 파일은 한 번만 셉니다.
+        if already_seen(&mut seen, &metadata, options) {
+            continue;
+        }
+        estimate.files += 1;
+        estimate.bytes += metadata.len();
+        progress.update("사전 추정 중", estimate.files, None);
+    }
+    progress.finish();
+    estimate
+}
 
-// std::io::{...}: 입출력(I/O) 작업을 위한 모듈.
-// - io: Result<T, io::Error> 와 같은 공통 I/O 타입을 사용하기 위함.
-// - BufReader: 파일을 효율적으로 읽기 위한 버퍼 리더.
-// - Read: 데이터를 읽어오는 기능을 제공하는 트레이트(trait).
-use std::io::{self, BufReader, Read};
+/// 사용자에게 예/아니오 질문을 하고 답을 반환합니다.
+/// 표준 입력이 터미널이 아니라면(스크립트 실행 등) 질문할 수 없으므로 "예"로 간주합니다.
+fn confirm(question: &str) -> bool {
+    if !io::stdin().is_terminal() {
+        return true;
+    }
+    print!("{} [y/N] ", question);
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes" | "예")
+}
 
-// std::path::{Path, PathBuf}: 파일 시스템 경로를 다루기 위한 타입.
-// - Path: 경로에 대한 빌려온(borrowed) 슬라이스. 변경 불가능.
-// - PathBuf: 경로를 소유(owned)하며 변경 가능한 문자열 버퍼.
-use std::path::{Path, PathBuf};
+/// 사용자가 지정한 루트 폴더들을 검사하고, 서로 겹치는 루트를 정리합니다.
+///
+/// 각 루트를 정규화(canonicalize: 심볼릭 링크와 `..`를 풀어낸 절대 경로)하여 비교하고,
+/// 같은 폴더이거나 다른 루트의 하위 폴더인 루트는 제외합니다. 그렇지 않으면 같은
+/// 파일이 두 번 탐색되어 자기 자신의 중복으로 잘못 보고됩니다.
+/// 탐색에는 사용자가 입력한 경로를 그대로 사용하여, 출력되는 경로 형식을 유지합니다.
+fn prepare_roots(roots: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut canonical_roots: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for root in roots {
+        let canonical = root
+            .canonicalize()
+            .ok()
+            .filter(|p| p.is_dir())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("'{}'은(는) 접근 가능한 폴더가 아닙니다.", root.display()),
+                )
+            })?;
+        canonical_roots.push((root.clone(), canonical));
+    }
 
-// 외부 라이브러리 `clap`: 커맨드라인 인자 파싱을 위한 강력한 도구.
-// derive 기능을 통해 구조체 정의만으로 손쉽게 CLI를 만들 수 있습니다.
-use clap::Parser;
+    // 상위 폴더가 먼저 오도록 정규화된 경로의 길이(구성 요소 수) 순으로 정렬한 뒤,
+    // 이미 선택된 루트 아래에 있는 루트는 건너뜁니다.
+    let mut order: Vec<usize> = (0..canonical_roots.len()).collect();
+    order.sort_by_key(|&i| canonical_roots[i].1.components().count());
+    let mut selected: Vec<usize> = Vec::new();
+    for i in order {
+        let (original, canonical) = &canonical_roots[i];
+        if let Some(&parent) = selected.iter().find(|&&j| canonical.starts_with(&canonical_roots[j].1)) {
+            eprintln!(
+                "참고: '{}'은(는) '{}'와(과) 겹치므로 별도로 검색하지 않습니다.",
+                original.display(),
+                canonical_roots[parent].0.display()
+            );
+            continue;
+        }
+        selected.push(i);
+    }
+    // 사용자가 입력한 순서를 유지합니다.
+    selected.sort_unstable();
+    Ok(selected.into_iter().map(|i| canonical_roots[i].0.clone()).collect())
+}
 
-// 외부 라이브러리 `sha2`: SHA-256 해시 알고리즘 구현체.
-// - Digest: 모든 해시 함수가 구현해야 하는 공통 트레이트.
-// - Sha256: SHA-256 해시 계산기.
-use sha2::{Digest, Sha256};
+/// 심볼릭 링크의 대상 경로를 링크가 있는 폴더 기준으로 풀어낸 경로를 반환합니다.
+///
+/// `a/link -> ../z.txt`와 `link2 -> z.txt`처럼 적힌 문자열은 달라도 같은 곳을 가리키는
+/// 링크를 한 그룹으로 묶기 위함입니다. 대상이 존재하지 않는(끊어진) 링크도 비교할 수 있도록
+/// 파일 시스템에 접근하지 않고 `.`과 `..`만 경로상에서 정리합니다.
+fn resolve_link_target(link: &Path, target: &Path) -> PathBuf {
+    use std::path::Component;
+    let joined = match link.parent() {
+        Some(parent) if target.is_relative() => parent.join(target),
+        _ => target.to_path_buf(),
+    };
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match resolved.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    resolved.pop();
+                }
+                // 루트의 상위 폴더는 루트 자신입니다.
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                // 앞부분이 이미 `..`이거나 비어 있는 상대 경로라면 `..`을 그대로 남깁니다.
+                _ => resolved.push(".."),
+            },
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
 
-// 외부 라이브러리 `walkdir`: 디렉터리를 재귀적으로 탐색하는 편리한 도구.
-use walkdir::WalkDir;
+/// 이미 처리한 물리적 파일인지 확인하고, 처음 보는 파일이라면 기록해 둡니다.
+/// --isolate라면 하드 링크를 각각 별개의 파일로 보므로 항상 거짓입니다.
+fn already_seen(seen: &mut HashSet<(u64, u64)>, metadata: &std::fs::Metadata, options: &ScanOptions) -> bool {
+    !options.isolate_hardlinks && file_identity(metadata).is_some_and(|id| !seen.insert(id))
+}
 
-/// 파일 시스템에서 중복된 파일을 찾아 그룹화하여 출력하는 프로그램
-// `#[derive(Parser, Debug)]`: clap의 derive 매크로를 사용하여 이 구조체를 CLI 파서로 만듭니다.
-// Debug 트레이트는 `{:#?}` 등을 통해 구조체를 보기 좋게 출력하는 데 필요합니다.
-#[derive(Parser, Debug)]
-// `#[command(...)]`: 프로그램의 버전, 설명 등 메타데이터를 설정합니다. `--help` 시 출력됩니다.
-#[command(version, about, long_about = None)]
-struct Args {
-    /// [필수] 검색을 시작할 루트 폴더 경로.
-    // `#[arg(...)]`: 각 필드에 대한 CLI 옵션 설정을 정의합니다.
-    // - short: 짧은 옵션 이름 (e.g., -r)
-    // - long: 긴 옵션 이름 (e.g., --root-folder)
-    // - value_name: 도움말에 표시될 값의 이름 (e.g., <FOLDER_PATH>)
-    // 이 필드는 Option<T>가 아니므로, clap은 자동으로 필수(required) 인자로 처리합니다.
-    #[arg(short, long, value_name = "FOLDER_PATH")]
-    root_folder: PathBuf,
+/// 파일의 물리적 정체(장치 번호, inode 번호)를 반환합니다.
+/// 경로가 달라도 이 값이 같다면 같은 파일(하드 링크 포함)이므로 중복으로 보고하지 않습니다.
+#[cfg(unix)]
+fn file_identity(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
 
-    /// 검색할 파일 이름을 지정합니다 (예: "report.txt", "*.log").
-    // 이 필드는 Option<String> 이므로, clap은 자동으로 선택적(optional) 인자로 처리합니다.
-    #[arg(short, long, value_name = "FILENAME_PATTERN")]
-    file_filter: Option<String>,
+/// 유닉스가 아닌 플랫폼에서는 메타데이터만으로 파일의 물리적 정체를 알 수 없으므로,
+/// 루트 폴더 단위의 겹침 검사에만 의존합니다.
+#[cfg(not(unix))]
+fn file_identity(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
 }
 
-/// 파일 이름 필터링의 다양한 모드를 정의하는 열거형(enum).
-/// 문자열을 직접 사용하는 것보다 타입-세이프(type-safe)하고,
-/// `match` 구문을 통해 코드를 명확하게 만들 수 있어 좋은 설계 패턴입니다.
-enum FilterMode {
-    /// 필터를 적용하지 않음 (모든 파일 대상).
-    None,
-    /// 정확한 파일 이름으로 필터링.
-    ByExactName(String),
-    /// 파일 확장자로 필터링.
-    ByExtension(String),
+/// 일반 파일도 디렉터리도 심볼릭 링크도 아닌 특수 파일이라면 그 종류를 반환합니다.
+/// 이름 있는 파이프(FIFO)는 여는 순간 상대편이 나타날 때까지 멈출 수 있고, 장치 파일은
+/// 끝없이 읽힐 수 있으므로 절대 해시를 계산하지 않습니다.
+#[cfg(unix)]
+fn special_file_kind(file_type: std::fs::FileType) -> Option<&'static str> {
+    use std::os::unix::fs::FileTypeExt;
+    if file_type.is_fifo() {
+        Some("fifo")
+    } else if file_type.is_socket() {
+        Some("socket")
+    } else if file_type.is_block_device() {
+        Some("block_device")
+    } else if file_type.is_char_device() {
+        Some("char_device")
+    } else {
+        None
+    }
 }
 
-/// 프로그램의 메인 진입점.
-fn main() {
-    // 1. 커맨드라인 인자 파싱
-    // `Args::parse()`는 사용자가 입력한 인자를 분석하여 `Args` 구조체를 채웁니다.
-    // 만약 사용자가 `--help`를 입력했거나 필수 인자(--root-folder)를 누락했다면,
-    // clap이 자동으로 도움말/오류 메시지를 출력하고 프로그램을 종료시켜 줍니다.
-    let args = Args::parse();
+/// 유닉스가 아닌 플랫폼에서는 일반 파일과 디렉터리 외의 파일 형식을 구분하지 않습니다.
+#[cfg(not(unix))]
+fn special_file_kind(_file_type: std::fs::FileType) -> Option<&'static str> {
+    None
+}
 
-    // 2. 검색할 루트 폴더 설정
-    // `root_folder`는 필수 인자이므로 이제 Option을 해제할 필요 없이 직접 사용합니다.
-    // args.root_folder는 PathBuf 타입의 소유권을 가집니다.
-    let root_path = args.root_folder;
+/// 루트 폴더를 탐색하여 해시할 일반 파일 목록을 만듭니다. (본 검색의 탐색 단계와 같은 규칙)
+/// 크기와 관계없이 모든 파일을 해시해야 하는 worker와 --compare-manifest가 씁니다.
+fn collect_files(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    errors: &mut Vec<ScanError>,
+    log: &mut ScanLog,
+    progress: &mut Progress,
+) -> Vec<(PathBuf, FileStamp)> {
+    walk_files(roots, options, false, errors, log, progress)
+}
 
-    // 3. 파일 이름 필터 모드 결정
-    // 사용자가 입력한 `--file-filter` 값을 분석하여 `FilterMode`를 결정합니다.
-    let filter_mode = match args.file_filter {
-        // 필터가 제공되지 않았다면 FilterMode::None
-        None => FilterMode::None,
-        // 필터 문자열이 제공되었다면
-        Some(filter_str) => {
-            // `strip_prefix("*.")`를 사용하여 문자열이 "*."로 시작하는지 확인합니다.
-            // 맞다면, 확장자 필터 모드로 설정하고 "*. " 부분을 제외한 나머지(확장자)를 저장합니다.
-            if let Some(ext) = filter_str.strip_prefix("*.") {
-                FilterMode::ByExtension(ext.to_string())
-            } else {
-                // "*." 패턴이 아니라면, 정확한 이름 필터 모드로 설정합니다.
-                FilterMode::ByExactName(filter_str)
-            }
+/// `collect_files`와 같지만, `keep_hardlinks`이면 같은 물리적 파일의 경로(하드 링크)를 모두 남깁니다.
+/// 내용이 아니라 경로끼리 짝을 짓는 --check-mirror가 씁니다.
+fn walk_files(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    keep_hardlinks: bool,
+    errors: &mut Vec<ScanError>,
+    log: &mut ScanLog,
+    progress: &mut Progress,
+) -> Vec<(PathBuf, FileStamp)> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in roots.iter().flat_map(WalkDir::new) {
+        if interrupt::requested() {
+            break;
         }
-    };
-    
-    // 4. 사용자에게 현재 검색 설정을 알려줌 (사용자 경험 개선)
-    // `root_path`는 `main` 함수가 소유하고 있으므로, 다른 함수에는 빌려주어야 합니다(&).
-    print_search_info(&root_path, &filter_mode);
-
-    // 5. 중복 파일 찾기 핵심 로직 실행
-    // `find_duplicates` 함수는 파일 I/O 작업을 수행하므로 실패할 수 있습니다. (io::Result)
-    // 따라서 `match` 구문을 사용하여 성공(Ok)과 실패(Err) 케이스를 모두 처리합니다.
-    match find_duplicates(&root_path, &filter_mode) {
-        // 성공 시, 찾은 중복 파일 그룹(duplicates)을 처리합니다.
-        Ok(duplicates) => {
-            if duplicates.is_empty() {
-                println!("✅ 중복된 파일을 찾지 못했습니다.");
-            } else {
-                println!("\n✨ {}개의 중복 파일 그룹을 찾았습니다:\n", duplicates.len());
-                // 결과 출력 함수를 호출합니다.
-                print_duplicates(duplicates);
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let kind = e.io_error().map_or(io::ErrorKind::Other, |io| io.kind());
+                record_error(errors, log, e.path().map(|p| p.to_path_buf()), "탐색", kind.to_string(), e.to_string());
+                continue;
             }
+        };
+        if !passes_filter(entry.path(), options)
+            || special_file_kind(entry.file_type()).is_some()
+            || !entry.file_type().is_file()
+        {
+            continue;
         }
-        // 실패 시, 표준 에러(stderr)에 오류 메시지를 출력합니다.
-        Err(e) => {
-            eprintln!("오류 발생: {}", e);
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let kind = e.io_error().map_or(io::ErrorKind::Other, |io| io.kind());
+                record_error(errors, log, Some(entry.into_path()), "탐색", kind.to_string(), e.to_string());
+                continue;
+            }
+        };
+        // 같은 물리적 파일은 한 번만, 크기가 0인 파일은 중복으로 보지 않으므로 건너뜁니다.
+        // 내려받지 않은 온라인 전용 파일도 --hydrate가 없으면 건너뜁니다.
+        if metadata.len() == 0
+            || (!keep_hardlinks && already_seen(&mut seen, &metadata, options))
+            || (!options.hydrate && placeholders::is_placeholder(entry.path(), &metadata))
+        {
+            continue;
         }
+        files.push((entry.into_path(), FileStamp::of(&metadata)));
+        progress.update("파일 탐색 중", files.len() as u64, None);
     }
+    files
 }
 
 /// 현재 검색 설정을 요약하여 화면에 출력하는 헬퍼 함수.
-fn print_search_info(root: &Path, filter: &FilterMode) {
+fn print_search_info(roots: &[PathBuf], filter: &FilterMode) {
     let filter_desc = match filter {
         FilterMode::None => "모든 파일".to_string(),
-        FilterMode::ByExactName(name) => format!("이름이 '{}'인 파일", name),
-        FilterMode::ByExtension(ext) => format!("확장자가 '.{}'인 파일", ext),
+        FilterMode::Expr(filter::Expr::Name(name)) => format!("이름이 '{}'인 파일", name),
+        FilterMode::Expr(filter::Expr::Ext(ext)) => format!("확장자가 '.{}'인 파일", ext),
+        FilterMode::Expr(expr) => format!("조건 '{}'에 맞는 파일", expr),
     };
     // `.display()` 메소드는 Path/PathBuf를 운영체제에 맞는 방식으로 출력 가능하게 만들어줍니다.
+    let roots_desc = roots
+        .iter()
+        .map(|r| format!("'{}'", r.display()))
+        .collect::<Vec<_>>()
+        .join(", ");
     println!(
-        "🔍 '{}' 폴더에서 {}을(를) 대상으로 중복 파일을 검색합니다...",
-        root.display(),
+        "🔍 {} 폴더에서 {}을(를) 대상으로 중복 파일을 검색합니다...",
+        roots_desc,
         filter_desc
     );
 }
 
-/// 지정된 경로에서 필터 조건에 맞는 중복 파일 그룹을 찾아 반환합니다.
-fn find_duplicates(root: &Path, filter_mode: &FilterMode) -> io::Result<Vec<Vec<PathBuf>>> {
+/// 지정된 루트 폴더들에서 필터 조건에 맞는 중복 파일 그룹을 찾아 반환합니다.
+/// 진행 상황(발견한 파일, 해시 결과, 경고, 단계별 소요 시간)은 `log`에 기록되고,
+/// 화면에는 `progress`로 표시됩니다. `expected_files`는 사전 추정으로 센 전체 파일 수입니다.
+fn find_duplicates(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    log: &mut ScanLog,
+    progress: &mut Progress,
+    expected_files: Option<u64>,
+) -> io::Result<ScanOutcome> {
     // --- 1단계: 파일 크기로 그룹화 (빠른 1차 필터링) ---
+    let walk_started = Instant::now();
+    let mut profile = Profile::default();
+    let mut found_files = 0u64;
+    let mut found_bytes = 0u64;
+    let mut root_totals = vec![RootTotals::default(); roots.len()];
     // `u64` (파일 크기)를 키로, `Vec<PathBuf>` (파일 경로 리스트)를 값으로 가집니다.
-    let mut files_by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    // 각 경로와 함께, 변경 감지를 위해 발견 시점의 크기/수정 시간(FileStamp)을 기록해 둡니다.
+    let mut files_by_size: HashMap<u64, Vec<(PathBuf, FileStamp)>> = HashMap::new();
+
+    // 처리하지 못한 파일/폴더 목록. 마지막 요약과 종료 코드(2)를 결정하는 데 사용됩니다.
+    let mut errors: Vec<ScanError> = Vec::new();
+
+    // --symlinks: 대상 경로 -> 그 대상을 가리키는 심볼릭 링크 경로 리스트.
+    let mut symlinks_by_target: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+    // 이미 본 물리적 파일(장치 번호, inode). 같은 파일이 다른 경로로 다시 나타나면 건너뜁니다.
+    let mut seen_files = HashSet::new();
+
+    // 건너뛴 특수 파일(FIFO, 소켓, 장치 파일)과 클라우드 온라인 전용 파일의 수. 요약에 표시합니다.
+    let mut special_files = 0usize;
+    let mut placeholder_files = 0usize;
+
+    // --match exif/office/pdf/audio: 내용 대신 촬영 정보, 문서 파트, 페이지 내용, 오디오 데이터로 비교할 파일들 (경로, 크기).
+    // 크기가 달라도 같은 사진/문서/음원일 수 있으므로 크기 그룹과 따로 모읍니다.
+    let mut loose_files: Vec<(PathBuf, FileStamp)> = Vec::new();
+
+    // --checkpoint: 주기적으로 진행 상태를 기록하고, --resume이면 기록된 상태를 되살립니다.
+    let mut checkpointer = options.checkpoint.as_ref().map(|c| checkpoint::Checkpointer::new(c, roots, options));
+    // 마지막으로 처리한 탐색 위치 (체크포인트에 기록합니다).
+    let mut position: Option<checkpoint::Position> = None;
+    let mut walk_complete = false;
+    // 이전 실행에서 계산을 마친 해시. 파일이 그대로라면 다시 읽지 않습니다.
+    let mut resumed_hashes: HashMap<PathBuf, (String, Option<String>)> = HashMap::new();
+    if let Some(resumed) = checkpointer.as_ref().map(|c| c.load()).transpose()?.flatten() {
+        walk_complete = resumed.walk_complete;
+        position = resumed.position;
+        found_files = resumed.found_files;
+        found_bytes = resumed.found_bytes;
+        // 이 항목이 생기기 전에 기록된 체크포인트라면 지금부터 센 수치만 남습니다.
+        if resumed.root_totals.len() == roots.len() {
+            root_totals = resumed.root_totals;
+        }
+        special_files = resumed.special_files;
+        placeholder_files = resumed.placeholder_files;
+        files_by_size = resumed.files_by_size;
+        seen_files = resumed.seen_files;
+        resumed_hashes = resumed.hashes;
+        symlinks_by_target = resumed.symlinks_by_target;
+        errors = resumed.errors;
+    }
+    // 이어서 하는 탐색은 이전 실행이 멈춘 위치 다음부터 시작합니다.
+    let resume_position = position.clone();
+    // 체크포인트에 기록할 현재 상태. 탐색과 해시 단계에서 같은 변수들을 빌려 씁니다.
+    macro_rules! snapshot {
+        ($walk_complete:expr, $files_by_hash:expr) => {
+            checkpoint::Snapshot {
+                walk_complete: $walk_complete,
+                position: position.as_ref(),
+                found_files,
+                found_bytes,
+                root_totals: &root_totals,
+                special_files,
+                placeholder_files,
+                files_by_size: &files_by_size,
+                seen_files: &seen_files,
+                files_by_hash: $files_by_hash,
+                symlinks_by_target: &symlinks_by_target,
+                errors: &errors,
+            }
+        };
+    }
+
+    // --bloom-prepass: 먼저 크기만 훑어, 크기가 한 번뿐인 파일은 아래 탐색에서 기억하지 않습니다.
+    let sketch = options.bloom_prepass.map(|memory| {
+        let prepass_started = Instant::now();
+        let sketch = prepass::size_sketch(roots, options, memory, progress);
+        log.event(
+            "stage_end",
+            json!({ "stage": "prepass", "duration_ms": prepass_started.elapsed().as_millis() as u64 }),
+        );
+        sketch
+    });
+
+    // --spotlight: 색인에 후보 파일을 물어, 후보가 없는 폴더는 아래 탐색에서 열지 않습니다.
+    let spotlight = if options.spotlight && !walk_complete {
+        let query_started = Instant::now();
+        let candidates = spotlight::query(roots, &options.filter_mode)?;
+        progress.stage_done(&format!(
+            "Spotlight 색인 조회: 후보 파일 {}개, 탐색할 폴더 {}개, {}초",
+            candidates.files,
+            candidates.dir_count(),
+            number_format::format().decimal(query_started.elapsed().as_secs_f64())
+        ));
+        Some(candidates)
+    } else {
+        None
+    };
 
     // `WalkDir::new(root)`는 지정된 폴더를 재귀적으로 탐색하는 이터레이터(iterator)를 생성합니다.
-    for entry in WalkDir::new(root)
-        .into_iter()
-        // `.filter_map(|e| e.ok())`: 탐색 중 권한 오류 등으로 발생할 수 있는 에러(Err)는
-        // 무시하고 성공적인 결과(Ok)만 다음 단계로 넘깁니다.
-        .filter_map(|e| e.ok())
-        // `.filter(|e| e.file_type().is_file())`: 디렉터리가 아닌 파일만 필터링합니다.
-        .filter(|e| e.file_type().is_file())
-        // `.filter(|e| ...)`: 사용자가 지정한 이름/확장자 필터를 적용합니다.
-        .filter(|e| passes_filter(e.path(), filter_mode))
-    {
-        // 파일의 메타데이터(크기, 수정 시간 등)를 가져옵니다. `?` 연산자는 에러 발생 시
-        // 함수에서 즉시 에러를 반환하게 해주는 문법적 설탕(syntactic sugar)입니다.
-        let metadata = entry.metadata()?;
+    // 체크포인트를 쓸 때는 마지막 위치만으로 이어서 할 수 있도록 폴더 안의 항목을 이름순으로 방문합니다.
+    let mut interrupted = false;
+    // --spill-threshold: 크기 그룹이 커지면 임시 파일로 내보냅니다.
+    let mut spill = options.spill.as_ref().map(spill::Spill::new);
+    let sorted_walk = checkpointer.is_some();
+    let walks = roots.iter().enumerate().filter(|_| !walk_complete).map(|(root_index, root)| {
+        let walker = if sorted_walk { WalkDir::new(root).sort_by_file_name() } else { WalkDir::new(root) };
+        let resume_position = resume_position.as_ref();
+        let spotlight = spotlight.as_ref();
+        walker
+            .into_iter()
+            .filter_entry(move |e| {
+                !checkpoint::already_walked(resume_position, root_index, e.path(), e.file_type().is_dir())
+                    && spotlight.is_none_or(|candidates| candidates.keep(root_index, root, e))
+            })
+            .map(move |entry| (root_index, entry))
+    });
+    for (root_index, entry) in walks.flatten() {
+        // 중단 요청을 받았다면 탐색을 멈춥니다. 아직 확인된 중복 그룹이 없으므로
+        // 해시 단계도 곧바로 건너뛰게 됩니다.
+        if interrupt::requested() {
+            interrupted = true;
+            break;
+        }
+        // --strict: 직전 항목에서 오류가 기록되었다면 더 진행하지 않습니다.
+        if options.strict && !errors.is_empty() {
+            break;
+        }
+        // 탐색 중 권한 오류 등으로 발생할 수 있는 에러(Err)는 건너뛰고 계속 진행하되,
+        // 스캔이 완전하지 않았다는 사실은 오류 목록과 로그에 남겨 둡니다.
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                // 심볼릭 링크 순환처럼 I/O 오류가 아닌 탐색 오류는 `Other`로 분류합니다.
+                let kind = e.io_error().map_or(io::ErrorKind::Other, |io| io.kind());
+                record_error(&mut errors, log, e.path().map(Path::to_path_buf), "탐색", kind.to_string(), e.to_string());
+                continue;
+            }
+        };
+        if checkpointer.is_some() {
+            position = Some((root_index, entry.path().to_path_buf()));
+        }
+        // 사용자가 지정한 이름/확장자 필터를 통과하지 못한 항목은 건너뜁니다.
+        if !passes_filter(entry.path(), options) {
+            continue;
+        }
+        // --symlinks: 심볼릭 링크는 따라가지 않고, 가리키는 대상 경로 자체를 내용으로 봅니다.
+        if options.include_symlinks && entry.path_is_symlink() {
+            match std::fs::read_link(entry.path()) {
+                Ok(target) => symlinks_by_target
+                    .entry(resolve_link_target(entry.path(), &target))
+                    .or_default()
+                    .push(entry.into_path()),
+                Err(e) => {
+                    record_error(&mut errors, log, Some(entry.into_path()), "탐색", e.kind().to_string(), e.to_string())
+                }
+            }
+            continue;
+        }
+        // FIFO, 소켓, 장치 파일은 내용을 비교할 수 있는 파일이 아니므로 명시적으로 건너뜁니다.
+        if let Some(kind) = special_file_kind(entry.file_type()) {
+            special_files += 1;
+            log.event("file_skipped", json!({ "path": path_style::display(entry.path()), "reason": kind }));
+            continue;
+        }
+        // 디렉터리가 아닌 일반 파일만 처리합니다.
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        // 파일의 메타데이터(크기, 수정 시간 등)를 가져옵니다. 읽을 수 없다면 다른 탐색
+        // 오류와 마찬가지로 기록만 하고 다음 파일로 넘어갑니다.
+        let stat_started = Instant::now();
+        let metadata = entry.metadata();
+        profile.stat += stat_started.elapsed();
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let kind = e.io_error().map_or(io::ErrorKind::Other, |io| io.kind());
+                record_error(&mut errors, log, Some(entry.into_path()), "탐색", kind.to_string(), e.to_string());
+                continue;
+            }
+        };
+        // 온라인 전용 파일은 여는 순간 내려받기가 시작되므로 --hydrate가 없으면 건너뜁니다.
+        if !options.hydrate && placeholders::is_placeholder(entry.path(), &metadata) {
+            placeholder_files += 1;
+            log.event("file_skipped", json!({ "path": path_style::display(entry.path()), "reason": "cloud_placeholder" }));
+            continue;
+        }
+        // --created-after/--created-before: 생성 시각이 범위 밖이거나 알 수 없는 파일은 건너뜁니다.
+        if !passes_created(&metadata, options) {
+            log.event("file_skipped", json!({ "path": path_style::display(entry.path()), "reason": "created_time" }));
+            continue;
+        }
+        // --bloom-prepass: 크기가 확실히 한 번뿐인 파일은 세기만 하고 기억하지 않습니다.
+        // (하드 링크는 크기가 같으므로 항상 후보가 되어 아래의 같은 파일 검사를 거칩니다)
+        let candidate = sketch.as_ref().is_none_or(|sketch| sketch.repeated(metadata.len()));
+        // 같은 물리적 파일(하드 링크, 겹치는 루트로 두 번 도달한 파일)은 한 번만 처리합니다.
+        // 그렇지 않으면 파일이 자기 자신의 중복으로 보고됩니다. (--isolate라면 하드 링크도 각각 처리합니다)
+        if candidate && already_seen(&mut seen_files, &metadata, options) {
+            log.event("file_skipped", json!({ "path": path_style::display(entry.path()), "reason": "same_file" }));
+            continue;
+        }
+        found_files += 1;
+        found_bytes += metadata.len();
+        root_totals[root_index].files += 1;
+        root_totals[root_index].bytes += metadata.len();
+        progress.update_detail(
+            "파일 탐색 중",
+            found_files,
+            expected_files,
+            progress::Detail { bytes: Some((found_bytes, None)), current: Some(entry.path()), note: None },
+        );
+        log.event("file_found", json!({ "path": path_style::display(entry.path()), "size": metadata.len() }));
         // 크기가 0인 파일은 내용이 없으므로 중복으로 간주하지 않습니다.
-        if metadata.len() > 0 {
+        let loose = match options.match_mode {
+            MatchMode::Exif => photos::is_photo(entry.path()),
+            MatchMode::Office => office::is_document(entry.path()),
+            MatchMode::Pdf => pdf::is_pdf(entry.path()),
+            MatchMode::Audio => audio::is_audio(entry.path()),
+            MatchMode::Content | MatchMode::NameSize => false,
+        };
+        if loose {
+            loose_files.push((entry.path().to_path_buf(), FileStamp::of(&metadata)));
+        }
+        if metadata.len() > 0 && candidate {
             // `entry(key).or_default()`: 해시 맵에서 `metadata.len()` 키를 찾습니다.
             // - 키가 존재하면: 해당 키의 값(파일 경로 리스트)에 접근합니다.
             // - 키가 없으면: 새로운 빈 벡터 `Vec::new()`를 생성하여 삽입하고 접근합니다.
@@ -192,97 +2872,954 @@ fn find_duplicates(root: &Path, filter_mode: &FilterMode) -> io::Result<Vec<Vec<
             files_by_size
                 .entry(metadata.len())
                 .or_default()
-                .push(entry.into_path());
+                .push((entry.into_path(), FileStamp::of(&metadata)));
+            if let Some(spill) = &mut spill {
+                spill.added(&mut files_by_size, metadata.len(), seen_files.len() * spill::IDENTITY_BYTES)?;
+            }
+        }
+        if let Some(checkpointer) = &mut checkpointer
+            && checkpointer.tick()
+        {
+            checkpointer.save(snapshot!(false, None));
+        }
+    }
+    // 중단되지 않았고 --strict로 멈추지도 않았다면 모든 루트를 끝까지 탐색한 것입니다.
+    walk_complete = !interrupted && (!options.strict || errors.is_empty());
+    // 탐색 도중 중단되었다면 지금까지의 탐색 결과를 기록해 두어 다음 실행이 이어서 하게 합니다.
+    if interrupted && let Some(checkpointer) = &mut checkpointer {
+        checkpointer.save(snapshot!(false, None));
+    }
+
+    progress.stage_done(&format!(
+        "폴더 탐색: 파일 {}개 ({}), {}초",
+        format_count(found_files),
+        format_size(found_bytes),
+        number_format::format().decimal(walk_started.elapsed().as_secs_f64())
+    ));
+
+    // 파일이 2개 이상인 크기 그룹만 해시 단계의 후보가 됩니다. 임시 파일로 내보냈다면
+    // 런들과 남은 그룹을 병합하여 후보만 다시 읽어 옵니다.
+    let grouping_started = Instant::now();
+    progress.update("크기별로 묶는 중", found_files, None);
+    let (mut candidate_groups, size_groups) = match spill {
+        Some(spill) => {
+            let candidates = spill.finish(std::mem::take(&mut files_by_size))?;
+            let size_groups = candidates.size_groups;
+            (candidates, size_groups)
+        }
+        None => {
+            let size_groups = files_by_size.len();
+            (spill::Candidates::from_map(std::mem::take(&mut files_by_size)), size_groups)
+        }
+    };
+    profile.walk = walk_started.elapsed().saturating_sub(profile.stat);
+    progress.stage_done(&format!(
+        "크기별 묶기: 크기가 같은 파일이 있는 후보 {}개 ({}), {}초",
+        format_count(candidate_groups.files),
+        format_size(candidate_groups.bytes),
+        number_format::format().decimal(grouping_started.elapsed().as_secs_f64())
+    ));
+    log.event(
+        "stage_end",
+        json!({
+            "stage": "walk",
+            "duration_ms": walk_started.elapsed().as_millis() as u64,
+            "size_groups": size_groups,
+        }),
+    );
+
+    // --match name-size: 파일을 읽지 않고 (이름, 크기)로만 묶어 곧바로 결과를 만듭니다.
+    if options.match_mode == MatchMode::NameSize {
+        let compare_started = Instant::now();
+        let mut duplicates = group_by_name_and_size(candidate_groups.next_batch(usize::MAX)?, options, log);
+        if options.compare_attrs {
+            duplicates = attrs::split_groups(duplicates, &mut errors, log);
+            interrupted |= interrupt::requested();
+        }
+        if options.require_same_mtime {
+            duplicates = same_mtime::split_groups(duplicates, &mut errors, log);
+            interrupted |= interrupt::requested();
         }
+        profile.compare = compare_started.elapsed();
+        let strict_abort = options.strict && !errors.is_empty();
+        let stats = ScanStats { files_scanned: found_files, bytes_scanned: found_bytes, ..ScanStats::default() };
+        if let Some(checkpointer) = &checkpointer
+            && walk_complete
+        {
+            checkpointer.finish();
+        }
+        return Ok(ScanOutcome {
+            duplicates,
+            errors,
+            interrupted,
+            strict_abort,
+            special_files,
+            placeholder_files,
+            stats,
+            root_totals,
+            profile,
+        });
     }
-    
+
     // --- 2단계: 파일 내용의 해시로 그룹화 (정밀 2차 필터링) ---
+    let hash_started = Instant::now();
+    // 해시를 계산해야 하는 파일 수(크기가 같은 파일이 있는 파일들)가 해시 단계의 전체 수입니다.
+    let candidates: u64 = candidate_groups.files;
+    let candidate_bytes = candidate_groups.bytes;
+    let mut processed = 0u64;
+    let mut processed_bytes = 0u64;
+    // 여러 스레드가 지금 읽고 있는 파일들. 진행 상황에 가장 오래 읽고 있는 파일을 보여줍니다.
+    let in_flight = progress::InFlight::default();
+    let (mut files_hashed, mut bytes_hashed) = (0u64, 0u64);
+    let mut cache = xattr_cache::CacheStats::default();
     // 최종 중복 그룹들을 담을 벡터입니다.
-    let mut final_duplicates = Vec::new();
-    // 1단계에서 만들어진 `files_by_size` 맵에서, 값이 2개 이상인 (즉, 중복 가능성이 있는)
-    // 그룹에 대해서만 반복문을 실행합니다.
-    for (_size, paths) in files_by_size.into_iter().filter(|(_, p)| p.len() > 1) {
-        let mut files_by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
-        // 크기가 같은 파일 리스트(paths) 내에서 각 파일의 해시를 계산합니다.
-        for path in paths {
-            match calculate_hash(&path) {
-                Ok(hash) => {
-                    files_by_hash.entry(hash).or_default().push(path);
-                }
-                // 해시 계산 중 오류 발생 시 경고 메시지만 출력하고 계속 진행합니다.
-                Err(e) => {
-                    eprintln!("경고: '{}' 파일의 해시를 계산할 수 없습니다: {}", path.display(), e);
+    let mut final_duplicates: Vec<DuplicateGroup> = Vec::new();
+    // (크기, 해시, 두 번째 해시) -> 그 내용을 가진 파일 경로 리스트.
+    // --extra-hash를 쓰면 두 해시가 모두 같은 파일만 한 그룹이 되므로, 한 알고리즘의 충돌도 걸러집니다.
+    let mut files_by_hash: HashMap<(u64, String, Option<String>), Vec<PathBuf>> = HashMap::new();
+    // 이번 묶음의 파일마다 해시하기 전에 확인한 크기와 수정 시간. 그룹으로 묶인 파일의 것만 그룹에 옮깁니다.
+    let mut stamps: HashMap<PathBuf, FileStamp> = HashMap::new();
+    // 크기 그룹을 모두 해시하여 먼저 확정하고 로그에 기록한 그룹들과, 그 회수 가능 공간의 합.
+    let mut announced: HashSet<(u64, String, Option<String>)> = HashSet::new();
+    let mut confirmed_bytes = 0u64;
+    // 파일 읽기는 여러 스레드에서 동시에 하되, 동시에 열린 파일 수는 예산을 넘지 않게 합니다.
+    let budget = FdBudget::new(options.fd_budget);
+    // --max-memory: 후보를 예산의 4분의 1씩 나누어 처리합니다. 크기가 다른 파일은 같을 수 없으므로
+    // 한 묶음의 해시 그룹은 그 묶음만으로 확정되어, 곧바로 결과로 옮기고 비울 수 있습니다.
+    // (예산이 없으면 모든 후보가 한 묶음입니다)
+    let batch_limit = options.max_memory.map_or(usize::MAX, |memory| memory / 4);
+    loop {
+        // 1단계에서 만들어진 후보 중, 이번 묶음의 크기 그룹 파일들을 해시 계산 작업으로 만듭니다.
+        // 체크포인트를 쓸 때는 해시 단계에서도 크기 그룹을 기록해야 하므로 맵을 그대로 남겨 둡니다.
+        files_by_size = candidate_groups.next_batch(batch_limit)?;
+        if files_by_size.is_empty() {
+            break;
+        }
+        stamps.extend(files_by_size.values().flatten().map(|(path, stamp)| (path.clone(), *stamp)));
+        // --stages sample: 앞뒤 일부만 읽어, 전체를 읽기 전에 내용이 다른 파일을 걸러 냅니다.
+        if options.stages.sample {
+            let by_sample = stages::group_by_sample(
+                std::mem::take(&mut files_by_size),
+                options,
+                &budget,
+                &mut errors,
+                log,
+                progress,
+            );
+            if options.stages.hash {
+                for ((size, _), files) in by_sample {
+                    files_by_size.entry(size).or_default().extend(files);
                 }
+            } else {
+                stages::sample_groups(by_sample, &mut files_by_hash);
             }
+            interrupted |= interrupt::requested();
         }
-        
+        // 전체 해시 단계가 없다면 크기 그룹을 그대로 바이트 비교로 넘깁니다. (해시는 비교 뒤에 정합니다)
+        if !options.stages.hash {
+            for (size, files) in std::mem::take(&mut files_by_size) {
+                files_by_hash.entry((size, String::new(), None)).or_default().extend(files.into_iter().map(|(path, _)| path));
+            }
+        }
+        let jobs: Vec<(u64, PathBuf, FileStamp)> = if checkpointer.is_some() {
+            files_by_size
+                .iter()
+                .flat_map(|(&size, paths)| paths.iter().map(move |(path, stamp)| (size, path.clone(), *stamp)))
+                .collect()
+        } else {
+            std::mem::take(&mut files_by_size)
+                .into_iter()
+                .flat_map(|(size, paths)| paths.into_iter().map(move |(path, stamp)| (size, path, stamp)))
+                .collect()
+        };
+        // --resume: 이전 실행에서 해시를 마친 파일은, 그 뒤로 바뀌지 않았다면 다시 읽지 않습니다.
+        let mut jobs: Vec<(u64, PathBuf, FileStamp)> = jobs
+            .into_iter()
+            .filter(|(size, path, stamp)| {
+                let Some((hash, extra)) = resumed_hashes.remove(path) else { return true };
+                if stamp.verify(path, "이어서 하기 전").is_err() {
+                    return true;
+                }
+                processed += 1;
+                files_by_hash.entry((*size, hash, extra)).or_default().push(path.clone());
+                false
+            })
+            .collect();
+        // 회수할 수 있는 공간이 큰 크기 그룹(크기 × 파일 수)부터 해시하여, 큰 중복을 먼저 확정합니다.
+        // 정렬은 안정적이고 같은 크기의 파일은 키가 같으므로, 한 크기 그룹의 파일들은 붙어서 처리됩니다.
+        // (--max-memory로 묶음을 나누었다면 묶음 안에서만 순서를 정합니다)
+        let mut remaining: HashMap<u64, usize> = HashMap::new();
+        for (size, _, _) in &jobs {
+            *remaining.entry(*size).or_default() += 1;
+        }
+        jobs.sort_by_key(|(size, _, _)| std::cmp::Reverse((u128::from(*size) * remaining[size] as u128, *size)));
+        // 크기마다 지금까지 나온 (해시, 두 번째 해시). 크기 그룹을 다 해시했을 때 그 그룹들을 바로 찾기 위해 둡니다.
+        let mut keys_by_size: HashMap<u64, Vec<(String, Option<String>)>> = HashMap::new();
+        // 바이트 비교나 확장 속성, 수정 시각 비교가 남아 있다면 해시만으로는 그룹이 확정되지 않습니다.
+        let confirm_early = !options.stages.bytes && !options.compare_attrs && !options.require_same_mtime;
+        reader_pool::run_on_devices(
+            jobs,
+            options,
+            |(_, path, _)| path,
+            |(size, path, stamp)| {
+                in_flight.start(&path, size);
+                let started = Instant::now();
+                let result = hash_unchanged(&path, &stamp, &budget, options);
+                in_flight.end(&path);
+                (size, path, result, started.elapsed())
+            },
+            |(size, path, result, elapsed)| {
+                processed += 1;
+                processed_bytes += size;
+                // 1초 넘게 읽고 있는 파일이 있다면 그 파일을, 아니면 방금 마친 파일을 보여줍니다.
+                let slow = in_flight.oldest().filter(|(_, _, reading)| *reading >= Duration::from_secs(1));
+                let mut notes = Vec::new();
+                if confirmed_bytes > 0 {
+                    notes.push(format!("확정된 회수 가능 공간 {}", format_size(confirmed_bytes)));
+                }
+                if let Some((_, size, reading)) = &slow {
+                    notes.push(format!("{} 파일을 {}초째 읽는 중", format_size(*size), reading.as_secs()));
+                }
+                let current = slow.as_ref().map_or(path.as_path(), |(slow_path, _, _)| slow_path.as_path());
+                progress.update_detail(
+                    "해시 계산 중",
+                    processed,
+                    Some(candidates),
+                    progress::Detail {
+                        bytes: Some((processed_bytes, Some(candidate_bytes))),
+                        current: Some(current),
+                        note: (!notes.is_empty()).then(|| notes.join(", ")),
+                    },
+                );
+                match result {
+                    Ok((Digests { hash, extra }, source)) => {
+                        files_hashed += 1;
+                        profile.record_file(&path, size, elapsed);
+                        cache.record(&source, size, elapsed);
+                        let cached = !matches!(source, HashSource::Read(_));
+                        if !cached {
+                            bytes_hashed += size;
+                        }
+                        log.event(
+                            "file_hashed",
+                            json!({
+                                "path": path_style::display(&path),
+                                "hash": hash,
+                                "extra_hash": extra,
+                                "cached": cached,
+                                "duration_ms": elapsed.as_millis() as u64,
+                            }),
+                        );
+                        let paths = files_by_hash.entry((size, hash.clone(), extra.clone())).or_default();
+                        if paths.is_empty() {
+                            keys_by_size.entry(size).or_default().push((hash, extra));
+                        }
+                        paths.push(path);
+                    }
+                    // 큰 파일을 읽던 중 중단 요청을 받은 경우는 오류가 아니므로 경고 없이 멈춥니다.
+                    // 이미 해시가 일치한 파일들은 그 자체로 확정된 중복이므로 아래에서 결과에 포함됩니다.
+                    Err(HashFailure::Io(e)) if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() => {
+                        interrupted = true;
+                        return false;
+                    }
+                    // 탐색 이후 또는 해시 계산 도중에 파일이 바뀌었다면 계산된 해시는 쓰지 않습니다.
+                    Err(HashFailure::Changed(kind, message)) => {
+                        record_error(&mut errors, log, Some(path), "변경 감지", kind, message);
+                    }
+                    // 다른 프로세스가 잠갔거나 쓰고 있는 파일은 읽지 않고 건너뛴 사실만 남깁니다.
+                    Err(HashFailure::InUse(reason)) => {
+                        record_error(&mut errors, log, Some(path), "사용 중 확인", KIND_IN_USE.to_string(), reason.to_string());
+                    }
+                    // 해시 계산 중 오류가 발생하면 기록만 해 두고 계속 진행합니다.
+                    // 결과 출력 사이에 경고가 끼어들지 않도록 요약은 마지막에 출력합니다.
+                    Err(HashFailure::Io(e)) => {
+                        record_error(&mut errors, log, Some(path), "해시 계산", e.kind().to_string(), e.to_string());
+                    }
+                }
+                // 크기 그룹의 마지막 파일까지 해시했다면 그 크기의 그룹들은 더 바뀌지 않으므로, 전체 검색이
+                // 끝나기를 기다리지 않고 로그에 기록합니다. (결과 출력은 검색이 끝난 뒤 그대로 합니다)
+                if let Some(left) = remaining.get_mut(&size) {
+                    *left -= 1;
+                    if *left == 0 && confirm_early {
+                        for (hash, extra) in keys_by_size.remove(&size).unwrap_or_default() {
+                            let key = (size, hash, extra);
+                            let paths = &files_by_hash[&key];
+                            if paths.len() < 2 || options.ignored_hashes.as_ref().is_some_and(|ignored| ignored.contains(&key.1)) {
+                                continue;
+                            }
+                            confirmed_bytes += size * (paths.len() as u64 - 1);
+                            log.event("duplicate_group", group_event(size, &key.1, paths));
+                            announced.insert(key);
+                        }
+                        log.flush();
+                    }
+                }
+                if let Some(checkpointer) = &mut checkpointer
+                    && walk_complete
+                    && checkpointer.tick()
+                {
+                    checkpointer.save(snapshot!(true, Some(&files_by_hash)));
+                }
+                // --strict: 오류가 하나라도 기록되었다면 남은 파일은 읽지 않습니다.
+                !options.strict || errors.is_empty()
+            },
+        );
+        // 결과를 기다리는 동안 들어온 중단 요청도 부분 결과로 처리합니다.
+        if interrupt::requested() {
+            interrupted = true;
+        }
+        // 체크포인트: 중단되었다면 계산을 마친 해시까지 기록합니다. (탐색 도중의 중단은 위에서 이미 기록했습니다)
+        if interrupted
+            && walk_complete
+            && let Some(checkpointer) = &mut checkpointer
+        {
+            checkpointer.save(snapshot!(true, Some(&files_by_hash)));
+        }
+
         // 해시 맵에서도 해시 값이 같은 파일이 2개 이상인 그룹만 찾아
-        // 최종 중복 리스트 `final_duplicates`에 추가합니다.
-        for (_hash, duplicate_paths) in files_by_hash.into_iter().filter(|(_, p)| p.len() > 1) {
-            final_duplicates.push(duplicate_paths);
+        // 최종 중복 리스트 `final_duplicates`에 추가하고, 다음 묶음을 위해 맵을 비웁니다.
+        let mut groups: Vec<_> = files_by_hash.drain().filter(|(_, p)| p.len() > 1).collect();
+        // --ignore-hashes: 목록에 있는 내용은 그룹으로 만들지 않으므로, 바이트 비교도 출력도 정리 동작도 거치지 않습니다.
+        if let Some(ignored) = &options.ignored_hashes {
+            groups.retain(|((_, hash, _), _)| !ignored.contains(hash));
+        }
+        // --stages bytes: 남은 그룹의 파일들을 실제로 한 바이트씩 비교합니다. 중단된 뒤라면 비교를
+        // 마치지 못한 그룹은 모두 빠지므로, 부분 결과에도 바이트 비교를 거친 그룹만 남습니다.
+        if options.stages.bytes {
+            groups = stages::confirm_bytes(groups, options, &budget, &mut errors, log, progress);
+            interrupted |= interrupt::requested();
+        }
+        let compare_started = Instant::now();
+        for ((size, hash, extra), duplicate_paths) in groups {
+            // 해시 도중 먼저 확정한 그룹은 이미 로그에 기록했습니다.
+            if !announced.remove(&(size, hash.clone(), extra.clone())) {
+                log.event("duplicate_group", group_event(size, &hash, &duplicate_paths));
+            }
+            let group = DuplicateGroup {
+                size,
+                hash,
+                algorithm: options.algorithm,
+                extra_hash: options.extra_hash.zip(extra),
+                stamps: duplicate_paths.iter().filter_map(|p| stamps.remove_entry(p)).collect(),
+                paths: duplicate_paths,
+                symlink_target: None,
+            };
+            final_duplicates.push(group);
+        }
+        stamps.clear();
+        profile.compare += compare_started.elapsed();
+        // 중단되었거나 --strict로 멈췄다면 남은 묶음은 읽지 않습니다.
+        if interrupted || (options.strict && !errors.is_empty()) {
+            break;
+        }
+    }
+    profile.hash = hash_started.elapsed().saturating_sub(profile.compare);
+    progress.stage_done(&format!(
+        "해시 계산: 파일 {}개 중 {}개 ({} 새로 읽음), {}초",
+        format_count(candidates),
+        format_count(processed),
+        format_size(bytes_hashed),
+        number_format::format().decimal(hash_started.elapsed().as_secs_f64())
+    ));
+    profile.bytes_hashed = bytes_hashed;
+    profile.hash_backends = std::iter::once(options.algorithm)
+        .chain(options.extra_hash)
+        .map(|algorithm| (algorithm.name(), hashing::describe(algorithm)))
+        .collect();
+    // 체크포인트: 끝까지 마쳤다면 파일을 지웁니다.
+    if let Some(checkpointer) = &checkpointer
+        && walk_complete
+        && !interrupted
+    {
+        checkpointer.finish();
+    }
+
+    // --match exif/office/pdf/audio: 내용 그룹을 모두 찾은 뒤, 촬영 정보, 문서 파트, 페이지 내용, 오디오 데이터가 같은 파일을 추가 그룹으로
+    // 묶습니다. 중단된 스캔에서는 파일 목록이 불완전하므로 건너뜁니다.
+    if !interrupted {
+        let matcher = match options.match_mode {
+            MatchMode::Exif => Some(&photos::MATCHER),
+            MatchMode::Office => Some(&office::MATCHER),
+            MatchMode::Pdf => Some(&pdf::MATCHER),
+            MatchMode::Audio => Some(&audio::MATCHER),
+            MatchMode::Content | MatchMode::NameSize => None,
+        };
+        let loose_groups = match matcher {
+            Some(matcher) => group_loose_files(loose_files, matcher, options, &mut errors, log, progress),
+            None => Vec::new(),
+        };
+        let loose_groups = beyond_content_groups(loose_groups, &final_duplicates);
+        final_duplicates.extend(loose_groups);
+    }
+
+    // --symlinks: 같은 대상을 가리키는 링크가 2개 이상이면 하나의 그룹으로 보고합니다.
+    // 대상 경로 문자열이 곧 "내용"이므로, 그 바이트의 해시를 그룹의 해시로 사용합니다.
+    let compare_started = Instant::now();
+    for (target, links) in symlinks_by_target.into_iter().filter(|(_, l)| l.len() > 1) {
+        let bytes = target.as_os_str().as_encoded_bytes();
+        let hash = options.algorithm.digest(bytes);
+        final_duplicates.push(DuplicateGroup {
+            size: bytes.len() as u64,
+            hash,
+            algorithm: options.algorithm,
+            extra_hash: options.extra_hash.map(|extra| (extra, extra.digest(bytes))),
+            paths: links,
+            symlink_target: Some(target),
+            stamps: HashMap::new(),
+        });
+    }
+    profile.compare += compare_started.elapsed();
+
+    // --compare-attrs: 내용으로 찾은 그룹을 확장 속성과 ACL까지 같은 멤버끼리 다시 나눕니다.
+    if options.compare_attrs {
+        let compare_started = Instant::now();
+        final_duplicates = attrs::split_groups(final_duplicates, &mut errors, log);
+        interrupted |= interrupt::requested();
+        profile.compare += compare_started.elapsed();
+    }
+    // --require-same-mtime: 같은 방법으로 수정 시각이 같은 멤버끼리 다시 나눕니다.
+    if options.require_same_mtime {
+        let compare_started = Instant::now();
+        final_duplicates = same_mtime::split_groups(final_duplicates, &mut errors, log);
+        interrupted |= interrupt::requested();
+        profile.compare += compare_started.elapsed();
+    }
+
+    log.event(
+        "stage_end",
+        json!({
+            "stage": "hash",
+            "duration_ms": hash_started.elapsed().as_millis() as u64,
+            "hashed_files": files_hashed,
+            "duplicate_groups": final_duplicates.len(),
+        }),
+    );
+
+    // 모든 작업이 끝났으므로, 최종 결과를 `Ok`로 감싸서 반환합니다.
+    // --strict 모드에서는 오류가 하나라도 있으면 결과 전체를 신뢰할 수 없습니다.
+    let strict_abort = options.strict && !errors.is_empty();
+    cache.finish(options.threads);
+    let stats = ScanStats { files_scanned: found_files, bytes_scanned: found_bytes, files_hashed, bytes_hashed, cache };
+    Ok(ScanOutcome {
+        duplicates: final_duplicates,
+        errors,
+        interrupted,
+        strict_abort,
+        special_files,
+        placeholder_files,
+        stats,
+        root_totals,
+        profile,
+    })
+}
+
+/// 크기가 같은 파일들을 다시 파일 이름으로 나누어, (이름, 크기)가 같은 파일이 2개 이상인
+/// 묶음을 그룹으로 만듭니다. 내용은 확인하지 않으므로 `hash`에는 해시 대신 표시와 이름을 넣습니다.
+/// (--normalize-names를 쓰면 NFC로 정규화한 이름끼리 비교합니다.)
+fn group_by_name_and_size(
+    files_by_size: HashMap<u64, Vec<(PathBuf, FileStamp)>>,
+    options: &ScanOptions,
+    log: &mut ScanLog,
+) -> Vec<DuplicateGroup> {
+    let mut by_name: HashMap<(u64, String), Vec<(PathBuf, FileStamp)>> = HashMap::new();
+    for (size, files) in files_by_size.into_iter().filter(|(_, f)| f.len() > 1) {
+        for (path, stamp) in files {
+            let name = path.file_name().map(|n| names::comparable(&n.to_string_lossy(), options.normalize_names).into_owned());
+            if let Some(name) = name {
+                by_name.entry((size, name)).or_default().push((path, stamp));
+            }
+        }
+    }
+    let groups: Vec<DuplicateGroup> = by_name
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, name), files)| DuplicateGroup {
+            size,
+            hash: format!("{}:{}", NAME_SIZE_MARK, name),
+            algorithm: options.algorithm,
+            extra_hash: None,
+            paths: files.iter().map(|(path, _)| path.clone()).collect(),
+            symlink_target: None,
+            stamps: files.into_iter().collect(),
+        })
+        .collect();
+    log.event("stage_end", json!({ "stage": "name_size", "duplicate_groups": groups.len() }));
+    groups
+}
+
+/// --match exif/office/pdf/audio의 비교 방법. 파일마다 내용 대신 비교할 키를 만들고, 키가 같은 파일을 묶습니다.
+struct LooseMatcher {
+    /// 그룹의 `hash` 앞에 붙여 내용 해시와 구별하는 표시.
+    mark: &'static str,
+    /// 로그의 `stage_end` 이벤트에 기록하는 단계 이름.
+    stage: &'static str,
+    /// 진행 상황에 표시하는 작업 이름.
+    progress: &'static str,
+    /// 키를 만들다 실패했을 때 오류 요약에 기록하는 작업 이름.
+    action: &'static str,
+    /// 파일의 비교 키. 비교할 수 없는 파일(촬영 정보가 없는 사진 등)이면 `None`.
+    key: fn(&Path, &ScanOptions) -> io::Result<Option<String>>,
+}
+
+/// 파일들을 `matcher`의 키로 묶습니다. `files`는 (경로, 발견했을 때의 상태) 목록입니다.
+/// 그룹의 `size`는 가장 작은 멤버의 크기로, 회수 가능 공간을 적게 잡는 쪽으로 추정합니다.
+fn group_loose_files(
+    files: Vec<(PathBuf, FileStamp)>,
+    matcher: &LooseMatcher,
+    options: &ScanOptions,
+    errors: &mut Vec<ScanError>,
+    log: &mut ScanLog,
+    progress: &mut Progress,
+) -> Vec<DuplicateGroup> {
+    let total = files.len() as u64;
+    let mut by_key: HashMap<String, Vec<(PathBuf, FileStamp)>> = HashMap::new();
+    for (read, (path, stamp)) in files.into_iter().enumerate() {
+        if interrupt::requested() {
+            break;
+        }
+        progress.update(matcher.progress, read as u64 + 1, Some(total));
+        match (matcher.key)(&path, options) {
+            Ok(Some(key)) => by_key.entry(key).or_default().push((path, stamp)),
+            Ok(None) => {}
+            Err(e) => record_error(errors, log, Some(path), matcher.action, e.kind().to_string(), e.to_string()),
+        }
+    }
+
+    let groups: Vec<DuplicateGroup> = by_key
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(key, members)| DuplicateGroup {
+            size: members.iter().map(|(_, stamp)| stamp.len).min().unwrap_or(0),
+            hash: format!("{}{}", matcher.mark, key),
+            algorithm: options.algorithm,
+            extra_hash: None,
+            paths: members.iter().map(|(path, _)| path.clone()).collect(),
+            symlink_target: None,
+            stamps: members.into_iter().collect(),
+        })
+        .collect();
+    log.event("stage_end", json!({ "stage": matcher.stage, "files": total, "duplicate_groups": groups.len() }));
+    groups
+}
+
+/// 로그의 `duplicate_group` 이벤트 내용.
+fn group_event(size: u64, hash: &str, paths: &[PathBuf]) -> serde_json::Value {
+    json!({
+        "id": group_id(size, hash),
+        "size": size,
+        "hash": hash,
+        "paths": paths.iter().map(|p| path_style::display(p)).collect::<Vec<_>>(),
+    })
+}
+
+/// 내용이 아닌 기준(--match exif, office, pdf, audio)으로 만든 그룹 중, 모든 멤버가 이미 한 내용 그룹에
+/// 들어 있어 새로운 정보가 없는 그룹을 뺍니다.
+fn beyond_content_groups(groups: Vec<DuplicateGroup>, content_groups: &[DuplicateGroup]) -> Vec<DuplicateGroup> {
+    // 경로 -> 그 경로가 속한 내용 그룹의 번호.
+    let content_group: HashMap<&Path, usize> = content_groups
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| g.symlink_target.is_none())
+        .flat_map(|(i, g)| g.paths.iter().map(move |p| (p.as_path(), i)))
+        .collect();
+    groups
+        .into_iter()
+        .filter(|group| {
+            let first = content_group.get(group.paths[0].as_path());
+            first.is_none() || group.paths.iter().any(|p| content_group.get(p.as_path()) != first)
+        })
+        .collect()
+}
+
+/// 그룹을 파일 크기(큰 순) → 해시 순으로, 그룹 안의 파일은 경로 순으로 정렬합니다.
+fn sort_groups(groups: &mut [DuplicateGroup]) {
+    for group in groups.iter_mut() {
+        group.paths.sort();
+    }
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.hash.cmp(&b.hash)));
+}
+
+/// `--emit-excludes FORMAT FILE`의 두 값을 (형식, 파일)로 풀어냅니다.
+/// 형식 이름은 `main`에서 미리 검사하므로 여기서는 올바르다고 가정합니다.
+fn emit_excludes_target(args: &Args) -> Option<(ExcludeFormat, PathBuf)> {
+    let values = args.emit_excludes.as_ref()?;
+    let format = ExcludeFormat::from_str(&values[0], true).ok()?;
+    Some((format, PathBuf::from(&values[1])))
+}
+
+/// TUI를 마친 뒤 정리한(dry-run이라면 정리할 수 있는) 빈 폴더 목록을 출력합니다.
+fn print_pruned(pruned: &PruneReport, mode: Option<PruneMode>) {
+    if !pruned.removed.is_empty() {
+        match mode {
+            Some(PruneMode::DryRun) => println!("🧹 지울 수 있는 빈 폴더 {}개 (dry-run):", pruned.removed.len()),
+            _ => println!("🧹 빈 폴더 {}개를 지웠습니다:", pruned.removed.len()),
         }
+        for dir in &pruned.removed {
+            println!("  - {}", dir.display());
+        }
+    }
+    for (dir, reason) in &pruned.failed {
+        eprintln!("⚠️ 빈 폴더 '{}'을(를) 지우지 못했습니다: {}", dir.display(), reason);
+    }
+}
+
+/// 처리하지 못한 파일/폴더를 오류 목록에 추가하고, 같은 내용을 로그 파일에도 남깁니다.
+fn record_error(
+    errors: &mut Vec<ScanError>,
+    log: &mut ScanLog,
+    path: Option<PathBuf>,
+    stage: &'static str,
+    kind: String,
+    message: String,
+) {
+    log.event(
+        "warning",
+        json!({
+            "path": path.as_ref().map(|p| path_style::display(p)),
+            "stage": stage,
+            "kind": kind,
+            "error": message,
+        }),
+    );
+    errors.push(ScanError { path, stage, kind, message });
+}
+
+/// 모아 둔 오류를 종류별 개수로 요약하여 표준 에러에 출력합니다.
+/// `show_all`이 참이면 오류 하나하나의 경로와 메시지도 함께 출력합니다.
+fn print_error_summary(errors: &[ScanError], show_all: bool) {
+    if errors.is_empty() {
+        return;
     }
+    eprintln!("⚠️  {}개의 파일/폴더를 처리하지 못했습니다. 결과가 불완전할 수 있습니다.", errors.len());
 
-    // 모든 작업이 성공적으로 끝났으므로, 최종 결과를 `Ok`로 감싸서 반환합니다.
-    Ok(final_duplicates)
+    // 오류 종류별 개수를 센 뒤, 많은 순서대로 출력합니다.
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for error in errors {
+        *counts.entry(error.kind.as_str()).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    for (kind, count) in counts {
+        eprintln!("   - {}: {}개", kind, count);
+    }
+
+    if show_all {
+        for error in errors {
+            let path = error.path.as_ref().map_or_else(|| String::from("(경로 없음)"), |p| path_style::display(p));
+            eprintln!("   [{}] {}: {}", error.stage, path, error.message);
+        }
+    } else {
+        eprintln!("   (전체 목록은 --show-errors 옵션으로 확인할 수 있습니다.)");
+    }
 }
 
 /// 주어진 파일 경로가 필터 조건을 만족하는지 여부를 반환하는 헬퍼 함수.
-fn passes_filter(path: &Path, filter_mode: &FilterMode) -> bool {
+fn passes_filter(path: &Path, options: &ScanOptions) -> bool {
     // `filter_mode`의 각 경우에 따라 다른 로직을 수행합니다.
-    match filter_mode {
+    match &options.filter_mode {
         FilterMode::None => true, // 필터가 없으면 무조건 true.
-        FilterMode::ByExactName(name) => {
-            // `path.file_name()`은 파일 이름을 `Option<&OsStr>`으로 반환합니다.
-            // 파일 이름이 존재하고, 그 값이 주어진 이름과 같을 때만 true.
-            path.file_name() == Some(OsStr::new(name))
-        },
-        FilterMode::ByExtension(ext) => {
-            // `path.extension()`은 확장자를 `Option<&OsStr>`으로 반환합니다.
-            // 확장자가 존재하고, 그 값이 주어진 확장자와 같을 때만 true.
-            path.extension() == Some(OsStr::new(ext))
-        },
+        // 조건식을 평가합니다. (--normalize-names라면 이름의 NFC/NFD 차이는 무시합니다.)
+        FilterMode::Expr(expr) => expr.matches(path, options.normalize_names),
+    }
+}
+
+/// 파일의 생성 시각이 --created-after/--created-before 범위 안에 있는지 확인합니다.
+/// 범위를 지정했는데 생성 시각을 알 수 없다면 범위 밖으로 봅니다.
+fn passes_created(metadata: &std::fs::Metadata, options: &ScanOptions) -> bool {
+    if options.created_after.is_none() && options.created_before.is_none() {
+        return true;
+    }
+    let Ok(created) = metadata.created() else { return false };
+    options.created_after.is_none_or(|after| created >= after) && options.created_before.is_none_or(|before| created <= before)
+}
+
+/// `--created-after` 등의 시각 값을 읽습니다. `2024-05-01`(그날 0시, UTC), `2024-05-01T09:30:00`(UTC),
+/// RFC 3339 시각, 또는 `30days`처럼 지금으로부터 거슬러 올라간 기간을 받습니다.
+fn parse_time(value: &str) -> Result<SystemTime, String> {
+    if let Ok(ago) = humantime::parse_duration(value) {
+        return SystemTime::now().checked_sub(ago).ok_or_else(|| format!("'{}'은(는) 너무 먼 과거입니다", value));
+    }
+    let time = if value.len() == 10 { format!("{}T00:00:00", value) } else { value.to_string() };
+    humantime::parse_rfc3339_weak(&time)
+        .map_err(|_| format!("'{}'은(는) 시각(예: 2024-05-01, 2024-05-01T09:30:00)이나 기간(예: 30days)이 아닙니다", value))
+}
+
+/// 해시 계산 작업 하나가 실패한 이유.
+enum HashFailure {
+    /// 탐색 이후 또는 해시 계산 도중에 파일이 바뀌었습니다. (오류 분류, 메시지)
+    Changed(String, String),
+    /// 다른 프로세스가 파일을 잠갔거나 쓰고 있습니다. (이유)
+    InUse(&'static str),
+    /// 파일을 열거나 읽지 못했습니다.
+    Io(io::Error),
+}
+
+/// 파일 하나에 대해 계산한 해시들.
+struct Digests {
+    /// --algorithm의 해시.
+    hash: String,
+    /// --extra-hash를 지정했다면 그 알고리즘의 해시.
+    extra: Option<String>,
+}
+
+/// 해시를 어디에서 얻었는지. 캐시 통계(--xattr-cache)에 씁니다.
+#[derive(Debug)]
+enum HashSource {
+    /// 확장 속성 캐시(--xattr-cache)에 기록된 해시.
+    Cache,
+    /// --import-hashes로 가져온 해시.
+    Imported,
+    /// 파일을 읽어 계산했습니다. 캐시를 사용 중이었다면 캐시를 찾아본 결과가 함께 있습니다.
+    Read(Option<xattr_cache::Lookup>),
+}
+
+/// 탐색 때 기록한 `stamp`와 비교하여 바뀌지 않은 파일의 해시만 계산합니다.
+/// 파일을 여는 동안에는 `budget`에서 받은 허가를 쥐고 있습니다.
+///
+/// `options.xattr_cache`가 참이면 확장 속성에 저장된 해시를 먼저 찾아보고, 새로 계산한 해시는
+/// 기록해 둡니다. 그다음으로 다른 도구에서 가져온 해시(--import-hashes)를 찾아봅니다.
+/// 반환값의 두 번째 값은 해시를 어디에서 얻었는지입니다.
+/// (--extra-hash를 쓰면 두 해시가 모두 캐시에 있을 때만 캐시를 사용합니다)
+fn hash_unchanged(
+    path: &Path,
+    stamp: &FileStamp,
+    budget: &FdBudget,
+    options: &ScanOptions,
+) -> Result<(Digests, HashSource), HashFailure> {
+    let algorithms: Vec<HashAlgorithm> = std::iter::once(options.algorithm).chain(options.extra_hash).collect();
+    // 탐색 이후 해시 계산 전까지 파일이 바뀌었다면 해시를 계산하지 않습니다.
+    stamp.verify(path, "탐색 이후").map_err(|(kind, message)| HashFailure::Changed(kind, message))?;
+    // 다른 프로세스가 쓰고 있는 파일은 캐시된 해시가 있더라도 곧 바뀔 수 있으므로 그룹에 넣지 않습니다.
+    {
+        let _permit = budget.acquire();
+        if let Some(reason) = in_use::check(path).map_err(HashFailure::Io)? {
+            return Err(HashFailure::InUse(reason));
+        }
+    }
+    // 캐시를 찾아본 결과. 알고리즘 중 하나라도 적중하지 않으면 파일을 읽으므로, 적중하지 않은 첫 결과를 남깁니다.
+    let mut lookup = None;
+    if options.xattr_cache {
+        let mut cached = Vec::new();
+        for &algorithm in &algorithms {
+            match xattr_cache::load(path, stamp, algorithm) {
+                xattr_cache::Lookup::Hit(hash) => cached.push(hash),
+                missed => {
+                    lookup = Some(missed);
+                    break;
+                }
+            }
+        }
+        if lookup.is_none() {
+            let extra = (cached.len() > 1).then(|| cached.remove(1));
+            return Ok((Digests { hash: cached.remove(0), extra }, HashSource::Cache));
+        }
+    }
+    // 가져온 해시는 --algorithm 하나뿐이므로, --extra-hash와는 함께 쓸 수 없습니다. (clap이 검사)
+    if let Some(hash) = options.imported_hashes.as_ref().and_then(|imported| imported.lookup(path, stamp)) {
+        // --xattr-cache를 함께 쓰면 가져온 해시를 기록해 두어, 다음부터는 가져오지 않아도 되게 합니다.
+        if options.xattr_cache {
+            xattr_cache::store(path, stamp, options.algorithm, &hash);
+        }
+        return Ok((Digests { hash, extra: None }, HashSource::Imported));
+    }
+    let mut hashes = {
+        let _permit = budget.acquire();
+        calculate_hashes(path, &algorithms).map_err(HashFailure::Io)?
+    };
+    // 읽는 도중에 파일이 바뀌었다면 계산된 해시는 어느 한 시점의 내용도 아니므로 버립니다.
+    stamp.verify(path, "해시 계산 도중").map_err(|(kind, message)| HashFailure::Changed(kind, message))?;
+    if options.xattr_cache {
+        for (&algorithm, hash) in algorithms.iter().zip(&hashes) {
+            xattr_cache::store(path, stamp, algorithm, hash);
+        }
     }
+    let extra = (hashes.len() > 1).then(|| hashes.remove(1));
+    Ok((Digests { hash: hashes.remove(0), extra }, HashSource::Read(lookup)))
+}
+
+/// 파일의 해시 값을 지정한 알고리즘으로 계산하여 16진수 문자열로 반환합니다.
+fn calculate_hash(path: &Path, algorithm: HashAlgorithm) -> io::Result<String> {
+    Ok(calculate_hashes(path, &[algorithm])?.remove(0))
 }
 
-/// 파일의 SHA-256 해시 값을 계산하여 16진수 문자열로 반환합니다.
+/// 파일을 한 번만 읽으면서 `algorithms`의 해시를 모두 계산하여, 같은 순서의 16진수 문자열로 반환합니다.
 /// 파일 내용을 바이트 단위로 읽으므로 텍스트, 바이너리 구분 없이 모든 파일에 적용 가능합니다.
-fn calculate_hash(path: &Path) -> io::Result<String> {
-    let file = File::open(path)?;
+fn calculate_hashes(path: &Path, algorithms: &[HashAlgorithm]) -> io::Result<Vec<String>> {
+    // 탐색 이후 같은 경로가 FIFO 등으로 바뀌었다면, 여는 순간 멈출 수 있으므로 열기 전에 거부합니다.
+    if !std::fs::metadata(path)?.is_file() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "일반 파일이 아닙니다"));
+    }
+    // 접근 시각을 바꾸지 않고, 읽은 데이터가 페이지 캐시를 채우지 않도록 엽니다. (--direct-io)
+    let file = read_hints::open_for_hashing(path)?;
+    // BLAKE3를 여러 스레드로 나누어 계산한다면(--hash-backend) 큰 조각으로 읽어야 나눌 수 있습니다.
+    let parallel = hashing::parallel_for(file.metadata()?.len(), algorithms);
+    // O_DIRECT로 열었다면 정렬된 버퍼에 블록 크기의 배수로 직접 읽어야 하므로 `BufReader`를 거치지 않습니다.
+    let direct = file.is_direct();
     // `BufReader`는 파일을 읽을 때 시스템 호출 횟수를 줄여 성능을 향상시킵니다.
     // 특히 대용량 파일을 처리할 때 효과적입니다.
-    let mut reader = BufReader::new(file);
-    let mut hasher = Sha256::new();
-    let mut buffer = [0; 4096]; // 4KB (4096 bytes) 크기의 버퍼.
+    let mut reader: Box<dyn Read> = if direct { Box::new(file) } else { Box::new(BufReader::new(file)) };
+    let mut hashers: Vec<Hasher> = algorithms.iter().map(|&a| Hasher::new(a)).collect();
+    // 보통은 4KB (4096 bytes) 크기의 버퍼.
+    let mut aligned = read_hints::AlignedBuffer::new(if parallel {
+        hashing::PARALLEL_CHUNK
+    } else if direct {
+        read_hints::DIRECT_CHUNK
+    } else {
+        4096
+    });
+    let buffer = aligned.as_mut_slice();
 
     // `loop`를 사용하여 파일을 버퍼 크기만큼씩 반복해서 읽습니다.
     loop {
+        // 수십 GB짜리 파일을 읽는 중에도 Ctrl+C에 바로 반응할 수 있도록, 버퍼를 읽을
+        // 때마다 중단 요청을 확인합니다.
+        if interrupt::requested() {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "사용자가 중단을 요청했습니다"));
+        }
         // `reader.read`는 버퍼에 데이터를 채우고 읽은 바이트 수를 반환합니다.
-        let bytes_read = reader.read(&mut buffer)?;
+        // O_DIRECT에서는 다음 읽기 위치가 블록 경계에 맞도록 버퍼를 끝까지 채웁니다.
+        let bytes_read = if parallel || direct { read_full(&mut reader, buffer)? } else { reader.read(buffer)? };
         // 읽은 바이트 수가 0이면 파일의 끝에 도달했다는 의미이므로 루프를 탈출합니다.
         if bytes_read == 0 {
             break;
         }
-        // `hasher.update`로 읽은 데이터 조각을 해시 계산기에 주입합니다.
+        // 읽은 데이터 조각을 모든 해시 계산기에 주입합니다.
         // 슬라이스 `&buffer[..bytes_read]`를 사용하여 버퍼에서 실제로 읽은 만큼만 전달합니다.
-        hasher.update(&buffer[..bytes_read]);
+        for hasher in &mut hashers {
+            if parallel {
+                hasher.update_parallel(&buffer[..bytes_read]);
+            } else {
+                hasher.update(&buffer[..bytes_read]);
+            }
+        }
     }
 
-    // `hasher.finalize()`로 최종 해시 결과를 얻고,
-    // `format!("{:x}", ...)`를 통해 16진수(hexadecimal) 문자열로 변환하여 반환합니다.
-    Ok(format!("{:x}", hasher.finalize()))
+    // 각 계산기의 최종 해시 결과를 16진수(hexadecimal) 문자열로 변환하여 반환합니다.
+    Ok(hashers.into_iter().map(Hasher::finalize).collect())
+}
+
+/// 파일 끝에 닿기 전까지 `buffer`를 채우고, 읽은 바이트 수를 반환합니다.
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
 }
 
 /// 찾은 중복 파일 그룹들을 형식에 맞게 화면에 출력하는 헬퍼 함수.
-fn print_duplicates(duplicates: Vec<Vec<PathBuf>>) {
+/// `explain`이 있다면(--explain) 그룹마다 판단 근거를, 유사 그룹은 멤버마다 유사도를 함께 출력합니다.
+fn print_duplicates(duplicates: Vec<DuplicateGroup>, scored: Option<&KeepRule>, explain: Option<&ScanOptions>) {
+    // 그룹 번호와 함께, 실행이 바뀌어도 같은 그룹을 가리키는 식별자를 머리글에 표시합니다.
+    let ids = group_ids(&duplicates);
     // `iter().enumerate()`를 사용하면 인덱스(i)와 값(group)을 동시에 얻을 수 있습니다.
-    for (i, group) in duplicates.iter().enumerate() {
+    for (i, (group, id)) in duplicates.iter().zip(&ids).enumerate() {
         // 그룹 번호는 1부터 시작하도록 i + 1을 사용합니다.
-        println!("--- 그룹 {} (총 {}개 파일) ---", i + 1, group.len());
-        for path in group {
-            println!("  - {}", path.display());
+        match &group.symlink_target {
+            Some(target) => println!(
+                "--- 그룹 {} [{}] (심볼릭 링크 {}개 → '{}') ---",
+                i + 1,
+                id,
+                group.paths.len(),
+                path_style::display(target)
+            ),
+            None if let Some(key) = photos::group_key(&group.hash) => println!(
+                "--- 그룹 {} [{}] (사진 {}장, 같은 촬영 정보 {}, 회수 가능 약 {}) ---",
+                i + 1,
+                id,
+                group.paths.len(),
+                key.replace('|', " / "),
+                format_size(group.wasted_bytes())
+            ),
+            None if office::group_hash(&group.hash).is_some() => println!(
+                "--- 그룹 {} [{}] (문서 {}개, 문서 속성을 빼면 같음, 회수 가능 약 {}) ---",
+                i + 1,
+                id,
+                group.paths.len(),
+                format_size(group.wasted_bytes())
+            ),
+            None if pdf::group_hash(&group.hash).is_some() => println!(
+                "--- 그룹 {} [{}] (PDF {}개, 페이지 내용이 같음, 회수 가능 약 {}) ---",
+                i + 1,
+                id,
+                group.paths.len(),
+                format_size(group.wasted_bytes())
+            ),
+            None if audio::group_hash(&group.hash).is_some() => println!(
+                "--- 그룹 {} [{}] (음원 {}개, 태그를 빼면 같음, 회수 가능 약 {}) ---",
+                i + 1,
+                id,
+                group.paths.len(),
+                format_size(group.wasted_bytes())
+            ),
+            None => println!(
+                "--- 그룹 {} [{}] (총 {}개 파일, 파일당 {}, 회수 가능 {}) ---",
+                i + 1,
+                id,
+                group.paths.len(),
+                format_size(group.size),
+                format_size(group.wasted_bytes())
+            ),
+        }
+        // --explain: 유사 그룹이라면 멤버마다 첫 번째 파일과의 유사도를 경로 뒤에 붙입니다.
+        let mut notes = vec![String::new(); group.paths.len()];
+        if let Some(options) = explain {
+            println!("  근거: {}", explain::basis(group, options));
+            if explain::is_near_duplicate(group) {
+                for (i, (note, similarity)) in notes.iter_mut().zip(explain::similarities(group)).enumerate() {
+                    *note = match similarity {
+                        Ok(_) if i == 0 => String::from("  (유사도 기준)"),
+                        Ok(similarity) => format!("  (유사도 {}%)", number_format::format().decimal(similarity * 100.0)),
+                        Err(e) => format!("  (유사도 계산 실패: {})", e),
+                    };
+                }
+            }
+        }
+        match scored {
+            // 심볼릭 링크 그룹은 정리할 내용이 없으므로 점수를 매기지 않습니다.
+            Some(keep) if group.symlink_target.is_none() => {
+                let scores = keep.scores(&group.paths);
+                let keeper = keep.keeper(&group.paths);
+                for (i, ((path, score), note)) in group.paths.iter().zip(&scores).zip(&notes).enumerate() {
+                    let mark = if i == keeper { "  ← 유지" } else { "" };
+                    println!("  - [점수 {:>6}] {}{}{}", number_format::format().decimal(*score), path_style::display(path), mark, note);
+                }
+            }
+            _ => {
+                for (path, note) in group.paths.iter().zip(&notes) {
+                    println!("  - {}{}", path_style::display(path), note);
+                }
+            }
         }
         // 그룹 간 구분을 위해 빈 줄을 하나 추가합니다.
         println!();
     }
-}
\ No newline at end of file
+}
+
+/// 바이트 수를 사람이 읽기 쉬운 단위(KB, MB, ...)로 변환합니다.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    // 숫자와 단위 기호는 사용자 로캘에 맞춰 씁니다. (--locale)
+    let numbers = number_format::format();
+    if unit == 0 {
+        format!("{} {}", numbers.count(bytes), numbers.unit(0))
+    } else {
+        format!("{} {}", numbers.decimal(value), numbers.unit(unit))
+    }
+}
+
+/// 개수를 사용자 로캘에 맞춰 천 단위로 묶어 씁니다. (--locale)
+fn format_count(n: impl TryInto<u64>) -> String {
+    number_format::format().count(n.try_into().unwrap_or(u64::MAX))
+}
@@ -0,0 +1,204 @@
+// ==============================================================================
+//  원격 해시 목록과 비교하기 (--compare-manifest)
+// ------------------------------------------------------------------------------
+//  중앙 보관 서버가 보관한 파일의 해시 목록(manifest)을 공개해 두면, 원격 저장소를 마운트하지
+//  않고도 로컬 파일 중 어떤 것이 이미 보관되었는지(지워도 되는지), 어떤 것이 아직 올라가지
+//  않았는지 알 수 있습니다. 목록은 HTTP(S) 주소나 로컬 파일로 지정합니다.
+//
+//  목록 형식은 체크섬 도구의 출력을 그대로 받습니다. (해시는 --algorithm과 같은 알고리즘)
+//  - GNU 형식 (`sha256sum`):   <해시>  <경로>      또는   <해시> *<경로>
+//  - BSD 형식 (`shasum --tag`): SHA256 (<경로>) = <해시>
+//  빈 줄과 `#`로 시작하는 줄은 건너뜁니다.
+//
+//  목록에는 크기가 없으므로, 크기로 후보를 줄이지 못하고 로컬 파일을 모두 해시합니다.
+//  (--xattr-cache를 쓰면 다음 실행부터는 바뀐 파일만 읽습니다.)
+//
+//  오프라인 드라이브 카탈로그(--against-catalogs)도 같은 방법으로 비교합니다. 카탈로그에는 크기가
+//  있으므로, 어느 카탈로그에도 없는 크기의 로컬 파일은 읽지 않고 "보관되지 않음"으로 판단합니다.
+// ==============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use serde_json::json;
+
+use crate::{
+    EXIT_DUPLICATES_FOUND, EXIT_FILE_ERRORS, EXIT_INTERRUPTED, EXIT_NO_DUPLICATES, EXIT_TIME_LIMIT, FdBudget, HashAlgorithm,
+    HashFailure, KIND_IN_USE, Progress, ScanError, ScanLog, ScanOptions, collect_files, format_size, hash_unchanged, interrupt,
+    print_error_summary, reader_pool, record_error,
+};
+
+/// 원격 목록: 해시 -> 그 내용을 보관한 원격 경로 (같은 해시가 여러 번 나오면 첫 경로).
+pub struct Manifest {
+    entries: HashMap<String, String>,
+    /// 목록에 있는 파일 크기들. 크기를 아는 목록이라면 이 크기가 아닌 로컬 파일은 해시하지 않습니다.
+    sizes: Option<HashSet<u64>>,
+}
+
+impl Manifest {
+    /// 크기를 아는 목록(오프라인 카탈로그)을 만듭니다.
+    pub fn with_sizes(entries: HashMap<String, String>, sizes: HashSet<u64>) -> Self {
+        Manifest { entries, sizes: Some(sizes) }
+    }
+
+    /// 목록을 읽습니다. `http://`나 `https://`로 시작하면 내려받고, 아니면 로컬 파일로 읽습니다.
+    pub fn load(source: &str, algorithm: HashAlgorithm) -> io::Result<Self> {
+        let context = |e: &dyn std::fmt::Display| format!("해시 목록 '{}': {}", source, e);
+        let reader: Box<dyn BufRead> = if source.starts_with("http://") || source.starts_with("https://") {
+            Box::new(BufReader::new(fetch(source).map_err(|e| io::Error::new(e.kind(), context(&e)))?))
+        } else {
+            Box::new(BufReader::new(File::open(source).map_err(|e| io::Error::new(e.kind(), context(&e)))?))
+        };
+        let mut entries = HashMap::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| io::Error::new(e.kind(), context(&e)))?;
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((hash, path)) = parse_line(line, algorithm) else {
+                let message = format!("{}번째 줄은 {} 체크섬 형식이 아닙니다: {}", number + 1, algorithm.name(), line);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, context(&message)));
+            };
+            entries.entry(hash).or_insert(path);
+        }
+        Ok(Manifest { entries, sizes: None })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// 목록을 HTTP(S)로 내려받는 스트림. 목록이 커도 한 줄씩 읽으므로 통째로 메모리에 올리지 않습니다.
+#[cfg(feature = "native")]
+fn fetch(url: &str) -> io::Result<impl io::Read> {
+    let response = ureq::get(url).call().map_err(io::Error::other)?;
+    Ok(response.into_body().into_reader())
+}
+
+/// WASI 빌드에는 HTTP 클라이언트가 없습니다.
+#[cfg(not(feature = "native"))]
+fn fetch(_url: &str) -> io::Result<io::Empty> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "이 빌드에서는 URL에서 목록을 받을 수 없습니다. 파일로 내려받아 경로를 지정하세요",
+    ))
+}
+
+/// 체크섬 줄 하나를 (소문자 해시, 경로)로 나눕니다. 형식이 다르거나 해시 길이가 맞지 않으면 `None`.
+fn parse_line(line: &str, algorithm: HashAlgorithm) -> Option<(String, String)> {
+    // BSD 형식: `SHA256 (경로) = 해시`
+    let bsd = line.split_once(" (").and_then(|(tag, rest)| {
+        let (path, hash) = rest.rsplit_once(") = ")?;
+        (tag.replace('-', "").eq_ignore_ascii_case(algorithm.name())).then_some((hash, path))
+    });
+    // GNU 형식: `해시  경로` 또는 `해시 *경로`. 이름에 특수 문자가 있는 줄은 앞에 `\`가 붙습니다.
+    let (hash, path) = match bsd {
+        Some(parsed) => parsed,
+        None => {
+            let (hash, path) = line.trim_start_matches('\\').split_once(' ')?;
+            (hash, path.strip_prefix(' ').or_else(|| path.strip_prefix('*')).unwrap_or(path))
+        }
+    };
+    let valid = hash.len() == algorithm.hex_len() && hash.bytes().all(|b| b.is_ascii_hexdigit());
+    valid.then(|| (hash.to_ascii_lowercase(), path.to_string()))
+}
+
+/// 로컬 루트의 파일을 모두 해시하여 원격 목록과 비교하고, 보관된 파일과 보관되지 않은 파일을 출력합니다.
+/// `summary_only`이면 개수와 크기만 출력합니다.
+pub fn compare(roots: &[PathBuf], options: &ScanOptions, manifest: &Manifest, summary_only: bool, log: &mut ScanLog) -> ExitCode {
+    let mut errors: Vec<ScanError> = Vec::new();
+    let mut progress = Progress::new();
+    // (로컬 경로, 크기, 보관한 원격 경로)
+    let mut archived: Vec<(PathBuf, u64, String)> = Vec::new();
+    let mut missing: Vec<(PathBuf, u64)> = Vec::new();
+    let mut jobs = collect_files(roots, options, &mut errors, log, &mut progress);
+    if let Some(sizes) = &manifest.sizes {
+        let (candidates, unknown): (Vec<_>, Vec<_>) = jobs.into_iter().partition(|(_, stamp)| sizes.contains(&stamp.len));
+        missing.extend(unknown.into_iter().map(|(path, stamp)| (path, stamp.len)));
+        jobs = candidates;
+    }
+    let total = jobs.len() as u64;
+    let budget = FdBudget::new(options.fd_budget);
+    let mut processed = 0u64;
+    reader_pool::run(
+        jobs,
+        options.threads,
+        |(path, stamp)| {
+            let result = hash_unchanged(&path, &stamp, &budget, options);
+            (path, stamp, result)
+        },
+        |(path, stamp, result)| {
+            processed += 1;
+            progress.update("해시 계산 중", processed, Some(total));
+            match result {
+                Ok((digests, _)) => match manifest.entries.get(&digests.hash) {
+                    Some(remote) => archived.push((path, stamp.len, remote.clone())),
+                    None => missing.push((path, stamp.len)),
+                },
+                Err(HashFailure::Io(e)) if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() => {
+                    return false;
+                }
+                Err(HashFailure::Changed(kind, message)) => {
+                    record_error(&mut errors, log, Some(path), "변경 감지", kind, message);
+                }
+                Err(HashFailure::InUse(reason)) => {
+                    record_error(&mut errors, log, Some(path), "사용 중 확인", KIND_IN_USE.to_string(), reason.to_string());
+                }
+                Err(HashFailure::Io(e)) => {
+                    record_error(&mut errors, log, Some(path), "해시 계산", e.kind().to_string(), e.to_string());
+                }
+            }
+            true
+        },
+    );
+    progress.finish();
+    let interrupted = interrupt::requested();
+    archived.sort();
+    missing.sort();
+    let archived_bytes: u64 = archived.iter().map(|(_, size, _)| size).sum();
+    let missing_bytes: u64 = missing.iter().map(|(_, size)| size).sum();
+    log.event(
+        "manifest_end",
+        json!({
+            "archived_files": archived.len(),
+            "archived_bytes": archived_bytes,
+            "missing_files": missing.len(),
+            "missing_bytes": missing_bytes,
+            "file_errors": errors.len(),
+            "interrupted": interrupted,
+        }),
+    );
+    log.flush();
+
+    if interrupted {
+        println!("\n⏹️  검색이 중단되었습니다. 아래는 중단 시점까지 확인한 파일만의 결과입니다.");
+    }
+    println!("\n☁️  이미 보관된 파일: {}개 ({})", archived.len(), format_size(archived_bytes));
+    if !summary_only {
+        for (path, _, remote) in &archived {
+            println!("  - {}  (= {})", path.display(), remote);
+        }
+    }
+    println!("\n📦 아직 보관되지 않은 파일: {}개 ({})", missing.len(), format_size(missing_bytes));
+    if !summary_only {
+        for (path, _) in &missing {
+            println!("  - {}", path.display());
+        }
+    }
+    print_error_summary(&errors, false);
+
+    ExitCode::from(if interrupted {
+        if interrupt::time_limit_reached() { EXIT_TIME_LIMIT } else { EXIT_INTERRUPTED }
+    } else if !errors.is_empty() {
+        EXIT_FILE_ERRORS
+    } else if !archived.is_empty() {
+        EXIT_DUPLICATES_FOUND
+    } else {
+        EXIT_NO_DUPLICATES
+    })
+}
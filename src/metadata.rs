@@ -0,0 +1,167 @@
+// ==============================================================================
+//  파일 메타데이터 보존 (감시 모드의 hardlink, quarantine 동작)
+// ------------------------------------------------------------------------------
+//  백업 프로그램은 수정 시각, 권한, 소유자, 확장 속성으로 바뀐 파일을 알아보거나 복원할 내용을
+//  정합니다. 중복을 정리하면서 이 정보가 소리 없이 바뀌면 다음 백업이 어긋납니다.
+//
+//  - quarantine: 같은 파일 시스템이라면 이름만 바꾸므로 모든 메타데이터가 그대로 남습니다.
+//    다른 파일 시스템이라 복사해야 한다면 복사본에 접근/수정 시각, 권한, 소유자, 확장 속성을
+//    다시 적용하고, 하나라도 적용하지 못하면 복사본을 지우고 원본을 그대로 둡니다.
+//  - hardlink: 링크는 기존 파일과 메타데이터를 함께 쓰므로 새 파일의 것을 남길 수 없습니다.
+//    권한, 소유자, 확장 속성이 기존 파일과 다르면 바꾸지 않고, 같을 때만 링크로 바꿉니다.
+//    (시각은 기존 파일의 것이 됩니다.)
+//  두 동작 모두 바꾸기 전의 메타데이터를 로그 파일(--log-file)의 `watch_duplicate` 레코드에
+//  `metadata` 필드로 남겨, 나중에 원래대로 되돌릴 수 있게 합니다.
+//  (--xattr-cache가 기록하는 해시 캐시 속성은 --compare-attrs와 마찬가지로 다루지 않습니다.)
+// ==============================================================================
+
+use std::ffi::OsString;
+use std::fs::{self, File, FileTimes, Permissions};
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde_json::{Value, json};
+
+/// 파일 하나의 메타데이터.
+pub struct Snapshot {
+    accessed: Option<SystemTime>,
+    modified: Option<SystemTime>,
+    permissions: Permissions,
+    /// (uid, gid). 유닉스가 아니면 `None`.
+    owner: Option<(u32, u32)>,
+    /// 이름순으로 정렬한 확장 속성. 유닉스가 아니면 비어 있습니다.
+    xattrs: Vec<(OsString, Vec<u8>)>,
+}
+
+impl Snapshot {
+    /// 파일의 메타데이터를 읽습니다.
+    pub fn capture(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(Snapshot {
+            accessed: metadata.accessed().ok(),
+            modified: metadata.modified().ok(),
+            permissions: metadata.permissions(),
+            owner: owner(&metadata),
+            xattrs: xattrs(path)?,
+        })
+    }
+
+    /// 권한, 소유자, 확장 속성이 같은지 비교합니다. 시각은 비교하지 않습니다.
+    pub fn same_protection(&self, other: &Snapshot) -> bool {
+        self.permissions == other.permissions && self.owner == other.owner && self.xattrs == other.xattrs
+    }
+
+    /// 다른 파일(복사본)에 이 메타데이터를 적용합니다. 적용하지 못한 항목이 있다면 그 이름들을 `Err`로 반환합니다.
+    ///
+    /// 소유자를 바꾸면 운영체제가 setuid 비트를 지우므로 권한은 소유자 다음에, 권한을 읽기 전용으로
+    /// 바꾸기 전에 시각을 적용합니다.
+    pub fn apply(&self, path: &Path) -> Result<(), String> {
+        let mut failed = Vec::new();
+        if set_xattrs(path, &self.xattrs).is_err() {
+            failed.push("확장 속성");
+        }
+        if let Some((uid, gid)) = self.owner
+            && set_owner(path, uid, gid).is_err()
+        {
+            failed.push("소유자");
+        }
+        let mut times = FileTimes::new();
+        if let Some(accessed) = self.accessed {
+            times = times.set_accessed(accessed);
+        }
+        if let Some(modified) = self.modified {
+            times = times.set_modified(modified);
+        }
+        if open_for_times(path).and_then(|file| file.set_times(times)).is_err() {
+            failed.push("시각");
+        }
+        if fs::set_permissions(path, self.permissions.clone()).is_err() {
+            failed.push("권한");
+        }
+        if failed.is_empty() { Ok(()) } else { Err(failed.join(", ")) }
+    }
+
+    /// 로그에 남길 형태. 확장 속성 값은 16진수 문자열로 씁니다.
+    pub fn to_json(&self) -> Value {
+        let time = |t: Option<SystemTime>| t.map(|t| humantime::format_rfc3339_nanos(t).to_string());
+        json!({
+            "accessed": time(self.accessed),
+            "modified": time(self.modified),
+            "mode": mode_text(&self.permissions),
+            "uid": self.owner.map(|(uid, _)| uid),
+            "gid": self.owner.map(|(_, gid)| gid),
+            "xattrs": self
+                .xattrs
+                .iter()
+                .map(|(name, value)| {
+                    let hex: String = value.iter().map(|b| format!("{:02x}", b)).collect();
+                    json!({ "name": name.to_string_lossy(), "value": hex })
+                })
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn owner(metadata: &fs::Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn owner(_metadata: &fs::Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(unix)]
+fn xattrs(path: &Path) -> io::Result<Vec<(OsString, Vec<u8>)>> {
+    crate::attrs::signature(path)
+}
+
+#[cfg(not(unix))]
+fn xattrs(_path: &Path) -> io::Result<Vec<(OsString, Vec<u8>)>> {
+    Ok(Vec::new())
+}
+
+#[cfg(unix)]
+fn set_xattrs(path: &Path, attrs: &[(OsString, Vec<u8>)]) -> io::Result<()> {
+    attrs.iter().try_for_each(|(name, value)| xattr::set(path, name, value))
+}
+
+#[cfg(not(unix))]
+fn set_xattrs(_path: &Path, _attrs: &[(OsString, Vec<u8>)]) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_owner(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    std::os::unix::fs::chown(path, Some(uid), Some(gid))
+}
+
+#[cfg(not(unix))]
+fn set_owner(_path: &Path, _uid: u32, _gid: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// 시각을 바꿀 파일 핸들. 유닉스에서는 소유자라면 읽기 전용으로 열어도 바꿀 수 있습니다.
+#[cfg(unix)]
+fn open_for_times(path: &Path) -> io::Result<File> {
+    File::open(path)
+}
+
+#[cfg(not(unix))]
+fn open_for_times(path: &Path) -> io::Result<File> {
+    File::options().write(true).open(path)
+}
+
+#[cfg(unix)]
+fn mode_text(permissions: &Permissions) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    format!("{:04o}", permissions.mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn mode_text(permissions: &Permissions) -> String {
+    String::from(if permissions.readonly() { "readonly" } else { "writable" })
+}
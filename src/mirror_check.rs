@@ -0,0 +1,175 @@
+// ==============================================================================
+//  미러 사이의 조용한 손상 찾기 (--check-mirror)
+// ------------------------------------------------------------------------------
+//  디스크의 비트가 저절로 바뀌는 손상(bit rot)은 오류 없이 읽히므로, 원본과 미러 중 한쪽이 망가져도
+//  알아챌 방법이 없습니다. 망가진 쪽을 원본으로 다시 미러링하면 멀쩡한 사본까지 덮어씁니다.
+//
+//  --check-mirror는 중복 검색 대신, 서로 같아야 하는 두 루트(원본과 미러)에서 상대 경로와 크기가 같은데
+//  내용 해시가 다른 파일을 찾아 따로 보고합니다.
+//  - 수정 시각까지 같다면(초 단위) 누가 고친 것이 아니므로 손상 의심으로 보고합니다.
+//  - 수정 시각이 다르면 한쪽을 고쳤거나 동기화가 늦은 것일 수 있으므로 따로 보여줍니다.
+//  크기가 다른 파일과 한쪽에만 있는 파일은 손상이 아니라 동기화 차이이므로 개수만 알립니다.
+//  (자세한 차이는 `compare` 하위 명령으로 봅니다) 해시는 항상 파일을 다시 읽어 계산하므로
+//  --xattr-cache, --import-hashes와 함께 쓸 수 없습니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use serde_json::json;
+
+use crate::root_stats::root_of;
+use crate::{
+    Digests, EXIT_DUPLICATES_FOUND, EXIT_FILE_ERRORS, EXIT_INTERRUPTED, EXIT_NO_DUPLICATES, EXIT_TIME_LIMIT, FdBudget, FileStamp,
+    HashFailure, KIND_IN_USE, Progress, ScanError, ScanLog, ScanOptions, format_count, format_size, hash_unchanged, interrupt,
+    print_error_summary, reader_pool, record_error, walk_files,
+};
+
+/// 내용이 다른 한 쌍: (원본 경로, 미러 경로, 크기, 수정 시각이 같은지).
+type Mismatch = (PathBuf, PathBuf, u64, bool);
+
+/// 두 루트의 같은 상대 경로, 같은 크기의 파일을 해시하여 내용이 다른 쌍을 출력합니다.
+/// `summary_only`이면 개수와 크기만 출력합니다.
+pub fn report(roots: &[PathBuf], options: &ScanOptions, summary_only: bool, log: &mut ScanLog) -> ExitCode {
+    let mut errors: Vec<ScanError> = Vec::new();
+    let mut progress = Progress::new();
+    // 원본과 미러의 파일이 서로 하드 링크이거나 한 루트 안에 하드 링크가 있어도, 경로마다 짝이 있어야 하므로
+    // 같은 물리적 파일의 경로도 모두 모읍니다.
+    let files = walk_files(roots, options, true, &mut errors, log, &mut progress);
+
+    // 루트마다 상대 경로 -> (경로, 상태).
+    let mut sides: [HashMap<PathBuf, (PathBuf, FileStamp)>; 2] = [HashMap::new(), HashMap::new()];
+    for (path, stamp) in files {
+        let Some(root) = root_of(roots, &path) else { continue };
+        let Ok(relative) = path.strip_prefix(&roots[root]) else { continue };
+        sides[root].insert(relative.to_path_buf(), (path, stamp));
+    }
+    let [original, mut mirror] = sides;
+
+    // 상대 경로와 크기가 같은 쌍만 해시합니다.
+    let mut jobs = Vec::new();
+    let mut unpaired = 0usize;
+    for (relative, (path, stamp)) in original {
+        match mirror.remove(&relative) {
+            Some((other, other_stamp)) if other_stamp.len == stamp.len => jobs.push((path, stamp, other, other_stamp)),
+            _ => unpaired += 1,
+        }
+    }
+    unpaired += mirror.len();
+
+    let total = jobs.len() as u64;
+    let budget = FdBudget::new(options.fd_budget);
+    let mut processed = 0u64;
+    let mut verified = 0u64;
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    reader_pool::run(
+        jobs,
+        options.threads,
+        |(path, stamp, other, other_stamp)| {
+            let result = hash_unchanged(&path, &stamp, &budget, options);
+            let other_result = hash_unchanged(&other, &other_stamp, &budget, options);
+            let same_time = same_second(&stamp, &other_stamp);
+            (path, other, stamp.len, same_time, result, other_result)
+        },
+        |(path, other, size, same_time, result, other_result)| {
+            processed += 1;
+            progress.update("미러 비교 중", processed, Some(total));
+            let mut hashes: Vec<Digests> = Vec::with_capacity(2);
+            for (path, result) in [(path.clone(), result), (other.clone(), other_result)] {
+                match result {
+                    Ok((digests, _)) => hashes.push(digests),
+                    Err(HashFailure::Io(e)) if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() => {
+                        return false;
+                    }
+                    Err(HashFailure::Changed(kind, message)) => {
+                        record_error(&mut errors, log, Some(path), "변경 감지", kind, message);
+                    }
+                    Err(HashFailure::InUse(reason)) => {
+                        record_error(&mut errors, log, Some(path), "사용 중 확인", KIND_IN_USE.to_string(), reason.to_string());
+                    }
+                    Err(HashFailure::Io(e)) => {
+                        record_error(&mut errors, log, Some(path), "해시 계산", e.kind().to_string(), e.to_string());
+                    }
+                }
+            }
+            if let [a, b] = hashes.as_slice() {
+                if a.hash == b.hash {
+                    verified += 1;
+                } else {
+                    mismatches.push((path, other, size, same_time));
+                }
+            }
+            true
+        },
+    );
+    progress.finish();
+    let interrupted = interrupt::requested();
+    mismatches.sort_by(|a, b| b.3.cmp(&a.3).then_with(|| b.2.cmp(&a.2)).then_with(|| a.0.cmp(&b.0)));
+    let suspected: Vec<&Mismatch> = mismatches.iter().filter(|m| m.3).collect();
+    let modified: Vec<&Mismatch> = mismatches.iter().filter(|m| !m.3).collect();
+    log.event(
+        "mirror_check_end",
+        json!({
+            "verified": verified,
+            "suspected_corruption": suspected.len(),
+            "modified_differently": modified.len(),
+            "unpaired": unpaired,
+            "file_errors": errors.len(),
+            "interrupted": interrupted,
+        }),
+    );
+    log.flush();
+
+    if interrupted {
+        println!("\n⏹️  검색이 중단되었습니다. 아래는 중단 시점까지 비교한 파일만의 결과입니다.");
+    }
+    println!("\n🪞 미러 점검: [1] {} ↔ [2] {}", roots[0].display(), roots[1].display());
+    println!("  내용까지 같은 파일: {}개", format_count(verified));
+    print_mismatches("⚠️  손상 의심 (경로, 크기, 수정 시각이 같은데 내용이 다름)", &suspected, summary_only);
+    print_mismatches("✏️  내용이 다름 (수정 시각이 달라 한쪽을 고쳤을 수 있음)", &modified, summary_only);
+    if unpaired > 0 {
+        println!("  ℹ️  크기가 다르거나 한쪽에만 있는 파일 {}개는 비교하지 않았습니다. (`compare`로 확인)", format_count(unpaired));
+    }
+    if !suspected.is_empty() {
+        println!("  손상 의심 파일은 다시 미러링하기 전에 어느 쪽이 맞는지 확인하세요. (다른 백업, 파일 열어 보기 등)");
+    }
+    print_error_summary(&errors, false);
+
+    ExitCode::from(if interrupted {
+        if interrupt::time_limit_reached() { EXIT_TIME_LIMIT } else { EXIT_INTERRUPTED }
+    } else if !errors.is_empty() {
+        EXIT_FILE_ERRORS
+    } else if !mismatches.is_empty() {
+        EXIT_DUPLICATES_FOUND
+    } else {
+        EXIT_NO_DUPLICATES
+    })
+}
+
+/// 두 파일의 수정 시각이 초 단위로 같은지. 파일 시스템마다 시각의 정밀도가 달라(FAT은 2초) 초 아래는 보지 않습니다.
+fn same_second(a: &FileStamp, b: &FileStamp) -> bool {
+    let seconds = |stamp: &FileStamp| {
+        stamp.modified.and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok()).map(|d| d.as_secs())
+    };
+    match (seconds(a), seconds(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn print_mismatches(title: &str, mismatches: &[&Mismatch], summary_only: bool) {
+    if mismatches.is_empty() {
+        return;
+    }
+    let bytes: u64 = mismatches.iter().map(|m| m.2).sum();
+    println!("  {}: {}개 ({})", title, format_count(mismatches.len()), format_size(bytes));
+    if summary_only {
+        return;
+    }
+    for (path, other, size, _) in mismatches {
+        println!("      - {} ({})", path.display(), format_size(*size));
+        println!("        {}", other.display());
+    }
+}
@@ -0,0 +1,34 @@
+// ==============================================================================
+//  파일 이름 비교 (유니코드 정규화)
+// ------------------------------------------------------------------------------
+//  macOS는 파일 이름을 분해형(NFD)으로 저장하는 경우가 많고, Linux/Windows는
+//  보통 조합형(NFC)을 사용합니다. 그래서 macOS에서 복사해 온 "사진.jpg"는 화면에서는
+//  똑같아 보여도 한글 자모가 분리된 다른 바이트열이라, 단순 비교로는 일치하지 않습니다.
+//
+//  `--normalize-names`를 켜면 이름을 비교하기 전에 양쪽을 모두 NFC로 정규화합니다.
+// ==============================================================================
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+
+use unicode_normalization::{UnicodeNormalization, is_nfc};
+
+/// 이름을 비교용 형태로 바꿉니다. `normalize`가 참이면 NFC로 정규화합니다.
+/// 이미 NFC인 이름(대부분의 경우)은 복사하지 않습니다.
+pub fn comparable(name: &str, normalize: bool) -> Cow<'_, str> {
+    if normalize && !is_nfc(name) {
+        Cow::Owned(name.nfc().collect())
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// 파일 시스템에서 읽은 이름 `actual`이 사용자가 지정한 이름 `wanted`와 같은지 비교합니다.
+///
+/// UTF-8이 아닌 이름은 정규화할 수 없으므로 바이트 그대로 비교합니다.
+pub fn matches(actual: &OsStr, wanted: &str, normalize: bool) -> bool {
+    match actual.to_str() {
+        Some(actual) => comparable(actual, normalize) == comparable(wanted, normalize),
+        None => actual == OsStr::new(wanted),
+    }
+}
@@ -0,0 +1,255 @@
+// ==============================================================================
+//  데몬 모드의 검색 결과 전달 (--webhook, --smtp-*)
+// ------------------------------------------------------------------------------
+//  주기적인 정리 작업의 결과는 터미널 로그보다 팀이 실제로 보는 곳(채팅 채널, 메일함)에
+//  도착해야 쓸모가 있습니다. 데몬 모드의 매 검색이 끝나면 요약을 다음과 같이 보냅니다.
+//
+//  - --webhook URL: JSON 요약을 본문으로 하는 POST 요청.
+//  - --smtp-server HOST:PORT: 같은 요약을 텍스트 메일로 보냅니다. 인증과 TLS 없이
+//    평문 SMTP로 보내므로, 같은 서버나 내부망의 메일 중계 서버(postfix 등)를 지정하세요.
+//
+//  전달에 실패해도 데몬은 멈추지 않고 경고만 출력한 뒤 다음 검색을 계속합니다.
+// ==============================================================================
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+
+use crate::{Args, RunReport, format_size, number_format};
+
+/// 메일 서버에 연결하고, 요청을 보내고, 응답을 기다리는 각각의 최대 시간.
+/// 응답하지 않는 서버 때문에 데몬의 다음 검색이 멈추지 않도록 합니다.
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// RFC 2047 인코딩 단어 하나에 담는 원문 바이트 수. Base64로 52자가 되어, `Subject: `를 붙인 첫 줄도
+/// 76자를 넘지 않습니다. (인코딩 단어 하나는 75자를 넘을 수 없습니다)
+const ENCODED_WORD_BYTES: usize = 39;
+
+/// 검색 요약을 보낼 곳들.
+pub struct Notifier {
+    webhook: Option<String>,
+    smtp: Option<SmtpSettings>,
+}
+
+struct SmtpSettings {
+    server: String,
+    from: String,
+    to: Vec<String>,
+}
+
+impl Notifier {
+    /// 명령줄 옵션에서 전달 대상을 만듭니다. 지정된 대상이 없으면 `None`입니다.
+    pub fn from_args(args: &Args) -> Option<Self> {
+        let smtp = args.smtp_server.as_ref().map(|server| SmtpSettings {
+            server: server.clone(),
+            from: args.smtp_from.clone().unwrap_or_default(),
+            to: args.smtp_to.clone(),
+        });
+        if args.webhook.is_none() && smtp.is_none() {
+            return None;
+        }
+        Some(Notifier { webhook: args.webhook.clone(), smtp })
+    }
+
+    /// 검색 한 번의 요약을 모든 대상에 보냅니다. 실패는 경고로만 출력합니다.
+    pub fn deliver(&self, roots: &[PathBuf], report: &RunReport) {
+        let summary = summary_json(roots, report);
+        if let Some(url) = &self.webhook {
+            let result = ureq::post(url)
+                .header("Content-Type", "application/json")
+                .send(summary.to_string())
+                .map_err(|e| e.to_string());
+            if let Err(e) = result {
+                eprintln!("⚠️  웹훅({})으로 결과를 보내지 못했습니다: {}", url, e);
+            }
+        }
+        if let Some(smtp) = &self.smtp {
+            let (subject, body) = email_text(roots, report);
+            if let Err(e) = send_mail(smtp, &subject, &body) {
+                eprintln!("⚠️  메일 서버({})로 결과를 보내지 못했습니다: {}", smtp.server, e);
+            }
+        }
+    }
+}
+
+/// 웹훅 본문으로 보낼 JSON 요약.
+fn summary_json(roots: &[PathBuf], report: &RunReport) -> Value {
+    json!({
+        "finished": humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        "roots": roots.iter().map(|r| r.display().to_string()).collect::<Vec<_>>(),
+        "duration_seconds": report.duration.as_secs_f64(),
+        "interrupted": report.interrupted,
+        "files_scanned": report.stats.files_scanned,
+        "bytes_hashed": report.stats.bytes_hashed,
+        "duplicate_groups": report.groups,
+        "duplicate_bytes": report.wasted_bytes,
+        "errors": report.errors_by_kind,
+    })
+}
+
+/// 메일 제목과 본문.
+fn email_text(roots: &[PathBuf], report: &RunReport) -> (String, String) {
+    let subject = format!(
+        "[duplicate_finder] 중복 그룹 {}개, {} 회수 가능",
+        report.groups,
+        format_size(report.wasted_bytes)
+    );
+    let mut body = String::new();
+    body.push_str(&format!("검색 완료: {}\n", humantime::format_rfc3339_seconds(SystemTime::now())));
+    for root in roots {
+        body.push_str(&format!("루트 폴더: {}\n", root.display()));
+    }
+    body.push_str(&format!("소요 시간: {}초\n", number_format::format().decimal(report.duration.as_secs_f64())));
+    body.push_str(&format!("검색한 파일: {}개\n", report.stats.files_scanned));
+    body.push_str(&format!("중복 그룹: {}개\n", report.groups));
+    body.push_str(&format!("회수 가능 공간: {}\n", format_size(report.wasted_bytes)));
+    if report.interrupted {
+        body.push_str("검색이 중단되어 위 수치는 일부 결과입니다.\n");
+    }
+    if !report.errors_by_kind.is_empty() {
+        body.push_str("\n오류:\n");
+        for (kind, count) in &report.errors_by_kind {
+            body.push_str(&format!("  {}: {}개\n", kind, count));
+        }
+    }
+    (subject, body)
+}
+
+/// 평문 SMTP로 메일 한 통을 보냅니다. (EHLO, MAIL FROM, RCPT TO, DATA, QUIT)
+fn send_mail(smtp: &SmtpSettings, subject: &str, body: &str) -> io::Result<()> {
+    let stream = connect(&smtp.server)?;
+    stream.set_read_timeout(Some(SMTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(SMTP_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    expect_reply(&mut reader, 220)?;
+    command(&mut writer, &mut reader, "EHLO duplicate-finder", 250)?;
+    command(&mut writer, &mut reader, &format!("MAIL FROM:<{}>", smtp.from), 250)?;
+    for to in &smtp.to {
+        command(&mut writer, &mut reader, &format!("RCPT TO:<{}>", to), 250)?;
+    }
+    command(&mut writer, &mut reader, "DATA", 354)?;
+
+    let now = SystemTime::now();
+    let mut message = String::new();
+    message.push_str(&format!("Date: {}\r\n", mail_date(now)));
+    message.push_str(&format!("Message-ID: {}\r\n", message_id(&smtp.from, now)));
+    message.push_str(&format!("From: {}\r\n", smtp.from));
+    message.push_str(&format!("To: {}\r\n", smtp.to.join(", ")));
+    message.push_str(&format!("Subject: {}\r\n", encode_header(subject)));
+    message.push_str("MIME-Version: 1.0\r\n");
+    message.push_str("Content-Type: text/plain; charset=UTF-8\r\n");
+    message.push_str("Content-Transfer-Encoding: 8bit\r\n\r\n");
+    for line in body.lines() {
+        // 점으로 시작하는 줄은 본문의 끝 표시(".")와 구분되도록 점을 하나 더 붙입니다.
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message.push_str(".\r\n");
+    writer.write_all(message.as_bytes())?;
+    expect_reply(&mut reader, 250)?;
+    command(&mut writer, &mut reader, "QUIT", 221)
+}
+
+/// 서버 주소(HOST:PORT)의 주소들에 차례로 연결을 시도합니다. 주소마다 `SMTP_TIMEOUT`까지만 기다립니다.
+fn connect(server: &str) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "메일 서버 주소를 찾을 수 없습니다");
+    for addr in server.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, SMTP_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// `Date:` 헤더의 RFC 5322 날짜 (예: `Thu, 15 Oct 2026 11:15:16 +0000`). 항상 UTC로 씁니다.
+fn mail_date(time: SystemTime) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    // RFC 3339 형식(`2026-10-15T11:15:16Z`)에서 날짜와 시각을 잘라 옮깁니다. 1970-01-01은 목요일입니다.
+    let stamp = humantime::format_rfc3339_seconds(time).to_string();
+    let days = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 86_400);
+    let month = stamp[5..7].parse::<usize>().unwrap_or(1);
+    format!(
+        "{}, {} {} {} {} +0000",
+        WEEKDAYS[(days % 7) as usize],
+        &stamp[8..10],
+        MONTHS[month - 1],
+        &stamp[..4],
+        &stamp[11..19]
+    )
+}
+
+/// `Message-ID:` 헤더. 보낸 시각과 프로세스 번호로 만들고, 도메인은 보내는 주소의 것을 씁니다.
+fn message_id(from: &str, time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let domain = from.rsplit_once('@').map_or("localhost", |(_, domain)| domain.trim_end_matches('>'));
+    format!("<{}.{:09}.{}@{}>", since.as_secs(), since.subsec_nanos(), process::id(), domain)
+}
+
+fn command(writer: &mut TcpStream, reader: &mut impl BufRead, line: &str, expected: u16) -> io::Result<()> {
+    writer.write_all(format!("{}\r\n", line).as_bytes())?;
+    expect_reply(reader, expected)
+}
+
+/// 서버 응답(여러 줄일 수 있음)을 읽고 상태 코드가 기대한 값인지 확인합니다.
+fn expect_reply(reader: &mut impl BufRead, expected: u16) -> io::Result<()> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "메일 서버가 연결을 닫았습니다"));
+        }
+        // "250-..."는 응답이 계속된다는 뜻이고, "250 ..."가 마지막 줄입니다.
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        let code: u16 = line.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+        if code != expected {
+            return Err(io::Error::other(format!("예상하지 못한 응답: {}", line.trim_end())));
+        }
+        return Ok(());
+    }
+}
+
+/// 헤더에 ASCII가 아닌 문자가 있으면 RFC 2047 형식(`=?UTF-8?B?...?=`)으로 인코딩합니다.
+/// 긴 값은 문자 경계에서 여러 인코딩 단어로 나누고, 단어마다 줄을 접습니다.
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        if i + c.len_utf8() - start > ENCODED_WORD_BYTES {
+            words.push(format!("=?UTF-8?B?{}?=", base64(&value.as_bytes()[start..i])));
+            start = i;
+        }
+    }
+    words.push(format!("=?UTF-8?B?{}?=", base64(&value.as_bytes()[start..])));
+    words.join("\r\n ")
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(TABLE[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
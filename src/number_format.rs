@@ -0,0 +1,180 @@
+// ==============================================================================
+//  사용자 로캘에 맞춘 숫자와 크기 표기 (--locale)
+// ------------------------------------------------------------------------------
+//  보고서는 엔지니어가 아닌 사람에게도 전달되는데, `1234567개`나 `12.5 GB`처럼 자릿수 구분이 없거나
+//  소수점 기호가 다른 나라 방식인 숫자는 잘못 읽히기 쉽습니다. (독일에서는 `12.5`를 12,500으로 읽습니다)
+//
+//  개수와 크기를 출력할 때 사용자의 로캘에 맞춰 천 단위 구분 기호, 소수점 기호, 크기 단위 기호를 씁니다.
+//  - 로캘은 --locale로 지정하거나, 값 없이 `--locale`만 쓰면 POSIX 규칙대로 LC_ALL, LC_NUMERIC, LANG
+//    순서로 처음 값이 있는 환경 변수를 씁니다. (예: `de_DE.UTF-8`, `fr_FR`, `ko_KR.UTF-8`)
+//  - --locale을 쓰지 않았거나 로캘이 `C`, `POSIX`라면 지금까지처럼 구분 기호 없이 씁니다. 스크립트가
+//    읽는 출력이 사용자의 환경 변수에 따라 바뀌지 않게 하기 위해서입니다.
+//  - 표에 없는 언어는 영어 방식(`1,234.5`)으로 씁니다.
+//  크기는 로캘과 관계없이 1024 단위로 계산하며, 프랑스어 로캘만 단위 기호를 옥텟(`o`, `Ko`, `Mo`...)으로
+//  씁니다. JSON, CSV, 결과 파일, 로그 파일, 지표 파일의 숫자와 요약의 `(… bytes)` 값은 바꾸지 않습니다.
+// ==============================================================================
+
+use std::sync::OnceLock;
+
+/// 숫자를 쓰는 방식.
+#[derive(Clone, Debug)]
+pub struct NumberFormat {
+    /// 천 단위 구분 기호. 비어 있으면 묶지 않습니다.
+    group: &'static str,
+    /// 소수점 기호.
+    decimal: char,
+    /// 바이트, KB, MB, GB, TB의 단위 기호.
+    units: [&'static str; 5],
+}
+
+const BYTES: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+const OCTETS: [&str; 5] = ["o", "Ko", "Mo", "Go", "To"];
+
+impl NumberFormat {
+    /// 로캘이 없을 때의 표기: 구분 기호 없이 `1234567`, `12.5 GB`.
+    pub const PLAIN: NumberFormat = NumberFormat { group: "", decimal: '.', units: BYTES };
+
+    /// 로캘 이름(`de_DE.UTF-8`, `fr-CA`, `ko` 등)에 맞는 표기.
+    pub fn for_locale(name: &str) -> Self {
+        // 인코딩(`.UTF-8`)과 변형(`@euro`)은 표기와 관계없으므로 뺍니다.
+        let name = name.split(['.', '@']).next().unwrap_or("");
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            return NumberFormat::PLAIN;
+        }
+        let mut parts = name.split(['_', '-']);
+        let language = parts.next().unwrap_or("").to_ascii_lowercase();
+        let country = parts.next().unwrap_or("").to_ascii_uppercase();
+        let units = if language == "fr" { OCTETS } else { BYTES };
+        let (group, decimal) = match (language.as_str(), country.as_str()) {
+            // 스위스와 리히텐슈타인은 어느 언어든 작은따옴표로 묶습니다.
+            (_, "CH" | "LI") => ("’", '.'),
+            ("de" | "nl" | "es" | "it" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr" | "vi", _) => (".", ','),
+            // 공백으로 묶는 언어는 줄이 바뀌지 않도록 줄 바꿈 없는 공백을 씁니다.
+            (
+                "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "uk" | "bg" | "hu" | "lt" | "lv" | "et",
+                _,
+            ) => ("\u{a0}", ','),
+            _ => (",", '.'),
+        };
+        NumberFormat { group, decimal, units }
+    }
+
+    /// 환경 변수의 로캘에 맞는 표기. (LC_ALL, LC_NUMERIC, LANG 순서)
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+            .map_or(NumberFormat::PLAIN, |locale| NumberFormat::for_locale(&locale))
+    }
+
+    /// 개수. (예: `1,234,567`)
+    pub fn count(&self, n: u64) -> String {
+        let digits = n.to_string();
+        if self.group.is_empty() {
+            return digits;
+        }
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * self.group.len());
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(self.group);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// 소수점 아래 한 자리까지의 값. (예: `1,234.5`, 독일어 로캘은 `1.234,5`)
+    pub fn decimal(&self, value: f64) -> String {
+        self.fixed(value, 1)
+    }
+
+    /// 소수점 아래 `places`자리까지의 값. 비율(%)과 걸린 시간(초)에 씁니다. (예: `0.125`, 독일어 로캘은 `0,125`)
+    pub fn fixed(&self, value: f64, places: usize) -> String {
+        // 반올림은 지금까지의 `{:.N}` 출력과 같게 하고, 기호만 바꿉니다.
+        let plain = format!("{:.*}", places, value);
+        let Some((whole, fraction)) = plain.split_once('.') else { return plain };
+        match whole.parse() {
+            Ok(whole) => format!("{}{}{}", self.count(whole), self.decimal, fraction),
+            Err(_) => plain,
+        }
+    }
+
+    /// `unit`번째(0: 바이트, 1: KB, ...) 크기 단위 기호.
+    pub fn unit(&self, unit: usize) -> &'static str {
+        self.units[unit]
+    }
+}
+
+/// 실행 중 쓰는 숫자 표기. 시작할 때 한 번 정합니다.
+static FORMAT: OnceLock<NumberFormat> = OnceLock::new();
+
+/// 숫자 표기를 정합니다. 두 번째부터는 무시합니다.
+pub fn set_format(format: NumberFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// 현재 숫자 표기. 정하지 않았다면 구분 기호 없이 씁니다.
+pub fn format() -> &'static NumberFormat {
+    FORMAT.get_or_init(|| NumberFormat::PLAIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_format_does_not_group() {
+        let plain = NumberFormat::PLAIN;
+        assert_eq!(plain.count(1_234_567), "1234567");
+        assert_eq!(plain.decimal(1234.56), "1234.6");
+        assert_eq!(plain.unit(4), "TB");
+    }
+
+    #[test]
+    fn groups_digits_by_locale() {
+        assert_eq!(NumberFormat::for_locale("en_US.UTF-8").count(1_234_567), "1,234,567");
+        assert_eq!(NumberFormat::for_locale("de_DE@euro").decimal(1234.5), "1.234,5");
+        assert_eq!(NumberFormat::for_locale("fr-CA").count(1234), "1\u{a0}234");
+        assert_eq!(NumberFormat::for_locale("de_CH").decimal(1234.5), "1’234.5");
+        assert_eq!(NumberFormat::for_locale("fr_FR").unit(2), "Mo");
+        // 표에 없는 언어는 영어 방식입니다.
+        assert_eq!(NumberFormat::for_locale("ko_KR.UTF-8").count(1000), "1,000");
+    }
+
+    #[test]
+    fn group_boundaries() {
+        let english = NumberFormat::for_locale("en");
+        for (n, text) in [(0, "0"), (999, "999"), (1000, "1,000"), (100_000, "100,000"), (u64::MAX, "18,446,744,073,709,551,615")] {
+            assert_eq!(english.count(n), text);
+        }
+        assert_eq!(english.decimal(999.96), "1,000.0");
+        assert_eq!(english.decimal(0.04), "0.0");
+    }
+
+    #[test]
+    fn fixed_places() {
+        let german = NumberFormat::for_locale("de_DE");
+        assert_eq!(german.fixed(0.0, 3), "0,000");
+        assert_eq!(german.fixed(1234.5678, 3), "1.234,568");
+        assert_eq!(NumberFormat::PLAIN.fixed(12.3456, 2), "12.35");
+    }
+
+    #[test]
+    fn odd_locale_names_fall_back() {
+        for name in ["", "C", "POSIX", "C.UTF-8", ".UTF-8", "@euro"] {
+            assert_eq!(NumberFormat::for_locale(name).count(1234), "1234", "{:?}", name);
+        }
+        for (name, text) in [("xx", "1,234"), ("_", "1,234"), ("-DE", "1,234"), ("de_", "1.234")] {
+            assert_eq!(NumberFormat::for_locale(name).count(1234), text, "{:?}", name);
+        }
+        assert_eq!(NumberFormat::for_locale("DE_de").decimal(1.5), "1,5");
+    }
+
+    #[test]
+    fn values_without_whole_number_are_left_as_is() {
+        let german = NumberFormat::for_locale("de");
+        assert_eq!(german.decimal(-1.5), "-1.5");
+        assert_eq!(german.decimal(f64::NAN), "NaN");
+        assert_eq!(german.decimal(f64::INFINITY), "inf");
+    }
+}
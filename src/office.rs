@@ -0,0 +1,95 @@
+// ==============================================================================
+//  오피스 문서 비교: 바뀌기 쉬운 메타데이터 무시 (--match office)
+// ------------------------------------------------------------------------------
+//  docx/xlsx/pptx(Office Open XML) 파일은 여러 XML 파트를 담은 ZIP 압축 파일입니다.
+//  누군가 문서를 열었다가 그대로 다시 저장하기만 해도 `docProps/core.xml`의 마지막 수정자,
+//  수정 시각, 수정 번호(revision)가 바뀌고, ZIP 안의 시각과 압축 결과도 달라지므로
+//  내용 해시로는 같은 문서로 보이지 않습니다.
+//
+//  --match office는 평소처럼 내용 해시로 중복을 찾은 뒤, 오피스 문서마다 ZIP 안의 파트들을
+//  이름순으로 풀어 "이름 + 길이 + 압축을 푼 내용"의 해시를 계산합니다. 이때 문서 속성 파트
+//  (`IGNORED_PARTS`)는 건너뛰므로, 메타데이터만 다른 문서는 같은 해시를 갖게 됩니다.
+//  그룹의 `hash` 자리에는 `office:`로 시작하는 표시와 이 해시를 넣어 내용 그룹과 구분합니다.
+// ==============================================================================
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+use crate::hashing::Hasher;
+use crate::{LooseMatcher, ScanOptions};
+
+/// 오피스 문서 그룹의 `hash` 앞에 붙는 표시. 뒤에는 파트들의 해시가 이어집니다.
+const OFFICE_MARK: &str = "office:";
+
+/// Office Open XML 확장자. (매크로 포함 형식 포함)
+const EXTENSIONS: &[&str] = &["docx", "docm", "xlsx", "xlsm", "pptx", "pptm"];
+
+/// 비교에서 제외하는 파트. 열고 저장하기만 해도 바뀌는 문서 속성입니다.
+/// - core.xml: 마지막 수정자(lastModifiedBy), 수정 시각, 수정 번호(revision)
+/// - app.xml: 총 편집 시간(TotalTime), 저장한 프로그램의 버전
+const IGNORED_PARTS: &[&str] = &["docProps/core.xml", "docProps/app.xml"];
+
+/// 확장자로 보아 Office Open XML 문서인지 확인합니다.
+pub fn is_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(e)))
+}
+
+/// 오피스 문서 그룹이라면 그 파트 해시를 반환합니다.
+pub fn group_hash(hash: &str) -> Option<&str> {
+    hash.strip_prefix(OFFICE_MARK)
+}
+
+/// 문서 속성 파트를 뺀 나머지 파트들의 해시를 계산합니다.
+/// ZIP 파일이 아니거나 손상되었다면 `None`이고, 파일을 읽을 수 없을 때만 `Err`입니다.
+pub fn parts_hash(path: &Path, options: &ScanOptions) -> io::Result<Option<String>> {
+    let mut archive = match ZipArchive::new(BufReader::new(File::open(path)?)) {
+        Ok(archive) => archive,
+        Err(ZipError::Io(e)) => return Err(e),
+        Err(_) => return Ok(None),
+    };
+    // ZIP 안의 순서는 저장한 프로그램마다 다를 수 있으므로 이름순으로 비교합니다.
+    let names: Result<Vec<String>, ZipError> = archive.file_names().map(|name| name.map(String::from)).collect();
+    let Ok(mut names) = names else { return Ok(None) };
+    names.retain(|name| !name.ends_with('/') && !IGNORED_PARTS.contains(&name.as_str()));
+    names.sort();
+    let mut hasher = Hasher::new(options.algorithm);
+    let mut buffer = [0; 8192];
+    for name in names {
+        let mut part = match archive.by_name(&name) {
+            Ok(part) => part,
+            Err(ZipError::Io(e)) => return Err(e),
+            Err(_) => return Ok(None),
+        };
+        // 파트의 경계가 분명하도록 이름과 길이를 내용 앞에 넣습니다.
+        hasher.update(name.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&part.size().to_le_bytes());
+        loop {
+            let read = match part.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                // 압축이 깨진 파트는 읽기 오류로 나타나므로 "비교할 수 없는 문서"로 봅니다.
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            hasher.update(&buffer[..read]);
+        }
+    }
+    Ok(Some(hasher.finalize()))
+}
+
+/// --match office: 파일을 문서 파트 해시로 묶습니다.
+pub const MATCHER: LooseMatcher = LooseMatcher {
+    mark: OFFICE_MARK,
+    stage: "office",
+    progress: "오피스 문서 비교 중",
+    action: "문서 읽기",
+    key: parts_hash,
+};
@@ -0,0 +1,129 @@
+// ==============================================================================
+//  사용자별 할당량 영향 (--by-owner)
+// ------------------------------------------------------------------------------
+//  여러 사용자가 쓰는 서버에서는 디스크 할당량(quota)이 파일 소유자마다 매겨집니다. 정리를
+//  설득하려면 "중복이 몇 GB"보다 "누구의 사용량이 얼마나 줄어드는가"가 필요합니다.
+//
+//  중복 그룹의 멤버를 소유자(uid)별로 모아 다음을 보여줍니다.
+//  - 그 사용자가 가진 중복 파일(원본 포함)의 수와 크기
+//  - 그룹마다 --keep 규칙으로 고른 원본 하나만 남기면(삭제하거나 원본의 하드 링크로 바꾸면) 줄어드는
+//    사용량. 하드 링크는 원본 아이노드의 소유자에게 매겨지므로 두 방법 모두 사본 소유자의 사용량이 줄어듭니다.
+//  - 그중 다른 사용자가 가진 원본과 합쳐지는 사본. 정리 뒤에는 그 사용자의 파일이 아니게 되므로
+//    정리 전에 알려야 할 대상입니다.
+//  크기는 논리 크기로 셉니다. 할당량은 블록 단위로 매겨지므로 작은 파일에서는 약간 다를 수 있습니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::keep::KeepRule;
+use crate::{DuplicateGroup, format_count, format_size, number_format};
+
+/// 사용자 한 명의 집계.
+#[derive(Clone, Copy, Default)]
+struct Row {
+    /// 중복 그룹에 든 이 사용자의 파일(원본 포함)의 수와 크기.
+    files: u64,
+    bytes: u64,
+    /// 원본을 하나만 남기면 사라지는 사본의 수와 크기. (줄어드는 사용량)
+    copies: u64,
+    copy_bytes: u64,
+    /// 그중 원본을 다른 사용자가 가진 사본의 수.
+    other_keeper: u64,
+}
+
+/// 소유자별 집계.
+pub struct OwnerStats {
+    rows: HashMap<u32, Row>,
+    /// 소유자를 읽을 수 없어 뺀 파일 수.
+    unreadable: u64,
+}
+
+impl OwnerStats {
+    /// 중복 그룹을 소유자별로 집계합니다. 심볼릭 링크 그룹은 공간을 차지하지 않으므로 제외합니다.
+    pub fn of(groups: &[DuplicateGroup], keep: &KeepRule) -> Self {
+        let mut rows: HashMap<u32, Row> = HashMap::new();
+        let mut unreadable = 0;
+        for group in groups.iter().filter(|g| g.symlink_target.is_none()) {
+            let keeper = keep.keeper(&group.paths);
+            let keeper_owner = owner(&group.paths[keeper]);
+            for (i, path) in group.paths.iter().enumerate() {
+                let Some(uid) = owner(path) else {
+                    unreadable += 1;
+                    continue;
+                };
+                let row = rows.entry(uid).or_default();
+                row.files += 1;
+                row.bytes += group.size;
+                if i == keeper {
+                    continue;
+                }
+                row.copies += 1;
+                row.copy_bytes += group.size;
+                if keeper_owner != Some(uid) {
+                    row.other_keeper += 1;
+                }
+            }
+        }
+        OwnerStats { rows, unreadable }
+    }
+
+    /// 줄어드는 사용량이 큰 사용자부터 출력합니다.
+    pub fn print(&self) {
+        println!("\n👥 사용자별 할당량 영향 (원본은 --keep 규칙으로 고릅니다)");
+        let mut rows: Vec<(&u32, &Row)> = self.rows.iter().collect();
+        rows.sort_by_key(|(uid, row)| (std::cmp::Reverse(row.copy_bytes), std::cmp::Reverse(row.bytes), **uid));
+        for (uid, row) in rows {
+            let share = if row.bytes == 0 { 0.0 } else { row.copy_bytes as f64 / row.bytes as f64 };
+            println!("  {} (uid {})", user_name(*uid).unwrap_or_else(|| String::from("?")), uid);
+            println!("      중복 파일 {}개 ({})", format_count(row.files), format_size(row.bytes));
+            println!(
+                "      정리하면 사용량 {} 감소 (사본 {}개, 중복 파일 크기의 {}%), 그중 다른 사용자의 원본과 합쳐지는 사본 {}개",
+                format_size(row.copy_bytes),
+                format_count(row.copies),
+                number_format::format().decimal(share * 100.0),
+                format_count(row.other_keeper)
+            );
+        }
+        let total: u64 = self.rows.values().map(|row| row.copy_bytes).sum();
+        println!("  합계: 모든 사용자의 사용량 {} 감소", format_size(total));
+        if self.unreadable > 0 {
+            println!("  (소유자를 읽을 수 없어 뺀 파일 {}개)", format_count(self.unreadable));
+        }
+    }
+}
+
+/// 파일 소유자의 uid. 심볼릭 링크는 링크가 아니라 대상 파일의 소유자를 봅니다.
+#[cfg(unix)]
+fn owner(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|metadata| metadata.uid())
+}
+
+/// 유닉스가 아닌 플랫폼. `main`에서 --by-owner를 미리 거부하므로 호출되지 않습니다.
+#[cfg(not(unix))]
+fn owner(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// uid의 사용자 이름 (시스템 사용자 데이터베이스). 없으면 `None`.
+#[cfg(unix)]
+fn user_name(uid: u32) -> Option<String> {
+    use std::ffi::CStr;
+
+    let mut buffer = vec![0 as libc::c_char; 4096];
+    let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    // SAFETY: 쓰기 가능한 passwd 구조체와 버퍼를 넘기며, 성공하면 이름은 버퍼 안의 C 문자열을 가리킵니다.
+    let status = unsafe { libc::getpwuid_r(uid, &mut entry, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+    if status != 0 || result.is_null() {
+        return None;
+    }
+    // SAFETY: 위에서 성공을 확인했으므로 pw_name은 NUL로 끝나는 문자열입니다.
+    Some(unsafe { CStr::from_ptr(entry.pw_name) }.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn user_name(_uid: u32) -> Option<String> {
+    None
+}
@@ -0,0 +1,121 @@
+// ==============================================================================
+//  출력 경로 형식 (--absolute, --relative-to)
+// ------------------------------------------------------------------------------
+//  검색 결과의 경로는 루트 폴더를 입력한 그대로 이어 붙인 것이라, `-r .`로 검색하면 `./a.txt`,
+//  `-r ../data`로 검색하면 `../data/a.txt`처럼 실행한 폴더에 따라 뜻이 달라집니다. 결과를 다른
+//  폴더에서 실행하는 스크립트에 넘기면 경로가 어디를 가리키는지 알 수 없습니다.
+//
+//  - --absolute: 모든 경로를 현재 폴더 기준의 절대 경로로 씁니다.
+//  - --relative-to BASE: 모든 경로를 BASE 폴더 기준의 상대 경로로 씁니다. (`../other/a.txt` 등)
+//  지정하지 않으면 지금처럼 찾은 그대로 씁니다.
+//
+//  적용 대상은 결과 목록, 오류 요약, 결과 파일(--save-results), 로그 파일(--log-file)의 경로입니다.
+//  파일 시스템에 접근하지 않고 경로상에서만 `.`과 `..`을 정리하므로 심볼릭 링크는 풀지 않습니다.
+//  검색과 정리 작업은 계속 원래 경로로 하며, 출력할 때만 형식을 바꿉니다.
+// ==============================================================================
+
+use std::borrow::Cow;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::resolve_link_target;
+
+/// 출력 경로 형식.
+#[derive(Clone, Debug, Default)]
+pub enum PathStyle {
+    /// 찾은 그대로.
+    #[default]
+    AsFound,
+    /// 절대 경로.
+    Absolute,
+    /// 이 폴더(절대 경로) 기준의 상대 경로.
+    RelativeTo(PathBuf),
+}
+
+impl PathStyle {
+    /// --absolute, --relative-to 값으로 형식을 정합니다. BASE는 있는 폴더여야 합니다.
+    pub fn new(absolute: bool, relative_to: Option<&Path>) -> io::Result<Self> {
+        match relative_to {
+            Some(base) if base.is_dir() => Ok(PathStyle::RelativeTo(absolute_path(base))),
+            Some(base) => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("--relative-to의 '{}'은(는) 접근 가능한 폴더가 아닙니다", base.display()),
+            )),
+            None if absolute => Ok(PathStyle::Absolute),
+            None => Ok(PathStyle::AsFound),
+        }
+    }
+
+    /// 상대 경로 출력의 기준 폴더.
+    pub fn base(&self) -> Option<&Path> {
+        match self {
+            PathStyle::RelativeTo(base) => Some(base),
+            _ => None,
+        }
+    }
+}
+
+/// 실행 중 쓰는 출력 경로 형식. 시작할 때 한 번 정합니다.
+static STYLE: OnceLock<PathStyle> = OnceLock::new();
+
+/// 출력 경로 형식을 정합니다. 두 번째부터는 무시합니다.
+pub fn set_style(style: PathStyle) {
+    let _ = STYLE.set(style);
+}
+
+/// 현재 출력 경로 형식.
+pub fn style() -> &'static PathStyle {
+    STYLE.get_or_init(PathStyle::default)
+}
+
+/// 경로를 출력할 형식으로 바꿉니다.
+pub fn shown(path: &Path) -> Cow<'_, Path> {
+    match style() {
+        PathStyle::AsFound => Cow::Borrowed(path),
+        PathStyle::Absolute => Cow::Owned(absolute_path(path)),
+        // 드라이브가 달라 상대 경로로 나타낼 수 없다면(Windows) 절대 경로로 씁니다.
+        PathStyle::RelativeTo(base) => {
+            let path = absolute_path(path);
+            Cow::Owned(relative(&path, base).unwrap_or(path))
+        }
+    }
+}
+
+/// 경로를 출력할 형식의 문자열로 바꿉니다.
+pub fn display(path: &Path) -> String {
+    shown(path).display().to_string()
+}
+
+/// 현재 폴더 기준의 절대 경로. `.`과 `..`은 경로상에서 정리합니다.
+fn absolute_path(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    // 절대 경로를 대상으로 주면 링크 위치와 관계없이 대상 경로를 정리만 합니다.
+    resolve_link_target(Path::new(""), &path)
+}
+
+/// `base`(절대 경로) 기준의 상대 경로. 공통된 앞부분이 없으면 `None`.
+fn relative(path: &Path, base: &Path) -> Option<PathBuf> {
+    let mut path_parts = path.components().peekable();
+    let mut base_parts = base.components().peekable();
+    while let (Some(a), Some(b)) = (path_parts.peek(), base_parts.peek())
+        && a == b
+    {
+        path_parts.next();
+        base_parts.next();
+    }
+    let mut relative = PathBuf::new();
+    for part in base_parts {
+        match part {
+            Component::Normal(_) => relative.push(".."),
+            Component::CurDir => {}
+            // 루트나 드라이브부터 다르다면 상대 경로로 나타낼 수 없습니다.
+            _ => return None,
+        }
+    }
+    relative.extend(path_parts);
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    Some(relative)
+}
@@ -0,0 +1,113 @@
+// ==============================================================================
+//  PDF 내용 비교: 페이지 내용만 비교하기 (--match pdf)
+// ------------------------------------------------------------------------------
+//  같은 보고서를 다시 생성한 PDF는 내용이 같아도 바이트가 같은 일이 거의 없습니다.
+//  - 트레일러의 문서 ID(/ID)와 정보 사전의 생성/수정 시각(/CreationDate, /ModDate)이 매번 바뀝니다.
+//  - 객체 번호, 압축 결과, 교차 참조 표의 위치가 생성기마다 다릅니다.
+//  - 증분 저장(incremental save)한 파일은 이전 버전의 객체가 파일 뒤에 그대로 남아 있습니다.
+//
+//  --match pdf는 평소처럼 내용 해시로 중복을 찾은 뒤, PDF마다 현재 유효한 페이지를 순서대로 따라가며
+//  페이지 내용 스트림과 그 페이지가 쓰는 XObject(이미지, 폼) 스트림을 압축을 풀어 해시합니다.
+//  트레일러, 정보 사전, 메타데이터 스트림, 더 이상 참조되지 않는 옛 객체는 해시에 들어가지 않으므로
+//  위와 같은 차이만 있는 PDF는 같은 해시를 갖게 됩니다.
+//  그룹의 `hash` 자리에는 `pdf:`로 시작하는 표시와 이 해시를 넣어 내용 그룹과 구분합니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+
+use crate::hashing::Hasher;
+use crate::{LooseMatcher, ScanOptions};
+
+/// PDF 그룹의 `hash` 앞에 붙는 표시. 뒤에는 페이지 내용의 해시가 이어집니다.
+const PDF_MARK: &str = "pdf:";
+
+/// 스트림 하나를 풀었을 때 허용하는 최대 크기. 작은 압축 스트림이 끝없이 부풀어 오르는
+/// 압축 폭탄으로부터 메모리를 지킵니다.
+const MAX_STREAM_BYTES: usize = 256 * 1024 * 1024;
+
+/// 확장자로 보아 PDF 파일인지 확인합니다.
+pub fn is_pdf(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+}
+
+/// PDF 그룹이라면 그 페이지 내용 해시를 반환합니다.
+pub fn group_hash(hash: &str) -> Option<&str> {
+    hash.strip_prefix(PDF_MARK)
+}
+
+/// 페이지 내용 스트림과 XObject 스트림의 해시를 계산합니다.
+/// PDF로 읽을 수 없거나(손상, 암호화 등) 스트림이 너무 크면 `None`이고, 파일을 읽을 수 없을 때만 `Err`입니다.
+pub fn content_hash(path: &Path, options: &ScanOptions) -> io::Result<Option<String>> {
+    let document = match Document::load(path) {
+        Ok(document) => document,
+        Err(lopdf::Error::IO(e)) => return Err(e),
+        Err(_) => return Ok(None),
+    };
+    let mut hasher = Hasher::new(options.algorithm);
+    // `get_pages`는 페이지 트리를 따라 현재 유효한 페이지만 페이지 번호순으로 돌려줍니다.
+    for (number, page_id) in document.get_pages() {
+        let Ok(content) = document.get_page_content_with_limit(page_id, MAX_STREAM_BYTES) else { return Ok(None) };
+        // 페이지와 스트림의 경계가 분명하도록 번호, 이름과 길이를 내용 앞에 넣습니다.
+        hasher.update(&number.to_le_bytes());
+        hasher.update(&(content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+        let Some(xobjects) = page_xobjects(&document, page_id) else { return Ok(None) };
+        for (name, data) in xobjects {
+            hasher.update(&name);
+            hasher.update(&[0]);
+            hasher.update(&(data.len() as u64).to_le_bytes());
+            hasher.update(&data);
+        }
+    }
+    Ok(Some(hasher.finalize()))
+}
+
+/// 페이지(와 상위 페이지 트리에서 물려받은) 리소스의 XObject들을 (이름, 압축을 푼 내용)으로
+/// 이름순으로 모읍니다. 스트림이 너무 크면 `None`입니다.
+fn page_xobjects(document: &Document, page_id: ObjectId) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+    let Ok((inline, inherited)) = document.get_page_resources(page_id) else { return Some(Vec::new()) };
+    let resources: Vec<&Dictionary> =
+        inline.into_iter().chain(inherited.into_iter().filter_map(|id| document.get_dictionary(id).ok())).collect();
+    let mut xobjects: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+    for resource in resources {
+        let Some(dictionary) = resource.get(b"XObject").ok().and_then(|o| dereference(document, o)?.as_dict().ok())
+        else {
+            continue;
+        };
+        for (name, object) in dictionary.iter() {
+            // 가까운 리소스에 있는 이름이 물려받은 리소스의 같은 이름보다 우선합니다.
+            if xobjects.contains_key(name) {
+                continue;
+            }
+            let Some(stream) = dereference(document, object).and_then(|o| o.as_stream().ok()) else { continue };
+            // 압축을 풀 수 없는 형식(JPEG 이미지 등)은 저장된 바이트를 그대로 비교합니다.
+            let data = match stream.decompressed_content_with_limit(MAX_STREAM_BYTES) {
+                Ok(data) => data,
+                Err(lopdf::Error::Decompress(lopdf::DecompressError::MemoryLimitExceeded { .. })) => return None,
+                Err(_) => stream.content.clone(),
+            };
+            xobjects.insert(name.clone(), data);
+        }
+    }
+    let mut xobjects: Vec<(Vec<u8>, Vec<u8>)> = xobjects.into_iter().collect();
+    xobjects.sort();
+    Some(xobjects)
+}
+
+/// 간접 참조라면 가리키는 객체를, 아니면 객체 자체를 반환합니다.
+fn dereference<'a>(document: &'a Document, object: &'a Object) -> Option<&'a Object> {
+    document.dereference(object).ok().map(|(_, object)| object)
+}
+
+/// --match pdf: 파일을 페이지 내용 해시로 묶습니다.
+pub const MATCHER: LooseMatcher = LooseMatcher {
+    mark: PDF_MARK,
+    stage: "pdf",
+    progress: "PDF 비교 중",
+    action: "PDF 읽기",
+    key: content_hash,
+};
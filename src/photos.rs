@@ -0,0 +1,84 @@
+// ==============================================================================
+//  사진 라이브러리 모드: EXIF 촬영 정보로 묶기 (--match exif)
+// ------------------------------------------------------------------------------
+//  사진 관리 프로그램에서 사진을 "내보내기"하면, 같은 사진이 다시 인코딩되거나 메타데이터가
+//  조금 바뀌어 바이트가 다른 파일로 저장됩니다. 내용 해시로는 이런 사본을 찾을 수 없습니다.
+//
+//  --match exif는 평소처럼 내용 해시로 중복을 찾은 뒤, 사진 파일의 EXIF에서
+//  (촬영 시각, 카메라 모델, 가로×세로 픽셀 수)를 읽어 이 값이 모두 같은 사진들을 한 그룹으로 더 묶습니다.
+//  - 촬영 시각(DateTimeOriginal)이 없는 사진은 묶지 않습니다. (편집 프로그램이 만든 그림 등이
+//    우연히 같은 값으로 묶이지 않도록)
+//  - 연속 촬영한 사진을 구분하기 위해, 1초 미만 단위(SubSecTimeOriginal)가 있으면 함께 비교합니다.
+//  - 모든 멤버가 이미 한 내용 그룹에 들어 있는 EXIF 그룹은 새로운 정보가 없으므로 보고하지 않습니다.
+//    (`beyond_content_groups`)
+//
+//  EXIF 그룹은 내용이 같다는 보장이 없는 "추정" 결과이므로, `hash` 자리에 `exif:`로 시작하는
+//  표시와 촬영 정보를 넣어 내용 그룹과 구분합니다.
+// ==============================================================================
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use exif::{In, Tag, Value};
+
+use crate::LooseMatcher;
+
+/// EXIF 그룹의 `hash` 앞에 붙는 표시. 뒤에는 촬영 정보 키가 이어집니다.
+const EXIF_MARK: &str = "exif:";
+
+/// EXIF를 읽어 볼 확장자. (JPEG, TIFF 계열 RAW, HEIF, PNG, WebP)
+const EXTENSIONS: &[&str] =
+    &["jpg", "jpeg", "jpe", "tif", "tiff", "dng", "cr2", "nef", "arw", "heic", "heif", "avif", "png", "webp"];
+
+/// 확장자로 보아 EXIF를 담고 있을 수 있는 사진 파일인지 확인합니다.
+pub fn is_photo(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(e)))
+}
+
+/// EXIF 그룹이라면 그 촬영 정보 키를 반환합니다.
+pub fn group_key(hash: &str) -> Option<&str> {
+    hash.strip_prefix(EXIF_MARK)
+}
+
+/// 사진의 촬영 정보 키 `촬영 시각|카메라 모델|가로x세로`를 만듭니다.
+/// EXIF가 없거나 촬영 시각이 없으면 `None`이고, 파일을 읽을 수 없을 때만 `Err`입니다.
+pub fn capture_key(path: &Path) -> io::Result<Option<String>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let exif = match exif::Reader::new().read_from_container(&mut reader) {
+        Ok(exif) => exif,
+        Err(exif::Error::Io(e)) => return Err(e),
+        // EXIF가 없거나 형식을 알 수 없는 파일은 오류가 아니라 "묶을 정보가 없음"입니다.
+        Err(_) => return Ok(None),
+    };
+    let text = |tag| exif.get_field(tag, In::PRIMARY).and_then(|f| ascii(&f.value));
+    let number = |tag| exif.get_field(tag, In::PRIMARY).and_then(|f| f.value.get_uint(0));
+    let Some(mut taken) = text(Tag::DateTimeOriginal) else { return Ok(None) };
+    if let Some(subsec) = text(Tag::SubSecTimeOriginal) {
+        taken = format!("{}.{}", taken, subsec);
+    }
+    let model = text(Tag::Model).unwrap_or_default();
+    // 원본 크기는 Exif IFD의 PixelX/YDimension에, 없으면 TIFF의 ImageWidth/Length에 있습니다.
+    let width = number(Tag::PixelXDimension).or_else(|| number(Tag::ImageWidth)).unwrap_or(0);
+    let height = number(Tag::PixelYDimension).or_else(|| number(Tag::ImageLength)).unwrap_or(0);
+    Ok(Some(format!("{}|{}|{}x{}", taken, model, width, height)))
+}
+
+/// ASCII 값의 첫 문자열. 끝의 공백과 NUL은 잘라냅니다. (카메라마다 채우는 방식이 다릅니다)
+fn ascii(value: &Value) -> Option<String> {
+    let Value::Ascii(strings) = value else { return None };
+    let text = String::from_utf8_lossy(strings.first()?);
+    let text = text.trim_end_matches(['\0', ' ']);
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// --match exif: 파일을 촬영 정보로 묶습니다.
+pub const MATCHER: LooseMatcher = LooseMatcher {
+    mark: EXIF_MARK,
+    stage: "exif",
+    progress: "EXIF 읽는 중",
+    action: "EXIF 읽기",
+    key: |path, _| capture_key(path),
+};
@@ -0,0 +1,61 @@
+// ==============================================================================
+//  클라우드 온라인 전용 파일 감지 (--hydrate)
+// ------------------------------------------------------------------------------
+//  OneDrive, iCloud Drive, Dropbox 같은 동기화 폴더에는 내용이 로컬에 없고 자리만 차지하는
+//  "온라인 전용" 파일이 있습니다. 크기와 수정 시간은 진짜 파일과 같아 보이지만, 해시하려고 여는 순간
+//  운영체제가 파일 전체를 내려받으므로 검색 한 번에 수 GB를 내려받거나, 오프라인이면 오류가 납니다.
+//
+//  탐색할 때 메타데이터(내용을 읽지 않는)만으로 아래 표시를 확인하여, 기본으로는 건너뛰고 그 수를 알려줍니다.
+//  - Windows: 파일 특성의 RECALL_ON_DATA_ACCESS, RECALL_ON_OPEN(클라우드 파일 API의 자리 표시자),
+//    OFFLINE(계층형 저장소에 옮겨진 파일).
+//  - macOS: 파일 플래그의 SF_DATALESS(iCloud Drive, File Provider 기반 OneDrive/Dropbox).
+//  - 모든 플랫폼: 예전 iCloud Drive가 남기는 `.<이름>.icloud` 자리 표시 파일.
+//  --hydrate를 지정하면 건너뛰지 않고 내려받아 해시합니다.
+// ==============================================================================
+
+use std::fs::Metadata;
+use std::path::Path;
+
+/// 파일 특성: 내용을 읽을 때 원격 저장소에서 불러옵니다. (FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+#[cfg(windows)]
+const RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+/// 파일 특성: 열 때 원격 저장소에서 불러옵니다. (FILE_ATTRIBUTE_RECALL_ON_OPEN)
+#[cfg(windows)]
+const RECALL_ON_OPEN: u32 = 0x0004_0000;
+/// 파일 특성: 내용이 오프라인 저장소로 옮겨졌습니다. (FILE_ATTRIBUTE_OFFLINE)
+#[cfg(windows)]
+const OFFLINE: u32 = 0x0000_1000;
+
+/// 파일 플래그: 내용이 로컬에 없습니다. (SF_DATALESS, <sys/stat.h>)
+#[cfg(target_os = "macos")]
+const SF_DATALESS: u32 = 0x4000_0000;
+
+/// 내용을 읽으면 내려받기가 시작되는 온라인 전용 파일인지 확인합니다.
+pub fn is_placeholder(path: &Path, metadata: &Metadata) -> bool {
+    legacy_icloud_stub(path) || dataless(metadata)
+}
+
+/// 예전 iCloud Drive는 내려받지 않은 `사진.jpg`를 작은 `.사진.jpg.icloud` 파일로 남깁니다.
+fn legacy_icloud_stub(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') && name.ends_with(".icloud") && name.len() > ".icloud".len() + 1)
+}
+
+#[cfg(windows)]
+fn dataless(metadata: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    metadata.file_attributes() & (RECALL_ON_DATA_ACCESS | RECALL_ON_OPEN | OFFLINE) != 0
+}
+
+#[cfg(target_os = "macos")]
+fn dataless(metadata: &Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+/// 다른 플랫폼에는 메타데이터로 알 수 있는 표시가 없습니다.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn dataless(_metadata: &Metadata) -> bool {
+    false
+}
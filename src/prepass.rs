@@ -0,0 +1,107 @@
+// ==============================================================================
+//  거대한 폴더 트리를 위한 크기 사전 조사 (--bloom-prepass)
+// ------------------------------------------------------------------------------
+//  기본 스캔은 발견한 모든 파일의 경로를 크기별로 메모리에 모아 둡니다(`files_by_size`).
+//  파일이 수억 개라면 대부분이 크기가 유일하여 어차피 비교 대상이 아닌 파일인데도, 그 경로들이
+//  수십 GB의 메모리를 차지합니다.
+//
+//  --bloom-prepass는 탐색을 두 번 합니다.
+//  1. 첫 번째 탐색은 경로를 기억하지 않고, 파일 크기만 고정 크기의 블룸 필터 두 개에 기록합니다.
+//     - `once`: 한 번 이상 본 크기.
+//     - `twice`: `once`에 이미 있던 크기를 다시 만났을 때 기록 = 두 번 이상 본 크기.
+//  2. 두 번째 탐색에서는 `twice`에 있는(크기가 반복되는) 파일만 크기 그룹에 넣습니다.
+//
+//  블룸 필터는 "없다"는 답은 항상 정확하고 "있다"는 답만 가끔 틀리므로, 크기가 반복되는 파일을
+//  놓치는 일은 없습니다. 가끔 크기가 유일한 파일이 후보에 섞이지만, 그런 파일은 크기 그룹에서
+//  혼자 남아 해시 계산 전에 걸러집니다. 대신 폴더를 두 번 읽으므로 탐색 시간은 늘어납니다.
+// ==============================================================================
+
+use std::path::PathBuf;
+
+use walkdir::WalkDir;
+
+use crate::{Progress, ScanOptions, interrupt, passes_created, passes_filter, special_file_kind};
+
+/// 크기 하나를 기록할 때 세우는 비트 수.
+const HASHES: u64 = 4;
+
+/// 고정된 메모리 안에서 "두 번 이상 본 파일 크기"를 기억하는 스케치.
+pub struct SizeSketch {
+    once: Vec<u64>,
+    twice: Vec<u64>,
+    /// 필터 하나의 비트 수.
+    bits: u64,
+}
+
+impl SizeSketch {
+    /// 두 필터를 합쳐 `memory` 바이트를 쓰는 스케치를 만듭니다.
+    pub fn new(memory: usize) -> Self {
+        let words = (memory / 16).max(1);
+        SizeSketch { once: vec![0; words], twice: vec![0; words], bits: words as u64 * 64 }
+    }
+
+    pub fn insert(&mut self, size: u64) {
+        if contains(&self.once, self.bits, size) {
+            set(&mut self.twice, self.bits, size);
+        } else {
+            set(&mut self.once, self.bits, size);
+        }
+    }
+
+    /// 이 크기의 파일을 두 번 이상 보았을 수 있다면 `true`. `false`라면 확실히 한 번뿐입니다.
+    pub fn repeated(&self, size: u64) -> bool {
+        contains(&self.twice, self.bits, size)
+    }
+}
+
+/// 크기 하나에 대응하는 비트 위치들. (두 해시 값을 섞어 여러 위치를 만드는 이중 해싱)
+fn positions(bits: u64, size: u64) -> impl Iterator<Item = u64> {
+    let h1 = mix(size);
+    let h2 = mix(h1) | 1;
+    (0..HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bits)
+}
+
+fn set(filter: &mut [u64], bits: u64, size: u64) {
+    for bit in positions(bits, size) {
+        filter[(bit / 64) as usize] |= 1 << (bit % 64);
+    }
+}
+
+fn contains(filter: &[u64], bits: u64, size: u64) -> bool {
+    positions(bits, size).all(|bit| filter[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+}
+
+/// splitmix64의 섞기 단계. 비슷한 크기들도 서로 먼 비트에 흩어지게 합니다.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// 첫 번째 탐색: 검색 대상 파일의 크기를 스케치에 기록합니다. 경로와 오류는 기억하지 않으며,
+/// 읽을 수 없는 항목은 두 번째 탐색에서 오류로 기록됩니다. 중단 요청을 받으면 그대로 멈춥니다.
+pub fn size_sketch(roots: &[PathBuf], options: &ScanOptions, memory: usize, progress: &mut Progress) -> SizeSketch {
+    let mut sketch = SizeSketch::new(memory);
+    let mut files = 0u64;
+    for entry in roots.iter().flat_map(WalkDir::new).filter_map(Result::ok) {
+        if interrupt::requested() {
+            break;
+        }
+        // 본 탐색과 같은 기준으로 파일을 고릅니다. (심볼릭 링크는 크기로 비교하지 않습니다)
+        let file_type = entry.file_type();
+        if !file_type.is_file() || special_file_kind(file_type).is_some() || !passes_filter(entry.path(), options) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !passes_created(&metadata, options) {
+            continue;
+        }
+        if metadata.len() > 0 {
+            sketch.insert(metadata.len());
+        }
+        files += 1;
+        progress.update("크기 사전 조사 중", files, None);
+    }
+    sketch
+}
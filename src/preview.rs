@@ -0,0 +1,277 @@
+// ==============================================================================
+//  TUI 이미지 미리보기 (--preview)
+// ------------------------------------------------------------------------------
+//  어떤 사진을 남길지 고를 때 파일 관리자로 옮겨 가지 않아도 되도록, 선택한 그룹의 이미지
+//  멤버들을 터미널 그래픽 프로토콜로 TUI 안에 작게 그려 줍니다.
+//
+//  - kitty:  kitty 그래픽 프로토콜 (kitty, Ghostty, WezTerm 등). RGBA 픽셀을 그대로 보냅니다.
+//  - iterm2: iTerm2 인라인 이미지 프로토콜 (iTerm2, WezTerm 등). PNG로 다시 인코딩하여 보냅니다.
+//  - sixel:  DEC sixel (foot, mlterm, xterm -ti vt340 등). 6x6x6 고정 팔레트로 줄여 보냅니다.
+//
+//  `auto`(기본)는 환경 변수(TERM, TERM_PROGRAM, KITTY_WINDOW_ID 등)로 터미널을 알아봅니다.
+//  tmux 같은 멀티플렉서 안에서는 이미지가 그대로 전달되지 않으므로 `auto`는 미리보기를 끕니다.
+//
+//  ratatui는 이미지가 그려진 칸을 빈칸으로 알고 있습니다. kitty는 이미지를 글자 위의 별도
+//  층에 두므로 지우기 명령으로 치우면 되지만, iterm2와 sixel은 칸의 내용을 덮어쓰므로 그릴
+//  이미지가 바뀔 때마다 이전 이미지가 있던 칸을 ratatui가 다시 쓰게 한 뒤 새 이미지를 그립니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, RgbaImage};
+use ratatui::crossterm::cursor::MoveTo;
+use ratatui::crossterm::{queue, terminal};
+use ratatui::layout::Rect;
+
+use crate::PreviewProtocol;
+
+/// 미리보기를 그리는 이미지 확장자 (소문자). `image` 라이브러리로 디코딩할 수 있는 형식입니다.
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "webp"];
+
+/// 디코딩한 이미지를 캐시에 둘 때의 최대 변 길이(픽셀). 큰 사진을 원본 크기로 들고 있지 않습니다.
+const CACHE_EDGE: u32 = 1024;
+
+/// 캐시에 둘 이미지 수. 넘으면 캐시를 비웁니다. (보통 한 그룹의 멤버 수 정도면 충분합니다)
+const CACHE_LIMIT: usize = 16;
+
+/// 픽셀 크기를 알려 주지 않는 터미널에서 가정하는 글자 칸 하나의 크기 (너비, 높이).
+const FALLBACK_CELL: (u32, u32) = (8, 16);
+
+/// kitty 프로토콜에서 한 번에 보내는 base64 조각의 최대 길이.
+const KITTY_CHUNK: usize = 4096;
+
+/// 미리보기 한 칸: 이미지를 그릴 화면 영역과 이미지 파일.
+pub type Slot = (Rect, PathBuf);
+
+/// `auto`를 실제 프로토콜로 바꿉니다. 미리보기를 쓰지 않는다면 `None`.
+pub fn resolve(protocol: PreviewProtocol) -> Option<PreviewProtocol> {
+    match protocol {
+        PreviewProtocol::Off => None,
+        PreviewProtocol::Auto => detect(),
+        explicit => Some(explicit),
+    }
+}
+
+/// 환경 변수로 터미널이 지원하는 그래픽 프로토콜을 알아봅니다.
+fn detect() -> Option<PreviewProtocol> {
+    if env::var_os("TMUX").is_some() || env::var("TERM").is_ok_and(|t| t.starts_with("screen")) {
+        return None;
+    }
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    if env::var_os("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || term == "xterm-ghostty" || program == "ghostty" {
+        Some(PreviewProtocol::Kitty)
+    } else if program == "iTerm.app" || program == "WezTerm" || env::var("LC_TERMINAL").is_ok_and(|t| t == "iTerm2") {
+        Some(PreviewProtocol::Iterm2)
+    } else if term.starts_with("foot") || term.starts_with("mlterm") || term.contains("sixel") {
+        Some(PreviewProtocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// 확장자로 미리보기를 그릴 수 있는 이미지인지 판단합니다.
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// 이미지를 디코딩해 두고, 화면의 미리보기 칸에 그리는 상태.
+pub struct Previewer {
+    protocol: PreviewProtocol,
+    /// 경로 -> 줄여 둔 이미지. 디코딩하지 못한 파일은 `None`으로 기억하여 다시 시도하지 않습니다.
+    cache: HashMap<PathBuf, Option<RgbaImage>>,
+    /// 마지막으로 그린 칸들. 같은 칸을 다시 그리지 않기 위해 기억합니다.
+    painted: Vec<Slot>,
+}
+
+impl Previewer {
+    /// `protocol`은 `resolve`로 정한 실제 프로토콜이어야 합니다.
+    pub fn new(protocol: PreviewProtocol) -> Self {
+        Previewer { protocol, cache: HashMap::new(), painted: Vec::new() }
+    }
+
+    /// 이미지를 읽어 둡니다. 디코딩할 수 없다면 `false`.
+    pub fn load(&mut self, path: &Path) -> bool {
+        if !self.cache.contains_key(path) {
+            if self.cache.len() >= CACHE_LIMIT {
+                self.cache.clear();
+            }
+            let image = image::open(path).ok().map(|image| {
+                let image = image.to_rgba8();
+                let (width, height) = fit(image.dimensions(), (CACHE_EDGE, CACHE_EDGE));
+                if (width, height) == image.dimensions() {
+                    image
+                } else {
+                    imageops::resize(&image, width, height, FilterType::Triangle)
+                }
+            });
+            self.cache.insert(path.to_path_buf(), image);
+        }
+        self.cache[path].is_some()
+    }
+
+    /// 마지막으로 그린 칸들.
+    pub fn painted(&self) -> &[Slot] {
+        &self.painted
+    }
+
+    /// 이미지가 글자 칸을 덮어써서, 바꾸기 전에 이전 칸을 다시 써야 하는 프로토콜인지 여부.
+    pub fn overwrites_cells(&self) -> bool {
+        self.protocol != PreviewProtocol::Kitty
+    }
+
+    /// 이전에 그린 이미지를 치우고 `slots`에 새 이미지를 그립니다.
+    pub fn paint(&mut self, out: &mut impl Write, slots: &[Slot]) -> io::Result<()> {
+        self.clear(out)?;
+        let cell = cell_size();
+        for (area, path) in slots {
+            let Some(Some(image)) = self.cache.get(path) else { continue };
+            let (width, height) = fit(image.dimensions(), (u32::from(area.width) * cell.0, u32::from(area.height) * cell.1));
+            if width == 0 || height == 0 {
+                continue;
+            }
+            let image = imageops::resize(image, width, height, FilterType::Triangle);
+            let sequence = match self.protocol {
+                PreviewProtocol::Kitty => kitty(&image),
+                PreviewProtocol::Iterm2 => iterm2(&image)?,
+                _ => sixel(&image),
+            };
+            queue!(out, MoveTo(area.x, area.y))?;
+            out.write_all(sequence.as_bytes())?;
+        }
+        out.flush()?;
+        self.painted = slots.to_vec();
+        Ok(())
+    }
+
+    /// kitty에서 그려 둔 이미지를 모두 지웁니다. 다른 프로토콜은 화면을 다시 그리면 지워집니다.
+    pub fn clear(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.protocol == PreviewProtocol::Kitty && !self.painted.is_empty() {
+            out.write_all(b"\x1b_Ga=d,q=2\x1b\\")?;
+            out.flush()?;
+        }
+        self.painted.clear();
+        Ok(())
+    }
+}
+
+/// 비율을 유지하면서 `bounds` 안에 들어가는 크기. 원본보다 키우지는 않습니다.
+fn fit((width, height): (u32, u32), (max_width, max_height): (u32, u32)) -> (u32, u32) {
+    if width <= max_width && height <= max_height {
+        return (width, height);
+    }
+    let scale = f64::min(f64::from(max_width) / f64::from(width), f64::from(max_height) / f64::from(height));
+    ((f64::from(width) * scale) as u32, (f64::from(height) * scale) as u32)
+}
+
+/// 글자 칸 하나의 픽셀 크기. 터미널이 알려 주지 않으면 흔한 값으로 가정합니다.
+fn cell_size() -> (u32, u32) {
+    match terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            u32::from(size.width / size.columns).max(1),
+            u32::from(size.height / size.rows).max(1),
+        ),
+        _ => FALLBACK_CELL,
+    }
+}
+
+/// kitty: RGBA 픽셀(f=32)을 base64 조각으로 나누어 보냅니다. C=1은 커서를 움직이지 않게, q=2는 응답을 끕니다.
+fn kitty(image: &RgbaImage) -> String {
+    let payload = STANDARD.encode(image.as_raw());
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=32,s={},v={},C=1,q=2,m={};{}\x1b\\", image.width(), image.height(), more, chunk);
+        } else {
+            let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    out
+}
+
+/// iTerm2: PNG 파일 내용을 base64로 보냅니다. 크기는 픽셀로 지정합니다.
+fn iterm2(image: &RgbaImage) -> io::Result<String> {
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).map_err(io::Error::other)?;
+    Ok(format!(
+        "\x1b]1337;File=inline=1;size={};width={}px;height={}px;preserveAspectRatio=1:{}\x07",
+        png.len(),
+        image.width(),
+        image.height(),
+        STANDARD.encode(&png)
+    ))
+}
+
+/// sixel: 색을 6x6x6 고정 팔레트로 줄이고, 6줄씩 묶은 띠마다 색별로 한 번씩 그립니다.
+/// 투명한 픽셀은 검은 배경과 섞습니다.
+fn sixel(image: &RgbaImage) -> String {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let level = |c: u8, a: u8| ((u32::from(c) * u32::from(a) / 255 * 5 + 127) / 255) as u8;
+    let colors: Vec<u8> = image
+        .pixels()
+        .map(|p| level(p[0], p[3]) * 36 + level(p[1], p[3]) * 6 + level(p[2], p[3]))
+        .collect();
+
+    let mut out = format!("\x1bP0;1q\"1;1;{};{}", width, height);
+    for color in 0..216u32 {
+        let _ = write!(out, "#{};2;{};{};{}", color, color / 36 * 20, color / 6 % 6 * 20, color % 6 * 20);
+    }
+    for top in (0..height).step_by(6) {
+        let rows = (height - top).min(6);
+        let band = &colors[top * width..(top + rows) * width];
+        let mut used = [false; 216];
+        for &color in band {
+            used[usize::from(color)] = true;
+        }
+        let mut first = true;
+        for color in (0..216u8).filter(|&c| used[usize::from(c)]) {
+            if !first {
+                // `$`: 같은 띠의 처음으로 돌아가 다음 색을 겹쳐 그립니다.
+                out.push('$');
+            }
+            first = false;
+            let _ = write!(out, "#{}", color);
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..width {
+                let bits = (0..rows).filter(|&dy| band[dy * width + x] == color).fold(0u8, |bits, dy| bits | (1 << dy));
+                let symbol = char::from(63 + bits);
+                run = match run {
+                    Some((previous, count)) if previous == symbol => Some((symbol, count + 1)),
+                    Some((previous, count)) => {
+                        push_run(&mut out, previous, count);
+                        Some((symbol, 1))
+                    }
+                    None => Some((symbol, 1)),
+                };
+            }
+            if let Some((symbol, count)) = run {
+                push_run(&mut out, symbol, count);
+            }
+        }
+        // `-`: 다음 띠로 내려갑니다.
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// 같은 sixel 문자가 반복되면 `!<횟수><문자>`로 줄여 씁니다.
+fn push_run(out: &mut String, symbol: char, count: usize) {
+    if count > 3 {
+        let _ = write!(out, "!{}{}", count, symbol);
+    } else {
+        out.extend(std::iter::repeat_n(symbol, count));
+    }
+}
@@ -0,0 +1,140 @@
+// ==============================================================================
+//  단계별 소요 시간 측정 (--profile)
+// ------------------------------------------------------------------------------
+//  검색이 몇 시간씩 걸릴 때는 "어느 단계가, 어떤 파일이" 느린지 알아야 대책을 세울 수
+//  있습니다. (느린 원격 마운트의 폴더 탐색인지, 거대한 파일 몇 개의 해시인지 등)
+//  스캔하는 동안 다음 시간을 모아 두었다가 마지막에 출력합니다.
+//
+//  - 폴더 탐색: 폴더 항목을 읽는 데(readdir) 걸린 시간.
+//  - 메타데이터 조회: 파일마다 크기와 수정 시간을 읽는 데(stat) 걸린 시간.
+//  - 해시 계산: 해시 단계의 실제 경과 시간과, 스레드들이 파일을 읽은 시간의 합계.
+//  - 그룹 비교: 해시가 같은 파일을 그룹으로 묶고 정렬하는 데 걸린 시간.
+//  - 가장 오래 걸린 파일: 해시 계산 시간이 가장 긴 파일들.
+//  - 해시 구현: 알고리즘마다 실제로 쓴 구현 (SHA-NI, AVX2, 다중 스레드 등, --hash-backend).
+// ==============================================================================
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::{format_size, number_format, path_style};
+
+/// 출력할 가장 느린 파일의 수.
+const SLOWEST_FILES: usize = 10;
+
+/// --profile의 출력 형식.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// 사람이 읽는 표.
+    #[default]
+    Text,
+    /// 다른 도구로 분석할 수 있는 JSON 객체. 결과 출력과 섞이지 않도록 표준 오류에 출력합니다.
+    Json,
+}
+
+/// 스캔 한 번의 단계별 소요 시간.
+#[derive(Clone, Default)]
+pub struct Profile {
+    /// 폴더 탐색(readdir)에 걸린 시간. 탐색 단계 전체 시간에서 메타데이터 조회 시간을 뺀 값입니다.
+    pub walk: Duration,
+    /// 파일 메타데이터 조회(stat)에 걸린 시간의 합.
+    pub stat: Duration,
+    /// 해시 단계의 실제 경과 시간.
+    pub hash: Duration,
+    /// 각 파일의 해시 계산 시간의 합. 여러 스레드가 동시에 읽으므로 `hash`보다 클 수 있습니다.
+    pub hash_total: Duration,
+    /// 해시를 새로 계산한(캐시를 쓰지 않은) 바이트 수.
+    pub bytes_hashed: u64,
+    /// 그룹을 묶고 정렬하는 데 걸린 시간.
+    pub compare: Duration,
+    /// 알고리즘마다 쓴 해시 구현 (알고리즘 이름, 설명).
+    pub hash_backends: Vec<(&'static str, String)>,
+    /// 해시 계산이 가장 오래 걸린 파일들 (경로, 크기, 소요 시간). 오래 걸린 순입니다.
+    slowest: Vec<(PathBuf, u64, Duration)>,
+}
+
+impl Profile {
+    /// 파일 하나의 해시 계산 시간을 기록합니다.
+    pub fn record_file(&mut self, path: &Path, size: u64, elapsed: Duration) {
+        self.hash_total += elapsed;
+        if self.slowest.len() == SLOWEST_FILES && self.slowest.last().is_some_and(|(_, _, d)| *d >= elapsed) {
+            return;
+        }
+        let index = self.slowest.partition_point(|(_, _, d)| *d >= elapsed);
+        self.slowest.insert(index, (path.to_path_buf(), size, elapsed));
+        self.slowest.truncate(SLOWEST_FILES);
+    }
+
+    /// 표 또는 JSON으로 출력합니다.
+    pub fn print(&self, format: ProfileFormat) {
+        match format {
+            ProfileFormat::Text => self.print_text(),
+            ProfileFormat::Json => eprintln!("{}", serde_json::to_string_pretty(&self.to_json()).unwrap_or_default()),
+        }
+    }
+
+    fn print_text(&self) {
+        let total = self.walk + self.stat + self.hash + self.compare;
+        let share = |d: Duration| if total.is_zero() { 0.0 } else { d.as_secs_f64() / total.as_secs_f64() * 100.0 };
+        println!("\n⏱️  단계별 소요 시간");
+        // 한글은 터미널에서 두 칸을 차지하므로 이름 열(16칸)은 공백으로 직접 맞춥니다.
+        for (label, duration) in [
+            ("폴더 탐색       ", self.walk),
+            ("메타데이터 조회 ", self.stat),
+            ("해시 계산       ", self.hash),
+            ("그룹 비교       ", self.compare),
+        ] {
+            println!("  {} {:>10} {:>5}%", label, format_seconds(duration), number_format::format().decimal(share(duration)));
+        }
+        if !self.hash_total.is_zero() {
+            println!(
+                "  (해시: 스레드 합계 {}, 새로 읽은 {}, 스레드당 {}/s)",
+                format_seconds(self.hash_total),
+                format_size(self.bytes_hashed),
+                format_size((self.bytes_hashed as f64 / self.hash_total.as_secs_f64()) as u64)
+            );
+        }
+        for (algorithm, backend) in &self.hash_backends {
+            println!("  (해시 구현: {} – {})", algorithm, backend);
+        }
+        if !self.slowest.is_empty() {
+            println!("\n🐢 해시 계산이 가장 오래 걸린 파일");
+            for (path, size, duration) in &self.slowest {
+                println!("  {:>10} {:>10}  {}", format_seconds(*duration), format_size(*size), path_style::display(path));
+            }
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        json!({
+            "walk_ms": ms(self.walk),
+            "stat_ms": ms(self.stat),
+            "hash_ms": ms(self.hash),
+            "hash_total_ms": ms(self.hash_total),
+            "bytes_hashed": self.bytes_hashed,
+            "compare_ms": ms(self.compare),
+            "hash_backends": self
+                .hash_backends
+                .iter()
+                .map(|(algorithm, backend)| json!({ "algorithm": algorithm, "backend": backend }))
+                .collect::<Vec<_>>(),
+            "slowest_files": self
+                .slowest
+                .iter()
+                .map(|(path, size, duration)| json!({
+                    "path": path_style::display(path),
+                    "size": size,
+                    "duration_ms": ms(*duration),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// 소요 시간을 초 단위로 표시합니다. (예: `12.345초`)
+fn format_seconds(duration: Duration) -> String {
+    format!("{}초", number_format::format().fixed(duration.as_secs_f64(), 3))
+}
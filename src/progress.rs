@@ -0,0 +1,181 @@
+// ==============================================================================
+//  진행 상황 표시
+// ------------------------------------------------------------------------------
+//  오래 걸리는 스캔에서 프로그램이 멈춘 것이 아님을 알 수 있도록, 표준 에러(stderr)의
+//  한 줄을 계속 덮어쓰며 현재 단계와 진행 정도를 표시합니다.
+//  - 사전 추정(--estimate)으로 전체 파일 수를 알고 있다면 "처리한 수 / 전체 수 (비율)"을,
+//    모른다면 처리한 수만 표시합니다.
+//  - 단계마다 바이트 수와 지금 보고 있는 경로를 함께 보여줍니다. 해시 단계에서는 가장 오래 읽고 있는
+//    파일과 그 경과 시간을 보여주므로, 멈춘 것처럼 보이는 검색이 메타데이터 조회에서 막혔는지 큰 파일을
+//    읽는 중인지 구별할 수 있습니다.
+//  - 단계(폴더 탐색, 크기별 묶기, 해시 계산)가 끝나면 그 단계의 결과를 한 줄로 남기고 다음 단계로 넘어갑니다.
+//  - 출력이 파일이나 파이프로 리디렉션된 경우에는 아무 것도 표시하지 않습니다.
+//  - API 서버(serve)처럼 화면 대신 다른 스레드가 진행 상황을 읽어야 할 때는
+//    `Progress::shared`로 공유 스냅샷에 기록합니다.
+// ==============================================================================
+
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{format_count, format_size, number_format};
+
+/// 화면을 너무 자주 갱신하면 오히려 스캔이 느려지므로, 최소 갱신 간격을 둡니다.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 진행 상황 줄에 보여줄 경로의 최대 글자 수. 넘으면 앞부분을 줄입니다.
+const MAX_PATH_CHARS: usize = 60;
+
+/// 한 줄짜리 진행 상황 표시기.
+pub struct Progress {
+    enabled: bool,
+    last_draw: Option<Instant>,
+    /// 진행 상황을 화면 대신(또는 화면과 함께) 기록할 공유 스냅샷.
+    shared: Option<Arc<Mutex<ProgressSnapshot>>>,
+}
+
+/// 가장 최근의 진행 상황. 다른 스레드가 읽을 수 있도록 `Progress::shared`가 기록합니다.
+#[derive(Clone, Default)]
+pub struct ProgressSnapshot {
+    pub stage: String,
+    pub done: u64,
+    pub total: Option<u64>,
+    pub bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub current: Option<String>,
+}
+
+/// 진행 상황 줄에 덧붙이는 세부 정보.
+#[derive(Default)]
+pub struct Detail<'a> {
+    /// 처리한 바이트 수와, 알고 있다면 전체 바이트 수.
+    pub bytes: Option<(u64, Option<u64>)>,
+    /// 지금 보고 있는 경로.
+    pub current: Option<&'a Path>,
+    /// 단계 이름 뒤에 붙일 짧은 설명. (예: 확정된 회수 가능 공간)
+    pub note: Option<String>,
+}
+
+impl Progress {
+    /// 표준 에러가 터미널일 때만 활성화되는 진행 상황 표시기를 만듭니다.
+    pub fn new() -> Self {
+        Progress { enabled: io::stderr().is_terminal(), last_draw: None, shared: None }
+    }
+
+    /// 화면에는 아무 것도 표시하지 않고, 진행 상황을 `snapshot`에만 기록하는 표시기를 만듭니다.
+    pub fn shared(snapshot: Arc<Mutex<ProgressSnapshot>>) -> Self {
+        Progress { enabled: false, last_draw: None, shared: Some(snapshot) }
+    }
+
+    /// 현재 단계(`stage`)에서 `done`개를 처리했음을 표시합니다.
+    /// `total`을 알고 있다면 전체 대비 비율도 함께 표시합니다.
+    pub fn update(&mut self, stage: &str, done: u64, total: Option<u64>) {
+        self.update_detail(stage, done, total, Detail::default());
+    }
+
+    /// `update`와 같지만 바이트 수, 지금 보고 있는 경로 같은 세부 정보도 함께 표시합니다.
+    pub fn update_detail(&mut self, stage: &str, done: u64, total: Option<u64>, detail: Detail) {
+        if let Some(shared) = &self.shared {
+            let mut snapshot = shared.lock().unwrap_or_else(|e| e.into_inner());
+            if snapshot.stage != stage {
+                snapshot.stage = stage.to_string();
+            }
+            snapshot.done = done;
+            snapshot.total = total;
+            snapshot.bytes = detail.bytes.map(|(bytes, _)| bytes);
+            snapshot.total_bytes = detail.bytes.and_then(|(_, total)| total);
+            snapshot.current = detail.current.map(|path| path.display().to_string());
+        }
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        if self.last_draw.is_some_and(|t| now.duration_since(t) < REDRAW_INTERVAL) {
+            return;
+        }
+        self.last_draw = Some(now);
+
+        let stage = match &detail.note {
+            Some(note) => format!("{} ({})", stage, note),
+            None => stage.to_string(),
+        };
+        let mut line = match total {
+            Some(total) if total > 0 => {
+                let percent = (done.min(total) as f64 / total as f64) * 100.0;
+                format!("{}: {} / {} ({}%)", stage, format_count(done), format_count(total), number_format::format().decimal(percent))
+            }
+            _ => format!("{}: {}", stage, format_count(done)),
+        };
+        match detail.bytes {
+            Some((bytes, Some(total))) => line.push_str(&format!(" | {} / {}", format_size(bytes), format_size(total))),
+            Some((bytes, None)) => line.push_str(&format!(" | {}", format_size(bytes))),
+            None => {}
+        }
+        if let Some(path) = detail.current {
+            line.push_str(&format!(" | {}", shorten(path)));
+        }
+        // `\r`로 줄의 처음으로 돌아가고, `\x1b[K`로 이전 내용의 나머지를 지웁니다.
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r{}\x1b[K", line);
+        let _ = stderr.flush();
+    }
+
+    /// 단계가 끝났음을 알립니다. 진행 상황 줄 대신 `summary`를 한 줄로 남기고, 다음 단계의 첫 갱신은
+    /// 간격과 관계없이 바로 그립니다.
+    pub fn stage_done(&mut self, summary: &str) {
+        if self.enabled {
+            let mut stderr = io::stderr().lock();
+            let _ = writeln!(stderr, "\r\x1b[K✔ {}", summary);
+            let _ = stderr.flush();
+        }
+        self.last_draw = None;
+    }
+
+    /// 진행 상황 줄을 지워, 이후의 결과 출력과 섞이지 않게 합니다.
+    pub fn finish(&mut self) {
+        if self.enabled && self.last_draw.take().is_some() {
+            let mut stderr = io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+/// 경로를 진행 상황 줄에 맞게 줄입니다. 파일 이름이 보이도록 앞부분을 `…`로 줄입니다.
+fn shorten(path: &Path) -> String {
+    let text = path.display().to_string();
+    let count = text.chars().count();
+    if count <= MAX_PATH_CHARS {
+        return text;
+    }
+    let tail: String = text.chars().skip(count - (MAX_PATH_CHARS - 1)).collect();
+    format!("…{}", tail)
+}
+
+/// 여러 스레드가 지금 읽고 있는 파일들. 해시 단계의 진행 상황에 가장 오래 읽고 있는 파일을 보여주는 데 씁니다.
+#[derive(Default)]
+pub struct InFlight {
+    files: Mutex<Vec<(PathBuf, u64, Instant)>>,
+}
+
+impl InFlight {
+    /// 파일을 읽기 시작했음을 기록합니다.
+    pub fn start(&self, path: &Path, size: u64) {
+        self.files.lock().unwrap_or_else(|e| e.into_inner()).push((path.to_path_buf(), size, Instant::now()));
+    }
+
+    /// 파일을 다 읽었음을 기록합니다.
+    pub fn end(&self, path: &Path) {
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(i) = files.iter().position(|(p, _, _)| p == path) {
+            files.swap_remove(i);
+        }
+    }
+
+    /// 가장 오래 읽고 있는 파일과 그 크기, 경과 시간.
+    pub fn oldest(&self) -> Option<(PathBuf, u64, Duration)> {
+        let files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        files.iter().min_by_key(|(_, _, started)| *started).map(|(path, size, started)| (path.clone(), *size, started.elapsed()))
+    }
+}
@@ -0,0 +1,184 @@
+// ==============================================================================
+//  검색용 파일 읽기: 접근 시각을 바꾸지 않고 페이지 캐시를 밀어내지 않기
+// ------------------------------------------------------------------------------
+//  운영 중인 서버의 보관 폴더 전체를 검색하면 수백만 개 파일을 한 번씩 읽습니다. 그냥 읽으면
+//  - 파일마다 접근 시각(atime)이 바뀌어 그만큼 메타데이터를 다시 디스크에 쓰고, atime으로 오래 안 쓴
+//    파일을 고르는 정리 스크립트나 계층형 저장소의 판단을 흐트러뜨립니다.
+//  - 한 번만 읽을 파일 데이터가 페이지 캐시를 채워, 서비스가 자주 쓰던 데이터가 캐시에서 밀려납니다.
+//
+//  해시, 샘플 비교, 바이트 비교에서 파일을 열 때 다음을 적용합니다.
+//  - Linux: O_NOATIME으로 엽니다. 파일 소유자가 아니고 CAP_FOWNER도 없으면 커널이 거부하므로(EPERM)
+//    그때는 보통대로 엽니다. 처음부터 끝까지 읽을 파일은 POSIX_FADV_SEQUENTIAL로 미리 읽기를 늘리고,
+//    다 읽어 닫을 때 POSIX_FADV_DONTNEED로 읽은 데이터를 캐시에서 내보냅니다.
+//  - Windows: 처음부터 끝까지 읽을 파일은 FILE_FLAG_SEQUENTIAL_SCAN으로 엽니다.
+//  - 그 밖의 플랫폼에서는 보통대로 엽니다.
+//  DONTNEED는 검색 전부터 캐시에 있던 같은 파일의 데이터도 내보냅니다. 그 파일을 다음에 읽을 때 한 번
+//  디스크에서 읽는 비용으로, 캐시 전체가 검색 데이터로 바뀌는 것을 막습니다.
+//
+//  --direct-io는 해시 단계에서 페이지 캐시를 아예 거치지 않고 읽습니다. (다 읽은 뒤 내보내는 DONTNEED와
+//  달리 읽는 동안에도 캐시를 쓰지 않고, 검색 전부터 캐시에 있던 데이터도 건드리지 않습니다)
+//  - Linux: O_DIRECT로 열고, 정렬된 버퍼에 블록 크기의 배수로 읽습니다. O_DIRECT를 지원하지 않는 파일
+//    시스템(tmpfs 등)의 파일은 위의 방법으로 읽습니다.
+//  - macOS: F_NOCACHE로 캐시에 남기지 않습니다.
+//  - 그 밖의 플랫폼에서는 쓸 수 없다고 알리고 위의 방법으로 읽습니다.
+// ==============================================================================
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// O_DIRECT 읽기의 버퍼 주소와 길이를 맞출 단위. 대부분의 장치의 논리 블록 크기(512 B, 4 KB)의 배수입니다.
+pub const DIRECT_ALIGN: usize = 4096;
+
+/// --direct-io로 한 번에 읽을 크기. 캐시의 미리 읽기가 없으므로 보통보다 크게 읽습니다.
+pub const DIRECT_CHUNK: usize = 1024 * 1024;
+
+/// 해시 단계에서 페이지 캐시를 거치지 않고 읽을지 (--direct-io). 시작할 때 한 번 정합니다.
+static DIRECT_IO: AtomicBool = AtomicBool::new(false);
+
+/// --direct-io를 켭니다.
+pub fn set_direct_io(on: bool) {
+    DIRECT_IO.store(on, Ordering::Relaxed);
+}
+
+/// 이 플랫폼에서 --direct-io를 쓸 수 있는지.
+pub const fn direct_io_supported() -> bool {
+    cfg!(any(target_os = "linux", target_os = "android", target_os = "macos"))
+}
+
+/// 검색용으로 연 파일. 닫을 때 읽은 데이터를 페이지 캐시에서 내보냅니다.
+pub struct ScanFile {
+    file: File,
+    /// O_DIRECT로 열었습니다. 정렬된 버퍼에 블록 크기의 배수로 읽어야 합니다.
+    direct: bool,
+}
+
+impl ScanFile {
+    pub fn is_direct(&self) -> bool {
+        self.direct
+    }
+}
+
+/// 해시할 파일을 엽니다. --direct-io라면 페이지 캐시를 거치지 않도록 엽니다.
+pub fn open_for_hashing(path: &Path) -> io::Result<ScanFile> {
+    if !DIRECT_IO.load(Ordering::Relaxed) {
+        return open(path, true);
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        use std::fs::OpenOptions;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        for flags in [libc::O_DIRECT | libc::O_NOATIME, libc::O_DIRECT] {
+            match OpenOptions::new().read(true).custom_flags(flags).open(path) {
+                Ok(file) => return Ok(ScanFile { file, direct: true }),
+                // O_NOATIME이 거부되면 빼고 다시, O_DIRECT를 지원하지 않는 파일 시스템이면 보통대로 엽니다.
+                Err(e) if matches!(e.raw_os_error(), Some(libc::EPERM | libc::EINVAL)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        open(path, true)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        let file = open(path, true)?;
+        unsafe { libc::fcntl(file.as_raw_fd(), libc::F_NOCACHE, 1) };
+        Ok(file)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+    open(path, true)
+}
+
+/// `DIRECT_ALIGN`에 맞춘 읽기 버퍼.
+pub struct AlignedBuffer {
+    storage: Vec<u8>,
+    offset: usize,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    /// `len` 바이트의 버퍼. `len`은 `DIRECT_ALIGN`의 배수로 올립니다.
+    pub fn new(len: usize) -> Self {
+        let len = len.div_ceil(DIRECT_ALIGN) * DIRECT_ALIGN;
+        let storage = vec![0; len + DIRECT_ALIGN];
+        let offset = storage.as_ptr().align_offset(DIRECT_ALIGN);
+        AlignedBuffer { storage, offset, len }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.storage[self.offset..self.offset + self.len]
+    }
+}
+
+/// 파일을 검색용으로 엽니다. `sequential`이면 처음부터 끝까지 읽는다고 운영체제에 알립니다.
+pub fn open(path: &Path, sequential: bool) -> io::Result<ScanFile> {
+    let file = open_file(path, sequential)?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if sequential {
+        use std::os::unix::io::AsRawFd;
+        // 힌트일 뿐이므로 실패해도 읽기에는 지장이 없습니다.
+        unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL) };
+    }
+    Ok(ScanFile { file, direct: false })
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn open_file(path: &Path, _sequential: bool) -> io::Result<File> {
+    use std::fs::OpenOptions;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    match OpenOptions::new().read(true).custom_flags(libc::O_NOATIME).open(path) {
+        // 다른 사용자의 파일에는 O_NOATIME을 쓸 수 없습니다.
+        Err(e) if e.raw_os_error() == Some(libc::EPERM) => File::open(path),
+        result => result,
+    }
+}
+
+#[cfg(windows)]
+fn open_file(path: &Path, sequential: bool) -> io::Result<File> {
+    use std::fs::OpenOptions;
+    use std::os::windows::fs::OpenOptionsExt;
+
+    const FILE_FLAG_SEQUENTIAL_SCAN: u32 = 0x0800_0000;
+    OpenOptions::new().read(true).custom_flags(if sequential { FILE_FLAG_SEQUENTIAL_SCAN } else { 0 }).open(path)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", windows)))]
+fn open_file(path: &Path, _sequential: bool) -> io::Result<File> {
+    File::open(path)
+}
+
+impl Deref for ScanFile {
+    type Target = File;
+
+    fn deref(&self) -> &File {
+        &self.file
+    }
+}
+
+impl Read for ScanFile {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buffer)
+    }
+}
+
+impl Seek for ScanFile {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.file.seek(position)
+    }
+}
+
+impl Drop for ScanFile {
+    fn drop(&mut self) {
+        // O_DIRECT로 읽은 데이터는 캐시에 없으므로, 원래 캐시에 있던 데이터를 내보내지 않도록 건너뜁니다.
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if !self.direct {
+            use std::os::unix::io::AsRawFd;
+            unsafe { libc::posix_fadvise(self.file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// ==============================================================================
+//  병렬 파일 읽기 풀과 파일 디스크립터 예산
+// ------------------------------------------------------------------------------
+//  해시 계산은 대부분 디스크를 기다리는 시간이므로, 여러 파일을 동시에 읽으면
+//  (특히 SSD나 여러 디스크에 걸친 검색에서) 전체 시간이 크게 줄어듭니다.
+//
+//  하지만 동시에 여는 파일 수에는 운영체제의 제한(RLIMIT_NOFILE, 흔히 1024)이 있어서,
+//  이를 넘으면 "Too many open files" 오류로 파일들이 무작위로 실패합니다.
+//  그래서 파일을 열기 전에 반드시 예산(`FdBudget`)에서 허가를 받도록 하여,
+//  스레드 수와 관계없이 동시에 열린 파일 수가 예산을 넘지 않게 합니다.
+//
+//  여러 물리 장치에 걸친 검색에서는 장치마다 알맞은 동시 읽기 수가 다릅니다. (SSD는 여럿,
+//  하드 디스크는 헤드가 오가지 않도록 하나나 둘) `run_partitioned`는 작업을 장치별 대기열로 나누고
+//  대기열마다 정해진 수의 스레드를 따로 두어, 느린 장치가 다른 장치의 읽기를 막지 않게 합니다.
+//
+//  스레드를 만들 수 없는 WASI(wasm32-wasip1)에서는 스레드 수와 관계없이 호출한 스레드에서
+//  작업을 차례로 실행합니다.
+// ==============================================================================
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use crate::{ScanOptions, devices};
+
+/// 로그 파일, 표준 입출력, 탐색 중인 디렉터리 등 풀 밖에서 쓰는 디스크립터를 위해
+/// 한도에서 남겨 두는 몫.
+const RESERVED_FDS: usize = 64;
+
+/// 한도를 알 수 없는 플랫폼에서 사용하는 기본 예산.
+const FALLBACK_BUDGET: usize = 256;
+
+/// 동시에 열 수 있는 파일 수를 제한하는 계수 세마포어.
+pub struct FdBudget {
+    available: Mutex<usize>,
+    freed: Condvar,
+}
+
+/// 예산에서 받은 파일 하나 분량의 허가. 범위를 벗어나면(drop) 자동으로 반납됩니다.
+pub struct FdPermit<'a> {
+    budget: &'a FdBudget,
+}
+
+impl FdBudget {
+    /// 최대 `limit`개의 파일을 동시에 열 수 있는 예산을 만듭니다. (최소 1)
+    pub fn new(limit: usize) -> Self {
+        FdBudget { available: Mutex::new(limit.max(1)), freed: Condvar::new() }
+    }
+
+    /// 파일 하나를 열 허가를 받습니다. 예산이 바닥났다면 다른 파일이 닫힐 때까지 기다립니다.
+    pub fn acquire(&self) -> FdPermit<'_> {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self.freed.wait(available).unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+        FdPermit { budget: self }
+    }
+}
+
+impl Drop for FdPermit<'_> {
+    fn drop(&mut self) {
+        *self.budget.available.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+        self.budget.freed.notify_one();
+    }
+}
+
+/// 프로세스의 열린 파일 수 제한(RLIMIT_NOFILE의 소프트 한도)에서 예약분을 뺀 기본 예산.
+#[cfg(unix)]
+pub fn default_fd_budget() -> usize {
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    // SAFETY: `limit`은 유효한 `rlimit` 구조체이며, getrlimit은 이 포인터에 쓰기만 합니다.
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 || limit.rlim_cur == libc::RLIM_INFINITY {
+        return FALLBACK_BUDGET;
+    }
+    let soft = usize::try_from(limit.rlim_cur).unwrap_or(usize::MAX);
+    // 한도가 낮은 환경에서는 여유분 대신 절반을 쓰되, 최소 하나의 파일은 열 수 있어야 합니다.
+    soft.saturating_sub(RESERVED_FDS).max(soft / 2).max(1)
+}
+
+/// 유닉스가 아닌 플랫폼에서는 한도를 조회하지 않고 보수적인 기본값을 사용합니다.
+#[cfg(not(unix))]
+pub fn default_fd_budget() -> usize {
+    FALLBACK_BUDGET
+}
+
+/// `threads`개의 스레드로 `jobs`를 나누어 `work`를 실행하고, 결과를 호출한 스레드에서
+/// 도착하는 순서대로 `on_result`에 넘깁니다.
+///
+/// 진행 표시, 로그 기록, 오류 수집처럼 공유 상태를 바꾸는 일은 `on_result`에서 하므로
+/// 별도의 잠금이 필요 없습니다. `on_result`가 `false`를 반환하면 아직 시작하지 않은
+/// 작업은 건너뛰고, 이미 실행 중인 작업이 끝나기를 기다린 뒤 반환합니다.
+pub fn run<J, R>(jobs: Vec<J>, threads: usize, work: impl Fn(J) -> R + Sync, on_result: impl FnMut(R) -> bool)
+where
+    J: Send,
+    R: Send,
+{
+    run_partitioned(jobs, |_| (), |_| threads, work, on_result);
+}
+
+/// 스캔 설정에 따라 작업을 실행합니다. --device-threads가 있다면 `path_of`가 돌려주는 파일이 있는 장치별로
+/// 나누어 실행하고, 없다면 `run`과 같습니다.
+pub fn run_on_devices<J, R>(
+    jobs: Vec<J>,
+    options: &ScanOptions,
+    path_of: impl Fn(&J) -> &Path,
+    work: impl Fn(J) -> R + Sync,
+    on_result: impl FnMut(R) -> bool,
+) where
+    J: Send,
+    R: Send,
+{
+    match &options.device_limits {
+        Some(limits) => run_partitioned(jobs, |job| devices::device_of(path_of(job)), |device| limits.limit(device), work, on_result),
+        None => run(jobs, options.threads, work, on_result),
+    }
+}
+
+/// `run`과 같지만, 작업을 `partition`이 정하는 묶음(예: 파일이 있는 장치)별 대기열로 나누고
+/// 묶음마다 `limit`개의 스레드가 그 묶음의 작업만 차례로 실행합니다. 묶음 안의 작업 순서는 유지됩니다.
+pub fn run_partitioned<J, R, K>(
+    jobs: Vec<J>,
+    partition: impl Fn(&J) -> K,
+    limit: impl Fn(&K) -> usize,
+    work: impl Fn(J) -> R + Sync,
+    mut on_result: impl FnMut(R) -> bool,
+) where
+    J: Send,
+    R: Send,
+    K: Ord,
+{
+    // 스레드를 만들 수 없다면 여기서 차례로 실행합니다. 결과의 순서는 작업 순서와 같습니다.
+    if cfg!(target_os = "wasi") {
+        for job in jobs {
+            if !on_result(work(job)) {
+                break;
+            }
+        }
+        return;
+    }
+    let mut queues: BTreeMap<K, Vec<J>> = BTreeMap::new();
+    for job in jobs {
+        queues.entry(partition(&job)).or_default().push(job);
+    }
+    // 묶음마다 (작업, 스레드 수). 스레드는 작업 수보다 많이 만들지 않습니다.
+    let queues: Vec<(Vec<J>, usize)> = queues
+        .into_iter()
+        .map(|(key, jobs)| {
+            let threads = limit(&key).clamp(1, jobs.len().max(1));
+            (jobs, threads)
+        })
+        .collect();
+    let threads: usize = queues.iter().map(|(_, threads)| threads).sum();
+    if threads <= 1 {
+        // 스레드가 하나뿐이라면(묶음도 하나) 새로 만들지 않고 여기서 실행합니다.
+        for job in queues.into_iter().flat_map(|(jobs, _)| jobs) {
+            if !on_result(work(job)) {
+                break;
+            }
+        }
+        return;
+    }
+    let queues: Vec<(Mutex<std::vec::IntoIter<J>>, usize)> =
+        queues.into_iter().map(|(jobs, threads)| (Mutex::new(jobs.into_iter()), threads)).collect();
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for (queue, threads) in &queues {
+            for _ in 0..*threads {
+                let sender = sender.clone();
+                let (stop, work) = (&stop, &work);
+                scope.spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        // 다음 작업을 꺼내는 동안만 큐를 잠급니다.
+                        let Some(job) = queue.lock().unwrap_or_else(|e| e.into_inner()).next() else { break };
+                        if sender.send(work(job)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+        // 작업 스레드들이 가진 송신자만 남겨야, 모두 끝났을 때 수신 반복이 종료됩니다.
+        drop(sender);
+        for result in receiver {
+            if !stop.load(Ordering::Relaxed) && !on_result(result) {
+                stop.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+}
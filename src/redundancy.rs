@@ -0,0 +1,157 @@
+// ==============================================================================
+//  복제가 부족한 파일 찾기 (--min-copies)
+// ------------------------------------------------------------------------------
+//  중복 검색의 반대 방향 분석입니다. 서로를 미러링해야 하는 루트들(예: 백업 디스크 3개)을
+//  지정하면, 내용이 N개 미만의 루트에만 있는 파일, 즉 복제가 충분하지 않은 데이터를 보고합니다.
+//
+//  - 루트 N개 미만에만 나타나는 크기의 파일은 내용을 읽지 않아도 복제가 부족합니다.
+//  - 나머지 파일만 해시하여, 같은 내용이 몇 개의 루트에 있는지 셉니다.
+//
+//  파일은 루트별 통계와 같은 규칙으로 루트에 속합니다. (자신을 포함하는 루트 중 앞에 적은 루트)
+//  같은 물리적 파일(하드 링크)은 한 번만 세므로, 두 루트에 걸친 하드 링크는 사본으로 치지 않습니다.
+// ==============================================================================
+
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use serde_json::json;
+
+use crate::root_stats::root_of;
+use crate::{
+    EXIT_DUPLICATES_FOUND, EXIT_FILE_ERRORS, EXIT_INTERRUPTED, EXIT_NO_DUPLICATES, EXIT_TIME_LIMIT, FdBudget, FileStamp, HashFailure,
+    KIND_IN_USE, Progress, ScanError, ScanLog, ScanOptions, collect_files, format_size, hash_unchanged, interrupt,
+    print_error_summary, reader_pool, record_error,
+};
+
+/// 복제가 부족한 파일 하나: (경로, 크기, 내용이 있는 루트 번호들).
+type Shortfall = (PathBuf, u64, BTreeSet<usize>);
+
+/// 루트들의 파일을 비교하여, 내용이 `min_copies`개 미만의 루트에만 있는 파일을 루트별로 출력합니다.
+/// `summary_only`이면 루트별 개수와 크기만 출력합니다.
+pub fn report(roots: &[PathBuf], options: &ScanOptions, min_copies: usize, summary_only: bool, log: &mut ScanLog) -> ExitCode {
+    let mut errors: Vec<ScanError> = Vec::new();
+    let mut progress = Progress::new();
+    let files = collect_files(roots, options, &mut errors, log, &mut progress);
+
+    // 크기마다 그 크기의 파일이 있는 루트들.
+    let mut roots_by_size: HashMap<u64, BTreeSet<usize>> = HashMap::new();
+    let located: Vec<(PathBuf, FileStamp, usize)> = files
+        .into_iter()
+        .filter_map(|(path, stamp)| root_of(roots, &path).map(|root| (path, stamp, root)))
+        .collect();
+    for (_, stamp, root) in &located {
+        roots_by_size.entry(stamp.len).or_default().insert(*root);
+    }
+
+    let mut shortfalls: Vec<Shortfall> = Vec::new();
+    let mut jobs = Vec::new();
+    for (path, stamp, root) in located {
+        if roots_by_size[&stamp.len].len() < min_copies {
+            shortfalls.push((path, stamp.len, BTreeSet::from([root])));
+        } else {
+            jobs.push((path, stamp, root));
+        }
+    }
+
+    // 크기만으로 판단할 수 없는 파일은 해시하여 내용마다 루트를 모읍니다.
+    let total = jobs.len() as u64;
+    let budget = FdBudget::new(options.fd_budget);
+    let mut hashed: Vec<(PathBuf, u64, usize, String)> = Vec::new();
+    let mut processed = 0u64;
+    reader_pool::run(
+        jobs,
+        options.threads,
+        |(path, stamp, root)| {
+            let result = hash_unchanged(&path, &stamp, &budget, options);
+            (path, stamp, root, result)
+        },
+        |(path, stamp, root, result)| {
+            processed += 1;
+            progress.update("해시 계산 중", processed, Some(total));
+            match result {
+                Ok((digests, _)) => hashed.push((path, stamp.len, root, digests.hash)),
+                Err(HashFailure::Io(e)) if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() => {
+                    return false;
+                }
+                Err(HashFailure::Changed(kind, message)) => {
+                    record_error(&mut errors, log, Some(path), "변경 감지", kind, message);
+                }
+                Err(HashFailure::InUse(reason)) => {
+                    record_error(&mut errors, log, Some(path), "사용 중 확인", KIND_IN_USE.to_string(), reason.to_string());
+                }
+                Err(HashFailure::Io(e)) => {
+                    record_error(&mut errors, log, Some(path), "해시 계산", e.kind().to_string(), e.to_string());
+                }
+            }
+            true
+        },
+    );
+    progress.finish();
+    let interrupted = interrupt::requested();
+    let mut roots_by_content: HashMap<(u64, &str), BTreeSet<usize>> = HashMap::new();
+    for (_, size, root, hash) in &hashed {
+        roots_by_content.entry((*size, hash)).or_default().insert(*root);
+    }
+    for (path, size, _, hash) in &hashed {
+        let present = &roots_by_content[&(*size, hash.as_str())];
+        if present.len() < min_copies {
+            shortfalls.push((path.clone(), *size, present.clone()));
+        }
+    }
+    shortfalls.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let bytes: u64 = shortfalls.iter().map(|(_, size, _)| size).sum();
+    log.event(
+        "redundancy_end",
+        json!({
+            "min_copies": min_copies,
+            "short_files": shortfalls.len(),
+            "short_bytes": bytes,
+            "file_errors": errors.len(),
+            "interrupted": interrupted,
+        }),
+    );
+    log.flush();
+
+    if interrupted {
+        // 중단되었다면 해시하지 못한 파일의 사본을 세지 못했으므로, 부족하다고 나온 파일 중 일부는 아닐 수 있습니다.
+        println!("\n⏹️  검색이 중단되었습니다. 아래는 중단 시점까지 확인한 파일만의 결과입니다.");
+    }
+    println!(
+        "\n🛡️  복제 수준 점검: 루트 {}개 중 {}개 미만에만 있는 파일 {}개 ({})",
+        roots.len(),
+        min_copies,
+        shortfalls.len(),
+        format_size(bytes)
+    );
+    for (i, root) in roots.iter().enumerate() {
+        let mine: Vec<&Shortfall> = shortfalls.iter().filter(|(path, _, _)| root_of(roots, path) == Some(i)).collect();
+        println!(
+            "  [{}] {}: {}개 ({})",
+            i + 1,
+            root.display(),
+            mine.len(),
+            format_size(mine.iter().map(|(_, size, _)| size).sum())
+        );
+        if summary_only {
+            continue;
+        }
+        for (path, size, present) in mine {
+            let present: Vec<String> = present.iter().map(|r| format!("[{}]", r + 1)).collect();
+            println!("      - {} ({}, 있는 루트: {})", path.display(), format_size(*size), present.join(", "));
+        }
+    }
+    print_error_summary(&errors, false);
+
+    ExitCode::from(if interrupted {
+        if interrupt::time_limit_reached() { EXIT_TIME_LIMIT } else { EXIT_INTERRUPTED }
+    } else if !errors.is_empty() {
+        EXIT_FILE_ERRORS
+    } else if !shortfalls.is_empty() {
+        EXIT_DUPLICATES_FOUND
+    } else {
+        EXIT_NO_DUPLICATES
+    })
+}
@@ -0,0 +1,140 @@
+// ==============================================================================
+//  저장된 두 결과의 비교 (`report diff` 하위 명령)
+// ------------------------------------------------------------------------------
+//  공유 드라이브를 매주 검색하여 --save-results로 저장해 두면, 두 결과를 비교해
+//  "지난주보다 중복이 늘었는가, 어디서 늘었는가"를 알 수 있습니다.
+//
+//  두 결과의 그룹은 그룹 식별자(id)로 맞춥니다. 식별자는 (크기, 해시)로 만들므로 같은 내용의
+//  그룹이면 경로가 바뀌어도 같은 그룹입니다. 식별자가 없는 예전 결과 파일은 같은 방법으로 계산합니다.
+//  - 새로 생긴 그룹 / 사라진 그룹
+//  - 커진 그룹(멤버가 늘어남) / 줄어든 그룹(멤버가 줄었지만 아직 중복)
+//  각 목록은 회수 가능 공간의 변화가 큰 순서로 보여줍니다.
+//
+//  두 결과의 해시 알고리즘이 다르면 같은 내용도 다른 식별자가 되므로 비교하지 않습니다.
+// ==============================================================================
+
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+
+use crate::results::{SavedGroup, SavedResults};
+use crate::{format_size, group_id};
+
+/// 그룹 하나의 변화.
+struct Change<'a> {
+    id: &'a str,
+    old: Option<&'a SavedGroup>,
+    new: Option<&'a SavedGroup>,
+}
+
+impl Change<'_> {
+    /// 새 결과에서 회수 가능 공간이 얼마나 늘었는지 (줄었다면 음수).
+    fn delta(&self) -> i128 {
+        wasted(self.new) as i128 - wasted(self.old) as i128
+    }
+}
+
+/// 그룹의 회수 가능 공간. 심볼릭 링크 그룹은 공간을 차지하지 않습니다.
+fn wasted(group: Option<&SavedGroup>) -> u64 {
+    match group {
+        Some(group) if group.symlink_target.is_none() => group.size * (group.paths.len() as u64).saturating_sub(1),
+        _ => 0,
+    }
+}
+
+/// 그룹의 식별자. 식별자가 없는 예전 결과 파일은 (크기, 해시)로 계산합니다.
+fn id_of(group: &SavedGroup) -> String {
+    group.id.clone().unwrap_or_else(|| group_id(group.size, &group.hash))
+}
+
+/// 두 결과를 비교하여 보고서를 출력합니다. 목록마다 변화가 큰 `top`개 그룹의 경로를 보여줍니다.
+pub fn diff(old: &SavedResults, new: &SavedResults, top: usize) -> io::Result<()> {
+    if old.algorithm != new.algorithm {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "두 결과의 해시 알고리즘({}, {})이 달라 그룹을 맞출 수 없습니다",
+                old.algorithm, new.algorithm
+            ),
+        ));
+    }
+    let old_ids: Vec<String> = old.groups.iter().map(id_of).collect();
+    let new_ids: Vec<String> = new.groups.iter().map(id_of).collect();
+    let old_by_id: HashMap<&str, &SavedGroup> = old_ids.iter().map(String::as_str).zip(&old.groups).collect();
+    let new_by_id: HashMap<&str, &SavedGroup> = new_ids.iter().map(String::as_str).zip(&new.groups).collect();
+    let ids: BTreeSet<&str> = old_by_id.keys().chain(new_by_id.keys()).copied().collect();
+
+    let (mut appeared, mut disappeared, mut grew, mut shrank) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for id in ids {
+        let change = Change { id, old: old_by_id.get(id).copied(), new: new_by_id.get(id).copied() };
+        match (change.old, change.new) {
+            (None, Some(_)) => appeared.push(change),
+            (Some(_), None) => disappeared.push(change),
+            (Some(o), Some(n)) if n.paths.len() > o.paths.len() => grew.push(change),
+            (Some(o), Some(n)) if n.paths.len() < o.paths.len() => shrank.push(change),
+            _ => {}
+        }
+    }
+
+    let old_wasted: u64 = old.groups.iter().map(|g| wasted(Some(g))).sum();
+    let new_wasted: u64 = new.groups.iter().map(|g| wasted(Some(g))).sum();
+    println!("📈 중복 추세: {} → {}", old.created, new.created);
+    println!("  중복 그룹: {}개 → {}개", old.groups.len(), new.groups.len());
+    println!(
+        "  회수 가능 공간: {} → {} ({})",
+        format_size(old_wasted),
+        format_size(new_wasted),
+        signed_size(new_wasted as i128 - old_wasted as i128)
+    );
+    if old.interrupted || new.interrupted {
+        println!("  (중단된 스캔의 부분 결과가 있어, 사라진 그룹 중 일부는 검색하지 못한 것일 수 있습니다.)");
+    }
+    if old.roots != new.roots {
+        println!("  (두 결과의 루트 폴더가 다릅니다: {:?} → {:?})", old.roots, new.roots);
+    }
+
+    print_section("🆕 새로 생긴 그룹", appeared, top);
+    print_section("🗑️  사라진 그룹", disappeared, top);
+    print_section("⬆️  커진 그룹", grew, top);
+    print_section("⬇️  줄어든 그룹", shrank, top);
+    Ok(())
+}
+
+/// 변화 목록 하나를 출력합니다. 회수 가능 공간의 변화가 큰 순서, 같으면 식별자 순서입니다.
+fn print_section(title: &str, mut changes: Vec<Change>, top: usize) {
+    let total: i128 = changes.iter().map(Change::delta).sum();
+    println!("\n{}: {}개 ({})", title, changes.len(), signed_size(total));
+    changes.sort_by(|a, b| b.delta().abs().cmp(&a.delta().abs()).then_with(|| a.id.cmp(b.id)));
+    for change in changes.iter().take(top) {
+        let (old_paths, new_paths) = (paths(change.old), paths(change.new));
+        let members = |group: Option<&SavedGroup>| group.map_or(0, |g| g.paths.len());
+        let size = change.new.or(change.old).map_or(0, |g| g.size);
+        println!(
+            "  [{}] 파일당 {}, 멤버 {}개 → {}개 ({})",
+            change.id,
+            format_size(size),
+            members(change.old),
+            members(change.new),
+            signed_size(change.delta())
+        );
+        // 새로 생기거나 사라진 그룹은 멤버 전체를, 크기가 바뀐 그룹은 바뀐 멤버만 보여줍니다.
+        for path in new_paths.difference(&old_paths) {
+            println!("      + {}", path);
+        }
+        for path in old_paths.difference(&new_paths) {
+            println!("      - {}", path);
+        }
+    }
+    if changes.len() > top {
+        println!("  ... 외 {}개 그룹", changes.len() - top);
+    }
+}
+
+fn paths(group: Option<&SavedGroup>) -> BTreeSet<&str> {
+    group.map(|g| g.paths.iter().map(String::as_str).collect()).unwrap_or_default()
+}
+
+/// 부호를 붙인 크기. (예: `+1.2 MB`, `-300 B`)
+fn signed_size(bytes: i128) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_size(bytes.unsigned_abs() as u64))
+}
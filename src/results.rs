@@ -0,0 +1,292 @@
+// ==============================================================================
+//  저장된 결과 파일 (--save-results)과 재검증 (`verify` 하위 명령)
+// ------------------------------------------------------------------------------
+//  스캔 결과를 JSON 파일로 저장해 두면, 며칠 뒤에 그 결과를 바탕으로 정리 작업을 할 수
+//  있습니다. 하지만 그 사이에 파일이 수정되거나 삭제되었을 수 있으므로, 저장된 결과를
+//  적용하기 전에 `verify`로 각 멤버를 다시 해시하여 그룹이 여전히 유효한지 확인합니다.
+//
+//  결과 파일 형식 (JSON):
+//  {
+//    "schema_version": 1,
+//    "created": "2024-01-01T00:00:00Z",
+//    "roots": ["/mnt/data"],
+//    "interrupted": false,
+//    "algorithm": "sha256",
+//    "groups": [ { "size": 1024, "hash": "<sha256>", "paths": ["...", "..."] }, ... ]
+//  }
+//  심볼릭 링크 그룹(--symlinks)에는 공통 대상 경로인 "symlink_target"이 추가됩니다.
+//  --extra-hash를 쓰면 "extra_algorithm"과 그룹마다 "extra_hash"가 추가됩니다.
+//  그룹마다 실행이 바뀌어도 같은 그룹을 가리키는 식별자 "id"가 있습니다. (이 항목이 없는 예전 결과 파일도 읽습니다)
+//  --relative-to BASE로 저장하면 경로가 BASE 기준의 상대 경로이며, "base"에 BASE의 절대 경로가 기록됩니다.
+//
+//  다른 도구가 이 파일을 안전하게 읽을 수 있도록 형식에 버전(schema_version)을 붙이고,
+//  JSON Schema 문서(schema/results.schema.json, `--schema`로 출력)를 함께 제공합니다.
+//  같은 버전 안에서는 선택 항목만 추가하며, 항목을 없애거나 의미를 바꾸면 버전을 올립니다.
+// ==============================================================================
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DuplicateGroup, atomic_file, HashAlgorithm, Progress, calculate_hashes, format_size, group_ids, path_style, resolve_link_target,
+};
+
+/// 현재 결과 파일 형식의 버전.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// 결과 파일 형식의 JSON Schema 문서. (`--schema`로 출력)
+pub const SCHEMA: &str = include_str!("../schema/results.schema.json");
+
+/// 결과 파일 전체.
+#[derive(Serialize, Deserialize)]
+pub struct SavedResults {
+    /// 결과 파일 형식의 버전. 이 항목이 없는 예전 결과 파일은 버전 1입니다.
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    /// 결과를 저장한 시각 (RFC 3339, UTC).
+    pub created: String,
+    /// 검색한 루트 폴더들.
+    pub roots: Vec<String>,
+    /// 스캔이 중단되어 부분 결과만 담겨 있다면 `true`.
+    pub interrupted: bool,
+    /// 그룹 해시의 알고리즘 (--algorithm). 이 항목이 없는 예전 결과 파일은 SHA-256입니다.
+    #[serde(default = "default_algorithm")]
+    pub algorithm: String,
+    /// 그룹마다 함께 기록한 두 번째 해시의 알고리즘 (--extra-hash).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_algorithm: Option<String>,
+    /// 경로를 이 폴더 기준의 상대 경로로 저장했다면 그 절대 경로 (--relative-to).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    pub groups: Vec<SavedGroup>,
+}
+
+/// 결과 파일에 저장된 중복 그룹 하나.
+///
+/// 경로는 JSON 문자열로 저장되므로, UTF-8이 아닌 이름은 대체 문자로 바뀌어
+/// 재검증 때 "사라짐"으로 보고됩니다.
+#[derive(Serialize, Deserialize)]
+pub struct SavedGroup {
+    /// (크기, 해시)로 만든 그룹 식별자. 실행이 바뀌어도 같은 그룹이면 같은 값입니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub size: u64,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_hash: Option<String>,
+    pub paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symlink_target: Option<String>,
+}
+
+fn first_schema_version() -> u32 {
+    1
+}
+
+fn default_algorithm() -> String {
+    HashAlgorithm::Sha256.name().to_string()
+}
+
+impl SavedResults {
+    pub fn new(roots: &[PathBuf], groups: &[DuplicateGroup], interrupted: bool, algorithm: HashAlgorithm) -> Self {
+        SavedResults {
+            schema_version: SCHEMA_VERSION,
+            created: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            roots: roots.iter().map(|r| path_style::display(r)).collect(),
+            interrupted,
+            algorithm: algorithm.name().to_string(),
+            extra_algorithm: groups.iter().find_map(|g| g.extra_hash.as_ref()).map(|(a, _)| a.name().to_string()),
+            base: path_style::style().base().map(|base| base.display().to_string()),
+            groups: groups
+                .iter()
+                .zip(group_ids(groups))
+                .map(|(g, id)| SavedGroup {
+                    id: Some(id),
+                    size: g.size,
+                    hash: g.hash.clone(),
+                    extra_hash: g.extra_hash.as_ref().map(|(_, hash)| hash.clone()),
+                    paths: g.paths.iter().map(|p| path_style::display(p)).collect(),
+                    symlink_target: g.symlink_target.as_ref().map(|t| path_style::display(t)),
+                })
+                .collect(),
+        }
+    }
+
+    /// 저장된 경로가 가리키는 파일의 경로. 상대 경로로 저장했다면 기준 폴더(`base`)에 이어 붙이고
+    /// `.`과 `..`을 정리합니다.
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        match &self.base {
+            Some(base) => resolve_link_target(Path::new(""), &Path::new(base).join(path)),
+            None => PathBuf::from(path),
+        }
+    }
+
+    /// 결과를 사람이 읽을 수 있는 형태(들여쓰기된 JSON)로 파일에 저장합니다.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut text = serde_json::to_string_pretty(self)?;
+        text.push('\n');
+        atomic_file::write(path, |writer| writer.write_all(text.as_bytes()))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        // 어떤 파일이 문제였는지 알 수 있도록 오류 메시지에 경로를 붙입니다.
+        let context = |e: &dyn std::fmt::Display| format!("결과 파일 '{}': {}", path.display(), e);
+        let text = fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), context(&e)))?;
+        let results: SavedResults =
+            serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, context(&e)))?;
+        // 더 새로운 버전의 파일은 항목의 의미가 달라졌을 수 있으므로 추측해서 읽지 않습니다.
+        if results.schema_version > SCHEMA_VERSION {
+            let message = format!(
+                "형식 버전 {}은(는) 이 프로그램이 읽을 수 있는 버전({})보다 새롭습니다. 프로그램을 업데이트하세요",
+                results.schema_version, SCHEMA_VERSION
+            );
+            return Err(io::Error::new(io::ErrorKind::InvalidData, context(&message)));
+        }
+        Ok(results)
+    }
+}
+
+/// 재검증에서 멤버 하나의 상태.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MemberState {
+    /// 저장할 때와 내용이 같습니다.
+    Unchanged,
+    /// 크기나 내용(해시), 또는 링크 대상이 바뀌었습니다.
+    Changed,
+    /// 파일이 더 이상 존재하지 않습니다.
+    Vanished,
+    /// 파일을 읽을 수 없어 확인하지 못했습니다.
+    Unreadable,
+}
+
+impl MemberState {
+    fn label(self) -> &'static str {
+        match self {
+            MemberState::Unchanged => "변경 없음",
+            MemberState::Changed => "변경됨",
+            MemberState::Vanished => "사라짐",
+            MemberState::Unreadable => "읽을 수 없음",
+        }
+    }
+}
+
+/// 저장된 결과의 모든 그룹을 다시 확인하고 보고서를 출력합니다.
+/// 모든 멤버가 그대로라면 `true`를 반환합니다.
+pub fn verify(results: &SavedResults) -> bool {
+    println!(
+        "🔎 {}에 저장된 {}개 그룹을 다시 확인합니다...",
+        results.created,
+        results.groups.len()
+    );
+    if results.interrupted {
+        println!("(이 결과는 중단된 스캔의 부분 결과입니다.)");
+    }
+    // 저장 당시와 같은 알고리즘으로 다시 계산해야 비교할 수 있습니다.
+    let names = std::iter::once(&results.algorithm).chain(&results.extra_algorithm);
+    let algorithms: Option<Vec<HashAlgorithm>> = names.map(|name| HashAlgorithm::from_name(name)).collect();
+    let Some(algorithms) = algorithms else {
+        eprintln!("오류 발생: 결과 파일의 해시 알고리즘을 알 수 없습니다.");
+        return false;
+    };
+
+    let total: u64 = results.groups.iter().map(|g| g.paths.len() as u64).sum();
+    let mut progress = Progress::new();
+    let mut checked = 0u64;
+    let (mut valid, mut partial, mut invalid) = (0usize, 0usize, 0usize);
+    let mut counts = [0usize; 4];
+
+    let mut reports = Vec::new();
+    for (i, group) in results.groups.iter().enumerate() {
+        // 상대 경로로 저장한 대상 경로도 기준 폴더에 이어 붙여 비교합니다.
+        let target = group.symlink_target.as_ref().map(|t| results.resolve(t));
+        let states: Vec<MemberState> = group
+            .paths
+            .iter()
+            .map(|path| {
+                checked += 1;
+                progress.update("검증 중", checked, Some(total));
+                check_member(group, &results.resolve(path), target.as_deref(), &algorithms)
+            })
+            .collect();
+        for state in &states {
+            counts[*state as usize] += 1;
+        }
+        let unchanged = states.iter().filter(|s| **s == MemberState::Unchanged).count();
+        if unchanged == states.len() {
+            valid += 1;
+            continue;
+        }
+        // 그대로인 멤버가 두 개 이상 남아 있다면, 그 멤버들끼리는 여전히 중복입니다.
+        let status = if unchanged >= 2 {
+            partial += 1;
+            "일부 유효"
+        } else {
+            invalid += 1;
+            "무효"
+        };
+        reports.push((i, status, states));
+    }
+    progress.finish();
+
+    for (i, status, states) in &reports {
+        let group = &results.groups[*i];
+        match &group.id {
+            Some(id) => println!("\n--- 그룹 {} [{}] ({}, 파일당 {}) ---", i + 1, id, status, format_size(group.size)),
+            None => println!("\n--- 그룹 {} ({}, 파일당 {}) ---", i + 1, status, format_size(group.size)),
+        }
+        for (path, state) in group.paths.iter().zip(states) {
+            println!("  [{}] {}", state.label(), path);
+        }
+    }
+
+    println!("\n📋 검증 결과");
+    println!("  유효한 그룹: {}개", valid);
+    println!("  일부만 유효한 그룹: {}개", partial);
+    println!("  무효인 그룹: {}개", invalid);
+    println!(
+        "  파일: 변경 없음 {}개, 변경됨 {}개, 사라짐 {}개, 읽을 수 없음 {}개",
+        counts[MemberState::Unchanged as usize],
+        counts[MemberState::Changed as usize],
+        counts[MemberState::Vanished as usize],
+        counts[MemberState::Unreadable as usize]
+    );
+    reports.is_empty()
+}
+
+/// 멤버 하나를 저장된 그룹 정보와 비교합니다.
+/// `algorithms`는 (결과 파일의 알고리즘, 두 번째 해시의 알고리즘) 순서이고, `target`은 심볼릭 링크 그룹의 대상 경로입니다.
+fn check_member(group: &SavedGroup, path: &Path, target: Option<&Path>, algorithms: &[HashAlgorithm]) -> MemberState {
+    // 심볼릭 링크 그룹은 링크가 여전히 같은 대상을 가리키는지 확인합니다.
+    if let Some(target) = target {
+        return match fs::read_link(path) {
+            Ok(current) if resolve_link_target(path, &current) == target => MemberState::Unchanged,
+            Ok(_) => MemberState::Changed,
+            Err(e) => io_state(&e),
+        };
+    }
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.len() != group.size => return MemberState::Changed,
+        Ok(_) => {}
+        Err(e) => return io_state(&e),
+    }
+    // 두 번째 해시가 기록되어 있다면 한 번 읽으면서 함께 계산하여 둘 다 비교합니다.
+    let expected: Vec<&String> = std::iter::once(&group.hash).chain(&group.extra_hash).collect();
+    match calculate_hashes(path, &algorithms[..expected.len().min(algorithms.len())]) {
+        Ok(hashes) if hashes.iter().eq(expected.iter().copied()) => MemberState::Unchanged,
+        Ok(_) => MemberState::Changed,
+        Err(e) => io_state(&e),
+    }
+}
+
+fn io_state(error: &io::Error) -> MemberState {
+    // 심볼릭 링크가 일반 파일로 바뀌는 등, 경로는 있지만 종류가 달라진 경우는 "변경됨"입니다.
+    match error.kind() {
+        io::ErrorKind::NotFound => MemberState::Vanished,
+        io::ErrorKind::InvalidInput => MemberState::Changed,
+        _ => MemberState::Unreadable,
+    }
+}
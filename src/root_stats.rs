@@ -0,0 +1,104 @@
+// ==============================================================================
+//  여러 루트를 검색했을 때의 루트별 통계
+// ------------------------------------------------------------------------------
+//  외장 디스크 B를 정리하려는 사용자가 알고 싶은 것은 "B의 몇 %가 이미 A에 있는가"입니다.
+//  전체 요약만으로는 이를 알 수 없으므로, 루트가 둘 이상이면 루트마다 다음을 보여줍니다.
+//  - 탐색에서 발견한 파일 수와 크기의 합
+//  - 그 루트에 있는 중복 사본(--keep 규칙으로 고른 원본을 뺀 멤버)의 수와 크기
+//  - 그중 원본이 다른 루트에 있는 사본. 이 사본들은 그 루트를 통째로 지워도 다른 루트에 남습니다.
+//
+//  --inter-root-only를 주면 멤버가 모두 한 루트 안에 있는 그룹은 빼고, 여러 루트에 걸친 그룹만
+//  보고합니다. ("B에 있는 것 중 이미 A에 있는 것"만 보고 싶을 때)
+//
+//  파일은 자신을 포함하는 루트 중 명령줄에서 앞에 적은 루트에 속합니다. 탐색도 같은 순서로
+//  이루어지므로, 겹치는 루트에서 두 번 만난 파일은 먼저 센 루트에만 들어갑니다.
+// ==============================================================================
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::keep::KeepRule;
+use crate::{DuplicateGroup, format_count, format_size, number_format};
+
+/// 탐색 단계에서 루트 하나에 대해 센 수치. 체크포인트에도 기록합니다.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RootTotals {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// 루트 하나의 집계.
+#[derive(Clone, Copy, Default)]
+struct Row {
+    totals: RootTotals,
+    /// 원본이 아닌 중복 사본의 수와 크기.
+    copies: u64,
+    copy_bytes: u64,
+    /// 그중 원본이 다른 루트에 있는 사본의 수와 크기.
+    elsewhere: u64,
+    elsewhere_bytes: u64,
+}
+
+/// 루트별 통계.
+pub struct RootStats {
+    roots: Vec<PathBuf>,
+    rows: Vec<Row>,
+}
+
+impl RootStats {
+    /// 탐색 수치와 중복 그룹으로 루트별 통계를 만듭니다. 심볼릭 링크 그룹은 공간을 차지하지 않으므로 제외합니다.
+    pub fn of(roots: &[PathBuf], totals: &[RootTotals], groups: &[DuplicateGroup], keep: &KeepRule) -> Self {
+        let mut rows: Vec<Row> = roots
+            .iter()
+            .enumerate()
+            .map(|(i, _)| Row { totals: totals.get(i).copied().unwrap_or_default(), ..Row::default() })
+            .collect();
+        for group in groups.iter().filter(|g| g.symlink_target.is_none()) {
+            let keeper = keep.keeper(&group.paths);
+            let keeper_root = root_of(roots, &group.paths[keeper]);
+            for (i, path) in group.paths.iter().enumerate() {
+                let Some(root) = root_of(roots, path).filter(|_| i != keeper) else { continue };
+                let row = &mut rows[root];
+                row.copies += 1;
+                row.copy_bytes += group.size;
+                if keeper_root != Some(root) {
+                    row.elsewhere += 1;
+                    row.elsewhere_bytes += group.size;
+                }
+            }
+        }
+        RootStats { roots: roots.to_vec(), rows }
+    }
+
+    /// 루트마다 파일 수, 크기, 중복 사본과 다른 루트와 겹치는 비율을 출력합니다.
+    pub fn print(&self) {
+        println!("\n📂 루트별 통계 (원본은 --keep 규칙으로 고릅니다)");
+        for (i, (root, row)) in self.roots.iter().zip(&self.rows).enumerate() {
+            let ratio = if row.totals.bytes == 0 { 0.0 } else { row.elsewhere_bytes as f64 / row.totals.bytes as f64 };
+            println!("  [{}] {}", i + 1, root.display());
+            println!("      파일 {}개, {}", format_count(row.totals.files), format_size(row.totals.bytes));
+            println!(
+                "      중복 사본 {}개 ({}), 그중 원본이 다른 루트에 있는 사본 {}개 ({}, 이 루트의 {}%)",
+                format_count(row.copies),
+                format_size(row.copy_bytes),
+                format_count(row.elsewhere),
+                format_size(row.elsewhere_bytes),
+                number_format::format().decimal(ratio * 100.0)
+            );
+        }
+    }
+}
+
+/// 경로를 포함하는 루트 중 가장 앞에 적은 루트의 번호.
+pub fn root_of(roots: &[PathBuf], path: &Path) -> Option<usize> {
+    roots.iter().position(|root| path.starts_with(root))
+}
+
+/// 그룹의 멤버가 둘 이상의 루트에 걸쳐 있는지 여부. (--inter-root-only)
+/// 어느 루트에도 속하지 않는 멤버(카탈로그, 보관 목록의 항목)는 따로 떨어진 위치로 봅니다.
+pub fn spans_roots(roots: &[PathBuf], group: &DuplicateGroup) -> bool {
+    let mut members = group.paths.iter().map(|path| root_of(roots, path));
+    let first = members.next();
+    members.any(|root| Some(root) != first)
+}
@@ -0,0 +1,75 @@
+// ==============================================================================
+//  파괴적 작업에 대한 안전장치
+// ------------------------------------------------------------------------------
+//  잘못된 한 번의 실행으로 시스템 전체나 홈 폴더 전체의 파일이 삭제되는 일은
+//  되돌릴 수 없습니다. 그래서 다음 루트에서는 `--force-dangerous-root` 없이
+//  삭제 같은 파괴적 작업을 허용하지 않습니다.
+//  - 파일 시스템 루트 (`/`, `C:\`)
+//  - 현재 사용자의 홈 폴더 전체
+//  - 마운트 지점의 루트 (예: `/mnt/backup` 자체)
+//
+//  또한 어떤 경우에도 각 중복 그룹에서 최소 한 개의 파일은 남아 있어야 합니다.
+// ==============================================================================
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// 루트 폴더가 파괴적 작업에 위험한 위치라면 그 이유를 반환합니다.
+pub fn dangerous_root_reason(root: &Path) -> Option<String> {
+    // 심볼릭 링크나 `..`로 위험한 위치를 우회하지 못하도록 정규화한 경로로 판단합니다.
+    let canonical = root.canonicalize().ok()?;
+
+    if canonical.parent().is_none() {
+        return Some(format!("'{}'은(는) 파일 시스템의 루트입니다", root.display()));
+    }
+    if home_dir().is_some_and(|home| home == canonical) {
+        return Some(format!("'{}'은(는) 사용자의 홈 폴더 전체입니다", root.display()));
+    }
+    if is_mount_root(&canonical) {
+        return Some(format!("'{}'은(는) 마운트 지점의 루트입니다", root.display()));
+    }
+    None
+}
+
+/// 모든 루트를 검사하여, 위험한 루트가 있다면 사용자에게 보여줄 이유 목록을 반환합니다.
+/// `force`가 참이면(--force-dangerous-root) 검사를 생략합니다.
+pub fn check_roots(roots: &[PathBuf], force: bool) -> Result<(), Vec<String>> {
+    if force {
+        return Ok(());
+    }
+    let reasons: Vec<String> = roots.iter().filter_map(|r| dangerous_root_reason(r)).collect();
+    if reasons.is_empty() { Ok(()) } else { Err(reasons) }
+}
+
+/// 현재 사용자의 홈 폴더를 정규화된 경로로 반환합니다.
+fn home_dir() -> Option<PathBuf> {
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    PathBuf::from(home).canonicalize().ok()
+}
+
+/// 폴더가 마운트 지점인지 확인합니다. 상위 폴더와 장치 번호가 다르면 마운트 지점입니다.
+/// (파일 시스템 루트는 상위 폴더가 없으므로 항상 마운트 지점입니다.)
+#[cfg(unix)]
+pub fn is_mount_root(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let Some(parent) = path.parent() else { return true };
+    match (path.metadata(), parent.metadata()) {
+        (Ok(own), Ok(up)) => own.dev() != up.dev(),
+        _ => false,
+    }
+}
+
+/// 유닉스가 아닌 플랫폼에서는 드라이브 루트(`parent()`가 없는 경로)만 마운트 루트로 봅니다.
+#[cfg(not(unix))]
+pub fn is_mount_root(_path: &Path) -> bool {
+    false
+}
+
+/// 그룹에서 삭제되지 않고 남는 멤버가 하나 이상인지 확인합니다.
+///
+/// `removed`는 멤버가 이번 작업으로 사라질 예정이면 `true`를 반환해야 합니다.
+/// 모든 사본을 지우면 원본 데이터 자체가 사라지므로, 파괴적 작업은 이 검사를
+/// 통과한 그룹에만 적용해야 합니다.
+pub fn has_survivor<T>(members: &[T], removed: impl Fn(&T) -> bool) -> bool {
+    members.iter().any(|m| !removed(m))
+}
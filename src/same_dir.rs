@@ -0,0 +1,66 @@
+// ==============================================================================
+//  한 폴더 안의 사본 보고서 (--same-dir)
+// ------------------------------------------------------------------------------
+//  파일을 한 폴더 안에서 여러 번 복사해 붙여넣거나, 같은 첨부 파일을 같은 다운로드 폴더에 여러 번
+//  받으면 `보고서.hwp`, `보고서 (1).hwp`, `보고서 - 복사본.hwp`가 나란히 쌓입니다. 이런 사본은
+//  폴더 구조를 고민할 필요 없이 바로 지울 수 있는 가장 쉬운 정리 대상입니다.
+//
+//  --same-dir는 그룹 목록 대신, 멤버가 모두 같은 폴더에 있는 그룹만 회수 가능한 공간이 큰 순서로
+//  폴더와 함께 보여줍니다. 멤버는 파일 이름만 쓰고, --keep 규칙으로 고른 원본에 표시를 붙입니다.
+//  (사본 이름 표시가 붙은 파일은 원본으로 고르지 않습니다)
+// ==============================================================================
+
+use std::path::Path;
+
+use crate::keep::KeepRule;
+use crate::{DuplicateGroup, format_count, format_size, path_style};
+
+/// 한 폴더 안의 사본 그룹을 출력합니다. `top`이 있으면 회수 가능한 공간이 큰 그룹부터 그만큼만 보여줍니다.
+pub fn report(groups: &[DuplicateGroup], keep: &KeepRule, top: Option<usize>) {
+    // 심볼릭 링크 그룹은 공간을 차지하지 않으므로 제외합니다.
+    let mut same: Vec<&DuplicateGroup> =
+        groups.iter().filter(|g| g.symlink_target.is_none() && shared_dir(g).is_some()).collect();
+    if same.is_empty() {
+        println!("✅ 한 폴더 안에서만 겹치는 중복 그룹이 없습니다. (전체 중복 그룹 {}개)", groups.len());
+        return;
+    }
+    // 안정 정렬이므로 회수 가능 공간이 같은 그룹끼리는 원래 순서가 유지됩니다.
+    same.sort_by_key(|g| std::cmp::Reverse(g.wasted_bytes()));
+    let total: u64 = same.iter().map(|g| g.wasted_bytes()).sum();
+    let files: usize = same.iter().map(|g| g.paths.len() - 1).sum();
+    println!(
+        "\n📂 한 폴더 안의 사본: 그룹 {}개, 사본 {}개, 회수 가능 {} (전체 중복 그룹 {}개 중)\n",
+        format_count(same.len()),
+        format_count(files),
+        format_size(total),
+        format_count(groups.len())
+    );
+    if let Some(n) = top.filter(|n| same.len() > *n) {
+        same.truncate(n);
+        println!("(회수 가능한 공간이 가장 큰 상위 {}개 그룹만 표시합니다.)\n", n);
+    }
+    for group in same {
+        let dir = shared_dir(group).unwrap_or(Path::new(""));
+        println!(
+            "--- {} (파일 {}개, 파일당 {}, 회수 가능 {}) ---",
+            path_style::display(dir),
+            group.paths.len(),
+            format_size(group.size),
+            format_size(group.wasted_bytes())
+        );
+        let keeper = keep.keeper(&group.paths);
+        for (i, path) in group.paths.iter().enumerate() {
+            let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+            let mark = if i == keeper { "  ← 유지" } else { "" };
+            println!("  - {}{}", name, mark);
+        }
+        println!();
+    }
+}
+
+/// 멤버가 모두 같은 폴더에 있다면 그 폴더.
+fn shared_dir(group: &DuplicateGroup) -> Option<&Path> {
+    let mut parents = group.paths.iter().map(|path| path.parent());
+    let first = parents.next()??;
+    parents.all(|parent| parent == Some(first)).then_some(first)
+}
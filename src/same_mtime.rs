@@ -0,0 +1,60 @@
+// ==============================================================================
+//  수정 시각까지 같은 파일만 묶기 (--require-same-mtime)
+// ------------------------------------------------------------------------------
+//  기록 보관 업무에서는 내용이 같아도 날짜가 다른 파일을 서로 다른 판(version)으로 보고 함께 보존해야
+//  할 때가 있습니다. (예: 매달 다시 제출한 같은 양식) 이런 파일을 하나로 정리하면 제출 이력이 사라집니다.
+//
+//  --require-same-mtime은 내용으로 찾은 그룹을 멤버들의 수정 시각으로 다시 나누어, 내용과 수정 시각이
+//  모두 같은 파일만 중복으로 남깁니다.
+//  - 수정 시각은 초 단위로 비교합니다. 파일 시스템마다 기록하는 정밀도(ext4는 나노초, exFAT은 10밀리초,
+//    FAT은 2초)가 달라 `cp -p`나 `rsync -t`로 시각을 보존한 사본도 초 아래 자리가 달라질 수 있기
+//    때문입니다. (FAT의 2초 단위는 맞추지 않습니다)
+//  - 수정 시각을 읽을 수 없는 파일은 오류 요약에 남기고 그룹에서 뺍니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde_json::json;
+
+use crate::{DuplicateGroup, ScanError, ScanLog, interrupt, record_error};
+
+/// 비교에 쓸 수정 시각. 유닉스 시각의 초 단위이며, 1970년 이전이면 음수입니다.
+fn modified_secs(path: &Path) -> io::Result<i64> {
+    let modified = fs::symlink_metadata(path)?.modified()?;
+    Ok(match modified.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs_f64().ceil() as i64),
+    })
+}
+
+/// 그룹마다 멤버들을 수정 시각이 같은 것끼리 다시 나누고, 두 개 이상 남은 부분만 그룹으로 돌려줍니다.
+pub fn split_groups(groups: Vec<DuplicateGroup>, errors: &mut Vec<ScanError>, log: &mut ScanLog) -> Vec<DuplicateGroup> {
+    let before = groups.len();
+    let mut split = Vec::new();
+    for group in groups {
+        // 중단 요청을 받았다면 아직 나누지 않은 그룹은 버립니다. 시각을 확인하지 않은 그룹을
+        // 보고하면 날짜가 다른 판을 중복으로 보고하게 됩니다.
+        if interrupt::requested() {
+            break;
+        }
+        let mut by_mtime: HashMap<i64, Vec<_>> = HashMap::new();
+        for path in group.paths.iter() {
+            match modified_secs(path) {
+                Ok(secs) => by_mtime.entry(secs).or_default().push(path.clone()),
+                Err(e) => record_error(errors, log, Some(path.clone()), "수정 시각 읽기", e.kind().to_string(), e.to_string()),
+            }
+        }
+        // 실행마다 같은 순서가 되도록 원래 그룹의 멤버 순서대로 나눈 그룹을 만듭니다.
+        let mut parts: Vec<Vec<_>> = by_mtime.into_values().filter(|paths| paths.len() > 1).collect();
+        parts.sort_by_key(|paths| group.paths.iter().position(|p| p == &paths[0]));
+        for paths in parts {
+            split.push(group.subgroup(paths));
+        }
+    }
+    log.event("stage_end", json!({ "stage": "mtime", "groups_before": before, "duplicate_groups": split.len() }));
+    split
+}
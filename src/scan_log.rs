@@ -0,0 +1,70 @@
+// ==============================================================================
+//  스캔 로그 파일 (--log-file)
+// ------------------------------------------------------------------------------
+//  무인으로 밤새 실행되는 스캔이 나중에 검토할 수 있는 기록을 남기도록,
+//  파일 단위 이벤트, 경고, 단계별 소요 시간을 콘솔 출력과는 별도의 파일에
+//  JSON Lines 형식(한 줄에 JSON 객체 하나)으로 기록합니다.
+//
+//  모든 레코드는 공통 필드를 가집니다.
+//  - ts: 기록 시각 (RFC 3339, UTC)
+//  - elapsed_ms: 스캔 시작 후 경과 시간 (밀리초)
+//  - event: 이벤트 종류 (scan_start, file_found, file_hashed, duplicate_group, warning,
+//    stage_end, scan_end)
+// ==============================================================================
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime};
+
+use serde_json::{Map, Value};
+
+/// 스캔 중 발생한 이벤트를 로그 파일에 기록하는 기록기.
+/// `--log-file`이 지정되지 않았다면 아무 것도 기록하지 않습니다.
+pub struct ScanLog {
+    writer: Option<BufWriter<File>>,
+    started: Instant,
+}
+
+impl ScanLog {
+    /// 아무 것도 기록하지 않는 로그를 만듭니다.
+    pub fn disabled() -> Self {
+        ScanLog { writer: None, started: Instant::now() }
+    }
+
+    /// 지정한 경로에 로그 파일을 만듭니다. 이미 파일이 있다면 덮어씁니다.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(ScanLog {
+            writer: Some(BufWriter::new(File::create(path)?)),
+            started: Instant::now(),
+        })
+    }
+
+    /// 이벤트 하나를 기록합니다. `fields`는 공통 필드 뒤에 덧붙일 JSON 객체입니다.
+    ///
+    /// 로그 기록 실패 때문에 스캔 자체가 중단되어서는 안 되므로, 쓰기 오류는
+    /// 한 번만 경고하고 이후에는 로그를 비활성화합니다.
+    pub fn event(&mut self, event: &str, fields: Value) {
+        let Some(writer) = self.writer.as_mut() else { return };
+
+        let mut record = Map::new();
+        record.insert("ts".into(), humantime::format_rfc3339_millis(SystemTime::now()).to_string().into());
+        record.insert("elapsed_ms".into(), (self.started.elapsed().as_millis() as u64).into());
+        record.insert("event".into(), event.into());
+        if let Value::Object(extra) = fields {
+            record.extend(extra);
+        }
+
+        if let Err(e) = writeln!(writer, "{}", Value::Object(record)) {
+            eprintln!("경고: 로그 파일에 기록할 수 없어 로그를 중단합니다: {}", e);
+            self.writer = None;
+        }
+    }
+
+    /// 버퍼에 남은 내용을 파일에 기록합니다.
+    pub fn flush(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            let _ = writer.flush();
+        }
+    }
+}
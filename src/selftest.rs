@@ -0,0 +1,289 @@
+// ==============================================================================
+//  자체 검사 (selftest 하위 명령)
+// ------------------------------------------------------------------------------
+//  새로 설치한 환경이나 처음 쓰는 파일 시스템에서는 하드 링크, 심볼릭 링크, 희소 파일, UTF-8이 아닌
+//  파일 이름처럼 플랫폼마다 다르게 동작하는 부분이 결과를 틀리게 만들 수 있습니다. 실제 데이터로
+//  확인하기 전에, 답을 미리 아는 폴더로 검색기를 검사합니다.
+//
+//  `selftest`는 임시 폴더(또는 --dir)에 다음 파일들을 만들고 검색한 뒤, 찾은 그룹이 예상과 정확히
+//  같은지 확인합니다.
+//  - 같은 내용의 사본 3개 (폴더와 이름이 다름) → 그룹 하나
+//  - 크기는 같지만 내용이 다른 파일, 앞뒤는 같고 가운데만 다른 큰 파일, 빈 파일 → 그룹 없음
+//  - 하드 링크 한 쌍과 그 내용의 사본 → 하드 링크는 파일 하나로 세어 2개짜리 그룹,
+//    --isolate로는 3개짜리 그룹
+//  - 구멍이 있는 희소 파일 2개와 0으로 채운 보통 파일 → 그룹 하나
+//  - 사본을 가리키는 심볼릭 링크 → 어느 그룹에도 들어가지 않음 (유닉스)
+//  - UTF-8이 아닌 이름의 사본 2개 → 그룹 하나 (유닉스)
+//  플랫폼이나 파일 시스템이 지원하지 않아 만들 수 없는 항목은 그 이유와 함께 건너뜁니다.
+//  검사가 끝나면 폴더를 지웁니다. (--keep으로 남길 수 있습니다)
+// ==============================================================================
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::stages::Pipeline;
+use crate::{
+    DuplicateGroup, FilterMode, HashAlgorithm, MatchMode, Progress, ScanLog, ScanOptions, default_threads, find_duplicates,
+    prepare_roots, reader_pool,
+};
+
+/// 큰 파일의 크기. 앞뒤 샘플(--stages sample)보다 커야 가운데만 다른 파일을 만들 수 있습니다.
+const LARGE: usize = 1024 * 1024;
+
+/// 검색에서 찾아야 하는 그룹 하나.
+struct Expected {
+    what: &'static str,
+    /// 반드시 들어 있어야 하는 멤버 (루트에 대한 상대 경로).
+    paths: Vec<PathBuf>,
+    /// 이 중 정확히 하나만 들어 있어야 하는 멤버. (하드 링크 쌍)
+    one_of: Vec<PathBuf>,
+}
+
+impl Expected {
+    fn new(what: &'static str, paths: &[PathBuf]) -> Self {
+        Expected { what, paths: paths.to_vec(), one_of: Vec::new() }
+    }
+
+    fn matches(&self, found: &BTreeSet<PathBuf>) -> bool {
+        let required: BTreeSet<PathBuf> = self.paths.iter().cloned().collect();
+        let rest: Vec<&PathBuf> = found.difference(&required).collect();
+        required.is_subset(found)
+            && if self.one_of.is_empty() { rest.is_empty() } else { rest.len() == 1 && self.one_of.contains(rest[0]) }
+    }
+}
+
+/// 만든 검사 폴더와 각 검색에서 찾아야 하는 그룹들.
+#[derive(Default)]
+struct Fixture {
+    /// 두 검색 모두에서 찾아야 하는 그룹.
+    expected: Vec<Expected>,
+    /// 기본 검색에서만 찾아야 하는 그룹.
+    default_only: Vec<Expected>,
+    /// --isolate 검색에서만 찾아야 하는 그룹. 하드 링크를 만들지 못했다면 비어 있고, 그때는
+    /// --isolate 검색을 하지 않습니다.
+    isolated_only: Vec<Expected>,
+    /// 만들 수 없어 건너뛴 항목과 그 이유.
+    skipped: Vec<(&'static str, String)>,
+}
+
+/// `selftest`: 검사 폴더를 만들고 검색 결과를 확인합니다.
+pub fn run(dir: Option<&Path>, keep: bool) -> io::Result<ExitCode> {
+    let root = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::temp_dir().join(format!("duplicate_finder-selftest-{}", std::process::id())),
+    };
+    // 사용자의 파일과 섞이거나 지우지 않도록 새 폴더에만 만듭니다.
+    if root.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}'이(가) 이미 있습니다. 없는 폴더를 지정하세요", root.display()),
+        ));
+    }
+    fs::create_dir_all(&root)?;
+    println!("🧪 검사 폴더: {}", root.display());
+    let result = build(&root).and_then(|fixture| check(&root, &fixture));
+    if keep {
+        println!("📂 검사 폴더를 남겨 두었습니다: {}", root.display());
+    } else if let Err(e) = fs::remove_dir_all(&root) {
+        eprintln!("⚠️  검사 폴더 '{}'을(를) 지우지 못했습니다: {}", root.display(), e);
+    }
+    let passed = result?;
+    Ok(if passed { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+/// `root` 아래의 상대 경로에 내용을 씁니다.
+fn write(root: &Path, relative: &Path, content: &[u8]) -> io::Result<PathBuf> {
+    let path = root.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, content)?;
+    Ok(relative.to_path_buf())
+}
+
+/// 검사 폴더의 파일들을 만들고, 찾아야 하는 그룹들을 돌려줍니다.
+fn build(root: &Path) -> io::Result<Fixture> {
+    let mut fixture = Fixture::default();
+    let text = "selftest duplicate\n".repeat(100);
+
+    // 같은 내용의 사본 3개.
+    let copies = [
+        write(root, Path::new("copies/a.txt"), text.as_bytes())?,
+        write(root, Path::new("copies/nested/a.txt"), text.as_bytes())?,
+        write(root, Path::new("copies/b.bin"), text.as_bytes())?,
+    ];
+    fixture.expected.push(Expected::new("같은 내용의 사본 3개", &copies));
+
+    // 그룹이 되면 안 되는 파일들: 크기만 같은 파일, 가운데만 다른 큰 파일, 빈 파일.
+    write(root, Path::new("distinct/same_size_1"), b"aaaa")?;
+    write(root, Path::new("distinct/same_size_2"), b"bbbb")?;
+    let mut large = vec![7u8; LARGE];
+    write(root, Path::new("distinct/middle_1"), &large)?;
+    large[LARGE / 2] = 8;
+    write(root, Path::new("distinct/middle_2"), &large)?;
+    write(root, Path::new("distinct/empty_1"), b"")?;
+    write(root, Path::new("distinct/empty_2"), b"")?;
+
+    // 하드 링크 쌍과 그 내용의 사본.
+    let original = write(root, Path::new("hardlinks/original"), b"hard link content\n")?;
+    let linked = PathBuf::from("hardlinks/linked");
+    match fs::hard_link(root.join(&original), root.join(&linked)) {
+        Ok(()) => {
+            let copy = write(root, Path::new("hardlinks/copy"), b"hard link content\n")?;
+            fixture.default_only.push(Expected {
+                what: "하드 링크는 파일 하나로 셈",
+                paths: vec![copy.clone()],
+                one_of: vec![original.clone(), linked.clone()],
+            });
+            fixture.isolated_only.push(Expected::new("--isolate는 하드 링크를 각각 셈", &[original, linked, copy]));
+        }
+        Err(e) => {
+            fs::remove_file(root.join(&original))?;
+            fixture.skipped.push(("하드 링크", e.to_string()));
+        }
+    }
+
+    // 구멍이 있는 희소 파일 2개와, 같은 내용을 실제로 쓴 파일.
+    let mut sparse = Vec::new();
+    for name in ["sparse/hole_1", "sparse/hole_2"] {
+        let path = root.join(name);
+        fs::create_dir_all(root.join("sparse"))?;
+        let mut file = File::create(&path)?;
+        // 끝의 한 바이트만 쓰면 앞부분은 디스크에 할당되지 않은 구멍으로 남습니다.
+        file.seek(SeekFrom::Start(LARGE as u64 - 1))?;
+        file.write_all(&[0])?;
+        sparse.push(PathBuf::from(name));
+    }
+    sparse.push(write(root, Path::new("sparse/dense"), &vec![0u8; LARGE])?);
+    fixture.expected.push(Expected::new("희소 파일과 0으로 채운 파일", &sparse));
+
+    symlink(root, &mut fixture)?;
+    non_utf8_names(root, &text, &mut fixture)?;
+    Ok(fixture)
+}
+
+/// 사본을 가리키는 심볼릭 링크. 기본 검색은 링크를 따라가지 않으므로 사본 그룹이 그대로여야 합니다.
+#[cfg(unix)]
+fn symlink(root: &Path, fixture: &mut Fixture) -> io::Result<()> {
+    fs::create_dir_all(root.join("symlinks"))?;
+    if let Err(e) = std::os::unix::fs::symlink("../copies/a.txt", root.join("symlinks/to_copy")) {
+        fixture.skipped.push(("심볼릭 링크", e.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn symlink(_root: &Path, fixture: &mut Fixture) -> io::Result<()> {
+    fixture.skipped.push(("심볼릭 링크", String::from("이 플랫폼에서는 검사하지 않습니다")));
+    Ok(())
+}
+
+/// UTF-8이 아닌 이름(Latin-1로 쓴 `café`와 잘못된 바이트)의 사본 2개.
+/// macOS(APFS)처럼 이런 이름을 거부하는 파일 시스템에서는 건너뜁니다.
+#[cfg(unix)]
+fn non_utf8_names(root: &Path, text: &str, fixture: &mut Fixture) -> io::Result<()> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    fs::create_dir_all(root.join("names"))?;
+    let names = [Path::new("names").join(OsStr::from_bytes(b"caf\xe9.txt")), Path::new("names").join(OsStr::from_bytes(b"\xff\xfe.txt"))];
+    let content = format!("{}non-utf-8\n", text);
+    for name in &names {
+        if let Err(e) = fs::write(root.join(name), &content) {
+            fixture.skipped.push(("UTF-8이 아닌 파일 이름", e.to_string()));
+            return Ok(());
+        }
+    }
+    fixture.expected.push(Expected::new("UTF-8이 아닌 파일 이름", &names));
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn non_utf8_names(_root: &Path, _text: &str, fixture: &mut Fixture) -> io::Result<()> {
+    fixture.skipped.push(("UTF-8이 아닌 파일 이름", String::from("이 플랫폼에서는 검사하지 않습니다")));
+    Ok(())
+}
+
+/// 기본 검색과 --isolate 검색을 실행하여 결과를 확인하고, 모두 통과했는지 돌려줍니다.
+fn check(root: &Path, fixture: &Fixture) -> io::Result<bool> {
+    let mut failures = 0;
+    let mut passes = 0;
+    for (isolate, only) in [(false, &fixture.default_only), (true, &fixture.isolated_only)] {
+        if isolate && only.is_empty() {
+            continue;
+        }
+        let title = if isolate { "--isolate 검색" } else { "기본 검색" };
+        let groups = scan(root, isolate)?;
+        println!("\n{}: 그룹 {}개", title, groups.len());
+        let mut unmatched: Vec<BTreeSet<PathBuf>> = groups;
+        for group in fixture.expected.iter().chain(only) {
+            match unmatched.iter().position(|found| group.matches(found)) {
+                Some(i) => {
+                    unmatched.remove(i);
+                    passes += 1;
+                    println!("  ✅ {}", group.what);
+                }
+                None => {
+                    failures += 1;
+                    println!("  ❌ {}: 예상한 그룹을 찾지 못했습니다", group.what);
+                }
+            }
+        }
+        for found in unmatched {
+            failures += 1;
+            println!("  ❌ 예상하지 못한 그룹:");
+            for path in found {
+                println!("      - {}", path.display());
+            }
+        }
+    }
+    for (what, reason) in &fixture.skipped {
+        println!("  ⏭️  {}: 만들 수 없어 건너뛰었습니다 ({})", what, reason);
+    }
+    println!("\n결과: 통과 {}개, 실패 {}개, 건너뜀 {}개", passes, failures, fixture.skipped.len());
+    Ok(failures == 0)
+}
+
+/// 검사 폴더를 검색하여 그룹마다 멤버의 상대 경로들을 돌려줍니다.
+fn scan(root: &Path, isolate: bool) -> io::Result<Vec<BTreeSet<PathBuf>>> {
+    let roots = prepare_roots(&[root.to_path_buf()])?;
+    let options = ScanOptions {
+        filter_mode: FilterMode::None,
+        created_after: None,
+        created_before: None,
+        include_symlinks: false,
+        strict: false,
+        normalize_names: false,
+        threads: default_threads(),
+        isolate_hardlinks: isolate,
+        hydrate: false,
+        device_limits: None,
+        fd_budget: reader_pool::default_fd_budget(),
+        xattr_cache: false,
+        compare_attrs: false,
+        require_same_mtime: false,
+        imported_hashes: None,
+        ignored_hashes: None,
+        match_mode: MatchMode::Content,
+        stages: Pipeline::default(),
+        algorithm: HashAlgorithm::Sha256,
+        extra_hash: None,
+        bloom_prepass: None,
+        spotlight: false,
+        spill: None,
+        max_memory: None,
+        checkpoint: None,
+    };
+    let outcome = find_duplicates(&roots, &options, &mut ScanLog::disabled(), &mut Progress::new(), None)?;
+    if let Some(error) = outcome.errors.first() {
+        return Err(io::Error::other(format!("검사 폴더를 읽지 못했습니다: {}", error.message)));
+    }
+    Ok(outcome.duplicates.iter().map(|group: &DuplicateGroup| relative(&roots[0], &group.paths)).collect())
+}
+
+fn relative(root: &Path, paths: &[PathBuf]) -> BTreeSet<PathBuf> {
+    paths.iter().map(|path| path.strip_prefix(root).unwrap_or(path).to_path_buf()).collect()
+}
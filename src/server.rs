@@ -0,0 +1,545 @@
+// ==============================================================================
+//  REST API 서버 (`serve` 하위 명령)
+// ------------------------------------------------------------------------------
+//  웹 UI나 다른 서비스가 CLI를 실행하고 텍스트 출력을 해석하는 대신, HTTP로 직접
+//  검색 엔진을 사용할 수 있도록 합니다. 모든 요청과 응답 본문은 JSON입니다.
+//
+//  POST /scans                 검색 시작. 본문: {"roots": [...], "filter": "*.jpg", ...}
+//                              응답: {"id": 1}
+//  GET  /scans                 모든 검색의 상태 목록
+//  GET  /scans/{id}            검색 상태 (진행 단계, 처리 수, 완료 후 요약)
+//  GET  /scans/{id}/progress   검색이 끝날 때까지 상태를 한 줄에 하나씩(NDJSON) 스트리밍
+//  GET  /scans/{id}/groups     찾은 중복 그룹 (--save-results와 같은 형식)
+//  POST /scans/{id}/actions    그룹 멤버 삭제. 본문: {"delete": ["/path/a", ...]}
+//                              "prune_empty_dirs": "remove" 또는 "dry-run"을 더하면 삭제로 비게 된
+//                              폴더를 검색 루트 안에서 정리(또는 목록만 보고)합니다.
+//
+//  삭제 요청도 CLI의 TUI와 같은 안전장치를 따릅니다. 위험한 루트(`/`, 홈 폴더 전체,
+//  마운트 루트)는 `--force-dangerous-root` 없이는 거부하고, 각 그룹에서 최소 한 개의
+//  파일은 남기며, 검색 이후 내용이 바뀐 파일은 삭제하지 않습니다.
+//
+//  서버는 파일을 지울 수 있으므로 모든 요청에 접근 조건을 확인합니다(`Access`).
+//  - `--token`(또는 DUPLICATE_FINDER_TOKEN 환경 변수)을 주면 모든 요청에
+//    `Authorization: Bearer <토큰>`이 있어야 합니다. 토큰 없이는 루프백 주소에서만 열 수 있습니다.
+//  - 요청 본문은 `Content-Type: application/json`이어야 합니다. 브라우저는 이 형식의 교차 출처
+//    요청을 사전 확인(preflight) 없이 보내지 못하므로, 다른 웹 페이지가 몰래 검색이나 삭제를 시작할 수 없습니다.
+//  - `Origin` 헤더가 있는 요청은 `--allow-origin`으로 허용한 출처에서 온 것만 받습니다.
+//  - 토큰이 없으면 `Host`가 루프백 이름인 요청만 받아, DNS 리바인딩으로 우회하는 것도 막습니다.
+// ==============================================================================
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::net::ToSocketAddrs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::actions::PruneMode;
+use crate::stages::Pipeline;
+use crate::progress::{Progress, ProgressSnapshot};
+use crate::{
+    DuplicateGroup, FilterMode, HashAlgorithm, MatchMode, ScanLog, ScanOptions, ScanSummary, SavedResults, actions, default_threads,
+    find_duplicates, interrupt, prepare_roots, reader_pool, safety, sort_groups,
+};
+
+/// 중단 요청을 확인하기 위해 요청 대기를 잠시 멈추는 간격.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 진행 상황 스트림에서 상태를 내보내는 간격.
+const STREAM_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `POST /scans`의 요청 본문. 필드 의미는 같은 이름의 CLI 옵션과 같습니다.
+#[derive(Deserialize)]
+struct ScanRequest {
+    roots: Vec<PathBuf>,
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    symlinks: bool,
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    normalize_names: bool,
+    #[serde(default)]
+    xattr_cache: bool,
+    #[serde(default)]
+    compare_attrs: bool,
+    #[serde(default)]
+    require_same_mtime: bool,
+}
+
+/// `POST /scans/{id}/actions`의 요청 본문.
+#[derive(Deserialize)]
+struct ActionRequest {
+    /// 삭제할 그룹 멤버 경로들.
+    delete: Vec<PathBuf>,
+    /// 삭제 뒤에 비게 된 폴더를 정리하는 방식. 없으면 정리하지 않습니다.
+    #[serde(default)]
+    prune_empty_dirs: Option<PruneMode>,
+}
+
+/// 검색 작업 하나의 진행 단계.
+enum JobState {
+    Running,
+    Finished { groups: Vec<DuplicateGroup>, errors: usize, interrupted: bool },
+    Failed(String),
+}
+
+/// 서버가 관리하는 검색 작업 하나.
+struct Job {
+    roots: Vec<PathBuf>,
+    started: Instant,
+    duration: Option<Duration>,
+    progress: Arc<Mutex<ProgressSnapshot>>,
+    state: JobState,
+    /// 위험한 루트를 검색했다면 삭제를 거부하는 이유.
+    delete_blocked: Option<String>,
+    /// 삭제 요청을 처리하는 중인지. 삭제는 잠금 밖에서 하므로 한 번에 하나만 받습니다.
+    deleting: bool,
+}
+
+/// 모든 요청 처리 스레드가 공유하는 서버 상태.
+struct ServerState {
+    jobs: Mutex<BTreeMap<u64, Job>>,
+    next_id: Mutex<u64>,
+    force_dangerous_root: bool,
+    access: Access,
+}
+
+/// 요청 처리 결과: (HTTP 상태 코드, JSON 본문).
+pub type Reply = (u16, Value);
+
+/// `--token`을 주지 않았을 때 토큰을 읽는 환경 변수. 명령줄과 달리 다른 사용자의 `ps`에 보이지 않습니다.
+pub const TOKEN_ENV: &str = "DUPLICATE_FINDER_TOKEN";
+
+/// `--token` 값이 없으면 환경 변수에서 토큰을 읽습니다. 빈 문자열은 토큰이 없는 것으로 봅니다.
+pub fn resolve_token(flag: Option<&str>) -> Option<String> {
+    flag.map(str::to_string).or_else(|| std::env::var(TOKEN_ENV).ok()).filter(|t| !t.is_empty())
+}
+
+/// 요청을 처리하기 전에 확인하는 접근 조건. `serve`와 `coordinator`가 함께 씁니다.
+pub struct Access {
+    token: Option<String>,
+    allowed_origins: Vec<String>,
+}
+
+impl Access {
+    /// 접근 조건을 만듭니다. 토큰 없이 루프백이 아닌 주소에서 요청을 받으려 하면 거부합니다.
+    pub fn new(listen: &str, token: Option<String>, allowed_origins: Vec<String>) -> io::Result<Access> {
+        if token.is_none() && !is_loopback(listen) {
+            return Err(io::Error::other(format!(
+                "루프백이 아닌 주소({})에서 요청을 받으려면 --token 또는 {} 환경 변수로 토큰을 지정해야 합니다",
+                listen, TOKEN_ENV
+            )));
+        }
+        let allowed_origins = allowed_origins.into_iter().map(|o| o.trim_end_matches('/').to_ascii_lowercase()).collect();
+        Ok(Access { token, allowed_origins })
+    }
+
+    /// 토큰, `Origin`, (토큰이 없을 때) `Host`를 확인합니다. 실패하면 보낼 응답을 돌려줍니다.
+    pub fn check(&self, request: &Request) -> Result<(), Reply> {
+        if let Some(origin) = header(request, "Origin") {
+            let origin = origin.trim_end_matches('/').to_ascii_lowercase();
+            if !self.allowed_origins.contains(&origin) {
+                return Err((403, json!({ "error": format!("허용되지 않은 출처입니다: {}", origin) })));
+            }
+        }
+        match &self.token {
+            Some(token) => {
+                let given = header(request, "Authorization").and_then(|v| v.strip_prefix("Bearer ")).unwrap_or("");
+                if !constant_time_eq(given.trim().as_bytes(), token.as_bytes()) {
+                    return Err((401, json!({ "error": "Authorization: Bearer 토큰이 없거나 올바르지 않습니다" })));
+                }
+            }
+            None => {
+                if !header(request, "Host").is_some_and(is_loopback_host) {
+                    return Err((403, json!({ "error": "토큰 없이 실행한 서버는 루프백 주소로 온 요청만 받습니다" })));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 요청 본문의 형식이 `expected`인지 확인합니다. (`; charset=utf-8` 같은 매개변수는 무시)
+pub fn check_content_type(request: &Request, expected: &str) -> Result<(), Reply> {
+    let media_type = header(request, "Content-Type").and_then(|v| v.split(';').next()).map(str::trim);
+    match media_type {
+        Some(media_type) if media_type.eq_ignore_ascii_case(expected) => Ok(()),
+        _ => Err((415, json!({ "error": format!("요청 본문은 Content-Type: {}이어야 합니다", expected) }))),
+    }
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.as_str())
+}
+
+/// 주소가 가리키는 모든 IP가 루프백인지. 주소를 해석할 수 없으면 아니라고 봅니다.
+fn is_loopback(listen: &str) -> bool {
+    listen.to_socket_addrs().map(|addrs| addrs.collect::<Vec<_>>()).is_ok_and(|addrs| {
+        !addrs.is_empty() && addrs.iter().all(|a| a.ip().is_loopback())
+    })
+}
+
+/// `Host` 헤더 값(포트 포함 가능)이 루프백 이름인지.
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(""),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// 토큰 비교에 걸리는 시간이 일치하는 앞부분의 길이에 따라 달라지지 않도록 모든 바이트를 비교합니다.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 서버를 시작하고, Ctrl+C(또는 SIGTERM)를 받을 때까지 요청을 처리합니다.
+pub fn run(listen: &str, force_dangerous_root: bool, access: Access) -> io::Result<ExitCode> {
+    let server = Server::http(listen).map_err(io::Error::other)?;
+    let state = Arc::new(ServerState {
+        jobs: Mutex::new(BTreeMap::new()),
+        next_id: Mutex::new(1),
+        force_dangerous_root,
+        access,
+    });
+    interrupt::install_handler();
+    println!("🌐 API 서버가 http://{} 에서 요청을 기다립니다. (Ctrl+C로 종료)", listen);
+
+    while !interrupt::requested() {
+        let Some(request) = server.recv_timeout(POLL_INTERVAL)? else { continue };
+        // 진행 상황 스트림처럼 오래 걸리는 요청이 다른 요청을 막지 않도록 요청마다 스레드를 씁니다.
+        let state = Arc::clone(&state);
+        thread::spawn(move || handle(request, &state));
+    }
+    println!("\n서버를 종료합니다.");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 요청 하나를 경로에 따라 처리하고 응답합니다.
+fn handle(mut request: Request, state: &Arc<ServerState>) {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let segments: Vec<&str> = url.split('?').next().unwrap_or("").split('/').filter(|s| !s.is_empty()).collect();
+
+    if let Err(reply) = state.access.check(&request) {
+        respond(request, reply);
+        return;
+    }
+
+    // 진행 상황 스트림은 JSON 한 덩어리가 아니라, 연결을 닫을 때까지 이어지는 본문으로 응답합니다.
+    if let (Method::Get, ["scans", id, "progress"]) = (&method, segments.as_slice()) {
+        match id.parse::<u64>().ok().filter(|id| lock(&state.jobs).contains_key(id)) {
+            Some(id) => {
+                let _ = stream_progress(request.into_writer(), state, id);
+            }
+            None => respond(request, (404, json!({ "error": "검색을 찾을 수 없습니다" }))),
+        }
+        return;
+    }
+
+    let reply = match (&method, segments.as_slice()) {
+        (Method::Post, ["scans"]) => match read_json::<ScanRequest>(&mut request) {
+            Ok(body) => start_scan(state, body),
+            Err(reply) => reply,
+        },
+        (Method::Get, ["scans"]) => {
+            let jobs = lock(&state.jobs);
+            (200, Value::Array(jobs.iter().map(|(id, job)| job_status(*id, job)).collect()))
+        }
+        (Method::Get, ["scans", id]) => with_job(state, id, |id, job| (200, job_status(id, job))),
+        (Method::Get, ["scans", id, "groups"]) => with_job(state, id, |_, job| match &job.state {
+            JobState::Finished { groups, interrupted, .. } => {
+                (200, json!(SavedResults::new(&job.roots, groups, *interrupted, HashAlgorithm::Sha256)))
+            }
+            _ => (409, json!({ "error": "검색이 아직 끝나지 않았거나 실패했습니다" })),
+        }),
+        (Method::Post, ["scans", id, "actions"]) => match read_json::<ActionRequest>(&mut request) {
+            Ok(body) => delete_members(state, id, body.delete, body.prune_empty_dirs),
+            Err(reply) => reply,
+        },
+        _ => (404, json!({ "error": "알 수 없는 경로입니다" })),
+    };
+    respond(request, reply);
+}
+
+/// 새 검색을 백그라운드 스레드에서 시작합니다.
+fn start_scan(state: &Arc<ServerState>, body: ScanRequest) -> Reply {
+    if body.roots.is_empty() {
+        return (400, json!({ "error": "roots에 검색할 폴더를 하나 이상 지정해야 합니다" }));
+    }
+    let roots = match prepare_roots(&body.roots) {
+        Ok(roots) => roots,
+        Err(e) => return (400, json!({ "error": e.to_string() })),
+    };
+    let options = ScanOptions {
+        filter_mode: FilterMode::from_arg(body.filter),
+        created_after: None,
+        created_before: None,
+        include_symlinks: body.symlinks,
+        strict: body.strict,
+        normalize_names: body.normalize_names,
+        threads: default_threads(),
+        isolate_hardlinks: false,
+        hydrate: false,
+        device_limits: None,
+        fd_budget: reader_pool::default_fd_budget(),
+        xattr_cache: body.xattr_cache,
+        compare_attrs: body.compare_attrs,
+        require_same_mtime: body.require_same_mtime,
+        imported_hashes: None,
+        ignored_hashes: None,
+        match_mode: MatchMode::Content,
+        stages: Pipeline::default(),
+        algorithm: HashAlgorithm::Sha256,
+        extra_hash: None,
+        bloom_prepass: None,
+        spotlight: false,
+        spill: None,
+        max_memory: None,
+        checkpoint: None,
+    };
+    let delete_blocked = safety::check_roots(&roots, state.force_dangerous_root)
+        .err()
+        .map(|reasons| format!("위험한 루트를 검색했으므로 삭제가 비활성화되어 있습니다: {}", reasons.join(", ")));
+
+    let id = {
+        let mut next_id = lock(&state.next_id);
+        *next_id += 1;
+        *next_id - 1
+    };
+    let progress = Arc::new(Mutex::new(ProgressSnapshot::default()));
+    lock(&state.jobs).insert(
+        id,
+        Job {
+            roots: roots.clone(),
+            started: Instant::now(),
+            duration: None,
+            progress: Arc::clone(&progress),
+            state: JobState::Running,
+            delete_blocked,
+            deleting: false,
+        },
+    );
+
+    let state = Arc::clone(state);
+    thread::spawn(move || {
+        let mut progress = Progress::shared(progress);
+        let result = find_duplicates(&roots, &options, &mut ScanLog::disabled(), &mut progress, None);
+        let mut jobs = lock(&state.jobs);
+        let Some(job) = jobs.get_mut(&id) else { return };
+        job.duration = Some(job.started.elapsed());
+        job.state = match result {
+            Ok(outcome) if outcome.strict_abort => JobState::Failed(format!(
+                "--strict: 처리할 수 없는 항목을 만나 검색을 중단했습니다 ({}개 오류)",
+                outcome.errors.len()
+            )),
+            Ok(mut outcome) => {
+                sort_groups(&mut outcome.duplicates);
+                JobState::Finished {
+                    groups: outcome.duplicates,
+                    errors: outcome.errors.len(),
+                    interrupted: outcome.interrupted,
+                }
+            }
+            Err(e) => JobState::Failed(e.to_string()),
+        };
+    });
+    (201, json!({ "id": id }))
+}
+
+/// 검색 작업의 현재 상태를 JSON으로 만듭니다.
+fn job_status(id: u64, job: &Job) -> Value {
+    let progress = lock(&job.progress).clone();
+    let mut status = json!({
+        "id": id,
+        "roots": job.roots.iter().map(|r| r.display().to_string()).collect::<Vec<_>>(),
+        "elapsed_ms": job.duration.unwrap_or_else(|| job.started.elapsed()).as_millis() as u64,
+        "progress": {
+            "stage": progress.stage,
+            "done": progress.done,
+            "total": progress.total,
+            "bytes": progress.bytes,
+            "total_bytes": progress.total_bytes,
+            "current": progress.current,
+        },
+    });
+    match &job.state {
+        JobState::Running => status["state"] = json!("running"),
+        JobState::Finished { groups, errors, interrupted } => {
+            let summary = ScanSummary::of(groups);
+            status["state"] = json!("finished");
+            status["summary"] = json!({
+                "groups": summary.groups,
+                "duplicate_files": summary.files,
+                "wasted_bytes": summary.wasted_bytes,
+                "file_errors": errors,
+                "interrupted": interrupted,
+            });
+        }
+        JobState::Failed(message) => {
+            status["state"] = json!("failed");
+            status["error"] = json!(message);
+        }
+    }
+    status
+}
+
+/// 완료된 검색의 그룹 멤버들을 삭제하고, 삭제한 경로와 건너뛴 경로를 보고합니다.
+/// `prune`이 있으면 비게 된 폴더도 정리하여 함께 보고합니다.
+///
+/// 삭제 전에 파일을 다시 해시하므로 오래 걸릴 수 있습니다. 그동안 다른 요청이 작업 목록을 볼 수 있도록
+/// 그룹을 복사해 잠금을 풀고 삭제한 뒤, 다시 잠가 결과를 반영합니다.
+fn delete_members(state: &ServerState, id: &str, targets: Vec<PathBuf>, prune: Option<PruneMode>) -> Reply {
+    let Some(id) = id.parse::<u64>().ok() else {
+        return (404, json!({ "error": "검색을 찾을 수 없습니다" }));
+    };
+    let (mut groups, roots) = {
+        let mut jobs = lock(&state.jobs);
+        let Some(job) = jobs.get_mut(&id) else {
+            return (404, json!({ "error": "검색을 찾을 수 없습니다" }));
+        };
+        if let Some(reason) = &job.delete_blocked {
+            return (403, json!({ "error": reason }));
+        }
+        if job.deleting {
+            return (409, json!({ "error": "다른 삭제 요청을 처리하는 중입니다" }));
+        }
+        let JobState::Finished { groups, .. } = &job.state else {
+            return (409, json!({ "error": "검색이 아직 끝나지 않았거나 실패했습니다" }));
+        };
+        job.deleting = true;
+        (groups.clone(), job.roots.clone())
+    };
+
+    let result = actions::delete_members(&mut groups, &targets.into_iter().collect());
+
+    // 삭제는 한 번에 하나뿐이므로 그동안 그룹을 바꾼 요청은 없습니다. 삭제한 결과로 바꿔 넣습니다.
+    if let Some(job) = lock(&state.jobs).get_mut(&id) {
+        job.deleting = false;
+        if result.is_ok()
+            && let JobState::Finished { groups: current, .. } = &mut job.state
+        {
+            *current = groups;
+        }
+    }
+
+    match result {
+        Ok(report) => {
+            let mut reply = json!({
+                "deleted": report.deleted.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+                "skipped": report
+                    .skipped
+                    .iter()
+                    .map(|(path, reason)| json!({ "path": path.display().to_string(), "reason": reason }))
+                    .collect::<Vec<_>>(),
+            });
+            if let Some(mode) = prune {
+                let pruned = actions::prune_empty_dirs(&report.deleted, &roots, mode);
+                reply["pruned_dirs"] = json!(pruned.removed.iter().map(|p| p.display().to_string()).collect::<Vec<_>>());
+                reply["prune_failed"] = json!(
+                    pruned
+                        .failed
+                        .iter()
+                        .map(|(path, reason)| json!({ "path": path.display().to_string(), "reason": reason }))
+                        .collect::<Vec<_>>()
+                );
+            }
+            (200, reply)
+        }
+        Err(message) => (400, json!({ "error": message })),
+    }
+}
+
+/// URL의 검색 번호로 작업을 찾아 `f`를 실행합니다. 없다면 404를 반환합니다.
+fn with_job(state: &ServerState, id: &str, f: impl FnOnce(u64, &Job) -> Reply) -> Reply {
+    let jobs = lock(&state.jobs);
+    match id.parse::<u64>().ok().and_then(|id| jobs.get(&id).map(|job| (id, job))) {
+        Some((id, job)) => f(id, job),
+        None => (404, json!({ "error": "검색을 찾을 수 없습니다" })),
+    }
+}
+
+/// 요청 본문을 JSON으로 해석합니다. 실패하면 400 응답을 반환합니다.
+fn read_json<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, Reply> {
+    check_content_type(request, "application/json")?;
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| (400, json!({ "error": e.to_string() })))?;
+    serde_json::from_str(&body).map_err(|e| (400, json!({ "error": format!("잘못된 요청 본문: {}", e) })))
+}
+
+pub fn respond(request: Request, (status, body): Reply) {
+    let response = Response::from_string(format!("{}\n", body))
+        .with_status_code(status)
+        .with_header(json_header("application/json"));
+    let _ = request.respond(response);
+}
+
+fn json_header(content_type: &str) -> Header {
+    Header::from_bytes("Content-Type", content_type).expect("정적인 헤더는 항상 올바릅니다")
+}
+
+/// 뮤텍스를 잠급니다. 다른 스레드가 패닉으로 잠금을 오염시켰더라도 상태는 계속 사용합니다.
+pub fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `GET /scans/{id}/progress`: 검색이 끝날 때까지 일정 간격으로 상태를 한 줄씩 내보내고,
+/// 마지막 상태를 내보낸 뒤 연결을 닫습니다.
+///
+/// 각 줄이 곧바로 클라이언트에 도착해야 하므로, 응답을 버퍼링하는 tiny_http의 `Response`
+/// 대신 연결에 직접 쓰고 줄마다 flush합니다. 본문의 끝은 연결 종료로 알립니다.
+fn stream_progress(mut writer: Box<dyn Write + Send>, state: &ServerState, id: u64) -> io::Result<()> {
+    write!(writer, "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n")?;
+    loop {
+        let (status, done) = {
+            let jobs = lock(&state.jobs);
+            let Some(job) = jobs.get(&id) else { return Ok(()) };
+            (job_status(id, job), !matches!(job.state, JobState::Running))
+        };
+        writeln!(writer, "{}", status)?;
+        writer.flush()?;
+        if done {
+            return Ok(());
+        }
+        thread::sleep(STREAM_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_hosts() {
+        assert!(is_loopback_host("localhost:8080"));
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("[::1]:8080"));
+        assert!(!is_loopback_host("evil.example:8080"));
+        assert!(!is_loopback_host("127.0.0.1.evil.example"));
+    }
+
+    #[test]
+    fn non_loopback_listen_needs_token() {
+        assert!(Access::new("127.0.0.1:0", None, Vec::new()).is_ok());
+        assert!(Access::new("0.0.0.0:0", None, Vec::new()).is_err());
+        assert!(Access::new("0.0.0.0:0", Some("t".into()), Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn token_comparison() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
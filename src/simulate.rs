@@ -0,0 +1,253 @@
+// ==============================================================================
+//  정리 방법별 회수 공간 시뮬레이션 (--simulate)
+// ------------------------------------------------------------------------------
+//  "중복 바이트"(크기 × (사본 수 - 1))는 실제로 돌려받는 공간과 다를 수 있습니다.
+//
+//  - 이미 다른 하드 링크가 있는 파일은 경로 하나를 지워도 데이터가 남습니다.
+//  - 하드 링크와 reflink는 같은 볼륨 안에서만 만들 수 있으므로, 다른 볼륨의 사본은
+//    볼륨마다 하나씩 남겨야 합니다. reflink는 파일 시스템(btrfs, xfs 등)이 지원해야 합니다.
+//  - 디스크는 블록 단위로 할당하므로, 작은 파일은 논리 크기보다 많은(또는 인라인 저장으로
+//    전혀 없는) 공간을 차지합니다. 그래서 논리 크기 대신 실제 할당 크기로 계산합니다.
+//  - btrfs, XFS에서 이미 reflink로 합친 사본은 원본과 같은 익스텐트를 가리키므로, 어떤 방법으로
+//    정리해도 공간이 돌아오지 않습니다. FIEMAP으로 배치가 같은지 확인하여 계산에서 뺍니다. (extents.rs)
+//
+//  이 모듈은 파일을 바꾸지 않고, 각 방법(삭제, 하드 링크, reflink)을 적용했을 때
+//  볼륨별로 실제로 회수되는 공간을 계산해 보여줍니다.
+// ==============================================================================
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::extents::{self, Layout};
+use crate::keep::KeepRule;
+use crate::{DuplicateGroup, format_size, safety};
+
+/// 멤버 한 개의 디스크 상태.
+struct Member<'a> {
+    path: &'a Path,
+    /// 파일이 있는 볼륨(장치 번호).
+    device: u64,
+    /// 이 파일(아이노드)을 가리키는 경로 수.
+    links: u64,
+    /// 실제로 할당된 바이트 수.
+    allocated: u64,
+    /// 모든 익스텐트를 다른 파일과 공유하고 있다면 그 배치.
+    layout: Option<Layout>,
+}
+
+/// 볼륨 하나에서 방법별로 회수되는 공간.
+#[derive(Default)]
+struct VolumeSavings {
+    /// 볼륨을 알아볼 수 있는 이름 (마운트 지점).
+    label: Option<PathBuf>,
+    delete: u64,
+    hardlink: u64,
+    reflink: u64,
+    /// 논리 크기로 계산한 중복 바이트 (비교용).
+    logical: u64,
+}
+
+/// 계산에서 드러난, 회수 공간이 줄어드는 이유별 파일 수.
+#[derive(Default)]
+struct Notes {
+    /// 다른 하드 링크가 남아 있어 정리해도 공간이 돌아오지 않는 파일.
+    other_links: usize,
+    /// 원본이나 앞의 사본과 이미 모든 익스텐트를 공유(reflink)하여 정리해도 공간이 돌아오지 않는 파일.
+    already_shared: usize,
+    /// 원본과 다른 볼륨에 있어 하드 링크/reflink로 합칠 수 없어 남겨야 하는 사본.
+    cross_device: usize,
+    /// 상태를 읽을 수 없어 계산에서 뺀 파일.
+    unreadable: usize,
+}
+
+/// 모든 그룹에 대해 방법별 회수 공간을 계산하여 출력합니다.
+pub fn report(groups: &[DuplicateGroup], keep: &KeepRule) {
+    let mut volumes: BTreeMap<u64, VolumeSavings> = BTreeMap::new();
+    let mut notes = Notes::default();
+
+    // 심볼릭 링크 그룹은 파일 내용을 저장하지 않으므로 회수할 공간이 없습니다.
+    for group in groups.iter().filter(|g| g.symlink_target.is_none()) {
+        let keeper_path = &group.paths[keep.keeper(&group.paths)];
+        let members: Vec<Member> = group
+            .paths
+            .iter()
+            .filter_map(|path| {
+                let member = inspect(path);
+                if member.is_none() {
+                    notes.unreadable += 1;
+                }
+                member
+            })
+            .collect();
+        let Some(keeper) = members.iter().find(|m| m.path == keeper_path.as_path()) else {
+            // 원본의 상태를 읽을 수 없다면 어느 사본이 남을지 알 수 없으므로 계산하지 않습니다.
+            continue;
+        };
+
+        // 이미 데이터를 공유하는 사본을 알아보기 위한 (볼륨, 배치). 원본부터 넣고, 사본은 나오는 순서대로
+        // 넣습니다. 배치가 앞의 파일과 같은 사본은 그 파일과 데이터가 한 벌이므로 정리해도 공간이 돌아오지 않습니다.
+        let mut layouts: HashSet<(u64, &Layout)> = HashSet::new();
+        if let Some(layout) = &keeper.layout {
+            layouts.insert((keeper.device, layout));
+        }
+
+        for member in &members {
+            let volume = volumes.entry(member.device).or_default();
+            if volume.label.is_none() {
+                volume.label = mount_point(member.path);
+            }
+            if member.path == keeper.path {
+                continue;
+            }
+            volume.logical += group.size;
+            // 다른 경로가 같은 데이터를 계속 가리키므로 어떤 방법으로도 공간이 돌아오지 않습니다.
+            let freed = if member.links > 1 {
+                notes.other_links += 1;
+                0
+            } else if let Some(layout) = &member.layout
+                && !layouts.insert((member.device, layout))
+            {
+                notes.already_shared += 1;
+                0
+            } else {
+                member.allocated
+            };
+            volume.delete += freed;
+
+            // 하드 링크와 reflink는 볼륨마다 사본 하나가 남아야 합니다. 원본이 없는 볼륨에서는
+            // 그 볼륨에서 처음 나온 사본을 남깁니다.
+            let local_keeper = if member.device == keeper.device {
+                keeper.path
+            } else {
+                members.iter().find(|m| m.device == member.device).map_or(member.path, |m| m.path)
+            };
+            if member.path == local_keeper {
+                notes.cross_device += 1;
+            } else {
+                volume.hardlink += freed;
+                if reflink_supported(member.path) != Some(false) {
+                    volume.reflink += freed;
+                }
+            }
+        }
+    }
+
+    print_report(&volumes, &notes, keep);
+}
+
+fn print_report(volumes: &BTreeMap<u64, VolumeSavings>, notes: &Notes, keep: &KeepRule) {
+    let policy = keep.policy.to_possible_value().map_or_else(String::new, |v| v.get_name().to_string());
+    println!("\n📐 정리 방법별 실제 회수 공간 (원본 규칙: {})", policy);
+    if volumes.is_empty() {
+        println!("회수할 수 있는 중복 파일이 없습니다.");
+        return;
+    }
+    let mut total = VolumeSavings::default();
+    for (device, volume) in volumes {
+        let label = volume.label.as_ref().map_or_else(|| format!("장치 {}", device), |p| p.display().to_string());
+        let reflink = match volume.label.as_deref().and_then(reflink_supported) {
+            Some(false) => "파일 시스템 미지원".to_string(),
+            _ => format_size(volume.reflink),
+        };
+        println!("\n볼륨 {}", label);
+        println!("  중복 (논리 크기): {}", format_size(volume.logical));
+        println!("  삭제:             {}", format_size(volume.delete));
+        println!("  하드 링크:        {}", format_size(volume.hardlink));
+        println!("  reflink:          {}", reflink);
+        total.logical += volume.logical;
+        total.delete += volume.delete;
+        total.hardlink += volume.hardlink;
+        total.reflink += volume.reflink;
+    }
+    if volumes.len() > 1 {
+        println!(
+            "\n합계: 삭제 {}, 하드 링크 {}, reflink {} (중복 논리 크기 {})",
+            format_size(total.delete),
+            format_size(total.hardlink),
+            format_size(total.reflink),
+            format_size(total.logical)
+        );
+    }
+
+    println!("\n- 실제 할당된 블록 기준입니다. 작은 파일은 논리 크기와 차이가 날 수 있습니다.");
+    println!("- 하드 링크로 합친 사본은 내용을 공유하므로 한 경로에서 고치면 다른 경로에도 반영됩니다.");
+    println!("  reflink는 쓰기 시 복사하므로 사본을 고쳐도 다른 경로에 영향이 없습니다.");
+    if notes.other_links > 0 {
+        println!("- 다른 하드 링크가 있어 정리해도 공간이 돌아오지 않는 파일: {}개", notes.other_links);
+    }
+    if notes.already_shared > 0 {
+        println!("- 이미 reflink로 데이터를 공유하고 있어 정리해도 공간이 돌아오지 않는 사본: {}개", notes.already_shared);
+    }
+    if notes.cross_device > 0 {
+        println!("- 원본과 다른 볼륨에 있어 하드 링크/reflink로는 남겨야 하는 사본: {}개", notes.cross_device);
+    }
+    if notes.unreadable > 0 {
+        println!("- 상태를 읽을 수 없어 계산에서 뺀 파일: {}개", notes.unreadable);
+    }
+}
+
+/// 파일의 볼륨, 링크 수, 실제 할당 크기를 읽습니다.
+#[cfg(unix)]
+fn inspect(path: &Path) -> Option<Member<'_>> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    // st_blocks는 항상 512바이트 단위입니다. (파일 시스템 블록 크기와 무관)
+    Some(Member {
+        path,
+        device: metadata.dev(),
+        links: metadata.nlink(),
+        allocated: metadata.blocks() * 512,
+        layout: extents::shared_layout(path),
+    })
+}
+
+/// 유닉스가 아닌 플랫폼에서는 볼륨과 링크 수를 알 수 없으므로, 모든 파일이 한 볼륨에
+/// 있고 다른 링크가 없으며 논리 크기만큼 할당되었다고 가정합니다.
+#[cfg(not(unix))]
+fn inspect(path: &Path) -> Option<Member<'_>> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    Some(Member { path, device: 0, links: 1, allocated: metadata.len(), layout: extents::shared_layout(path) })
+}
+
+/// 파일이 있는 볼륨의 마운트 지점을 찾습니다.
+fn mount_point(path: &Path) -> Option<PathBuf> {
+    let mut current = path.canonicalize().ok()?;
+    while !safety::is_mount_root(&current) {
+        current = current.parent()?.to_path_buf();
+    }
+    Some(current)
+}
+
+/// 경로가 있는 파일 시스템이 reflink(공유 데이터 복사)를 지원하는지 확인합니다.
+/// 판단할 수 없으면 `None`입니다.
+#[cfg(target_os = "linux")]
+fn reflink_supported(path: &Path) -> Option<bool> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    // 파일 시스템 종류 번호 (linux/magic.h)
+    const BTRFS: i64 = 0x9123_683E;
+    const XFS: i64 = 0x5846_5342;
+    const BCACHEFS: i64 = 0xCA45_1A4E;
+    const OCFS2: i64 = 0x7461_636F;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    // SAFETY: 올바른 C 문자열과 쓰기 가능한 statfs 구조체를 넘깁니다.
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    match stat.f_type as i64 {
+        BTRFS | BCACHEFS | OCFS2 => Some(true),
+        // xfs는 reflink=1로 만든 경우에만 지원하므로 확실히 알 수 없습니다.
+        XFS => None,
+        _ => Some(false),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink_supported(_path: &Path) -> Option<bool> {
+    None
+}
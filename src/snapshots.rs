@@ -0,0 +1,395 @@
+// ==============================================================================
+//  스냅샷 폴더 사이의 하드 링크 정리 (snapshots 하위 명령)
+// ------------------------------------------------------------------------------
+//  매일 폴더 하나에 전체를 복사하는 백업(`daily.0`, `daily.1`, ...)은 바뀌지 않은 파일이 세대마다
+//  다시 저장됩니다. rsnapshot이나 rdfind처럼 새 스냅샷의 파일을 이전 스냅샷의 같은 파일에 대한
+//  하드 링크로 바꾸면, 두 스냅샷 모두 온전한 폴더로 남으면서 내용은 한 번만 저장됩니다.
+//
+//  `snapshots link OLDER NEWER`는 NEWER의 파일마다 OLDER에서 같은 파일을 찾아 하드 링크로 바꿉니다.
+//  같은 상대 경로의 파일을 먼저 보고, 없으면 OLDER 안의 다른 경로에서 찾습니다. (이름을 바꾼 파일)
+//  백업을 망가뜨리지 않도록 다음을 모두 만족할 때만 바꿉니다.
+//  - 두 폴더가 서로 다르고 서로 안에 있지 않으며, 같은 파일 시스템에 있습니다.
+//  - 일반 파일이고(심볼릭 링크는 그대로 둡니다), 크기와 수정 시각이 같습니다.
+//  - 권한, 소유자, 확장 속성이 같습니다. 링크는 OLDER 파일의 메타데이터를 함께 쓰기 때문입니다.
+//  - 해시가 같고, 바꾸기 직전의 바이트 비교로도 같습니다.
+//  - 다른 프로세스가 쓰고 있지 않습니다. (in_use.rs)
+//  이미 같은 아이노드를 가리키는 파일은 건너뜁니다. 바꾸기는 임시 이름으로 링크를 만든 뒤 이름을
+//  바꾸므로(watch.rs와 같은 방법) 중간에 실패해도 파일이 사라지지 않습니다.
+//
+//  바꾼 파일마다 링크를 만들기 전에 되돌리기 기록(journal, JSON Lines)에 한 줄씩 써 둡니다.
+//  `snapshots undo JOURNAL`은 기록을 거꾸로 읽으며, 아직 OLDER 파일과 아이노드를 공유하는 파일을
+//  독립된 복사본으로 되돌립니다. 접근 시각을 뺀 메타데이터는 링크 전과 같았으므로 그대로 복원됩니다.
+//  --dry-run은 기록 없이 바꿀 파일과 회수될 공간만 보여줍니다.
+// ==============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde_json::{Value, json};
+use walkdir::WalkDir;
+
+use crate::hashing::HashAlgorithm;
+use crate::metadata::Snapshot;
+use crate::stages::same_content;
+use crate::{Progress, calculate_hash, file_identity, format_size, in_use, interrupt, safety, watch};
+
+/// 되돌리기 기록의 첫 줄에 쓰는 종류 이름.
+const JOURNAL_KIND: &str = "snapshots_link";
+
+/// 실행 결과를 종료 코드로 나타내기 위한 요약.
+pub struct Summary {
+    /// 처리하지 못한 파일이 있습니다.
+    pub failed: bool,
+}
+
+/// `snapshots link`의 설정.
+pub struct LinkOptions<'a> {
+    pub older: &'a Path,
+    pub newer: &'a Path,
+    /// 되돌리기 기록 파일. `None`이면 바꾸지 않고 보고만 합니다. (--dry-run)
+    pub journal: Option<&'a Path>,
+    pub algorithm: HashAlgorithm,
+    /// 이보다 작은 파일은 바꾸지 않습니다.
+    pub min_size: u64,
+}
+
+/// 건너뛴 이유별 개수와 실패한 경로.
+#[derive(Default)]
+struct Counts {
+    checked: u64,
+    linked: u64,
+    linked_bytes: u64,
+    already_linked: u64,
+    skipped: BTreeMap<&'static str, u64>,
+    failed: Vec<(PathBuf, String)>,
+}
+
+/// NEWER의 파일을 OLDER의 같은 파일에 대한 하드 링크로 바꿉니다.
+pub fn link(options: &LinkOptions) -> io::Result<Summary> {
+    check_folders(options.older, options.newer)?;
+    let mut journal = match options.journal {
+        Some(path) => Some(Journal::create(path, options)?),
+        None => None,
+    };
+    interrupt::install_handler();
+
+    println!("📚 '{}'의 파일을 크기별로 색인합니다...", options.older.display());
+    let mut progress = Progress::new();
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut indexed = 0u64;
+    for (path, size) in regular_files(options.older) {
+        if interrupt::requested() {
+            break;
+        }
+        if size >= options.min_size.max(1) {
+            by_size.entry(size).or_default().push(path);
+        }
+        indexed += 1;
+        progress.update("이전 스냅샷 색인 중", indexed, None);
+    }
+    progress.finish();
+
+    let verb = if journal.is_some() { "바꿉니다" } else { "바꿀 파일을 찾습니다 (--dry-run)" };
+    println!("🔗 '{}'의 같은 파일을 하드 링크로 {}...", options.newer.display(), verb);
+    let mut counts = Counts::default();
+    // OLDER 파일의 해시는 여러 NEWER 파일과 비교할 수 있으므로 기억해 둡니다.
+    let mut hashes: HashMap<PathBuf, Option<String>> = HashMap::new();
+    for (path, size) in regular_files(options.newer) {
+        if interrupt::requested() {
+            eprintln!("\n⚠️  중단 요청을 받아 여기까지만 처리합니다.");
+            break;
+        }
+        counts.checked += 1;
+        progress.update("새 스냅샷 비교 중", counts.checked, None);
+        if size < options.min_size.max(1) {
+            *counts.skipped.entry("--min-size보다 작음").or_default() += 1;
+            continue;
+        }
+        let relative = path.strip_prefix(options.newer).unwrap_or(&path);
+        let same_place = options.older.join(relative);
+        let Some(candidates) = by_size.get(&size) else {
+            *counts.skipped.entry("이전 스냅샷에 같은 크기의 파일이 없음").or_default() += 1;
+            continue;
+        };
+        // 같은 상대 경로의 파일을 먼저 봅니다.
+        let ordered = candidates.iter().filter(|c| **c == same_place).chain(candidates.iter().filter(|c| **c != same_place));
+        match find_match(&path, ordered, options.algorithm, &mut hashes) {
+            Ok(Match::Found(target, hash)) => {
+                let result = match &mut journal {
+                    Some(journal) => replace(&path, &target, size, &hash, journal),
+                    None => Ok(()),
+                };
+                match result {
+                    Ok(()) => {
+                        counts.linked += 1;
+                        counts.linked_bytes += size;
+                    }
+                    Err(e) => counts.failed.push((path, e.to_string())),
+                }
+            }
+            Ok(Match::AlreadyLinked) => counts.already_linked += 1,
+            Ok(Match::None(reason)) => *counts.skipped.entry(reason).or_default() += 1,
+            Err(e) => counts.failed.push((path, e.to_string())),
+        }
+    }
+    progress.finish();
+
+    let done = if journal.is_some() { "하드 링크로 바꿈" } else { "하드 링크로 바꿀 수 있음" };
+    println!("\n📋 새 스냅샷의 파일 {}개 중", counts.checked);
+    println!("  {}: {}개 ({} 회수)", done, counts.linked, format_size(counts.linked_bytes));
+    println!("  이미 이전 스냅샷과 연결됨: {}개", counts.already_linked);
+    for (reason, count) in &counts.skipped {
+        println!("  건너뜀 ({}): {}개", reason, count);
+    }
+    if !counts.failed.is_empty() {
+        println!("  처리하지 못함: {}개", counts.failed.len());
+        for (path, error) in &counts.failed {
+            println!("    - {}: {}", path.display(), error);
+        }
+    }
+    if let (Some(journal), true) = (options.journal, counts.linked > 0) {
+        println!("\n되돌리려면: duplicate_finder snapshots undo {}", journal.display());
+    }
+    Ok(Summary { failed: !counts.failed.is_empty() })
+}
+
+/// 두 스냅샷 폴더가 하드 링크로 합쳐도 되는 위치인지 확인합니다.
+fn check_folders(older: &Path, newer: &Path) -> io::Result<()> {
+    let invalid = |message: String| Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    let mut identities = Vec::new();
+    for folder in [older, newer] {
+        let metadata = fs::metadata(folder)?;
+        if !metadata.is_dir() {
+            return invalid(format!("'{}'은(는) 폴더가 아닙니다", folder.display()));
+        }
+        if let Some(reason) = safety::dangerous_root_reason(folder) {
+            return invalid(format!("{}. 스냅샷 폴더를 직접 지정하세요", reason));
+        }
+        identities.push(file_identity(&metadata));
+    }
+    let (older_real, newer_real) = (older.canonicalize()?, newer.canonicalize()?);
+    if older_real.starts_with(&newer_real) || newer_real.starts_with(&older_real) {
+        return invalid(String::from("두 스냅샷 폴더가 같거나 한쪽이 다른 쪽 안에 있습니다"));
+    }
+    match (identities[0], identities[1]) {
+        (Some((older_device, _)), Some((newer_device, _))) if older_device == newer_device => Ok(()),
+        (Some(_), Some(_)) => invalid(String::from("하드 링크는 같은 파일 시스템 안에서만 만들 수 있습니다")),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "snapshots 하위 명령은 아이노드를 알 수 있는 유닉스 계열 운영체제에서만 쓸 수 있습니다",
+        )),
+    }
+}
+
+/// 폴더 아래의 일반 파일과 그 크기. 심볼릭 링크는 따라가지도, 포함하지도 않습니다.
+/// 읽을 수 없는 항목은 건너뜁니다.
+fn regular_files(folder: &Path) -> impl Iterator<Item = (PathBuf, u64)> {
+    WalkDir::new(folder)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let size = entry.metadata().ok()?.len();
+            Some((entry.into_path(), size))
+        })
+}
+
+/// NEWER 파일 하나를 OLDER 후보들과 비교한 결과.
+enum Match {
+    /// 하드 링크로 바꿀 수 있는 OLDER 파일과 그 해시.
+    Found(PathBuf, String),
+    /// 이미 후보 중 하나와 같은 아이노드입니다.
+    AlreadyLinked,
+    /// 바꿀 수 없는 이유.
+    None(&'static str),
+}
+
+/// 후보 중 수정 시각, 권한, 소유자, 확장 속성, 해시, 바이트가 모두 같은 첫 파일을 찾습니다.
+fn find_match<'a>(
+    path: &Path,
+    candidates: impl Iterator<Item = &'a PathBuf>,
+    algorithm: HashAlgorithm,
+    hashes: &mut HashMap<PathBuf, Option<String>>,
+) -> io::Result<Match> {
+    let metadata = fs::symlink_metadata(path)?;
+    let snapshot = Snapshot::capture(path)?;
+    let identity = file_identity(&metadata);
+    let mut hash = None;
+    // 후보가 걸러진 이유. 수정 시각보다 뒤 단계에서 걸러진 후보가 있다면 그 이유를 씁니다.
+    let mut reason = "수정 시각이 다름";
+    for candidate in candidates {
+        let Ok(candidate_metadata) = fs::symlink_metadata(candidate) else { continue };
+        if identity.is_some() && file_identity(&candidate_metadata) == identity {
+            return Ok(Match::AlreadyLinked);
+        }
+        if candidate_metadata.modified().ok() != metadata.modified().ok() {
+            continue;
+        }
+        if !Snapshot::capture(candidate).is_ok_and(|other| snapshot.same_protection(&other)) {
+            reason = "권한, 소유자, 확장 속성 중 다른 것이 있음";
+            continue;
+        }
+        let candidate_hash = hashes.entry(candidate.clone()).or_insert_with(|| calculate_hash(candidate, algorithm).ok());
+        let own_hash = match &hash {
+            Some(own) => own,
+            None => hash.insert(calculate_hash(path, algorithm)?),
+        };
+        if candidate_hash.as_ref() != Some(own_hash) {
+            reason = "내용이 다름";
+            continue;
+        }
+        if same_content(path, candidate)? {
+            return Ok(Match::Found(candidate.clone(), own_hash.clone()));
+        }
+        reason = "해시는 같지만 바이트가 다름";
+    }
+    Ok(Match::None(reason))
+}
+
+/// 기록에 남긴 뒤 NEWER 파일을 OLDER 파일의 하드 링크로 바꿉니다.
+fn replace(path: &Path, target: &Path, size: u64, hash: &str, journal: &mut Journal) -> io::Result<()> {
+    let original = Snapshot::capture(path)?;
+    // 링크를 만든 뒤에 기록이 실패하면 되돌릴 수 없으므로 먼저 기록합니다.
+    // 바꾸기가 실패한 항목은 undo가 "연결되어 있지 않음"으로 건너뜁니다.
+    journal.record(json!({
+        "path": absolute(path)?,
+        "target": absolute(target)?,
+        "size": size,
+        "hash": hash,
+        "metadata": original.to_json(),
+    }))?;
+    watch::replace_with_link(path, target, &original)
+}
+
+/// 기록에 쓸 절대 경로. UTF-8이 아닌 경로는 기록할 수 없으므로 바꾸지 않습니다.
+fn absolute(path: &Path) -> io::Result<String> {
+    std::path::absolute(path)?
+        .into_os_string()
+        .into_string()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "UTF-8이 아닌 경로는 되돌리기 기록에 남길 수 없습니다"))
+}
+
+/// 되돌리기 기록 파일. 한 줄마다 디스크에 내린 뒤 다음 작업으로 넘어갑니다.
+struct Journal {
+    writer: BufWriter<File>,
+}
+
+impl Journal {
+    /// 새 기록 파일을 만듭니다. 이전 기록을 덮어쓰지 않도록 이미 있으면 실패합니다.
+    fn create(path: &Path, options: &LinkOptions) -> io::Result<Self> {
+        // 기록 파일이 스냅샷 안에 있으면 그 스냅샷의 일부로 백업되거나 함께 지워집니다.
+        let parent = std::path::absolute(path)?.parent().map(Path::canonicalize).transpose()?;
+        for folder in [options.older, options.newer] {
+            if parent.as_ref().is_some_and(|parent| folder.canonicalize().is_ok_and(|folder| parent.starts_with(folder))) {
+                let message = format!("되돌리기 기록은 스냅샷 폴더 '{}' 밖에 두어야 합니다", folder.display());
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+            }
+        }
+        let file = OpenOptions::new().write(true).create_new(true).open(path).map_err(|e| {
+            if e.kind() == io::ErrorKind::AlreadyExists {
+                io::Error::new(e.kind(), format!("되돌리기 기록 '{}'이(가) 이미 있습니다. 다른 이름을 지정하세요", path.display()))
+            } else {
+                e
+            }
+        })?;
+        let mut journal = Journal { writer: BufWriter::new(file) };
+        journal.record(json!({
+            "kind": JOURNAL_KIND,
+            "older": absolute(options.older)?,
+            "newer": absolute(options.newer)?,
+            "algorithm": options.algorithm.name(),
+            "started_at": humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        }))?;
+        Ok(journal)
+    }
+
+    fn record(&mut self, line: Value) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &line)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()
+    }
+}
+
+/// 되돌리기 기록의 파일들을 독립된 복사본으로 되돌립니다.
+pub fn undo(journal: &Path) -> io::Result<Summary> {
+    let reader = BufReader::new(File::open(journal)?);
+    let mut entries = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{}번째 줄: {}", number + 1, message));
+        let value: Value = serde_json::from_str(&line).map_err(|e| invalid(&e.to_string()))?;
+        if number == 0 {
+            if value["kind"] != JOURNAL_KIND {
+                return Err(invalid("snapshots link의 되돌리기 기록이 아닙니다"));
+            }
+            continue;
+        }
+        let (Some(path), Some(target)) = (value["path"].as_str(), value["target"].as_str()) else {
+            return Err(invalid("path와 target이 없습니다"));
+        };
+        entries.push((PathBuf::from(path), PathBuf::from(target)));
+    }
+    interrupt::install_handler();
+
+    println!("↩️  하드 링크 {}개를 독립된 파일로 되돌립니다...", entries.len());
+    let (mut restored, mut restored_bytes, mut not_linked) = (0u64, 0u64, 0u64);
+    let mut failed = Vec::new();
+    // 나중에 바꾼 것부터 되돌립니다.
+    for (path, target) in entries.iter().rev() {
+        if interrupt::requested() {
+            eprintln!("\n⚠️  중단 요청을 받아 여기까지만 되돌립니다. 같은 명령을 다시 실행하면 이어서 되돌립니다.");
+            break;
+        }
+        match unlink(path, target) {
+            Ok(Some(size)) => {
+                restored += 1;
+                restored_bytes += size;
+            }
+            Ok(None) => not_linked += 1,
+            Err(e) => failed.push((path, e.to_string())),
+        }
+    }
+    println!("\n📋 되돌림: {}개 (디스크 {} 더 사용)", restored, format_size(restored_bytes));
+    println!("  이미 연결되어 있지 않음: {}개", not_linked);
+    if !failed.is_empty() {
+        println!("  되돌리지 못함: {}개", failed.len());
+        for (path, error) in &failed {
+            println!("    - {}: {}", path.display(), error);
+        }
+    }
+    Ok(Summary { failed: !failed.is_empty() })
+}
+
+/// 아직 `target`과 아이노드를 공유하는 `path`를 독립된 복사본으로 바꾸고 그 크기를 반환합니다.
+/// 이미 공유하지 않는다면(되돌렸거나, 바꾸기가 실패했거나, 그 뒤 다른 파일로 바뀌었다면) `None`.
+fn unlink(path: &Path, target: &Path) -> io::Result<Option<u64>> {
+    let (Ok(metadata), Ok(target_metadata)) = (fs::symlink_metadata(path), fs::symlink_metadata(target)) else {
+        return Ok(None);
+    };
+    let identity = file_identity(&metadata);
+    if !metadata.is_file() || identity.is_none() || identity != file_identity(&target_metadata) {
+        return Ok(None);
+    }
+    in_use::ensure_idle(path)?;
+    let shared = Snapshot::capture(path)?;
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let temporary = path.with_file_name(format!(".{}.duplicate_finder-unlink", name));
+    let result = fs::copy(target, &temporary).and_then(|_| {
+        shared.apply(&temporary).map_err(|failed| io::Error::other(format!("복사본에 {}을(를) 적용하지 못했습니다", failed)))?;
+        if !same_content(&temporary, target)? {
+            return Err(io::Error::other("복사본의 내용이 원본과 다릅니다"));
+        }
+        fs::rename(&temporary, path)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result.map(|()| Some(metadata.len()))
+}
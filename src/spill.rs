@@ -0,0 +1,524 @@
+// ==============================================================================
+//  크기 그룹의 디스크 분할 저장 (--spill-threshold)
+// ------------------------------------------------------------------------------
+//  크기별로 묶은 경로 목록(`files_by_size`)은 파일 수에 비례해 커지므로, 파일의 메타데이터만으로도
+//  메모리를 넘는 볼륨은 검색할 수 없었습니다.
+//
+//  --spill-threshold N을 지정하면 메모리에 모인 파일이 N개가 될 때마다 그 목록을 크기순으로
+//  정렬하여 임시 파일("런")에 쓰고 메모리를 비웁니다. 탐색이 끝나면 모든 런을 크기순으로 동시에
+//  읽어 나가는 외부 병합(k-way merge)으로 한 번에 한 크기씩 모으고, 파일이 2개 이상인 크기만
+//  해시 단계로 넘깁니다. 따라서 메모리에는 N개의 파일과 실제 비교 후보만 남습니다.
+//  런이 너무 많으면 파일을 한꺼번에 열 수 없으므로, 먼저 몇십 개씩 묶어 더 큰 런으로 합칩니다.
+//
+//  런 파일은 --spill-dir(기본: 시스템 임시 폴더) 아래에 이 검색만 쓰는 새 폴더(유닉스에서는 권한 0700)를
+//  만들어 그 안에 쓰며, 검색이 끝나면(실패해도) 폴더째 지워집니다. 공유 임시 폴더에 다른 사용자가 같은
+//  이름의 파일이나 심볼릭 링크를 미리 만들어 두어도 따라가거나 덮어쓰지 않도록, 폴더와 파일 모두 이미
+//  있으면 실패하는 방식으로 만듭니다.
+//
+//  --max-memory를 지정하면 파일 수 대신 추정 메모리 사용량으로 같은 일을 합니다. 크기 그룹과
+//  같은 파일 확인용 목록(inode 집합)이 예산의 절반에 닿으면 런을 쓰고, 병합한 후보도 한꺼번에
+//  메모리로 가져오지 않고 예산의 4분의 1씩 묶음(`Candidates::next_batch`)으로 해시 단계에 넘깁니다.
+//  (남은 4분의 1은 블룸 필터와 결과 그룹 등의 몫입니다)
+// ==============================================================================
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::FileStamp;
+use crate::checkpoint::{decode_time, encode_time};
+
+/// 분할 저장 설정 (--spill-threshold, --spill-dir, --max-memory).
+#[derive(Clone)]
+pub struct Config {
+    pub dir: PathBuf,
+    /// 메모리에 모아 둘 파일 수의 상한.
+    pub threshold: Option<usize>,
+    /// 크기 그룹과 같은 파일 확인용 목록에 쓸 메모리(바이트)의 상한.
+    pub memory: Option<usize>,
+}
+
+/// 크기 그룹 하나의 멤버: (경로, 발견 시점의 크기/수정 시간).
+type Member = (PathBuf, FileStamp);
+
+/// 런 파일을 읽는 쪽: 읽기 버퍼와, 미리 읽어 둔 다음 레코드.
+type RunReader = (BufReader<File>, Option<(u64, Member)>);
+
+/// 한 번에 열어 병합할 런의 최대 수.
+const MAX_FAN_IN: usize = 64;
+
+/// 메모리 예산 때문에 쓰는 런의 최소 파일 수. 내보낼 수 없는 목록만으로 예산을 넘었을 때
+/// 파일마다 런이 하나씩 생기지 않게 합니다.
+const MIN_RUN_FILES: usize = 1024;
+
+/// 같은 파일 확인용 목록에서 항목 하나(장치 번호, inode)가 차지하는 메모리의 추정치.
+pub const IDENTITY_BYTES: usize = size_of::<(u64, u64)>() + 16;
+
+/// 크기 그룹의 항목 하나가 차지하는 메모리의 추정치. (경로 바이트 + 구조체 + 해시 맵과 할당기의 여유분)
+pub fn entry_bytes(path: &Path) -> usize {
+    size_of::<Member>() + 32 + path.as_os_str().len()
+}
+
+/// 탐색 중에 쓴 런 파일들.
+pub struct Spill {
+    config: Config,
+    /// 런 파일을 담는 이 검색 전용 폴더. 첫 런을 쓸 때 만듭니다.
+    dir: Option<PathBuf>,
+    runs: Vec<PathBuf>,
+    /// 지금까지 만든 런 파일 수 (파일 이름에 씁니다).
+    created: usize,
+    /// 마지막으로 내보낸 뒤 메모리에 모인 파일 수.
+    pending: usize,
+    /// 마지막으로 내보낸 뒤 메모리에 모인 항목의 추정 크기(바이트).
+    pending_bytes: usize,
+    /// 예산 초과 경고를 이미 출력했는지 여부.
+    warned: bool,
+}
+
+impl Spill {
+    pub fn new(config: &Config) -> Self {
+        Spill { config: config.clone(), dir: None, runs: Vec::new(), created: 0, pending: 0, pending_bytes: 0, warned: false }
+    }
+
+    /// 크기가 `size`인 그룹의 끝에 파일이 추가되었음을 알립니다. 상한에 닿으면 그룹 전체를 런 파일로 내보냅니다.
+    /// `resident`는 내보낼 수 없이 메모리에 남는 목록(같은 파일 확인용 inode 집합)의 추정 크기입니다.
+    pub fn added(&mut self, files_by_size: &mut HashMap<u64, Vec<Member>>, size: u64, resident: usize) -> io::Result<()> {
+        self.pending += 1;
+        self.pending_bytes += files_by_size.get(&size).and_then(|members| members.last()).map_or(0, |(path, _)| entry_bytes(path));
+        let over_count = self.config.threshold.is_some_and(|threshold| self.pending >= threshold);
+        let over_memory = self.config.memory.is_some_and(|memory| {
+            if resident >= memory && !self.warned {
+                self.warned = true;
+                eprintln!("⚠️ 같은 파일 확인용 목록만으로 --max-memory 예산을 넘었습니다. 메모리 사용량이 계속 늘어납니다.");
+            }
+            self.pending_bytes + resident >= memory && self.pending >= MIN_RUN_FILES
+        });
+        if over_count || over_memory {
+            self.write_run(files_by_size)?;
+        }
+        Ok(())
+    }
+
+    /// 메모리에 남은 그룹과 모든 런을 크기순으로 병합하여, 파일이 2개 이상인 크기 그룹만 후보 런에 씁니다.
+    /// 런을 하나도 쓰지 않았다면 메모리의 그룹을 그대로 후보로 씁니다.
+    pub fn finish(mut self, mut files_by_size: HashMap<u64, Vec<Member>>) -> io::Result<Candidates> {
+        if self.runs.is_empty() {
+            return Ok(Candidates::from_map(files_by_size));
+        }
+        self.write_run(&mut files_by_size)?;
+
+        // 런이 너무 많으면 한꺼번에 열 수 없으므로, MAX_FAN_IN개씩 묶어 더 큰 런으로 합치기를 반복합니다.
+        while self.runs.len() > MAX_FAN_IN {
+            let batch = self.runs[..MAX_FAN_IN].to_vec();
+            self.merge_into_run(&batch, |_| true)?;
+        }
+
+        // 마지막 병합에서는 크기가 반복되는 그룹만 남긴 후보 런 하나를 만듭니다.
+        let runs = self.runs.clone();
+        let (path, size_groups, files, bytes) = self.merge_into_run(&runs, |members| members.len() > 1)?;
+        let mut reader = BufReader::new(File::open(path)?);
+        let next = read_record(&mut reader)?;
+        Ok(Candidates { groups: Vec::new().into_iter(), run: Some((reader, next)), size_groups, files, bytes, _spill: Some(self) })
+    }
+
+    /// `runs`를 병합하여 `keep`을 통과한 크기 그룹만 새 런 파일에 쓰고, 원래 런들은 지웁니다.
+    /// 새 런의 경로와 그 안의 크기 그룹 수, 파일 수, 파일 크기의 합을 반환합니다.
+    fn merge_into_run(&mut self, runs: &[PathBuf], keep: impl Fn(&[Member]) -> bool) -> io::Result<(PathBuf, usize, u64, u64)> {
+        let path = self.next_run_path()?;
+        self.runs.push(path.clone());
+        let mut writer = BufWriter::new(create_run(&path)?);
+        let (mut size_groups, mut files, mut bytes) = (0usize, 0u64, 0u64);
+        merge(runs, |size, members| {
+            if !keep(&members) {
+                return Ok(());
+            }
+            size_groups += 1;
+            files += members.len() as u64;
+            bytes += size * members.len() as u64;
+            members.iter().try_for_each(|(path, stamp)| write_record(&mut writer, size, path, stamp))
+        })?;
+        writer.flush()?;
+        // 병합이 끝난 뒤에야 목록에서 빼므로, 도중에 실패해도 원래 런들은 Drop에서 지워집니다.
+        self.runs.retain(|run| !runs.contains(run));
+        for run in runs {
+            let _ = fs::remove_file(run);
+        }
+        Ok((path, size_groups, files, bytes))
+    }
+
+    /// 메모리의 그룹을 크기순으로 정렬하여 새 런 파일에 쓰고 비웁니다.
+    fn write_run(&mut self, files_by_size: &mut HashMap<u64, Vec<Member>>) -> io::Result<()> {
+        self.pending = 0;
+        self.pending_bytes = 0;
+        if files_by_size.is_empty() {
+            return Ok(());
+        }
+        let path = self.next_run_path()?;
+        // 쓰는 도중에 실패해도 지울 수 있도록 먼저 목록에 올립니다.
+        self.runs.push(path.clone());
+        let mut writer = BufWriter::new(create_run(&path)?);
+        let mut groups: Vec<(u64, Vec<Member>)> = files_by_size.drain().collect();
+        groups.sort_unstable_by_key(|(size, _)| *size);
+        for (size, members) in groups {
+            for (path, stamp) in members {
+                write_record(&mut writer, size, &path, &stamp)?;
+            }
+        }
+        writer.flush()
+    }
+
+    fn next_run_path(&mut self) -> io::Result<PathBuf> {
+        let dir = match &self.dir {
+            Some(dir) => dir,
+            None => self.dir.insert(create_private_dir(&self.config.dir)?),
+        };
+        self.created += 1;
+        Ok(dir.join(format!("run-{}.bin", self.created)))
+    }
+}
+
+/// `parent` 아래에 이 검색만 쓰는 새 폴더를 만듭니다. 같은 이름이 이미 있으면(심볼릭 링크 포함) 다른 이름으로
+/// 다시 시도합니다.
+fn create_private_dir(parent: &Path) -> io::Result<PathBuf> {
+    let seed = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    for attempt in 0..100u32 {
+        let dir = parent.join(format!("duplicate_finder-spill-{}-{:08x}", std::process::id(), seed.wrapping_add(attempt)));
+        let builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        let builder = {
+            let mut builder = builder;
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+            builder
+        };
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("'{}'에 분할 저장용 폴더를 만들 수 없습니다", parent.display())))
+}
+
+/// 런 파일을 새로 만듭니다. 이미 있는 파일이나 심볼릭 링크를 열지 않습니다.
+fn create_run(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+/// 여러 런을 크기순으로 동시에 읽어, 크기마다 모든 런의 멤버를 모아 `emit`에 넘깁니다.
+fn merge(runs: &[PathBuf], mut emit: impl FnMut(u64, Vec<Member>) -> io::Result<()>) -> io::Result<()> {
+    let mut readers = Vec::new();
+    for run in runs {
+        let mut reader = BufReader::new(File::open(run)?);
+        let next = read_record(&mut reader)?;
+        readers.push((reader, next));
+    }
+    // (다음 레코드의 크기, 런 번호)의 최소 힙. 가장 작은 크기를 가진 런부터 읽습니다.
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> =
+        readers.iter().enumerate().filter_map(|(i, (_, next))| next.as_ref().map(|(size, _)| Reverse((*size, i)))).collect();
+    while let Some(Reverse((size, _))) = heap.peek().copied() {
+        // 이 크기의 레코드를 모든 런에서 모읍니다. (각 런 안에서는 같은 크기가 연속해 있습니다)
+        let mut members = Vec::new();
+        while let Some(Reverse((next_size, index))) = heap.peek().copied()
+            && next_size == size
+        {
+            heap.pop();
+            let (reader, next) = &mut readers[index];
+            read_size(reader, next, size, &mut members)?;
+            if let Some((next_size, _)) = next {
+                heap.push(Reverse((*next_size, index)));
+            }
+        }
+        emit(size, members)?;
+    }
+    Ok(())
+}
+
+/// `next`부터 크기가 `size`인 레코드를 연달아 읽어 `members`에 더합니다. 끝나면 `next`에는
+/// 크기가 다른 첫 레코드(또는 파일의 끝이라면 `None`)가 남습니다.
+fn read_size(reader: &mut impl Read, next: &mut Option<(u64, Member)>, size: u64, members: &mut Vec<Member>) -> io::Result<()> {
+    while let Some((record_size, member)) = next.take() {
+        if record_size != size {
+            *next = Some((record_size, member));
+            break;
+        }
+        members.push(member);
+        *next = read_record(reader)?;
+    }
+    Ok(())
+}
+
+/// 해시 단계로 넘길 후보 크기 그룹들. 메모리에 있거나, 분할 저장했다면 후보 런에서 차례로 읽습니다.
+pub struct Candidates {
+    groups: std::vec::IntoIter<(u64, Vec<Member>)>,
+    run: Option<RunReader>,
+    /// 후보 크기 그룹의 수.
+    pub size_groups: usize,
+    /// 후보 파일의 수.
+    pub files: u64,
+    /// 후보 파일 크기의 합. (해시 단계에서 읽을 최대 바이트 수)
+    pub bytes: u64,
+    /// 후보 런 파일을 다 읽을 때까지 지우지 않도록 붙잡아 둡니다.
+    _spill: Option<Spill>,
+}
+
+impl Candidates {
+    /// 메모리의 크기 그룹 중 파일이 2개 이상인 그룹을 후보로 삼습니다.
+    /// 회수할 수 있는 공간이 큰 그룹(크기 × 파일 수)부터 묶음에 넣습니다.
+    pub fn from_map(files_by_size: HashMap<u64, Vec<Member>>) -> Self {
+        let mut groups: Vec<(u64, Vec<Member>)> = files_by_size.into_iter().filter(|(_, members)| members.len() > 1).collect();
+        groups.sort_by_key(|(size, members)| Reverse((u128::from(*size) * members.len() as u128, *size)));
+        let files = groups.iter().map(|(_, members)| members.len() as u64).sum();
+        let bytes = groups.iter().map(|(size, members)| size * members.len() as u64).sum();
+        Candidates { size_groups: groups.len(), files, bytes, groups: groups.into_iter(), run: None, _spill: None }
+    }
+
+    /// 추정 크기가 `limit` 바이트에 닿을 때까지 크기 그룹을 모아 반환합니다. (그룹은 나누지 않으므로
+    /// 적어도 한 그룹은 들어갑니다) 남은 후보가 없으면 빈 맵입니다.
+    pub fn next_batch(&mut self, limit: usize) -> io::Result<HashMap<u64, Vec<Member>>> {
+        let mut batch = HashMap::new();
+        let mut bytes = 0usize;
+        while bytes < limit {
+            let group = match &mut self.run {
+                Some((reader, next)) => match next.as_ref().map(|(size, _)| *size) {
+                    Some(size) => {
+                        let mut members = Vec::new();
+                        read_size(reader, next, size, &mut members)?;
+                        Some((size, members))
+                    }
+                    None => None,
+                },
+                None => self.groups.next(),
+            };
+            let Some((size, members)) = group else { break };
+            bytes = bytes.saturating_add(members.iter().map(|(path, _)| entry_bytes(path)).sum());
+            batch.insert(size, members);
+        }
+        Ok(batch)
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        for run in &self.runs {
+            let _ = fs::remove_file(run);
+        }
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// 레코드 하나: 크기(u64), 수정 시간 유무(u8), 초(i64), 나노초(u32), 경로 길이(u32), 경로 바이트.
+fn write_record(writer: &mut impl Write, size: u64, path: &Path, stamp: &FileStamp) -> io::Result<()> {
+    let (secs, nanos) = stamp.modified.map(encode_time).unwrap_or_default();
+    let bytes = path.as_os_str().as_encoded_bytes();
+    writer.write_all(&size.to_le_bytes())?;
+    writer.write_all(&[u8::from(stamp.modified.is_some())])?;
+    writer.write_all(&secs.to_le_bytes())?;
+    writer.write_all(&nanos.to_le_bytes())?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+/// 런 파일에서 읽은 경로 바이트. 유닉스에서는 어떤 바이트든 경로이고, 그 밖에서는 UTF-8이어야 합니다.
+#[cfg(unix)]
+fn os_string(bytes: Vec<u8>) -> io::Result<OsString> {
+    Ok(std::os::unix::ffi::OsStringExt::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn os_string(bytes: Vec<u8>) -> io::Result<OsString> {
+    String::from_utf8(bytes).map(OsString::from).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// 레코드 하나를 읽습니다. 런 파일의 끝이면 `None`이고, 레코드 중간에서 끝났다면 오류입니다.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<(u64, Member)>> {
+    let mut size = [0u8; 8];
+    let mut filled = 0;
+    while filled < size.len() {
+        match reader.read(&mut size[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    match filled {
+        0 => return Ok(None),
+        8 => {}
+        _ => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "런 파일이 레코드 중간에서 끝났습니다")),
+    }
+    let size = u64::from_le_bytes(size);
+    let mut header = [0u8; 1 + 8 + 4 + 4];
+    reader.read_exact(&mut header)?;
+    let has_modified = header[0] != 0;
+    let secs = i64::from_le_bytes(header[1..9].try_into().unwrap_or_default());
+    let nanos = u32::from_le_bytes(header[9..13].try_into().unwrap_or_default());
+    let length = u32::from_le_bytes(header[13..17].try_into().unwrap_or_default());
+    // 길이를 먼저 할당하지 않고 읽은 만큼만 담아, 망가진 길이 값으로 큰 메모리를 잡지 않습니다.
+    let mut bytes = Vec::new();
+    reader.take(u64::from(length)).read_to_end(&mut bytes)?;
+    if bytes.len() != length as usize {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "런 파일이 경로 중간에서 끝났습니다"));
+    }
+    let path = PathBuf::from(os_string(bytes)?);
+    let stamp = FileStamp { len: size, modified: has_modified.then(|| decode_time((secs, nanos))) };
+    Ok(Some((size, (path, stamp))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    fn stamp(len: u64) -> FileStamp {
+        FileStamp { len, modified: Some(UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789)) }
+    }
+
+    /// 테스트마다 따로 쓰는 임시 폴더. 끝나면 지웁니다.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("duplicate_finder-spill-test-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn encoded(records: &[(u64, &str, FileStamp)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (size, path, stamp) in records {
+            write_record(&mut bytes, *size, Path::new(path), stamp).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let no_time = FileStamp { len: 0, modified: None };
+        let bytes = encoded(&[(7, "/a/b", stamp(7)), (0, "", no_time)]);
+        let mut reader = bytes.as_slice();
+        let (size, (path, read)) = read_record(&mut reader).unwrap().unwrap();
+        assert!(size == 7 && path == Path::new("/a/b") && read == stamp(7));
+        let (size, (path, read)) = read_record(&mut reader).unwrap().unwrap();
+        assert!(size == 0 && path == Path::new("") && read == no_time);
+        assert!(read_record(&mut reader).unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_paths_round_trip() {
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/tmp/\xff\xfe"));
+        let mut bytes = Vec::new();
+        write_record(&mut bytes, 1, path, &stamp(1)).unwrap();
+        assert_eq!(read_record(&mut bytes.as_slice()).unwrap().unwrap().1.0, path);
+    }
+
+    #[test]
+    fn truncated_records_are_errors() {
+        let bytes = encoded(&[(5, "/some/path", stamp(5))]);
+        // 크기 필드, 헤더, 경로 어디에서 끊겨도 파일의 끝이 아니라 오류입니다.
+        for cut in 1..bytes.len() {
+            let error = read_record(&mut &bytes[..cut]).err();
+            assert_eq!(error.map(|e| e.kind()), Some(io::ErrorKind::UnexpectedEof), "cut at {}", cut);
+        }
+        assert!(read_record(&mut &bytes[..0]).unwrap().is_none());
+    }
+
+    #[test]
+    fn corrupt_path_length_does_not_allocate_it() {
+        let mut bytes = encoded(&[(5, "/p", stamp(5))]);
+        // 경로 길이 필드(크기 8바이트 + 헤더 13바이트 뒤)를 최댓값으로 바꿉니다.
+        bytes[21..25].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(read_record(&mut bytes.as_slice()).err().map(|e| e.kind()), Some(io::ErrorKind::UnexpectedEof));
+    }
+
+    #[test]
+    fn merge_collects_each_size_from_all_runs_in_order() {
+        let temp = TempDir::new("merge");
+        let runs = [
+            (temp.0.join("a"), encoded(&[(1, "/a1", stamp(1)), (3, "/a3", stamp(3)), (3, "/a3b", stamp(3))])),
+            (temp.0.join("b"), encoded(&[(2, "/b2", stamp(2)), (3, "/b3", stamp(3)), (9, "/b9", stamp(9))])),
+            (temp.0.join("empty"), Vec::new()),
+        ];
+        for (path, bytes) in &runs {
+            fs::write(path, bytes).unwrap();
+        }
+        let paths: Vec<PathBuf> = runs.iter().map(|(path, _)| path.clone()).collect();
+        let mut merged = Vec::new();
+        merge(&paths, |size, members| {
+            merged.push((size, members.into_iter().map(|(path, _)| path.display().to_string()).collect::<Vec<_>>()));
+            Ok(())
+        })
+        .unwrap();
+        let expected: Vec<(u64, Vec<String>)> = vec![
+            (1, vec!["/a1".into()]),
+            (2, vec!["/b2".into()]),
+            (3, vec!["/a3".into(), "/a3b".into(), "/b3".into()]),
+            (9, vec!["/b9".into()]),
+        ];
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn merge_reports_corrupt_runs() {
+        let temp = TempDir::new("corrupt");
+        let run = temp.0.join("run");
+        let mut bytes = encoded(&[(1, "/a", stamp(1)), (2, "/b", stamp(2))]);
+        bytes.truncate(bytes.len() - 1);
+        fs::write(&run, bytes).unwrap();
+        assert!(merge(&[run], |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn spilled_scan_keeps_only_repeated_sizes_and_cleans_up() {
+        let temp = TempDir::new("spill");
+        let config = Config { dir: temp.0.clone(), threshold: Some(2), memory: None };
+        let mut spill = Spill::new(&config);
+        let mut files_by_size: HashMap<u64, Vec<Member>> = HashMap::new();
+        for (i, size) in [10u64, 20, 10, 30, 20, 10, 40].into_iter().enumerate() {
+            files_by_size.entry(size).or_default().push((PathBuf::from(format!("/f{}", i)), stamp(size)));
+            spill.added(&mut files_by_size, size, 0).unwrap();
+        }
+        let private_dir = spill.dir.clone().unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&private_dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+
+        let mut candidates = spill.finish(files_by_size).unwrap();
+        assert_eq!((candidates.size_groups, candidates.files, candidates.bytes), (2, 5, 70));
+        let batch = candidates.next_batch(usize::MAX).unwrap();
+        let mut sizes: Vec<(u64, usize)> = batch.iter().map(|(size, members)| (*size, members.len())).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![(10, 3), (20, 2)]);
+        assert!(candidates.next_batch(usize::MAX).unwrap().is_empty());
+        drop(candidates);
+        assert!(!private_dir.exists());
+    }
+
+    #[test]
+    fn private_dir_is_never_reused() {
+        let temp = TempDir::new("private");
+        let first = create_private_dir(&temp.0).unwrap();
+        let second = create_private_dir(&temp.0).unwrap();
+        assert_ne!(first, second);
+        let run = first.join("run-1.bin");
+        create_run(&run).unwrap();
+        assert_eq!(create_run(&run).err().map(|e| e.kind()), Some(io::ErrorKind::AlreadyExists));
+    }
+}
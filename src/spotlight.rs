@@ -0,0 +1,150 @@
+// ==============================================================================
+//  macOS Spotlight 색인으로 탐색 줄이기 (--spotlight)
+// ------------------------------------------------------------------------------
+//  홈 폴더처럼 파일이 수백만 개인 트리에서 `--filter 'ext:jpg or ext:heic'`로 사진만 찾더라도, 폴더를
+//  모두 읽어야 사진이 어느 폴더에 있는지 알 수 있습니다. macOS는 Spotlight 색인에 이미 모든 파일의
+//  이름과 크기를 기록해 두므로, 그 답을 먼저 물어보면 후보가 없는 폴더는 열지 않아도 됩니다.
+//
+//  --spotlight는 탐색 전에 루트마다 `mdfind -onlyin <루트>`로 필터와 크기 조건(0바이트 초과)에 맞는
+//  파일을 묻고, 그 파일들이 있는 폴더(와 그 상위 폴더)만 탐색합니다.
+//  - 색인의 답은 후보를 고르는 데만 씁니다. 탐색하는 폴더 안의 파일은 평소처럼 직접 확인하므로,
+//    색인에 아직 없는 새 파일도 후보가 있는 폴더에 있다면 빠지지 않습니다.
+//  - Spotlight 질의로 옮길 수 없는 조건(path:, not)은 "모든 파일"로 넓혀 묻습니다. 질의 결과는 항상
+//    필터에 맞는 파일을 모두 포함하고, 정확한 필터 검사는 탐색할 때 다시 합니다.
+//  - 색인에 없는 폴더(숨김 폴더, Spotlight 개인 정보 보호 목록, 색인을 끈 볼륨)에만 있는 파일은 찾지
+//    못합니다. 루트의 색인이 꺼져 있다면(`mdutil -s`) 시작하기 전에 거부합니다.
+// ==============================================================================
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use walkdir::DirEntry;
+
+use crate::FilterMode;
+use crate::filter::Expr;
+
+/// 루트마다 후보 파일이 있는 폴더들.
+pub struct Candidates {
+    /// 루트에 대한 상대 경로. 루트 자신은 빈 경로입니다. (`roots`와 같은 순서)
+    dirs: Vec<HashSet<PathBuf>>,
+    /// 색인이 알려준 후보 파일 수.
+    pub files: usize,
+}
+
+impl Candidates {
+    /// 탐색이 이 항목으로 들어가야 하는지. 후보가 없는 하위 폴더는 건너뜁니다.
+    pub fn keep(&self, root_index: usize, root: &Path, entry: &DirEntry) -> bool {
+        if entry.depth() == 0 || !entry.file_type().is_dir() {
+            return true;
+        }
+        entry.path().strip_prefix(root).is_ok_and(|relative| self.dirs[root_index].contains(relative))
+    }
+
+    /// 탐색할 폴더 수. (루트 포함)
+    pub fn dir_count(&self) -> usize {
+        self.dirs.iter().map(HashSet::len).sum()
+    }
+}
+
+/// 루트마다 Spotlight 색인에 후보 파일을 묻습니다.
+pub fn query(roots: &[PathBuf], filter: &FilterMode) -> io::Result<Candidates> {
+    let condition = match filter {
+        FilterMode::Expr(expr) => to_query(expr),
+        FilterMode::None => None,
+    };
+    let query = match condition {
+        Some(condition) => format!("kMDItemFSSize > 0 && ({})", condition),
+        None => String::from("kMDItemFSSize > 0"),
+    };
+    let mut candidates = Candidates { dirs: Vec::new(), files: 0 };
+    for root in roots {
+        let (dirs, files) = query_root(root, &query)?;
+        candidates.dirs.push(dirs);
+        candidates.files += files;
+    }
+    Ok(candidates)
+}
+
+/// 루트 하나의 후보 파일이 있는 폴더들과 후보 파일 수.
+#[cfg(unix)]
+fn query_root(root: &Path, query: &str) -> io::Result<(HashSet<PathBuf>, usize)> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::process::Command;
+
+    // 색인은 심볼릭 링크를 풀어낸 실제 경로(/tmp → /private/tmp)로 답하므로 루트도 풀어서 비교합니다.
+    let real_root = root.canonicalize()?;
+    let status = run(Command::new("mdutil").arg("-s").arg(&real_root), "mdutil")?;
+    if !String::from_utf8_lossy(&status).contains("Indexing enabled") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("'{}'의 Spotlight 색인이 꺼져 있거나 상태를 알 수 없습니다. --spotlight 없이 검색하세요", root.display()),
+        ));
+    }
+    let found = run(Command::new("mdfind").arg("-0").arg("-onlyin").arg(&real_root).arg(query), "mdfind")?;
+    let mut dirs = HashSet::from([PathBuf::new()]);
+    let mut files = 0;
+    for raw in found.split(|b| *b == 0).filter(|raw| !raw.is_empty()) {
+        let Ok(relative) = Path::new(OsStr::from_bytes(raw)).strip_prefix(&real_root) else { continue };
+        files += 1;
+        // 상위 폴더는 위쪽부터 이미 들어 있을 수 있으므로, 처음으로 이미 있는 폴더를 만나면 멈춥니다.
+        for dir in relative.ancestors().skip(1) {
+            if !dirs.insert(dir.to_path_buf()) {
+                break;
+            }
+        }
+    }
+    Ok((dirs, files))
+}
+
+/// 명령을 실행하여 표준 출력을 받습니다. 실패하면 표준 에러를 메시지에 담습니다.
+#[cfg(unix)]
+fn run(command: &mut std::process::Command, name: &str) -> io::Result<Vec<u8>> {
+    let output = command
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("{}을(를) 실행할 수 없습니다: {} (--spotlight는 macOS 전용)", name, e)))?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("{}이(가) 실패했습니다: {}", name, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    Ok(output.stdout)
+}
+
+/// Spotlight 색인이 없는 플랫폼. (main에서 macOS가 아니면 --spotlight를 거부합니다)
+#[cfg(not(unix))]
+fn query_root(_root: &Path, _query: &str) -> io::Result<(HashSet<PathBuf>, usize)> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "--spotlight는 macOS에서만 쓸 수 있습니다"))
+}
+
+/// 조건식을 Spotlight 질의로 옮깁니다. 옮길 수 없는 조건은 `None`(모든 파일)으로 넓힙니다.
+/// 대소문자와 발음 구별 기호를 가리지 않고(`cd`), `?`도 `*`로 넓혀 물으므로 답은 항상 조건보다 넓습니다.
+fn to_query(expr: &Expr) -> Option<String> {
+    let name = |pattern: &str| format!("kMDItemFSName == \"{}\"cd", escape(pattern));
+    match expr {
+        Expr::Ext(pattern) => Some(name(&format!("*.{}", pattern))),
+        Expr::Name(pattern) => Some(name(pattern)),
+        // 경로 패턴은 질의로 옮길 수 없고, 넓힌 조건의 부정은 오히려 좁아지므로 모두 넓힙니다.
+        Expr::Path(_) | Expr::Not(_) => None,
+        Expr::And(left, right) => match (to_query(left), to_query(right)) {
+            (Some(left), Some(right)) => Some(format!("({}) && ({})", left, right)),
+            (left, right) => left.or(right),
+        },
+        Expr::Or(left, right) => Some(format!("({}) || ({})", to_query(left)?, to_query(right)?)),
+    }
+}
+
+/// 질의 문자열 안의 값. 큰따옴표와 역슬래시는 이스케이프하고, Spotlight가 모르는 `?`는 `*`로 넓힙니다.
+fn escape(pattern: &str) -> String {
+    let mut escaped = String::new();
+    for c in pattern.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '?' => escaped.push('*'),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,299 @@
+// ==============================================================================
+//  비교 단계 선택 (--stages)
+// ------------------------------------------------------------------------------
+//  기본 비교는 "크기 → 전체 해시" 두 단계입니다. 데이터에 따라 더 빠르게, 또는 더 확실하게
+//  비교하고 싶을 때 아래 단계를 골라 이 순서대로 이어 붙입니다. (예: `--stages size,sample,hash`)
+//
+//  - size:   크기가 같은 파일만 후보로 남깁니다. 항상 첫 단계입니다.
+//  - sample: 파일의 앞과 뒤 일부(각 16 KB)만 읽어 해시합니다. 크기는 같지만 내용이 다른 큰 파일
+//            (같은 길이의 동영상, 디스크 이미지 등)을 전체를 읽기 전에 걸러 냅니다.
+//  - hash:   전체 내용의 해시(--algorithm)를 비교합니다.
+//  - bytes:  남은 그룹의 파일들을 실제로 한 바이트씩 비교합니다. 해시 충돌까지 배제합니다.
+//
+//  hash와 bytes를 모두 빼면(`size,sample`) 앞뒤 일부만 같은 파일도 묶이는 추정 결과가 되며,
+//  그룹의 `hash` 자리에는 `sample:`로 시작하는 표시와 샘플 해시를 넣어 내용 그룹과 구분합니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::checkpoint::HashGroups;
+use crate::hashing::Hasher;
+use crate::read_hints;
+use crate::reader_pool::{self, FdBudget};
+use crate::{FileStamp, Progress, ScanError, ScanLog, ScanOptions, calculate_hash, interrupt, record_error};
+
+/// 샘플 단계에서 파일의 앞과 뒤에서 각각 읽는 바이트 수.
+const SAMPLE_BYTES: u64 = 16 * 1024;
+
+/// 샘플 그룹의 `hash` 앞에 붙는 표시. 뒤에는 샘플 해시가 이어집니다.
+const SAMPLE_MARK: &str = "sample:";
+
+/// 해시 단계의 그룹 키: (크기, 해시, 두 번째 해시).
+type GroupKey = (u64, String, Option<String>);
+
+/// 비교 단계 하나.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// 크기 비교 (항상 첫 단계)
+    Size,
+    /// 파일 앞뒤 일부의 해시 비교
+    Sample,
+    /// 전체 내용의 해시 비교
+    Hash,
+    /// 바이트 단위 비교
+    Bytes,
+}
+
+/// 크기 비교 뒤에 거칠 단계들.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pipeline {
+    pub sample: bool,
+    pub hash: bool,
+    pub bytes: bool,
+}
+
+impl Default for Pipeline {
+    /// 기본 비교: 크기 → 전체 해시.
+    fn default() -> Self {
+        Pipeline { sample: false, hash: true, bytes: false }
+    }
+}
+
+impl Pipeline {
+    /// `--stages`로 받은 단계 목록을 검사합니다. size로 시작하고, 위의 순서를 따르며,
+    /// 내용을 읽는 단계가 하나 이상 있어야 합니다.
+    pub fn new(stages: &[Stage]) -> Result<Self, String> {
+        if stages.first() != Some(&Stage::Size) {
+            return Err(String::from("--stages는 size로 시작해야 합니다"));
+        }
+        if stages.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(String::from("--stages의 단계는 size, sample, hash, bytes 순서로 한 번씩만 적어야 합니다"));
+        }
+        let pipeline = Pipeline {
+            sample: stages.contains(&Stage::Sample),
+            hash: stages.contains(&Stage::Hash),
+            bytes: stages.contains(&Stage::Bytes),
+        };
+        if !(pipeline.sample || pipeline.hash || pipeline.bytes) {
+            return Err(String::from("--stages에는 size 뒤에 sample, hash, bytes 중 하나 이상이 있어야 합니다"));
+        }
+        Ok(pipeline)
+    }
+
+    /// 전체 내용을 비교하지 않는(샘플만 비교한) 추정 결과인지 여부.
+    pub fn estimated(&self) -> bool {
+        !self.hash && !self.bytes
+    }
+}
+
+/// 샘플 그룹이라면 그 샘플 해시를 반환합니다.
+pub fn group_sample(hash: &str) -> Option<&str> {
+    hash.strip_prefix(SAMPLE_MARK)
+}
+
+/// 파일의 앞과 뒤 `SAMPLE_BYTES`씩을 해시합니다. 그보다 작은 파일은 전체를 해시합니다.
+fn sample_hash(path: &Path, size: u64, options: &ScanOptions) -> io::Result<String> {
+    // 앞뒤만 읽으므로 순차 읽기 힌트는 주지 않습니다.
+    let mut file = read_hints::open(path, false)?;
+    let mut hasher = Hasher::new(options.algorithm);
+    let mut buffer = vec![0; SAMPLE_BYTES as usize];
+    let head = size.min(SAMPLE_BYTES * 2);
+    read_into(&mut file, head, &mut buffer, &mut hasher)?;
+    if size > head {
+        file.seek(SeekFrom::Start(size - SAMPLE_BYTES))?;
+        read_into(&mut file, SAMPLE_BYTES, &mut buffer, &mut hasher)?;
+    }
+    Ok(hasher.finalize())
+}
+
+/// `file`에서 `len` 바이트를 읽어 `hasher`에 넣습니다. 그 전에 파일이 끝나면 오류입니다.
+fn read_into(file: &mut impl Read, len: u64, buffer: &mut [u8], hasher: &mut Hasher) -> io::Result<()> {
+    let mut remaining = len;
+    while remaining > 0 {
+        let want = remaining.min(buffer.len() as u64) as usize;
+        file.read_exact(&mut buffer[..want])?;
+        hasher.update(&buffer[..want]);
+        remaining -= want as u64;
+    }
+    Ok(())
+}
+
+/// 크기 그룹들을 (크기, 샘플 해시)로 다시 나누고, 두 개 이상 남은 묶음만 반환합니다.
+pub fn group_by_sample(
+    files_by_size: HashMap<u64, Vec<(PathBuf, FileStamp)>>,
+    options: &ScanOptions,
+    budget: &FdBudget,
+    errors: &mut Vec<ScanError>,
+    log: &mut ScanLog,
+    progress: &mut Progress,
+) -> HashMap<(u64, String), Vec<(PathBuf, FileStamp)>> {
+    let jobs: Vec<(u64, PathBuf, FileStamp)> = files_by_size
+        .into_iter()
+        .flat_map(|(size, files)| files.into_iter().map(move |(path, stamp)| (size, path, stamp)))
+        .collect();
+    let total = jobs.len() as u64;
+    let mut processed = 0u64;
+    let mut by_sample: HashMap<(u64, String), Vec<(PathBuf, FileStamp)>> = HashMap::new();
+    reader_pool::run_on_devices(
+        jobs,
+        options,
+        |(_, path, _)| path,
+        |(size, path, stamp)| {
+            let _permit = budget.acquire();
+            let result = stamp.verify(&path, "탐색 이후").map(|()| sample_hash(&path, size, options));
+            (size, path, stamp, result)
+        },
+        |(size, path, stamp, result)| {
+            processed += 1;
+            progress.update("샘플 비교 중", processed, Some(total));
+            match result {
+                Ok(Ok(sample)) => by_sample.entry((size, sample)).or_default().push((path, stamp)),
+                Ok(Err(e)) => record_error(errors, log, Some(path), "샘플 비교", e.kind().to_string(), e.to_string()),
+                Err((kind, message)) => record_error(errors, log, Some(path), "변경 감지", kind, message),
+            }
+            !interrupt::requested() && (!options.strict || errors.is_empty())
+        },
+    );
+    by_sample.retain(|_, files| files.len() > 1);
+    log.event("stage_end", json!({ "stage": "sample", "files": total, "candidate_groups": by_sample.len() }));
+    by_sample
+}
+
+/// 샘플 그룹들을 해시 단계의 결과 모양으로 바꿉니다. (hash와 bytes 단계가 없을 때)
+pub fn sample_groups(by_sample: HashMap<(u64, String), Vec<(PathBuf, FileStamp)>>, into: &mut HashGroups) {
+    for ((size, sample), files) in by_sample {
+        let key = (size, format!("{}{}", SAMPLE_MARK, sample), None);
+        into.entry(key).or_default().extend(files.into_iter().map(|(path, _)| path));
+    }
+}
+
+/// 두 파일의 내용이 바이트 단위로 같은지 비교합니다.
+pub fn same_content(a: &Path, b: &Path) -> io::Result<bool> {
+    let (mut a, mut b) = (BufReader::new(read_hints::open(a, true)?), BufReader::new(read_hints::open(b, true)?));
+    let (mut left, mut right) = (vec![0; 64 * 1024], vec![0; 64 * 1024]);
+    loop {
+        // 큰 파일을 비교하는 중에도 Ctrl+C에 바로 반응합니다.
+        if interrupt::requested() {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "사용자가 중단을 요청했습니다"));
+        }
+        let (read_a, read_b) = (fill(&mut a, &mut left)?, fill(&mut b, &mut right)?);
+        if read_a != read_b || left[..read_a] != right[..read_b] {
+            return Ok(false);
+        }
+        if read_a == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// 버퍼가 가득 차거나 파일이 끝날 때까지 읽고, 읽은 바이트 수를 반환합니다.
+fn fill(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted && !interrupt::requested() => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// 그룹 하나를 바이트 단위로 같은 파일끼리 나눈 결과.
+struct Split {
+    /// 같은 내용의 묶음들. 각 묶음의 첫 파일이 대표입니다.
+    classes: Vec<Vec<PathBuf>>,
+    failed: Vec<(PathBuf, io::Error)>,
+}
+
+/// 그룹의 파일들을 대표 파일과 차례로 비교하여 같은 내용끼리 나눕니다.
+fn split_group(paths: Vec<PathBuf>, budget: &FdBudget) -> Split {
+    let mut split = Split { classes: Vec::new(), failed: Vec::new() };
+    'files: for path in paths {
+        for class in split.classes.iter_mut() {
+            // 두 파일을 함께 열지만 허가는 하나만 받아, 예산이 1일 때도 멈추지 않게 합니다.
+            let _permit = budget.acquire();
+            match same_content(&class[0], &path) {
+                Ok(true) => {
+                    class.push(path);
+                    continue 'files;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    split.failed.push((path, e));
+                    continue 'files;
+                }
+            }
+        }
+        split.classes.push(vec![path]);
+    }
+    split
+}
+
+/// 해시(또는 크기, 샘플) 그룹의 파일들을 바이트 단위로 비교하여, 실제로 같은 파일끼리의 그룹만 남깁니다.
+/// 전체 해시 단계가 없었다면 묶음마다 대표 파일의 해시를 계산하여 그룹의 해시로 씁니다.
+pub fn confirm_bytes(
+    groups: Vec<(GroupKey, Vec<PathBuf>)>,
+    options: &ScanOptions,
+    budget: &FdBudget,
+    errors: &mut Vec<ScanError>,
+    log: &mut ScanLog,
+    progress: &mut Progress,
+) -> Vec<(GroupKey, Vec<PathBuf>)> {
+    let total = groups.len() as u64;
+    let mut processed = 0u64;
+    let mut confirmed = Vec::new();
+    reader_pool::run_on_devices(
+        groups,
+        options,
+        |(_, paths)| &paths[0],
+        |(key, paths)| {
+            let split = split_group(paths, budget);
+            // 해시 단계를 거치지 않았다면 그룹의 해시는 아직 내용의 해시가 아닙니다.
+            let classes: Vec<(io::Result<String>, Vec<PathBuf>)> = split
+                .classes
+                .into_iter()
+                .filter(|class| class.len() > 1)
+                .map(|class| {
+                    let hash = if options.stages.hash {
+                        Ok(key.1.clone())
+                    } else {
+                        let _permit = budget.acquire();
+                        calculate_hash(&class[0], options.algorithm)
+                    };
+                    (hash, class)
+                })
+                .collect();
+            (key, classes, split.failed)
+        },
+        |((size, hash, extra), classes, failed)| {
+            processed += 1;
+            progress.update("바이트 비교 중", processed, Some(total));
+            for (path, e) in failed {
+                if e.kind() == io::ErrorKind::Interrupted && interrupt::requested() {
+                    continue;
+                }
+                record_error(errors, log, Some(path), "바이트 비교", e.kind().to_string(), e.to_string());
+            }
+            // 해시가 같은데 내용이 다른 묶음이 여럿이라면 해시 충돌입니다. 드문 일이므로 로그에 남깁니다.
+            if options.stages.hash && classes.len() > 1 {
+                log.event("hash_collision", json!({ "size": size, "hash": hash, "classes": classes.len() }));
+            }
+            for (class_hash, paths) in classes {
+                match class_hash {
+                    Ok(class_hash) => confirmed.push(((size, class_hash, extra.clone()), paths)),
+                    Err(e) => record_error(errors, log, Some(paths[0].clone()), "해시 계산", e.kind().to_string(), e.to_string()),
+                }
+            }
+            !interrupt::requested() && (!options.strict || errors.is_empty())
+        },
+    );
+    log.event("stage_end", json!({ "stage": "bytes", "groups": total, "duplicate_groups": confirmed.len() }));
+    confirmed
+}
@@ -0,0 +1,149 @@
+// ==============================================================================
+//  저장소 잠금 파일 (카탈로그 폴더, 무시 목록, --checkpoint, --lock-wait)
+// ------------------------------------------------------------------------------
+//  cron으로 시작한 검색이 길어져 다음 실행과 겹치면, 두 프로세스가 같은 저장소를 동시에 고쳐 써서
+//  한쪽의 변경이 사라지거나 반쯤 쓴 파일을 읽게 됩니다.
+//
+//  저장소마다 잠금 파일을 두고 운영 체제의 파일 잠금(유닉스 flock, Windows LockFileEx)을 겁니다.
+//  - 읽기만 하는 실행(`index list`, --against-catalogs, 검색 시작 때 무시 목록 읽기)은 공유 잠금을
+//    잡으므로 서로 막지 않습니다.
+//  - 고쳐 쓰는 실행(`index build`/`remove`, `ignore add`/`remove`, TUI의 `i` 키)은 읽고 쓰는 동안만
+//    배타 잠금을 잡습니다. `index build`도 해시를 모두 계산한 뒤 저장하는 순간에만 잡습니다.
+//  - --checkpoint 파일은 한 실행이 검색하는 내내 고쳐 쓰므로, 검색 전체에 걸쳐 배타 잠금을 잡습니다.
+//  잠금을 잡지 못하면 기본으로 바로 실패하며, 잠금을 쥔 실행의 PID와 시작 시각을 알려 줍니다.
+//  --lock-wait DURATION을 주면 그 시간까지 차례를 기다립니다.
+//
+//  잠금은 열린 파일에 걸리므로 프로세스가 죽으면 운영 체제가 풀어 줍니다. 남은 잠금 파일은 지우지
+//  않아도 됩니다. 파일 잠금을 지원하지 않는 플랫폼(WASI)에서는 잠그지 않고 진행합니다.
+// ==============================================================================
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::interrupt;
+
+/// 기다리는 동안 잠금을 다시 시도하는 간격.
+const RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 잠금의 종류.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// 읽기만 합니다. 다른 공유 잠금과 함께 잡을 수 있습니다.
+    Shared,
+    /// 고쳐 씁니다. 다른 어떤 잠금과도 함께 잡을 수 없습니다.
+    Exclusive,
+}
+
+/// 잠금을 기다릴 최대 시간 (--lock-wait). 시작할 때 한 번 정합니다.
+static WAIT: OnceLock<Duration> = OnceLock::new();
+
+/// 잠금을 기다릴 최대 시간을 정합니다. 정하지 않으면 기다리지 않고 바로 실패합니다.
+pub fn set_wait(wait: Duration) {
+    let _ = WAIT.set(wait);
+}
+
+/// 잡은 잠금. 값이 사라질 때 파일을 닫으며 잠금이 풀립니다.
+pub struct StoreLock {
+    /// 잠금을 건 파일. 잠글 필요가 없거나 잠글 수 없는 환경이면 `None`입니다.
+    file: Option<File>,
+    access: Access,
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        // 배타 잠금을 쥔 실행의 기록을 지워, 다음에 막힌 실행이 끝난 실행을 가리키지 않게 합니다.
+        if let Some(file) = &self.file
+            && self.access == Access::Exclusive
+        {
+            let _ = file.set_len(0);
+        }
+    }
+}
+
+/// 파일 하나로 된 저장소 옆의 잠금 파일 (`<파일 이름>.lock`).
+pub fn beside(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".lock");
+    PathBuf::from(name)
+}
+
+/// 폴더로 된 저장소 안의 잠금 파일 (`.lock`).
+pub fn inside(dir: &Path) -> PathBuf {
+    dir.join(".lock")
+}
+
+/// `lock_path`에 잠금을 잡습니다. `what`은 메시지에 쓸 저장소 이름입니다. (예: "카탈로그 폴더")
+///
+/// 공유 잠금은 읽기만 하므로, 저장소 폴더가 아직 없거나 잠금 파일을 만들 권한이 없으면 잠그지 않고
+/// 진행합니다. 배타 잠금은 필요하면 폴더를 만듭니다.
+pub fn acquire(lock_path: &Path, access: Access, what: &str) -> io::Result<StoreLock> {
+    let unlocked = StoreLock { file: None, access };
+    let parent = lock_path.parent().filter(|p| !p.as_os_str().is_empty());
+    if access == Access::Exclusive
+        && let Some(parent) = parent
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = match OpenOptions::new().read(true).write(true).create(true).truncate(false).open(lock_path) {
+        Ok(file) => file,
+        Err(_) if access == Access::Shared => return Ok(unlocked),
+        Err(e) => return Err(io::Error::new(e.kind(), format!("잠금 파일 '{}'을(를) 열 수 없습니다: {}", lock_path.display(), e))),
+    };
+    let wait = WAIT.get().copied().unwrap_or_default();
+    let deadline = Instant::now() + wait;
+    let mut announced = false;
+    loop {
+        let attempt = match access {
+            Access::Shared => file.try_lock_shared(),
+            Access::Exclusive => file.try_lock(),
+        };
+        match attempt {
+            Ok(()) => break,
+            Err(TryLockError::Error(e)) if e.kind() == io::ErrorKind::Unsupported => return Ok(unlocked),
+            Err(TryLockError::Error(e)) => return Err(e),
+            Err(TryLockError::WouldBlock) => {
+                let holder = holder(&mut file).map(|h| format!(" ({})", h)).unwrap_or_default();
+                if Instant::now() >= deadline || interrupt::requested() {
+                    let hint = if wait.is_zero() { "--lock-wait로 기다릴 시간을 지정하거나 " } else { "" };
+                    return Err(io::Error::new(
+                        io::ErrorKind::WouldBlock,
+                        format!(
+                            "{} '{}'을(를) 다른 실행이 사용 중입니다{}. {}그 실행이 끝난 뒤 다시 실행하세요",
+                            what,
+                            lock_path.display(),
+                            holder,
+                            hint
+                        ),
+                    ));
+                }
+                if !announced {
+                    eprintln!("⏳ {}을(를) 다른 실행이 사용 중이라 최대 {}초 기다립니다{}.", what, wait.as_secs(), holder);
+                    announced = true;
+                }
+                thread::sleep(RETRY_INTERVAL);
+            }
+        }
+    }
+    if access == Access::Exclusive {
+        // 막힌 실행이 누구를 기다리는지 알 수 있도록 PID와 시작 시각을 적어 둡니다.
+        let started = humantime::format_rfc3339_seconds(SystemTime::now());
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "PID {}, {} 시작", std::process::id(), started)?;
+        file.flush()?;
+    }
+    Ok(StoreLock { file: Some(file), access })
+}
+
+/// 배타 잠금을 쥔 실행이 적어 둔 기록. 공유 잠금만 걸려 있다면 비어 있습니다.
+fn holder(file: &mut File) -> Option<String> {
+    let mut text = String::new();
+    file.rewind().ok()?;
+    file.read_to_string(&mut text).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
@@ -0,0 +1,87 @@
+// ==============================================================================
+//  실행 지표 파일 (--metrics-out)
+// ------------------------------------------------------------------------------
+//  cron이나 systemd 타이머로 주기적으로 돌리는 검색은 사람이 화면을 보지 않으므로, 모니터링 도구가
+//  "검색이 제때 끝났는가, 느려지고 있는가, 오류가 늘었는가"를 알 수 있어야 합니다.
+//
+//  --metrics-out FILE은 검색이 끝날 때마다 다음 수치를 JSON 객체 하나로 FILE에 씁니다.
+//  - 상태 (completed, interrupted, failed)와 실패했다면 그 이유
+//  - 전체 소요 시간과 단계별 소요 시간 (--profile과 같은 단계: 폴더 탐색, 메타데이터 조회, 해시 계산, 그룹 비교)
+//  - 파일 수, 바이트 수 (발견, 새로 읽음), 중복 그룹 수와 회수 가능 공간
+//  - 해시 캐시(--xattr-cache)의 적중, 실패, 무효화 수와 적중률, 추정 절약 시간
+//  - 오류 분류별 개수
+//  수치 이름에는 단위(_ms, _bytes)를 붙여 수집기가 그대로 받아들일 수 있게 합니다.
+//  데몬 모드(--daemon)에서는 매 검색마다 같은 파일을 덮어씁니다. 수집기가 쓰는 도중의 파일을 읽지
+//  않도록 임시 파일에 쓴 뒤 이름을 바꿉니다.
+// ==============================================================================
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Value, json};
+
+use crate::{RunReport, atomic_file};
+
+/// 지표 형식의 버전. 항목의 의미가 바뀌면 올립니다.
+const SCHEMA_VERSION: u32 = 1;
+
+/// 검색 한 번의 지표. 검색이 실패했다면 `report`가 없고 `error`에 이유가 있습니다.
+pub fn metrics(roots: &[PathBuf], report: Option<&RunReport>, error: Option<String>) -> Value {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mut metrics = json!({
+        "schema_version": SCHEMA_VERSION,
+        "finished_at": humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        "roots": roots.iter().map(|r| r.display().to_string()).collect::<Vec<_>>(),
+    });
+    let Some(report) = report else {
+        metrics["status"] = json!("failed");
+        metrics["error"] = json!(error);
+        return metrics;
+    };
+    let stats = &report.stats;
+    let profile = &report.profile;
+    let status = if report.interrupted { "interrupted" } else { "completed" };
+    metrics["status"] = json!(status);
+    metrics["duration_ms"] = json!(ms(report.duration));
+    metrics["stages_ms"] = json!({
+        "walk": ms(profile.walk),
+        "stat": ms(profile.stat),
+        "hash": ms(profile.hash),
+        "compare": ms(profile.compare),
+    });
+    metrics["hash_thread_total_ms"] = json!(ms(profile.hash_total));
+    metrics["files_scanned"] = json!(stats.files_scanned);
+    metrics["scanned_bytes"] = json!(stats.bytes_scanned);
+    metrics["hashed_bytes"] = json!(stats.bytes_hashed);
+    metrics["hashed_files"] = json!(stats.files_hashed);
+    // 단계별 처리 속도. 걸린 시간이 0이었던 단계는 `null`입니다.
+    metrics["throughput"] = stats
+        .throughput(&report.profile)
+        .into_iter()
+        .map(|(stage, rate)| (stage.to_string(), json!(rate.map(|(files, bytes)| json!({"files_per_s": files, "bytes_per_s": bytes})))))
+        .collect();
+    metrics["duplicate_groups"] = json!(report.groups);
+    metrics["wasted_bytes"] = json!(report.wasted_bytes);
+    metrics["cache"] = json!({
+        "hits": stats.cache.hits,
+        "misses": stats.cache.misses,
+        "invalidations": stats.cache.invalidated,
+        // 캐시를 쓰지 않았다면 비율도 절약 시간도 없습니다.
+        "hit_ratio": stats.cache.hit_ratio(),
+        "saved_ms": stats.cache.saved.map(ms),
+    });
+    metrics["errors"] = json!({
+        "total": report.errors_by_kind.values().sum::<usize>(),
+        "by_kind": report.errors_by_kind,
+    });
+    metrics
+}
+
+/// 지표를 파일에 씁니다. 임시 파일에 쓴 뒤 이름을 바꾸어, 읽는 쪽이 항상 온전한 파일을 보게 합니다.
+pub fn write(path: &Path, metrics: &Value) -> io::Result<()> {
+    atomic_file::write(path, |writer| {
+        serde_json::to_writer_pretty(&mut *writer, metrics)?;
+        writer.write_all(b"\n")
+    })
+}
@@ -0,0 +1,708 @@
+// ==============================================================================
+//  대화형 TUI 모드 (--tui)
+// ------------------------------------------------------------------------------
+//  수천 개의 중복 그룹을 텍스트 출력만으로 정리하기는 어렵기 때문에,
+//  ratatui 기반의 화면에서 그룹을 탐색하고 파일별로 유지/삭제를 표시한 뒤
+//  인터페이스 안에서 바로 삭제를 실행할 수 있도록 합니다.
+//
+//  화면 구성:
+//  - 왼쪽: 중복 그룹 목록
+//  - 오른쪽: 선택한 그룹 멤버들의 메타데이터(크기, 수정 시간, 권한) 비교 표
+//  - 아래쪽: 단축키 안내 및 상태 메시지
+//
+//  삭제하기 전에 어떤 사진/문서인지 눈으로 확인할 수 있도록, 선택한 파일을
+//  운영체제의 기본 프로그램(xdg-open/open/start)으로 열 수 있습니다.
+//
+//  일부러 남겨 둘 그룹은 `i` 키로 무시 목록에 기록하여 다음 검색부터 보고하지 않게 합니다.
+//
+//  터미널이 그래픽 프로토콜을 지원하면 선택한 그룹의 이미지 멤버들을 오른쪽 아래에 나란히
+//  미리 보여 줍니다. (preview 모듈, --preview)
+// ==============================================================================
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::actions::{self, PruneMode, PruneReport};
+use crate::elevate::Denied;
+use crate::ignore::{IgnoreList, IgnoredGroup};
+use crate::preview::{self, Previewer, Slot};
+use crate::{DuplicateGroup, FileStamp, PreviewProtocol, format_size, in_use, safety};
+
+/// 미리보기 영역에 한 번에 나란히 그리는 이미지 수.
+const PREVIEW_SLOTS: usize = 4;
+
+/// 그룹 멤버 하나에 대해 사용자가 지정한 처리 방식.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    /// 파일을 그대로 둡니다. (기본값)
+    Keep,
+    /// 실행(x) 시 파일을 삭제합니다.
+    Delete,
+}
+
+/// 현재 키보드 입력을 받는 화면 영역.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Groups,
+    Members,
+}
+
+/// 중복 그룹 하나의 멤버들과, 무시 목록에 기록할 때 쓰는 그룹 정보.
+struct Group {
+    members: Vec<Member>,
+    ignore: IgnoredGroup,
+    /// 심볼릭 링크 그룹인지 여부. 관리자 권한으로 다시 지울 때 내용 해시를 확인하지 않습니다.
+    symlink: bool,
+}
+
+/// 멤버 파일 하나의 경로와, 화면에 표시할 메타데이터.
+struct Member {
+    path: PathBuf,
+    mark: Mark,
+    /// 스캔에서 확인된 그룹의 파일 크기.
+    expected_size: u64,
+    /// 해시하기 전에 확인한 크기와 수정 시간. 삭제 직전에 다시 비교하여,
+    /// 그 사이에 바뀐 파일(더 이상 중복이 아닐 수 있는 파일)은 삭제하지 않습니다.
+    /// 파일을 읽지 않은 그룹(심볼릭 링크)은 화면에 불러올 때의 값입니다.
+    stamp: Option<FileStamp>,
+    /// 메타데이터를 읽지 못했다면 `None` (예: 스캔 이후 삭제된 파일).
+    info: Option<MemberInfo>,
+}
+
+/// 메타데이터 비교 표에 표시되는 값들.
+struct MemberInfo {
+    size: u64,
+    modified: String,
+    permissions: String,
+}
+
+/// TUI의 전체 상태.
+struct App {
+    groups: Vec<Group>,
+    group_state: ListState,
+    member_state: TableState,
+    focus: Focus,
+    /// 삭제 실행 확인 창이 떠 있는지 여부.
+    confirming: bool,
+    /// 화면 하단에 표시할 마지막 상태 메시지.
+    status: String,
+    /// 위험한 루트(--force-dangerous-root 없이)를 검색했다면 삭제를 막는 이유.
+    delete_blocked: Option<String>,
+    /// 삭제 뒤에 빈 폴더를 정리할 (검색 루트, 방식). (--prune-empty-dirs)
+    prune: Option<(Vec<PathBuf>, PruneMode)>,
+    /// 지금까지 정리한(dry-run이라면 정리할 수 있는) 빈 폴더. 종료 후 출력합니다.
+    pruned: PruneReport,
+    /// 권한이 없어 지우지 못한 파일. 종료 후 관리자 권한으로 다시 지울 수 있습니다. (elevate 모듈)
+    denied: Vec<Denied>,
+    /// `i` 키로 그룹을 기록할 무시 목록 파일. 위치를 알 수 없다면 `None`.
+    ignore_file: Option<PathBuf>,
+    /// 이미지 미리보기. 터미널이 그래픽 프로토콜을 지원하지 않으면 `None`.
+    preview: Option<Previewer>,
+    /// 이번 화면에서 이미지를 그릴 칸들. 화면을 그릴 때 정하고, 그린 뒤에 이미지를 그립니다.
+    preview_slots: Vec<Slot>,
+}
+
+/// TUI를 닫은 뒤에 처리할 결과.
+pub struct Outcome {
+    pub pruned: PruneReport,
+    pub denied: Vec<Denied>,
+}
+
+/// 찾은 중복 그룹들을 TUI로 보여주고, 사용자가 종료할 때까지 이벤트 루프를 실행합니다.
+///
+/// `delete_blocked`가 `Some`이면 그룹 탐색과 표시는 가능하지만 삭제 실행은 거부합니다.
+/// `prune`이 `Some`이면 삭제할 때마다 비게 된 폴더를 정리하고, 정리한 폴더를 반환합니다.
+/// 권한이 없어 지우지 못한 파일도 함께 반환합니다.
+/// `ignore_file`은 `i` 키로 그룹을 기록할 무시 목록 파일입니다.
+/// `preview`가 `Some`이면 그 프로토콜로 이미지 멤버를 미리 보여 줍니다.
+pub fn run(
+    duplicates: Vec<DuplicateGroup>,
+    delete_blocked: Option<String>,
+    prune: Option<(Vec<PathBuf>, PruneMode)>,
+    ignore_file: Option<PathBuf>,
+    preview: Option<PreviewProtocol>,
+) -> io::Result<Outcome> {
+    let mut app = App::new(duplicates, delete_blocked, prune, ignore_file, preview);
+    // `ratatui::init()`은 raw 모드 진입, 대체 화면 전환, 패닉 훅 설치를 한 번에 처리합니다.
+    let mut terminal = ratatui::init();
+    let mut result = app.event_loop(&mut terminal);
+    // kitty 이미지는 글자와 따로 남으므로 화면을 돌려놓기 전에 지웁니다.
+    if let Some(preview) = &mut app.preview {
+        result = result.and(preview.clear(terminal.backend_mut()));
+    }
+    // 오류가 나더라도 터미널은 반드시 원래 상태로 되돌려야 합니다.
+    ratatui::restore();
+    result.map(|()| Outcome { pruned: app.pruned, denied: app.denied })
+}
+
+impl App {
+    fn new(
+        duplicates: Vec<DuplicateGroup>,
+        delete_blocked: Option<String>,
+        prune: Option<(Vec<PathBuf>, PruneMode)>,
+        ignore_file: Option<PathBuf>,
+        preview: Option<PreviewProtocol>,
+    ) -> Self {
+        let groups = duplicates
+            .into_iter()
+            .map(|group| {
+                let size = group.size;
+                let ignore = IgnoredGroup::of(&group);
+                let symlink = group.symlink_target.is_some();
+                let members = group
+                    .paths
+                    .into_iter()
+                    .map(|path| Member {
+                        info: read_member_info(&path),
+                        stamp: group.stamps.get(&path).copied().or_else(|| fs::metadata(&path).ok().map(|m| FileStamp::of(&m))),
+                        path,
+                        mark: Mark::Keep,
+                        expected_size: size,
+                    })
+                    .collect();
+                Group { members, ignore, symlink }
+            })
+            .collect();
+        let mut app = App {
+            groups,
+            group_state: ListState::default(),
+            member_state: TableState::default(),
+            focus: Focus::Groups,
+            confirming: false,
+            status: String::from("↑/↓ 이동, Tab 영역 전환, d 삭제 표시, k 유지 표시, o 열기, i 무시, x 실행, q 종료"),
+            delete_blocked,
+            prune,
+            pruned: PruneReport::default(),
+            denied: Vec::new(),
+            ignore_file,
+            preview: preview.map(Previewer::new),
+            preview_slots: Vec::new(),
+        };
+        app.select_group(0);
+        app
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            self.render(terminal)?;
+            // 키를 누를 때(Press)만 처리하여, 일부 플랫폼에서 Release 이벤트로
+            // 같은 동작이 두 번 실행되는 것을 막습니다.
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if self.confirming {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        self.confirming = false;
+                        self.execute();
+                    }
+                    _ => {
+                        self.confirming = false;
+                        self.status = String::from("삭제를 취소했습니다.");
+                    }
+                }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Tab | KeyCode::Left | KeyCode::Right => self.toggle_focus(),
+                KeyCode::Down => self.move_selection(1),
+                KeyCode::Up => self.move_selection(-1),
+                KeyCode::Char('d') => self.set_mark(Mark::Delete),
+                KeyCode::Char('k') => self.set_mark(Mark::Keep),
+                KeyCode::Char(' ') => self.toggle_mark(),
+                KeyCode::Char('o') => self.open_selected(),
+                KeyCode::Char('i') => self.ignore_selected(),
+                KeyCode::Char('x') => self.request_execute(),
+                _ => {}
+            }
+        }
+    }
+
+    /// 화면을 그리고, 미리보기 칸이 바뀌었다면 이미지를 다시 그립니다.
+    fn render(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        terminal.draw(|frame| self.draw(frame))?;
+        let Some(preview) = &self.preview else { return Ok(()) };
+        if preview.painted() == self.preview_slots {
+            return Ok(());
+        }
+        // 칸을 덮어쓴 이전 이미지는 ratatui가 빈칸으로 알고 있어 다시 쓰지 않습니다. 이전 칸들을 한 번
+        // 다른 배경색으로 그렸다가 되돌려, ratatui가 그 칸들을 새로 쓰면서 이미지를 지우게 합니다.
+        // (`terminal.clear()`는 커서 위치를 터미널에 묻느라 응답을 기다리므로 쓰지 않습니다)
+        if preview.overwrites_cells() {
+            let stale: Vec<Rect> = preview.painted().iter().map(|(area, _)| *area).collect();
+            terminal.draw(|frame| {
+                self.draw(frame);
+                for area in &stale {
+                    frame.render_widget(Block::default().style(Style::default().bg(Color::Black)), *area);
+                }
+            })?;
+            terminal.draw(|frame| self.draw(frame))?;
+        }
+        let slots = std::mem::take(&mut self.preview_slots);
+        let result = match &mut self.preview {
+            Some(preview) => preview.paint(terminal.backend_mut(), &slots),
+            None => Ok(()),
+        };
+        self.preview_slots = slots;
+        result
+    }
+
+    // --- 선택 및 표시 상태 변경 ---
+
+    fn selected_group(&self) -> Option<usize> {
+        self.group_state.selected().filter(|&i| i < self.groups.len())
+    }
+
+    fn select_group(&mut self, index: usize) {
+        if self.groups.is_empty() {
+            self.group_state.select(None);
+            self.member_state.select(None);
+        } else {
+            self.group_state.select(Some(index.min(self.groups.len() - 1)));
+            self.member_state.select(Some(0));
+        }
+    }
+
+    fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Groups => Focus::Members,
+            Focus::Members => Focus::Groups,
+        };
+    }
+
+    /// 현재 포커스된 영역에서 선택을 `delta`만큼 이동합니다. (양 끝에서 멈춤)
+    fn move_selection(&mut self, delta: isize) {
+        let Some(group) = self.selected_group() else { return };
+        match self.focus {
+            Focus::Groups => {
+                let next = group.saturating_add_signed(delta).min(self.groups.len() - 1);
+                self.select_group(next);
+            }
+            Focus::Members => {
+                let len = self.groups[group].members.len();
+                let current = self.member_state.selected().unwrap_or(0);
+                let next = current.saturating_add_signed(delta).min(len - 1);
+                self.member_state.select(Some(next));
+            }
+        }
+    }
+
+    fn selected_member_mut(&mut self) -> Option<&mut Member> {
+        let group = self.selected_group()?;
+        let member = self.member_state.selected()?;
+        self.groups[group].members.get_mut(member)
+    }
+
+    fn set_mark(&mut self, mark: Mark) {
+        if let Some(member) = self.selected_member_mut() {
+            member.mark = mark;
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(member) = self.selected_member_mut() {
+            member.mark = match member.mark {
+                Mark::Keep => Mark::Delete,
+                Mark::Delete => Mark::Keep,
+            };
+        }
+    }
+
+    /// 선택한 멤버 파일을 시스템 기본 프로그램으로 엽니다.
+    fn open_selected(&mut self) {
+        let Some(path) = self.selected_member_mut().map(|m| m.path.clone()) else { return };
+        self.status = match open_in_viewer(&path) {
+            Ok(()) => format!("'{}'을(를) 기본 프로그램으로 열었습니다.", path.display()),
+            Err(e) => format!("'{}'을(를) 열 수 없습니다: {}", path.display(), e),
+        };
+    }
+
+    /// 선택한 그룹을 무시 목록에 기록하고 목록에서 뺍니다. 다음 검색부터 보고되지 않습니다.
+    fn ignore_selected(&mut self) {
+        let Some(index) = self.selected_group() else { return };
+        let Some(path) = &self.ignore_file else {
+            self.status = String::from("무시 목록 파일의 위치를 알 수 없습니다. --ignore-file로 지정하세요.");
+            return;
+        };
+        // 다른 실행이 그 사이에 기록한 그룹을 잃지 않도록 저장 직전에 잠그고 다시 읽습니다.
+        let result = IgnoreList::update(path, |list| {
+            list.add(self.groups[index].ignore.clone());
+            Ok(())
+        });
+        self.status = match result {
+            Ok(()) => {
+                self.groups.remove(index);
+                self.select_group(index);
+                format!("그룹 {}을(를) 무시 목록에 기록했습니다. 다음 검색부터 보고하지 않습니다.", index + 1)
+            }
+            Err(e) => format!("무시 목록에 기록할 수 없습니다: {}", e),
+        };
+    }
+
+    // --- 삭제 실행 ---
+
+    /// 삭제 표시된 파일이 있는지, 그리고 모든 그룹에 유지할 파일이 남는지 확인한 뒤
+    /// 확인 창을 띄웁니다.
+    fn request_execute(&mut self) {
+        if let Some(reason) = &self.delete_blocked {
+            self.status = format!("{} 삭제하려면 --force-dangerous-root 옵션으로 다시 실행하세요.", reason);
+            return;
+        }
+        let pending = self.pending_deletions();
+        if pending == 0 {
+            self.status = String::from("삭제 표시된 파일이 없습니다.");
+            return;
+        }
+        // 그룹의 모든 사본을 지워버리면 원본 데이터 자체가 사라지므로 허용하지 않습니다.
+        if let Some(i) = self
+            .groups
+            .iter()
+            .position(|g| !safety::has_survivor(&g.members, |m| m.mark == Mark::Delete))
+        {
+            self.status = format!("그룹 {}의 모든 파일이 삭제 표시되어 있습니다. 최소 하나는 유지해야 합니다.", i + 1);
+            self.select_group(i);
+            return;
+        }
+        self.confirming = true;
+    }
+
+    fn pending_deletions(&self) -> usize {
+        self.groups
+            .iter()
+            .flat_map(|g| &g.members)
+            .filter(|m| m.mark == Mark::Delete)
+            .count()
+    }
+
+    /// 삭제 표시된 파일들을 실제로 삭제하고, 더 이상 중복이 아닌 그룹은 목록에서 제거합니다.
+    fn execute(&mut self) {
+        let mut deleted_paths = Vec::new();
+        let mut failed = 0;
+        let mut denied = 0;
+        let mut changed = 0;
+        let mut busy = 0;
+        let mut orphaned = 0;
+        for Group { members: group, ignore, symlink } in &mut self.groups {
+            // 유지하기로 한 파일이 그 사이 외부에서 삭제되었다면, 표시대로 지울 경우
+            // 그룹의 사본이 하나도 남지 않습니다. 이런 그룹은 아무것도 삭제하지 않습니다.
+            if !safety::has_survivor(group, |m| m.mark == Mark::Delete || fs::metadata(&m.path).is_err()) {
+                orphaned += 1;
+                continue;
+            }
+            let survivors: Vec<PathBuf> = group.iter().filter(|m| m.mark != Mark::Delete).map(|m| m.path.clone()).collect();
+            group.retain(|member| {
+                if member.mark != Mark::Delete {
+                    return true;
+                }
+                // 스캔 이후 바뀐 파일은 더 이상 그룹의 다른 파일과 같다고 보장할 수 없으므로
+                // 삭제하지 않고 목록에 남겨 둡니다.
+                let unchanged = member
+                    .stamp
+                    .is_some_and(|s| s.len == member.expected_size && s.verify(&member.path, "").is_ok());
+                if !unchanged {
+                    changed += 1;
+                    return true;
+                }
+                // 다른 프로세스가 잠갔거나 쓰고 있는 파일은 지우지 않습니다.
+                if in_use::ensure_idle(&member.path).is_err() {
+                    busy += 1;
+                    return true;
+                }
+                match fs::remove_file(&member.path) {
+                    Ok(()) => {
+                        deleted_paths.push(member.path.clone());
+                        false
+                    }
+                    // 권한이 없는 파일은 종료 후 관리자 권한으로 다시 지울 수 있도록 모아 둡니다.
+                    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                        denied += 1;
+                        if !self.denied.iter().any(|d| d.path == member.path) {
+                            self.denied.push(Denied {
+                                path: member.path.clone(),
+                                size: member.expected_size,
+                                hash: (!*symlink).then(|| ignore.hash.clone()),
+                                algorithm: ignore.algorithm.clone(),
+                                survivors: survivors.clone(),
+                            });
+                        }
+                        true
+                    }
+                    // 실패한 파일은 목록에 남겨 두어 사용자가 확인할 수 있게 합니다.
+                    Err(_) => {
+                        failed += 1;
+                        true
+                    }
+                }
+            });
+        }
+        // 멤버가 하나 이하로 줄어든 그룹은 더 이상 중복 그룹이 아닙니다.
+        self.groups.retain(|g| g.members.len() > 1);
+        let current = self.selected_group().unwrap_or(0);
+        self.select_group(current);
+
+        let mut status = format!("{}개 파일을 삭제했습니다.", deleted_paths.len());
+        if failed > 0 {
+            status.push_str(&format!(" {}개 파일은 삭제하지 못했습니다.", failed));
+        }
+        if denied > 0 {
+            status.push_str(&format!(" {}개 파일은 권한이 없어 지우지 못했습니다. (종료 후 관리자 권한으로 다시 시도)", denied));
+        }
+        if changed > 0 {
+            status.push_str(&format!(" {}개 파일은 스캔 이후 변경되어 건너뛰었습니다.", changed));
+        }
+        if busy > 0 {
+            status.push_str(&format!(" {}개 파일은 다른 프로세스가 사용 중이어서 건너뛰었습니다.", busy));
+        }
+        if orphaned > 0 {
+            status.push_str(&format!(
+                " {}개 그룹은 유지할 파일이 더 이상 존재하지 않아 건너뛰었습니다.",
+                orphaned
+            ));
+        }
+        if let Some((roots, mode)) = &self.prune {
+            let report = actions::prune_empty_dirs(&deleted_paths, roots, *mode);
+            status.push_str(&match mode {
+                PruneMode::Remove => format!(" 빈 폴더 {}개를 지웠습니다.", report.removed.len()),
+                PruneMode::DryRun => format!(" 빈 폴더 {}개를 지울 수 있습니다. (dry-run)", report.removed.len()),
+            });
+            // dry-run에서는 폴더가 그대로 남으므로, 다음 삭제에서 같은 폴더가 다시 보고될 수 있습니다.
+            for dir in report.removed {
+                if !self.pruned.removed.contains(&dir) {
+                    self.pruned.removed.push(dir);
+                }
+            }
+            self.pruned.failed.extend(report.failed);
+        }
+        self.status = status;
+    }
+
+    // --- 화면 그리기 ---
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(frame.area());
+        let [groups_area, members_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(main_area);
+
+        self.draw_groups(frame, groups_area);
+        self.preview_slots.clear();
+        // 확인 창 위에 이미지가 겹치지 않도록, 창이 떠 있는 동안에는 미리보기를 그리지 않습니다.
+        let images = if self.preview.is_some() && !self.confirming { self.image_members() } else { Vec::new() };
+        if images.is_empty() {
+            self.draw_members(frame, members_area);
+        } else {
+            let [table_area, preview_area] =
+                Layout::vertical([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(members_area);
+            self.draw_members(frame, table_area);
+            self.draw_previews(frame, preview_area, &images);
+        }
+
+        let status = Paragraph::new(self.status.as_str()).block(Block::default().borders(Borders::ALL));
+        frame.render_widget(status, status_area);
+
+        if self.confirming {
+            self.draw_confirm(frame);
+        }
+    }
+
+    fn draw_groups(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .groups
+            .iter()
+            .enumerate()
+            .map(|(i, group)| {
+                let marked = group.members.iter().filter(|m| m.mark == Mark::Delete).count();
+                let size = group.members.iter().find_map(|m| m.info.as_ref()).map_or(0, |info| info.size);
+                ListItem::new(format!(
+                    "그룹 {} · {}개 · {} · 삭제 {}",
+                    i + 1,
+                    group.members.len(),
+                    format_size(size),
+                    marked
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(focus_block(" 중복 그룹 ", self.focus == Focus::Groups))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.group_state);
+    }
+
+    fn draw_members(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<Row> = self
+            .selected_group()
+            .map(|g| self.groups[g].members.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|member| {
+                let (mark, style) = match member.mark {
+                    Mark::Keep => ("유지", Style::default().fg(Color::Green)),
+                    Mark::Delete => ("삭제", Style::default().fg(Color::Red)),
+                };
+                let (size, modified, permissions) = match &member.info {
+                    Some(info) => (format_size(info.size), info.modified.clone(), info.permissions.clone()),
+                    None => (String::from("?"), String::from("(읽을 수 없음)"), String::from("?")),
+                };
+                Row::new(vec![
+                    mark.to_string(),
+                    member.path.display().to_string(),
+                    size,
+                    modified,
+                    permissions,
+                ])
+                .style(style)
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(20),
+                Constraint::Length(10),
+            ],
+        )
+        .header(
+            Row::new(vec!["처리", "경로", "크기", "수정 시간 (UTC)", "권한"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(focus_block(" 멤버 메타데이터 ", self.focus == Focus::Members))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.member_state);
+    }
+
+    /// 미리보기로 그릴 이미지 멤버들의 번호. 선택한 멤버가 보이도록 최대 `PREVIEW_SLOTS`개를 고릅니다.
+    fn image_members(&self) -> Vec<usize> {
+        let Some(group) = self.selected_group() else { return Vec::new() };
+        let images: Vec<usize> = self.groups[group]
+            .members
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| preview::is_image(&m.path))
+            .map(|(i, _)| i)
+            .collect();
+        let selected = self.member_state.selected().unwrap_or(0);
+        let position = images.iter().position(|&i| i >= selected).unwrap_or(images.len());
+        let start = (position + 1).saturating_sub(PREVIEW_SLOTS).min(images.len().saturating_sub(PREVIEW_SLOTS));
+        images.into_iter().skip(start).take(PREVIEW_SLOTS).collect()
+    }
+
+    /// 이미지 멤버마다 테두리 있는 칸을 나란히 그리고, 이미지를 그릴 안쪽 영역을 기록합니다.
+    fn draw_previews(&mut self, frame: &mut Frame, area: Rect, images: &[usize]) {
+        let Some(group) = self.selected_group() else { return };
+        let selected = self.member_state.selected();
+        let areas = Layout::horizontal(vec![Constraint::Ratio(1, images.len() as u32); images.len()]).split(area);
+        for (&index, &cell) in images.iter().zip(areas.iter()) {
+            let path = &self.groups[group].members[index].path;
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let title = format!(" {}. {} ", index + 1, name);
+            let block = focus_block(&title, self.focus == Focus::Members && selected == Some(index));
+            let inner = block.inner(cell);
+            frame.render_widget(block, cell);
+            let loaded = self.preview.as_mut().is_some_and(|p| p.load(path));
+            if loaded {
+                self.preview_slots.push((inner, path.clone()));
+            } else {
+                frame.render_widget(Paragraph::new("(미리 볼 수 없음)"), inner);
+            }
+        }
+    }
+
+    fn draw_confirm(&self, frame: &mut Frame) {
+        let [area] = Layout::vertical([Constraint::Length(5)]).flex(Flex::Center).areas(frame.area());
+        let [area] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+        let text = format!(
+            "삭제 표시된 {}개 파일을 삭제합니다. 되돌릴 수 없습니다.\n계속하려면 y, 취소하려면 다른 키를 누르세요.",
+            self.pending_deletions()
+        );
+        let popup = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" 삭제 확인 ")
+                .border_style(Style::default().fg(Color::Red)),
+        );
+        // 팝업 아래의 내용이 비쳐 보이지 않도록 먼저 영역을 지웁니다.
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+}
+
+/// 포커스 여부에 따라 테두리 색을 바꾼 블록을 만듭니다.
+fn focus_block(title: &str, focused: bool) -> Block<'_> {
+    let color = if focused { Color::Yellow } else { Color::Gray };
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(color))
+}
+
+/// 플랫폼의 기본 프로그램으로 파일을 엽니다.
+///
+/// 뷰어가 TUI 화면에 출력을 섞지 않도록 표준 입출력을 모두 닫고,
+/// 뷰어가 종료될 때까지 기다리지 않습니다. 종료된 뷰어가 좀비 프로세스로 남지 않도록
+/// 따로 띄운 스레드가 종료를 기다려 거둡니다.
+fn open_in_viewer(path: &Path) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // `start`는 cmd 내장 명령입니다. 첫 번째 따옴표 인자는 창 제목으로 해석되므로 빈 제목을 넘깁니다.
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|mut child| {
+            thread::spawn(move || child.wait());
+        })
+}
+
+/// 파일의 메타데이터를 읽어 화면 표시용 문자열로 변환합니다.
+fn read_member_info(path: &Path) -> Option<MemberInfo> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .map(|t| humantime::format_rfc3339_seconds(t).to_string())
+        .unwrap_or_else(|_| String::from("?"));
+    Some(MemberInfo {
+        size: metadata.len(),
+        modified,
+        permissions: format_permissions(&metadata),
+    })
+}
+
+/// 유닉스 계열에서는 8진수 권한(예: 644)을, 그 외에서는 읽기 전용 여부를 표시합니다.
+#[cfg(unix)]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    format!("{:o}", metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    if metadata.permissions().readonly() {
+        String::from("읽기 전용")
+    } else {
+        String::from("읽기/쓰기")
+    }
+}
@@ -0,0 +1,370 @@
+// ==============================================================================
+//  감시 모드: 드롭 폴더에 들어온 새 파일을 기존 내용과 비교 (--watch)
+// ------------------------------------------------------------------------------
+//  스캐너, 업로드, 동기화 프로그램이 파일을 내려놓는 폴더(드롭 폴더)에서는 이미 보관 중인
+//  파일이 계속 다시 들어옵니다. --watch는 시작할 때 루트 폴더들과 드롭 폴더의 파일을 크기별로
+//  색인한 뒤, 드롭 폴더를 주기적으로 다시 살펴 새로 생긴 파일이 색인된 내용과 같은지 확인합니다.
+//
+//  새 파일은 두 번 연속으로 살펴보는 동안 크기와 수정 시간이 그대로이고 다른 프로세스가 쓰고
+//  있지 않을 때(쓰기가 끝났을 때) 비교합니다. 크기가 같은 색인 파일만 해시하고(해시는 기억해 둡니다),
+//  해시가 같으면 바이트 비교로 확인한 뒤 --watch-action에 따라 처리합니다.
+//  - report: 보고와 로그 기록만 합니다.
+//  - hardlink: 새 파일을 기존 파일의 하드 링크로 바꿔 공간을 돌려받습니다. (같은 파일 시스템에서만)
+//  - quarantine: 새 파일을 --quarantine-dir로 옮깁니다. 드롭 폴더 안의 상대 경로를 그대로 씁니다.
+//  파일을 바꾸는 동작은 바꾸기 전의 시각, 권한, 소유자, 확장 속성을 로그에 남기고, 남는 파일에서
+//  메타데이터가 사라지지 않게 합니다. (metadata.rs)
+//  기존 내용과 다른 새 파일은 색인에 더하므로, 다음에 같은 파일이 들어오면 그 파일과 비교합니다.
+//  빈 파일은 비교하지 않습니다.
+//
+//  Ctrl+C(또는 SIGTERM)를 받으면 처리한 파일 수를 요약하고 종료합니다.
+// ==============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use serde_json::json;
+use walkdir::WalkDir;
+
+use crate::metadata::Snapshot;
+use crate::{
+    FileStamp, ScanLog, ScanOptions, calculate_hash, file_identity, in_use, interrupt, passes_filter, path_style, stages,
+};
+
+/// 다음 살펴보기를 기다리는 동안 중단 요청을 확인하는 간격.
+const WAIT_STEP: Duration = Duration::from_millis(200);
+
+/// 새 파일이 기존 내용과 같을 때 할 일 (--watch-action).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum WatchAction {
+    /// 보고와 로그 기록만 합니다.
+    Report,
+    /// 새 파일을 기존 파일의 하드 링크로 바꿉니다.
+    Hardlink,
+    /// 새 파일을 --quarantine-dir로 옮깁니다.
+    Quarantine,
+}
+
+/// 감시 설정.
+pub struct Config {
+    /// 새 파일을 기다리는 드롭 폴더.
+    pub folder: PathBuf,
+    /// 시작할 때 색인할 폴더들. (루트 폴더와 드롭 폴더, 겹침은 정리된 상태)
+    pub index_roots: Vec<PathBuf>,
+    pub action: WatchAction,
+    /// quarantine 동작에서 파일을 옮길 폴더.
+    pub quarantine_dir: Option<PathBuf>,
+    /// 드롭 폴더를 다시 살펴보는 간격.
+    pub interval: Duration,
+}
+
+/// 크기별 파일 색인과, 한 번 계산한 해시.
+#[derive(Default)]
+struct Index {
+    by_size: HashMap<u64, Vec<PathBuf>>,
+    /// 경로 -> (해시를 계산할 때의 크기/수정 시간, 해시).
+    hashes: HashMap<PathBuf, (FileStamp, String)>,
+}
+
+impl Index {
+    fn add(&mut self, path: PathBuf, size: u64) {
+        self.by_size.entry(size).or_default().push(path);
+    }
+
+    /// 색인 파일의 해시. 지난번 계산 이후 파일이 바뀌었다면 다시 계산합니다.
+    fn hash(&mut self, path: &Path, options: &ScanOptions) -> io::Result<String> {
+        let stamp = FileStamp::of(&fs::metadata(path)?);
+        if let Some((recorded, hash)) = self.hashes.get(path)
+            && *recorded == stamp
+        {
+            return Ok(hash.clone());
+        }
+        let hash = calculate_hash(path, options.algorithm)?;
+        self.hashes.insert(path.to_path_buf(), (stamp, hash.clone()));
+        Ok(hash)
+    }
+
+    /// 새 파일과 내용이 같은 색인 파일을 찾습니다. 사라졌거나 크기가 바뀐 색인 파일은 색인에서 뺍니다.
+    fn find_match(&mut self, path: &Path, size: u64, options: &ScanOptions) -> io::Result<Option<PathBuf>> {
+        let Some(candidates) = self.by_size.get(&size).cloned() else { return Ok(None) };
+        let identity = file_identity(&fs::metadata(path)?);
+        let hash = calculate_hash(path, options.algorithm)?;
+        // --ignore-hashes에 있는 내용은 중복으로 다루지 않습니다.
+        if options.ignored_hashes.as_ref().is_some_and(|ignored| ignored.contains(&hash)) {
+            return Ok(None);
+        }
+        for candidate in candidates {
+            let metadata = match fs::metadata(&candidate) {
+                Ok(metadata) if metadata.is_file() && metadata.len() == size => metadata,
+                _ => {
+                    self.forget(&candidate, size);
+                    continue;
+                }
+            };
+            // 이미 같은 파일(하드 링크)이라면 정리할 것이 없습니다.
+            if identity.is_some() && file_identity(&metadata) == identity {
+                continue;
+            }
+            let Ok(existing) = self.hash(&candidate, options) else { continue };
+            // 해시가 같아도 충돌일 수 있으므로, 파일을 바꾸기 전에 바이트 단위로 확인합니다.
+            if existing == hash && stages::same_content(path, &candidate)? {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
+    fn forget(&mut self, path: &Path, size: u64) {
+        if let Some(paths) = self.by_size.get_mut(&size) {
+            paths.retain(|p| p != path);
+        }
+        self.hashes.remove(path);
+    }
+}
+
+/// 처리 결과 집계.
+#[derive(Default)]
+struct Counts {
+    new_files: u64,
+    duplicates: u64,
+    acted: u64,
+    reclaimed: u64,
+    failed: u64,
+}
+
+/// 중단 요청을 받을 때까지 드롭 폴더를 감시합니다.
+pub fn run(config: &Config, options: &ScanOptions, log: &mut ScanLog) -> ExitCode {
+    println!("📇 기존 파일을 크기별로 색인합니다...");
+    let mut index = Index::default();
+    let mut indexed = 0u64;
+    for root in &config.index_roots {
+        for (path, size) in regular_files(root, options) {
+            index.add(path, size);
+            indexed += 1;
+        }
+    }
+    // 시작할 때 드롭 폴더에 있던 파일은 새 파일이 아니라 기존 내용입니다.
+    let mut known: HashSet<PathBuf> = regular_files(&config.folder, options).into_iter().map(|(path, _)| path).collect();
+    println!(
+        "👀 '{}'을(를) {} 간격으로 감시합니다. 색인된 파일 {}개, 동작: {} (Ctrl+C로 종료)",
+        config.folder.display(),
+        humantime::format_duration(config.interval),
+        indexed,
+        action_name(config.action)
+    );
+    log.event("watch_start", json!({ "folder": config.folder.display().to_string(), "indexed": indexed }));
+
+    // 쓰기가 끝나기를 기다리는 새 파일: 경로 -> 지난번 살펴볼 때의 크기/수정 시간.
+    let mut pending: HashMap<PathBuf, FileStamp> = HashMap::new();
+    let mut counts = Counts::default();
+    while !interrupt::requested() {
+        let started = Instant::now();
+        let present = regular_files(&config.folder, options);
+        let present_paths: HashSet<&PathBuf> = present.iter().map(|(path, _)| path).collect();
+        // 처리하기 전에 사라진 파일은 잊습니다. 같은 이름으로 다시 생기면 새 파일로 봅니다.
+        known.retain(|path| present_paths.contains(path));
+        pending.retain(|path, _| present_paths.contains(path));
+
+        for (path, size) in present {
+            if interrupt::requested() {
+                break;
+            }
+            if known.contains(&path) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(&path) else { continue };
+            let stamp = FileStamp::of(&metadata);
+            // 두 번 연속 크기와 수정 시간이 그대로이고 다른 프로세스가 쓰고 있지 않아야 쓰기가 끝난 것으로 봅니다.
+            let settled = pending.insert(path.clone(), stamp) == Some(stamp)
+                && !matches!(in_use::check(&path), Ok(Some(_)));
+            if !settled {
+                continue;
+            }
+            pending.remove(&path);
+            known.insert(path.clone());
+            // 빈 파일은 잠금 파일이나 자리 표시용인 경우가 많아 모두 같은 내용으로 볼 수 없습니다.
+            if size == 0 {
+                continue;
+            }
+            counts.new_files += 1;
+            handle_new_file(config, options, log, &mut index, &mut counts, path, size);
+        }
+        log.flush();
+
+        while !interrupt::requested() && started.elapsed() < config.interval {
+            thread::sleep(WAIT_STEP.min(config.interval.saturating_sub(started.elapsed())));
+        }
+    }
+
+    println!(
+        "\n감시를 종료합니다. 새 파일 {}개 중 기존 내용과 같은 파일 {}개, 처리 {}개 (회수 {}), 실패 {}개",
+        counts.new_files,
+        counts.duplicates,
+        counts.acted,
+        crate::format_size(counts.reclaimed),
+        counts.failed
+    );
+    log.event(
+        "watch_end",
+        json!({
+            "new_files": counts.new_files,
+            "duplicates": counts.duplicates,
+            "acted": counts.acted,
+            "reclaimed_bytes": counts.reclaimed,
+            "failed": counts.failed,
+        }),
+    );
+    ExitCode::SUCCESS
+}
+
+/// 쓰기가 끝난 새 파일 하나를 색인과 비교하고, 같은 내용이 있다면 설정한 동작을 적용합니다.
+fn handle_new_file(
+    config: &Config,
+    options: &ScanOptions,
+    log: &mut ScanLog,
+    index: &mut Index,
+    counts: &mut Counts,
+    path: PathBuf,
+    size: u64,
+) {
+    let existing = match index.find_match(&path, size, options) {
+        Ok(Some(existing)) => existing,
+        Ok(None) => {
+            index.add(path, size);
+            return;
+        }
+        Err(e) => {
+            counts.failed += 1;
+            eprintln!("⚠️  '{}'을(를) 비교하지 못했습니다: {}", path.display(), e);
+            log.event("warning", json!({ "path": path_style::display(&path), "stage": "watch", "error": e.to_string() }));
+            return;
+        }
+    };
+    counts.duplicates += 1;
+    // 파일을 바꾸기 전의 메타데이터. 로그에 남겨 두면 나중에 원래대로 되돌릴 수 있습니다.
+    let mut original = None;
+    let result = match config.action {
+        WatchAction::Report => Ok(None),
+        WatchAction::Hardlink => Snapshot::capture(&path)
+            .and_then(|snapshot| replace_with_link(&path, &existing, original.insert(snapshot)))
+            .map(|()| None),
+        WatchAction::Quarantine => match &config.quarantine_dir {
+            Some(dir) => Snapshot::capture(&path)
+                .and_then(|snapshot| quarantine(&path, &config.folder, dir, original.insert(snapshot)))
+                .map(Some),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "--quarantine-dir가 지정되지 않았습니다")),
+        },
+    };
+    let fields = |outcome: &str, detail: Option<String>| {
+        json!({
+            "path": path_style::display(&path),
+            "existing": path_style::display(&existing),
+            "size": size,
+            "action": action_name(config.action),
+            "outcome": outcome,
+            "detail": detail,
+            "metadata": original.as_ref().map(Snapshot::to_json),
+        })
+    };
+    match result {
+        Ok(moved_to) => {
+            let done = match (config.action, &moved_to) {
+                (WatchAction::Report, _) => String::new(),
+                (WatchAction::Quarantine, Some(to)) => format!(" → '{}'(으)로 옮김", to.display()),
+                _ => String::from(" → 하드 링크로 바꿈"),
+            };
+            println!("🔁 '{}'은(는) 기존 '{}'과(와) 같습니다{}", path.display(), existing.display(), done);
+            if config.action != WatchAction::Report {
+                counts.acted += 1;
+                counts.reclaimed += size;
+            }
+            log.event("watch_duplicate", fields("ok", moved_to.map(|to| to.display().to_string())));
+        }
+        Err(e) => {
+            counts.failed += 1;
+            eprintln!(
+                "⚠️  '{}'은(는) 기존 '{}'과(와) 같지만 처리하지 못했습니다: {}",
+                path.display(),
+                existing.display(),
+                e
+            );
+            log.event("watch_duplicate", fields("failed", Some(e.to_string())));
+        }
+    }
+}
+
+/// 새 파일을 기존 파일의 하드 링크로 바꿉니다. 임시 이름으로 링크를 만든 뒤 이름을 바꾸므로,
+/// 중간에 실패해도 새 파일이 사라진 채로 남지 않습니다.
+/// 링크는 기존 파일의 메타데이터를 쓰므로, 권한, 소유자, 확장 속성이 다르면 바꾸지 않습니다.
+pub fn replace_with_link(path: &Path, existing: &Path, original: &Snapshot) -> io::Result<()> {
+    in_use::ensure_idle(path)?;
+    if !original.same_protection(&Snapshot::capture(existing)?) {
+        return Err(io::Error::other("권한, 소유자, 확장 속성 중 기존 파일과 다른 것이 있어 하드 링크로 바꾸지 않았습니다"));
+    }
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let temporary = path.with_file_name(format!(".{}.duplicate_finder-link", name));
+    fs::hard_link(existing, &temporary)?;
+    fs::rename(&temporary, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temporary);
+    })
+}
+
+/// 새 파일을 격리 폴더로 옮기고, 옮긴 경로를 반환합니다. 드롭 폴더 안의 상대 경로를 유지하며,
+/// 같은 이름이 이미 있다면 번호를 붙입니다. 다른 파일 시스템이라면 복사하여 메타데이터를 다시 적용한 뒤
+/// 원본을 지웁니다. 메타데이터를 모두 적용하지 못했다면 복사본을 지우고 원본을 남깁니다.
+fn quarantine(path: &Path, folder: &Path, dir: &Path, original: &Snapshot) -> io::Result<PathBuf> {
+    in_use::ensure_idle(path)?;
+    let relative = path.strip_prefix(folder).unwrap_or(path);
+    let mut target = dir.join(relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let stem = target.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = target.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let mut number = 1;
+    while target.symlink_metadata().is_ok() {
+        target.set_file_name(format!("{} ({}){}", stem, number, extension));
+        number += 1;
+    }
+    match fs::rename(path, &target) {
+        Ok(()) => Ok(target),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(path, &target)?;
+            if let Err(failed) = original.apply(&target) {
+                let _ = fs::remove_file(&target);
+                return Err(io::Error::other(format!("격리 폴더의 복사본에 {}을(를) 옮기지 못했습니다", failed)));
+            }
+            fs::remove_file(path)?;
+            Ok(target)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 폴더 아래에서 필터에 맞는 일반 파일의 (경로, 크기) 목록. 읽을 수 없는 항목은 건너뜁니다.
+fn regular_files(root: &Path, options: &ScanOptions) -> Vec<(PathBuf, u64)> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && passes_filter(entry.path(), options))
+        // 하드 링크로 바꾸는 도중의 임시 파일은 새 파일이 아닙니다.
+        .filter(|entry| !entry.file_name().to_string_lossy().ends_with(".duplicate_finder-link"))
+        .filter_map(|entry| {
+            let size = entry.metadata().ok()?.len();
+            Some((entry.into_path(), size))
+        })
+        .collect()
+}
+
+fn action_name(action: WatchAction) -> &'static str {
+    match action {
+        WatchAction::Report => "report",
+        WatchAction::Hardlink => "hardlink",
+        WatchAction::Quarantine => "quarantine",
+    }
+}
@@ -0,0 +1,151 @@
+// ==============================================================================
+//  여러 검색을 모아 두는 작업 공간 (`workspace` 하위 명령)
+// ------------------------------------------------------------------------------
+//  보관 자료가 여러 드라이브에 나뉘어 있고 드라이브를 한꺼번에 연결할 수 없으면, 한 번의 검색으로는
+//  드라이브 사이의 중복을 찾을 수 없습니다. 작업 공간은 서로 다른 때에 서로 다른 루트를 검색한 결과를
+//  이름 하나 아래에 쌓아 두고, 보고서를 그 전체(합집합)에 대해 만듭니다.
+//
+//  작업 공간은 사용자 데이터 폴더의 `duplicate_finder/workspaces/<이름>/`이며, 검색 하나가
+//  `index build`와 같은 형식의 카탈로그 파일 하나입니다. (catalog.rs)
+//  - `workspace <이름> add ROOT`: 루트를 해시하여 작업 공간에 더합니다. 같은 --label로 다시 더하면
+//    그 검색만 새로 바꿉니다. 작업 공간의 검색은 모두 같은 해시 알고리즘이어야 합니다.
+//  - `workspace <이름> report`: 모든 검색을 합쳐 여러 번 저장된 내용과, 검색마다 그 검색에만 있는
+//    내용(그 드라이브를 잃으면 사라지는 내용)을 보고합니다. 드라이브를 연결하지 않아도 됩니다.
+//  - 작업 공간 폴더는 카탈로그 폴더이기도 하므로, 새 데이터를 작업 공간 전체와 비교하려면
+//    `--against-catalogs --catalog-dir <작업 공간 폴더>`를 씁니다.
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::catalog::{self, Catalog};
+use crate::store_lock::{self, Access};
+use crate::{EXIT_DUPLICATES_FOUND, EXIT_NO_DUPLICATES, HashAlgorithm, format_count, format_size};
+
+/// 내용 하나: (크기, 해시).
+type Content<'a> = (u64, &'a str);
+/// 내용이 있는 위치 하나: (검색 번호, 루트에 대한 경로).
+type Location<'a> = (usize, &'a str);
+
+/// 사용자 데이터 폴더 안의 기본 작업 공간 폴더들의 위치.
+pub fn default_parent() -> Option<PathBuf> {
+    Some(catalog::data_dir()?.join("workspaces"))
+}
+
+/// 이름이 `name`인 작업 공간의 폴더.
+pub fn dir(parent: &Path, name: &str) -> io::Result<PathBuf> {
+    if !catalog::valid_label(name) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("작업 공간 이름 '{}'에는 영문자, 숫자, '.', '_', '-'만 쓸 수 있습니다", name),
+        ));
+    }
+    Ok(parent.join(name))
+}
+
+/// `workspace add`: 루트를 해시하여 작업 공간에 검색 하나로 더합니다. 이름을 생략하면 루트 폴더의 이름을 씁니다.
+pub fn add(dir: &Path, root: &Path, label: Option<&str>, algorithm: HashAlgorithm) -> io::Result<ExitCode> {
+    let label = match label {
+        Some(label) => label.to_string(),
+        None => root
+            .canonicalize()
+            .ok()
+            .and_then(|root| root.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .filter(|name| catalog::valid_label(name))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("'{}'의 폴더 이름을 검색 이름으로 쓸 수 없습니다. --label로 이름을 지정하세요", root.display()),
+                )
+            })?,
+    };
+    // 알고리즘이 다른 검색은 해시를 비교할 수 없으므로, 긴 해시 계산을 시작하기 전에 거부합니다.
+    if let Some(other) = load_all(dir)?.into_iter().find(|c| c.label != label && c.algorithm != algorithm.name()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "작업 공간의 '{}' 검색은 {} 해시로 만들었으므로 --algorithm {}과 함께 써야 합니다",
+                other.label, other.algorithm, other.algorithm
+            ),
+        ));
+    }
+    catalog::build(dir, root, &label, algorithm)
+}
+
+/// 작업 공간의 모든 검색 (이름 순서). 작업 공간이 아직 없으면 비어 있습니다.
+fn load_all(dir: &Path) -> io::Result<Vec<Catalog>> {
+    let _lock = store_lock::acquire(&store_lock::inside(dir), Access::Shared, "작업 공간")?;
+    catalog::catalog_files(dir)?.iter().map(|path| catalog::load(path)).collect()
+}
+
+/// `workspace report`: 모든 검색을 합쳐 여러 번 저장된 내용과 검색마다 그 검색에만 있는 내용을 보고합니다.
+pub fn report(dir: &Path, name: &str, top: usize) -> io::Result<ExitCode> {
+    let scans = load_all(dir)?;
+    if scans.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("'{}' 작업 공간에 검색이 없습니다. 먼저 `workspace {} add ROOT`로 더하세요", name, name),
+        ));
+    }
+    if let Some(other) = scans.iter().find(|scan| scan.algorithm != scans[0].algorithm) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("'{}'({})와 '{}'({})의 해시 알고리즘이 다릅니다", scans[0].label, scans[0].algorithm, other.label, other.algorithm),
+        ));
+    }
+
+    // 내용마다 그 내용이 있는 위치들.
+    let mut contents: HashMap<Content, Vec<Location>> = HashMap::new();
+    for (index, scan) in scans.iter().enumerate() {
+        for file in &scan.files {
+            contents.entry((file.size, &file.hash)).or_default().push((index, &file.path));
+        }
+    }
+
+    println!("🗂️  '{}' 작업 공간: 검색 {}개, 서로 다른 내용 {}개", name, scans.len(), format_count(contents.len()));
+    for (index, scan) in scans.iter().enumerate() {
+        let bytes: u64 = scan.files.iter().map(|f| f.size).sum();
+        // 이 검색에만 있는 내용 (다른 어느 검색에도 없는 것). 그 드라이브를 잃으면 사라집니다.
+        let (only_count, only_bytes) = contents
+            .iter()
+            .filter(|(_, locations)| locations.iter().all(|(scan, _)| *scan == index))
+            .fold((0u64, 0u64), |(count, total), ((size, _), _)| (count + 1, total + size));
+        println!(
+            "  {}: 파일 {}개 ({}), {} ({} 검색), 이 검색에만 있는 내용 {}개 ({})",
+            scan.label,
+            format_count(scan.files.len()),
+            format_size(bytes),
+            scan.root,
+            scan.created,
+            format_count(only_count),
+            format_size(only_bytes)
+        );
+    }
+
+    let mut redundant: Vec<(&Content, &Vec<Location>)> =
+        contents.iter().filter(|(_, locations)| locations.len() > 1).collect();
+    let wasted = |(size, _): &Content, locations: &Vec<Location>| size * (locations.len() as u64 - 1);
+    let wasted_total: u64 = redundant.iter().map(|(key, locations)| wasted(key, locations)).sum();
+    let spanning = redundant.iter().filter(|(_, locations)| locations.iter().any(|(scan, _)| *scan != locations[0].0)).count();
+    println!(
+        "\n여러 번 저장된 내용: {}개 (그중 여러 검색에 걸친 것 {}개), 회수 가능 {}",
+        format_count(redundant.len()),
+        format_count(spanning),
+        format_size(wasted_total)
+    );
+
+    // 회수 가능 공간이 큰 순서, 같으면 해시 순서로 정렬하여 실행마다 같은 결과를 냅니다.
+    redundant.sort_by(|(a, la), (b, lb)| wasted(b, lb).cmp(&wasted(a, la)).then_with(|| a.1.cmp(b.1)));
+    if top > 0 && !redundant.is_empty() {
+        println!("\n회수 가능 공간이 큰 내용 상위 {}개:", top.min(redundant.len()));
+    }
+    for (i, ((size, _), locations)) in redundant.iter().take(top).enumerate() {
+        println!("\n--- 내용 {} ({}번 저장, 파일당 {}) ---", i + 1, locations.len(), format_size(*size));
+        for (scan, path) in locations.iter() {
+            println!("  - {}:{}", scans[*scan].label, path);
+        }
+    }
+    Ok(ExitCode::from(if redundant.is_empty() { EXIT_NO_DUPLICATES } else { EXIT_DUPLICATES_FOUND }))
+}
@@ -0,0 +1,227 @@
+// ==============================================================================
+//  확장 속성(xattr) 해시 캐시 (--xattr-cache)
+// ------------------------------------------------------------------------------
+//  계산한 해시를 파일 자신의 사용자 확장 속성에 기록해 두고, 다음 실행에서 파일의
+//  크기와 수정 시간이 그대로라면 파일을 다시 읽지 않고 그 해시를 사용합니다.
+//  캐시가 파일과 함께 다니므로 별도의 캐시 폴더가 없어지거나, 파일을 다른 폴더로
+//  옮겨도(같은 파일 시스템 안에서) 캐시가 유지됩니다.
+//
+//  속성은 shatag/cshatag과 같은 형식으로 저장하므로, 그 도구들이 남긴 해시를 그대로 쓰고 이 프로그램이
+//  남긴 해시도 그 도구들이 씁니다.
+//  - `user.shatag.<알고리즘>`: 해시 16진수 (sha256, blake3 등 알고리즘마다 따로 저장)
+//  - `user.shatag.ts`:         해시를 계산할 때의 수정 시간 `<유닉스 초>.<나노초 9자리>`
+//  수정 시간이 기록과 같을 때만 해시를 씁니다. (shatag 형식에는 크기가 없으므로 수정 시간만 봅니다)
+//  해시를 먼저 쓰고 시각을 나중에 쓰므로, 도중에 멈추면 시각이 맞지 않아 다음 실행에서 파일을 다시
+//  읽습니다. 시각이 바뀔 때는 다른 알고리즘의 해시를 지워, 이전 내용의 해시가 새 시각과 짝지어지지
+//  않게 합니다. 형식이 맞지 않는 값은 기록이 없는 것으로 보고 파일을 다시 읽으므로, 손상된 캐시가
+//  이후의 검색을 오염시키지 않습니다.
+//
+//  기록이 있지만 수정 시간이 달라 쓰지 못한 경우는 "무효화"로 따로 셉니다. 그중 수정 시간의 초가 같고
+//  초 미만만 다른 기록은, 네트워크 마운트처럼 시각을 거칠게 저장하는 파일 시스템 탓에 캐시가 매번
+//  무효화되는 신호이므로 다시 따로 알려줍니다.
+// ==============================================================================
+
+use std::path::Path;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::UNIX_EPOCH;
+
+#[cfg(unix)]
+use clap::ValueEnum;
+
+use crate::{FileStamp, HashAlgorithm, HashSource, format_size, number_format};
+
+/// 캐시 속성 이름의 접두사. (shatag 형식)
+pub const PREFIX: &str = "user.shatag.";
+
+/// 해시를 계산할 때의 수정 시간을 저장하는 속성. 모든 알고리즘이 함께 씁니다.
+#[cfg(unix)]
+const TS_ATTR: &str = "user.shatag.ts";
+
+/// 해시를 저장하는 사용자 확장 속성의 이름.
+#[cfg(unix)]
+fn attr_name(algorithm: HashAlgorithm) -> String {
+    format!("{}{}", PREFIX, algorithm.name())
+}
+
+/// 파일의 수정 시간 (유닉스 초, 나노초). 알 수 없다면 캐시가 유효한지 판단할 수 없으므로 `None`입니다.
+#[cfg(unix)]
+fn modified(stamp: &FileStamp) -> Option<(u64, u32)> {
+    let modified = stamp.modified?.duration_since(UNIX_EPOCH).ok()?;
+    Some((modified.as_secs(), modified.subsec_nanos()))
+}
+
+/// `user.shatag.ts` 값을 읽습니다. 다른 도구가 소수 부분을 9자리보다 짧게 썼어도 받아들입니다.
+#[cfg(unix)]
+fn parse_ts(text: &str) -> Option<(u64, u32)> {
+    let text = text.trim();
+    let (secs, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = if fraction.is_empty() { 0 } else { format!("{:0<9}", fraction).parse().ok()? };
+    Some((secs.parse().ok()?, nanos))
+}
+
+/// 캐시를 찾아본 결과.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Lookup {
+    /// 기록 당시의 수정 시간이 지금과 같은 해시.
+    Hit(String),
+    /// 기록은 있지만 수정 시간이 달라 쓸 수 없습니다. `coarse`이면 한쪽의 초 미만이 잘려 나간 듯 수정 시간의 초 미만만 다릅니다.
+    Stale { coarse: bool },
+    /// 기록이 없거나 읽을 수 없습니다.
+    Missing,
+}
+
+/// 파일에 기록된 해시가 있고, 기록 당시의 수정 시간이 `stamp`와 같다면 그 해시를 반환합니다.
+#[cfg(unix)]
+pub fn load(path: &Path, stamp: &FileStamp, algorithm: HashAlgorithm) -> Lookup {
+    let get = |name: &str| xattr::get(path, name).ok().flatten().and_then(|v| String::from_utf8(v).ok());
+    let Some(value) = get(&attr_name(algorithm)) else { return Lookup::Missing };
+    let hash = value.trim();
+    if hash.len() != algorithm.hex_len() || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Lookup::Missing;
+    }
+    let Some(recorded) = get(TS_ATTR).as_deref().and_then(parse_ts) else { return Lookup::Missing };
+    let Some(current) = modified(stamp) else { return Lookup::Stale { coarse: false } };
+    if recorded == current {
+        return Lookup::Hit(hash.to_ascii_lowercase());
+    }
+    // 초가 같고 한쪽의 나노초가 0이면, 시각을 초 단위로만 저장하는 파일 시스템을 거치며 초 미만이
+    // 잘려 나간 것으로 봅니다.
+    let coarse = recorded.0 == current.0 && (recorded.1 == 0 || current.1 == 0);
+    Lookup::Stale { coarse }
+}
+
+/// 계산한 해시를 파일의 확장 속성에 기록합니다.
+///
+/// 읽기 전용 파일, 확장 속성을 지원하지 않는 파일 시스템 등에서는 기록에 실패하지만,
+/// 캐시는 다음 실행을 빠르게 하기 위한 것일 뿐이므로 오류를 무시합니다.
+#[cfg(unix)]
+pub fn store(path: &Path, stamp: &FileStamp, algorithm: HashAlgorithm, hash: &str) {
+    let Some(current) = modified(stamp) else { return };
+    // 기록된 시각이 다르면 다른 알고리즘의 해시는 이전 내용의 것이므로, 새 시각을 쓰기 전에 지웁니다.
+    let recorded = xattr::get(path, TS_ATTR).ok().flatten().and_then(|v| String::from_utf8(v).ok());
+    if recorded.as_deref().and_then(parse_ts) != Some(current) {
+        for other in HashAlgorithm::value_variants().iter().filter(|other| **other != algorithm) {
+            let _ = xattr::remove(path, attr_name(*other));
+        }
+    }
+    if xattr::set(path, attr_name(algorithm), hash.as_bytes()).is_ok() {
+        let _ = xattr::set(path, TS_ATTR, format!("{}.{:09}", current.0, current.1).as_bytes());
+    }
+}
+
+/// 확장 속성을 지원하지 않는 플랫폼에서는 캐시를 사용하지 않습니다.
+#[cfg(not(unix))]
+pub fn load(_path: &Path, _stamp: &FileStamp, _algorithm: HashAlgorithm) -> Lookup {
+    Lookup::Missing
+}
+
+#[cfg(not(unix))]
+pub fn store(_path: &Path, _stamp: &FileStamp, _algorithm: HashAlgorithm, _hash: &str) {}
+
+/// 검색 한 번 동안의 캐시 사용 수치.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CacheStats {
+    /// 캐시에서 해시를 가져온 파일 수와 그 크기의 합.
+    pub hits: u64,
+    pub hit_bytes: u64,
+    /// 캐시를 사용 중이지만 유효한 해시가 없어 파일을 읽은 수. (무효화 포함)
+    pub misses: u64,
+    /// 그중 기록은 있었지만 수정 시간이 달라 쓰지 못한 수.
+    pub invalidated: u64,
+    /// 그중 한쪽 수정 시간의 초 미만이 잘려 나가 달라진 것으로 보이는 수.
+    pub coarse: u64,
+    /// 캐시에서 가져오는 데 든 시간과, 파일을 읽어 해시한 바이트 수와 시간. (스레드별 시간의 합)
+    hit_time: Duration,
+    read_bytes: u64,
+    read_time: Duration,
+    /// 캐시 덕분에 줄어든 검색 시간의 추정치. 이번 검색에서 읽은 파일이 없으면 추정할 수 없습니다.
+    pub saved: Option<Duration>,
+}
+
+impl CacheStats {
+    /// 파일 하나의 해시를 얻은 경로와 걸린 시간을 기록합니다.
+    pub fn record(&mut self, source: &HashSource, size: u64, elapsed: Duration) {
+        match source {
+            HashSource::Cache => {
+                self.hits += 1;
+                self.hit_bytes += size;
+                self.hit_time += elapsed;
+            }
+            HashSource::Imported => {}
+            HashSource::Read(lookup) => {
+                self.read_bytes += size;
+                self.read_time += elapsed;
+                match lookup {
+                    Some(Lookup::Stale { coarse }) => {
+                        self.misses += 1;
+                        self.invalidated += 1;
+                        self.coarse += u64::from(*coarse);
+                    }
+                    Some(_) => self.misses += 1,
+                    None => {}
+                }
+            }
+        }
+    }
+
+    /// 절약한 시간을 추정합니다. 적중한 파일을 이번 검색의 읽기 속도로 읽었다면 걸렸을 시간에서 캐시를
+    /// 찾아보는 데 든 시간을 빼고, 동시에 읽는 스레드 수로 나눕니다.
+    pub fn finish(&mut self, threads: usize) {
+        if self.read_bytes == 0 || self.read_time.is_zero() {
+            self.saved = None;
+            return;
+        }
+        let per_byte = self.read_time.as_secs_f64() / self.read_bytes as f64;
+        let saved = (self.hit_bytes as f64 * per_byte - self.hit_time.as_secs_f64()).max(0.0) / threads.max(1) as f64;
+        self.saved = Some(Duration::from_secs_f64(saved));
+    }
+
+    /// 찾아본 수 중 적중한 비율. 찾아본 적이 없으면 `None`.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+
+    /// 검색이 끝난 뒤 캐시 사용 요약을 출력합니다.
+    pub fn print(&self) {
+        println!("\n🗃️  해시 캐시 (--xattr-cache)");
+        let Some(ratio) = self.hit_ratio() else {
+            println!("  해시할 후보 파일이 없어 캐시를 찾아보지 않았습니다.");
+            return;
+        };
+        println!("  적중: {}개 ({}), 적중률 {}%", self.hits, format_size(self.hit_bytes), number_format::format().decimal(ratio * 100.0));
+        println!("  실패: {}개, 그중 수정 시각이 바뀌어 무효화된 기록 {}개", self.misses, self.invalidated);
+        match self.saved {
+            Some(saved) => println!("  추정 절약 시간: 약 {}초 (이번 검색의 읽기 속도 기준)", number_format::format().decimal(saved.as_secs_f64())),
+            None if self.hits > 0 => println!("  추정 절약 시간: 이번 검색에서 읽은 파일이 없어 추정할 수 없습니다."),
+            None => {}
+        }
+        if self.coarse > 0 {
+            println!(
+                "  ⚠️  무효화된 기록 중 {}개는 수정 시각의 초 미만이 잘려 나가 달라졌습니다. 네트워크 마운트처럼 시각을 초 단위로 저장하는",
+                self.coarse
+            );
+            println!("     파일 시스템에서는 캐시가 매번 무효화될 수 있습니다.");
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_shatag_timestamps() {
+        assert_eq!(parse_ts("1560177334.020775051"), Some((1560177334, 20775051)));
+        // shatag(파이썬)은 소수 부분을 짧게 쓰기도 합니다.
+        assert_eq!(parse_ts("1560177334.5\n"), Some((1560177334, 500_000_000)));
+        assert_eq!(parse_ts("1560177334"), Some((1560177334, 0)));
+        assert_eq!(parse_ts("1560177334.0207750511"), None);
+        assert_eq!(parse_ts("-1.5"), None);
+        assert_eq!(parse_ts("abc"), None);
+    }
+}